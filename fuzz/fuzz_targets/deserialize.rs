@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use maze::matrix;
+use maze::Maze;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(maze) = serde_json::from_slice::<Maze<()>>(data) {
+        // Any deserialised maze must be safe to use, even if it is invalid
+        let _ = maze.validate();
+        for pos in maze.positions() {
+            for &wall in maze.walls(pos) {
+                maze.is_open((pos, wall));
+            }
+        }
+        if let Some(last) = maze.positions().last() {
+            let _ = maze.try_walk(last, matrix::Pos { col: 0, row: 0 });
+        }
+    }
+});
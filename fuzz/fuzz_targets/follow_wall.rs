@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use maze::matrix;
+use maze::Maze;
+
+fuzz_target!(|input: (Maze<()>, matrix::Pos, usize)| {
+    let (maze, pos, index) = input;
+    let walls = maze.walls(pos);
+    let wall_pos = (pos, walls[index % walls.len()]);
+
+    // Every wall is visited at most once from every side
+    let limit = 2 * maze.positions().count() * walls.len() + 1;
+    assert!(maze.follow_wall(wall_pos).take(limit + 1).count() <= limit);
+});
@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use maze::matrix;
+use maze::Maze;
+
+fuzz_target!(|input: (Maze<()>, matrix::Pos, matrix::Pos)| {
+    let (maze, from, to) = input;
+    match maze.try_walk(from, to) {
+        Ok(path) => {
+            let positions = path.try_positions().unwrap();
+            assert_eq!(Some(&from), positions.first());
+            assert_eq!(Some(&to), positions.last());
+            assert!(positions
+                .windows(2)
+                .all(|pair| maze.connected(pair[0], pair[1])));
+        }
+        Err(_) => assert!(maze.walk(from, to).is_none()),
+    }
+});
@@ -0,0 +1,297 @@
+//! # Configuration files
+//!
+//! A configuration file is a _TOML_ file describing a maze and how to render
+//! it, so that complex render pipelines can be reproduced without a long
+//! command line.
+//!
+//! The keys are the names of the long command line options, and the values
+//! use the same syntax as on the command line:
+//!
+//! ```toml
+//! walls = 6
+//! width = 40
+//! height = 30
+//! method = "braid,winding"
+//! seed = 42
+//! heat-map = "full,#000080,#ff0000"
+//! break = "full,2"
+//! ```
+//!
+//! Paths in a configuration file are relative to the working directory.
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use clap::parser::ValueSource;
+use clap::ArgMatches;
+use serde::{Deserialize, Deserializer};
+
+use crate::manifest::Run;
+use crate::types::*;
+use crate::Arguments;
+
+/// A configuration file.
+///
+/// All values are optional, and values passed on the command line take
+/// precedence.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// The number of walls per room.
+    #[serde(deserialize_with = "walls")]
+    walls: Option<maze::Shape>,
+
+    /// The width of the maze, in rooms.
+    width: Option<usize>,
+
+    /// The height of the maze, in rooms.
+    height: Option<usize>,
+
+    /// The initialisation methods to use.
+    #[serde(deserialize_with = "parsed")]
+    method: Option<Methods<Random>>,
+
+    /// A relative size for the maze, applied to rooms.
+    scale: Option<f32>,
+
+    /// An angle, in degrees, by which to rotate the maze clockwise.
+    rotate: Option<f32>,
+
+    /// A seed for the random number generator.
+    ///
+    /// Since TOML integers are signed, large seeds may be passed as strings.
+    #[serde(deserialize_with = "seed")]
+    seed: Option<u64>,
+
+    /// A phrase from which to derive the seed.
+    seed_phrase: Option<String>,
+
+    /// The number of mazes to generate.
+    count: Option<usize>,
+
+    /// Whether to generate several mazes in parallel.
+    parallel: Option<bool>,
+
+    /// The margin around the maze.
+    margin: Option<f32>,
+
+    /// The thickness of walls, relative to the size of rooms.
+    wall_width: Option<f32>,
+
+    /// A mask image and threshold, or luminosity bands.
+    #[serde(deserialize_with = "parsed")]
+    mask: Option<MaskInitializer<Random>>,
+
+    /// A text to hide in the solution.
+    #[serde(deserialize_with = "parsed")]
+    hidden_text: Option<HiddenTextInitializer<Random>>,
+
+    /// A heat map description.
+    #[serde(deserialize_with = "parsed")]
+    heat_map: Option<HeatMapRenderer>,
+
+    /// A background description.
+    #[serde(deserialize_with = "parsed")]
+    background: Option<BackgroundRenderer>,
+
+    /// A ratio for pixels per room when using a background.
+    ratio: Option<f32>,
+
+    /// A text to draw on the maze.
+    #[serde(deserialize_with = "parsed")]
+    text: Option<TextRenderer>,
+
+    /// A label description.
+    #[serde(deserialize_with = "parsed")]
+    label: Option<LabelRenderer>,
+
+    /// An endpoint marker description.
+    #[serde(deserialize_with = "parsed")]
+    mark_endpoints: Option<EndpointsRenderer>,
+
+    /// The grid colour.
+    #[serde(deserialize_with = "parsed")]
+    grid: Option<GridRenderer>,
+
+    /// The solution colour.
+    #[serde(deserialize_with = "parsed")]
+    solve: Option<SolveRenderer>,
+
+    /// A break description.
+    #[serde(rename = "break", deserialize_with = "parsed")]
+    post_break: Option<BreakPostProcessor>,
+
+    /// The output format.
+    #[serde(deserialize_with = "parsed")]
+    format: Option<OutputFormat>,
+
+    /// An animation description.
+    #[serde(deserialize_with = "parsed")]
+    animate: Option<Animation>,
+
+    /// The path of a JSON export of the maze and its solution.
+    export_json: Option<PathBuf>,
+
+    /// A heat map export description.
+    #[serde(deserialize_with = "parsed")]
+    heat_map_export: Option<HeatMapExport>,
+
+    /// Whether to render a maze read from standard input.
+    render_only: Option<bool>,
+
+    /// The path of a maze to render.
+    load: Option<PathBuf>,
+
+    /// A fixed page size.
+    #[serde(deserialize_with = "parsed")]
+    page: Option<Page>,
+
+    /// A division of the output into pages.
+    #[serde(deserialize_with = "parsed")]
+    tile: Option<Tiling>,
+
+    /// A description of the run, if this is a manifest.
+    manifest: Option<Run>,
+}
+
+impl Config {
+    /// Loads a configuration file.
+    ///
+    /// If the file is a manifest, it must have been written by this version.
+    ///
+    /// # Arguments
+    /// *  `path` - The path to the configuration file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        let config = toml::from_str::<Self>(&data).map_err(|e| {
+            format!("invalid configuration {}: {}", path.display(), e)
+        })?;
+        if let Some(run) = &config.manifest {
+            run.check()?;
+        }
+        Ok(config)
+    }
+
+    /// Applies this configuration to command line arguments.
+    ///
+    /// Only arguments not passed on the command line are updated.
+    ///
+    /// # Arguments
+    /// *  `args` - The arguments to update.
+    /// *  `matches` - The matches from which `args` were parsed.
+    pub fn apply(self, args: &mut Arguments, matches: &ArgMatches) {
+        let merge = |id: &str| {
+            matches.value_source(id) != Some(ValueSource::CommandLine)
+        };
+        macro_rules! apply {
+            ($id:expr, $target:expr, $value:expr) => {
+                if let Some(value) = $value {
+                    if merge($id) {
+                        $target = value;
+                    }
+                }
+            };
+        }
+
+        apply!("SHAPE", args.shape, self.walls);
+        apply!("WIDTH", args.width, self.width.map(Some));
+        apply!("HEIGHT", args.height, self.height.map(Some));
+        apply!("METHOD", args.methods, self.method.map(Some));
+        apply!("SCALE", args.scale, self.scale);
+        apply!("ROTATE", args.rotate, self.rotate);
+        apply!("SEED", args.seed, self.seed.map(Some));
+        apply!("SEED_PHRASE", args.seed_phrase, self.seed_phrase.map(Some));
+        apply!("COUNT", args.count, self.count);
+        apply!("parallel", args.parallel, self.parallel);
+        apply!("MARGIN", args.margin, self.margin);
+        apply!("WALL_WIDTH", args.wall_width, self.wall_width.map(Some));
+        apply!("INITIALIZE", args.initialize_mask, self.mask.map(Some));
+        apply!(
+            "HIDDEN_TEXT",
+            args.initialize_hidden_text,
+            self.hidden_text.map(Some)
+        );
+        apply!("HEATMAP", args.render_heatmap, self.heat_map.map(Some));
+        apply!(
+            "BACKGROUND",
+            args.render_background,
+            self.background.map(Some)
+        );
+        apply!("RATIO", args.render_background_ratio, self.ratio.map(Some));
+        apply!("TEXT", args.render_text, self.text.map(Some));
+        apply!("LABEL", args.render_label, self.label.map(Some));
+        apply!(
+            "ENDPOINTS",
+            args.render_endpoints,
+            self.mark_endpoints.map(Some)
+        );
+        apply!("GRID", args.render_grid, self.grid.map(Some));
+        apply!("SOLVE", args.render_solve, self.solve.map(Some));
+        apply!("post_break", args.post_break, self.post_break.map(Some));
+        apply!("FORMAT", args.format, self.format.map(Some));
+        apply!("ANIMATE", args.animate, self.animate.map(Some));
+        apply!("EXPORT_JSON", args.export_json, self.export_json.map(Some));
+        apply!(
+            "HEATMAP_EXPORT",
+            args.export_heatmap,
+            self.heat_map_export.map(Some)
+        );
+        apply!("RENDER_ONLY", args.render_only, self.render_only);
+        apply!("LOAD", args.load, self.load.map(Some));
+        apply!("PAGE", args.page, self.page.map(Some));
+        apply!("TILE", args.tile, self.tile.map(Some));
+    }
+}
+
+/// Deserialises a value using its string representation.
+///
+/// This allows configuration files to use the same syntax as the command
+/// line.
+fn parsed<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| s.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// Deserialises a seed from either a number or a string.
+fn seed<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Seed {
+        Number(u64),
+        Text(String),
+    }
+
+    Option::<Seed>::deserialize(deserializer)?
+        .map(|seed| match seed {
+            Seed::Number(seed) => Ok(seed),
+            Seed::Text(seed) => seed.parse().map_err(serde::de::Error::custom),
+        })
+        .transpose()
+}
+
+/// Deserialises a shape from its number of walls.
+fn walls<'de, D>(deserializer: D) -> Result<Option<maze::Shape>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<u32>::deserialize(deserializer)?
+        .map(|n| {
+            n.try_into().map_err(|e| {
+                serde::de::Error::custom(format!(
+                    "invalid number of walls: {}",
+                    e
+                ))
+            })
+        })
+        .transpose()
+}
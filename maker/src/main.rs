@@ -1,10 +1,16 @@
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
-use clap::{arg, Parser};
+use clap::{CommandFactory, FromArgMatches, Parser};
+use rayon::prelude::*;
 use svg::Node;
 
-use maze::render::svg::ToPath;
+use maze::render::svg::{ToOutline, ToPath};
 
+mod config;
+use self::config::Config;
+mod manifest;
+use self::manifest::Manifest;
 mod types;
 use self::types::*;
 
@@ -12,6 +18,13 @@ use self::types::*;
 #[derive(Parser)]
 #[command(author, version, about)]
 struct Arguments {
+    /// A configuration file describing the maze and how to render it.
+    ///
+    /// The keys of the file are the names of the long options, and values
+    /// passed on the command line take precedence.
+    #[arg(id = "CONFIG", long = "config")]
+    config: Option<PathBuf>,
+
     /// The number of walls per room: 3, 4 or 6.
     #[arg(
         id = "SHAPE",
@@ -31,6 +44,7 @@ struct Arguments {
         id = "WIDTH",
         long = "width",
         required_unless_present_all(["BACKGROUND", "RATIO"]),
+        required_unless_present_any(["CONFIG", "RENDER_ONLY", "LOAD"]),
     )]
     width: Option<usize>,
 
@@ -39,6 +53,7 @@ struct Arguments {
         id = "HEIGHT",
         long = "height",
         required_unless_present_all(["BACKGROUND", "RATIO"]),
+        required_unless_present_any(["CONFIG", "RENDER_ONLY", "LOAD"]),
     )]
     height: Option<usize>,
 
@@ -53,35 +68,133 @@ struct Arguments {
     /// winding: A maze with long corridors.
     ///
     /// clear: A clear area.
-    #[arg(id = "METHOD", long = "method", required(true))]
-    methods: Methods<Random>,
+    ///
+    /// The texture of branching and winding mazes can be tuned by appending
+    /// parameters: horizontal=N, a preference for horizontal passages from 0
+    /// to 100, and branch=N, the probability in percent of branching off from
+    /// a random room, for example winding:horizontal=80:branch=10.
+    ///
+    /// A single method may be followed by the room in which to start, for
+    /// example winding@0,0.
+    ///
+    /// This cannot be used with a mask mapping luminosity bands to
+    /// initialisation methods.
+    #[arg(
+        id = "METHOD",
+        long = "method",
+        required_unless_present_any([
+            "CONFIG",
+            "RENDER_ONLY",
+            "LOAD",
+            "INITIALIZE",
+        ]),
+    )]
+    methods: Option<Methods<Random>>,
 
     /// A relative size for the maze, applied to rooms.
     #[arg(id = "SCALE", long = "scale", default_value_t = 10.0)]
     scale: f32,
 
+    /// An angle, in degrees, by which to rotate the maze clockwise.
+    ///
+    /// This is useful for example to lay out hexagonal rooms with pointy
+    /// tops.
+    #[arg(id = "ROTATE", long = "rotate", default_value_t = 0.0)]
+    rotate: f32,
+
     /// A seed for the random number generator.
+    ///
+    /// When generating several mazes, this is the seed of the first maze, and
+    /// every following maze uses the next seed.
     #[arg(id = "SEED", long = "seed")]
     seed: Option<u64>,
 
+    /// A phrase from which to derive the seed.
+    ///
+    /// The same phrase always generates the same maze, so this can be used
+    /// as a memorable identifier.
+    #[arg(id = "SEED_PHRASE", long = "seed-phrase", conflicts_with = "SEED")]
+    seed_phrase: Option<String>,
+
+    /// The number of mazes to generate.
+    ///
+    /// When generating more than one maze, the output path must contain
+    /// "{seed}" or "{index}", which are replaced by the seed and index of
+    /// each maze.
+    #[arg(id = "COUNT", long = "count", default_value_t = 1)]
+    count: usize,
+
+    /// Whether to generate several mazes in parallel.
+    #[arg(long = "parallel")]
+    parallel: bool,
+
     /// The margin around the maze.
     #[arg(id = "MARGIN", long = "margin", default_value_t = 10.0)]
     margin: f32,
 
+    /// The thickness of walls, relative to the size of rooms.
+    ///
+    /// If specified, walls are drawn as filled polygons with this thickness
+    /// instead of as lines, which is useful for cutting the maze.
+    #[arg(id = "WALL_WIDTH", long = "wall-width")]
+    wall_width: Option<f32>,
+
     /// A mask image to determine which rooms are part of the mask and
     /// thenshold luminosity value between 0 and 1 on the form "path,0.5".
+    ///
+    /// Instead of a threshold, luminosity bands may be mapped to
+    /// initialisation methods on the form "path,0.3=winding,0.6=branching";
+    /// rooms darker than 0.3 are then winding, rooms darker than 0.6 are
+    /// branching and brighter rooms are not part of the maze. The bands
+    /// replace the initialisation methods, so --method cannot be used in this
+    /// case.
     #[arg(id = "INITIALIZE", long = "mask")]
     initialize_mask: Option<MaskInitializer<Random>>,
 
+    /// A text to hide in the solution.
+    ///
+    /// The solution is carved through the rooms covered by the text before
+    /// the rest of the maze is initialised, so a heat map of the solution
+    /// reveals the text.
+    #[arg(
+        id = "HIDDEN_TEXT",
+        long = "hidden-text",
+        conflicts_with = "INITIALIZE"
+    )]
+    initialize_hidden_text: Option<HiddenTextInitializer<Random>>,
+
     /// Whether to create a heat map.
+    ///
+    /// This is the heat map type, optionally followed by a comma separated
+    /// list of gradient colours and the scale, "linear" or "log", on the form
+    /// "full,#000080,#00ff00,#ff0000,log".
     #[arg(id = "HEATMAP", long = "heat-map")]
     render_heatmap: Option<HeatMapRenderer>,
 
+    /// Whether to export a heat map for external tools, and the path of the
+    /// export.
+    ///
+    /// The path may be followed by the heat map type, which defaults to
+    /// "full", on the form "heat.csv,betweenness". The heat map is written as
+    /// a greymap image if the extension is "pgm", and as comma separated
+    /// values otherwise. The strings "{seed}" and "{index}" are replaced as
+    /// in the output path.
+    #[arg(id = "HEATMAP_EXPORT", long = "heat-map-export")]
+    export_heatmap: Option<HeatMapExport>,
+
     /// A background image to colour rooms.
+    ///
+    /// Instead of the path of an image, procedural noise, on the form
+    /// "noise:42", or a horizontal gradient, on the form
+    /// "gradient:#ffffff..#000000", may be used.
+    ///
+    /// The source may be followed by a comma separated list of palette
+    /// colours, to which the room colours are quantised, and "dither" to apply
+    /// ordered dithering, on the form "photo.png,#000000,#ffffff,dither".
     #[arg(id = "BACKGROUND", long = "background")]
     render_background: Option<BackgroundRenderer>,
 
-    /// A ratio for pixels per room when using a background.
+    /// A ratio for pixels per room when using a background image.
     #[arg(
         id = "RATIO",
         long = "ratio",
@@ -94,8 +207,38 @@ struct Arguments {
     #[arg(id = "TEXT", long = "text")]
     render_text: Option<TextRenderer>,
 
+    /// Whether to label the maze with metadata.
+    ///
+    /// This is a comma separated list of an optional corner, "top-left",
+    /// "top-right", "bottom-left" or "bottom-right", followed by the fields
+    /// to include: "seed", "size", "difficulty" and "method". If no fields
+    /// are specified, all fields are included.
+    #[arg(id = "LABEL", long = "label")]
+    render_label: Option<LabelRenderer>,
+
+    /// Whether to mark the endpoints of the solution, and the marker shape:
+    /// circle, arrow or star.
+    ///
+    /// The shape may be followed by the colour of both markers, or the colours
+    /// of the start and finish markers, on the form "arrow,#008000,#ff0000".
+    #[arg(id = "ENDPOINTS", long = "mark-endpoints")]
+    render_endpoints: Option<EndpointsRenderer>,
+
+    /// Whether to draw the grid of rooms beneath the maze, and the colour of
+    /// the grid lines. If not specified, the colour defaults to "lightgray".
+    #[arg(
+        id = "GRID",
+        long = "grid",
+        num_args = 0..=1,
+        default_missing_value = "lightgray",
+    )]
+    render_grid: Option<GridRenderer>,
+
     /// Whether to solve the maze, and the solution colour. If not specified,
     /// the colour defaults to "black".
+    ///
+    /// Two hex encoded colours separated by "..", such as "#ff0000..#0000ff",
+    /// colour the solution with a gradient from the start to the finish.
     #[arg(
         id = "SOLVE",
         long = "solve",
@@ -105,10 +248,104 @@ struct Arguments {
     render_solve: Option<SolveRenderer>,
 
     /// Whether to break the maze.
+    ///
+    /// This is the heat map type, optionally followed by the number of times
+    /// to break the maze and an image whose brightness is the probability of
+    /// breaking walls in the rooms it covers, on the form
+    /// "full,2,plazas.png".
     #[arg(long = "break")]
     post_break: Option<BreakPostProcessor>,
 
-    /// The output SVG.
+    /// The output format: svg, png, pdf, txt, html or json.
+    ///
+    /// If not specified, the format is determined by the extension of the
+    /// output path, defaulting to svg. The html format draws only the walls,
+    /// and the json format writes the maze itself, to be rendered later with
+    /// --render-only.
+    #[arg(id = "FORMAT", long = "format")]
+    format: Option<OutputFormat>,
+
+    /// Whether to write an animation of the maze being initialised, and the
+    /// path of the animation.
+    ///
+    /// The animation lasts 10 seconds, unless another duration is passed on
+    /// the form "frames.svg,5". Post-processing is not part of the animation.
+    #[arg(id = "ANIMATE", long = "animate")]
+    animate: Option<Animation>,
+
+    /// Whether to export the maze and its solution as JSON for puzzle apps,
+    /// and the path of the JSON file.
+    ///
+    /// The strings "{seed}" and "{index}" are replaced as in the output path.
+    #[arg(id = "EXPORT_JSON", long = "export-json")]
+    export_json: Option<PathBuf>,
+
+    /// Whether to render a maze read from standard input instead of
+    /// generating one.
+    ///
+    /// The maze is read as written by the json output format, so mazes can
+    /// be generated, processed by other tools and rendered in a pipeline.
+    #[arg(
+        id = "RENDER_ONLY",
+        long = "render-only",
+        conflicts_with_all([
+            "INITIALIZE",
+            "HIDDEN_TEXT",
+            "ANIMATE",
+            "RATIO",
+        ]),
+    )]
+    render_only: bool,
+
+    /// A maze to render instead of generating one.
+    ///
+    /// The maze is read as written by the json output format, and only
+    /// renderers and post-processors are applied, so the same maze can be
+    /// rendered again, for example at a different scale or with its
+    /// solution.
+    #[arg(
+        id = "LOAD",
+        long = "load",
+        conflicts_with_all([
+            "INITIALIZE",
+            "HIDDEN_TEXT",
+            "ANIMATE",
+            "RATIO",
+            "RENDER_ONLY",
+        ]),
+    )]
+    load: Option<PathBuf>,
+
+    /// A fixed page size, on the form "widthxheight", or one of "a3", "a4",
+    /// "a5" and "letter".
+    ///
+    /// The maze, including its margin, is scaled to fit the page and centred
+    /// on it.
+    #[arg(id = "PAGE", long = "page")]
+    page: Option<Page>,
+
+    /// Whether to split the output into pages, and the number of pages on the
+    /// form "columnsxrows".
+    ///
+    /// The pages overlap by 10 units, unless another overlap is passed on
+    /// the form "3x2,15". Every page is written to a file with the row and
+    /// column appended to the name of the output file.
+    #[arg(id = "TILE", long = "tile")]
+    tile: Option<Tiling>,
+
+    /// Whether to write a manifest of the run, and the path of the manifest.
+    ///
+    /// The manifest records every parameter, the resolved seed, the versions
+    /// used and timing information. Passing it back with --config generates
+    /// identical mazes.
+    #[arg(id = "MANIFEST", long = "manifest")]
+    manifest: Option<PathBuf>,
+
+    /// The output file.
+    ///
+    /// The strings "{seed}" and "{index}" are replaced by the seed and the
+    /// index of the maze. If this is "-", the maze is written to standard
+    /// output.
     #[arg(id = "PATH", required(true))]
     output: PathBuf,
 }
@@ -116,35 +353,124 @@ struct Arguments {
 #[allow(unused_variables, clippy::too_many_arguments)]
 fn run<P>(
     maze: Maze,
-    scale: f32,
+    transform: maze::physical::Transform,
     margin: f32,
-    renderers: &[&dyn Renderer],
+    wall_width: Option<f32>,
+    renderers: &[(&str, &dyn Renderer)],
+    page: Option<Page>,
+    tiling: Option<Tiling>,
+    format: OutputFormat,
     output: P,
 ) where
     P: AsRef<Path>,
 {
-    let document = svg::Document::new()
-        .set("viewBox", maze_to_viewbox(&maze, scale, margin));
-    let mut container = svg::node::element::Group::new()
-        .set("transform", format!("scale({})", scale));
+    let viewbox = maze.viewbox().transform(transform).expand(margin);
+    let (transform, viewbox) = if let Some(page) = page {
+        (
+            transform.then(viewbox.fit_into(page.viewbox())),
+            page.viewbox(),
+        )
+    } else {
+        (transform, viewbox)
+    };
+
+    // A canvas contains only the walls, and an exported maze is not drawn at
+    // all, so no renderers apply
+    match format {
+        OutputFormat::Html => {
+            create_output(output)
+                .and_then(|mut writer| {
+                    writer
+                        .write_all(
+                            maze.to_canvas_html(transform, viewbox).as_bytes(),
+                        )
+                        .map_err(|e| e.to_string())
+                })
+                .expect("failed to write output");
+            return;
+        }
+        OutputFormat::Json => {
+            create_output(output)
+                .and_then(|writer| {
+                    serde_json::to_writer(writer, &maze)
+                        .map_err(|e| e.to_string())
+                })
+                .expect("failed to write output");
+            return;
+        }
+        _ => {}
+    }
+
+    let viewbox = viewbox.tuple();
+    let mut container = layer("Maze").set("transform", transform.to_string());
 
-    for renderer in renderers {
-        renderer.render(&maze, &mut container);
+    // Every renderer draws to its own layer, which is omitted if empty
+    for (label, renderer) in renderers {
+        let mut layer = layer(label);
+        renderer.render(&maze, &mut layer);
+        if !layer.get_children().is_empty() {
+            container.append(layer);
+        }
     }
 
     // Draw the maze
-    container.append(
-        svg::node::element::Path::new()
-            .set("fill", "none")
-            .set("stroke", "black")
-            .set("stroke-linecap", "round")
-            .set("stroke-linejoin", "round")
-            .set("stroke-width", 0.4)
-            .set("vector-effect", "non-scaling-stroke")
-            .set("d", maze.to_path_d()),
-    );
-
-    svg::save(output, &document.add(container)).expect("failed to write SVG");
+    let mut walls = layer("Walls");
+    if let Some(wall_width) = wall_width {
+        walls.append(
+            svg::node::element::Path::new()
+                .set("fill", "black")
+                .set("fill-rule", "evenodd")
+                .set("stroke", "none")
+                .set("d", maze.to_outline_d(wall_width)),
+        );
+    } else {
+        walls.append(
+            svg::node::element::Path::new()
+                .set("fill", "none")
+                .set("stroke", "black")
+                .set("stroke-linecap", "round")
+                .set("stroke-linejoin", "round")
+                .set("stroke-width", 0.4)
+                .set("vector-effect", "non-scaling-stroke")
+                .set("d", maze.to_path_d()),
+        );
+    }
+    container.append(walls);
+
+    if let Some(tiling) = tiling {
+        for (column, row, page) in tiling.pages(&container, viewbox) {
+            format
+                .save(&page, Tiling::page_path(output.as_ref(), column, row))
+                .expect("failed to write output");
+        }
+    } else {
+        format
+            .save(
+                &svg::Document::new()
+                    .set("xmlns:inkscape", INKSCAPE_NAMESPACE)
+                    .set("viewBox", viewbox)
+                    .add(container),
+                output,
+            )
+            .expect("failed to write output");
+    }
+}
+
+/// Generates the output path for a maze.
+///
+/// # Arguments
+/// *  `template` - The output path template.
+/// *  `index` - The index of the maze.
+/// *  `seed` - The seed used to generate the maze, if any.
+fn output_path(template: &Path, index: usize, seed: Option<u64>) -> PathBuf {
+    template
+        .to_string_lossy()
+        .replace("{index}", &index.to_string())
+        .replace(
+            "{seed}",
+            &seed.map(|seed| seed.to_string()).unwrap_or_default(),
+        )
+        .into()
 }
 
 /// Calculates the view box for a maze with a margin.
@@ -163,52 +489,319 @@ fn maze_to_viewbox(
 
 #[allow(unused_mut)]
 fn main() {
-    let args = Arguments::parse();
+    let matches = Arguments::command().get_matches();
+    let mut args =
+        Arguments::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    // The manifest records the parameters before they are merged
+    let manifest = args.manifest.as_ref().map(|_| {
+        Manifest::new(&matches, args.config.as_deref()).unwrap_or_else(|e| {
+            Arguments::command()
+                .error(clap::error::ErrorKind::Io, e)
+                .exit()
+        })
+    });
+
+    // Values not passed on the command line are read from the configuration
+    if let Some(path) = args.config.clone() {
+        match Config::load(&path) {
+            Ok(config) => config.apply(&mut args, &matches),
+            Err(e) => Arguments::command()
+                .error(clap::error::ErrorKind::Io, e)
+                .exit(),
+        }
+    }
+    if let Some(phrase) = &args.seed_phrase {
+        args.seed = Some(maze::initialize::seed_from_phrase(phrase));
+    }
+    if args.render_only {
+        args.load = Some(STDIO.into());
+    }
+    // Mask bands determine the initialisation methods themselves
+    if args.methods.is_some()
+        && args
+            .initialize_mask
+            .as_ref()
+            .is_some_and(|mask| !mask.bands.is_empty())
+    {
+        Arguments::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--method cannot be used with a mask defining luminosity bands",
+            )
+            .exit();
+    }
+    let methods = args.methods.clone().or_else(|| {
+        if args.load.is_some() {
+            None
+        } else if args
+            .initialize_mask
+            .as_ref()
+            .is_some_and(|mask| !mask.bands.is_empty())
+        {
+            Some(Methods::default())
+        } else {
+            Arguments::command()
+                .error(
+                    clap::error::ErrorKind::MissingRequiredArgument,
+                    "the initialisation methods must be specified",
+                )
+                .exit()
+        }
+    });
+    let background_image = args
+        .render_background
+        .as_ref()
+        .and_then(BackgroundRenderer::image);
+    if args.load.is_none()
+        && (args.render_background_ratio.is_none()
+            || background_image.is_none())
+        && (args.width.is_none() || args.height.is_none())
+    {
+        Arguments::command()
+            .error(
+                clap::error::ErrorKind::MissingRequiredArgument,
+                "the width and height must be specified",
+            )
+            .exit();
+    }
+
+    // Determine the output format before doing any work
+    let format = args
+        .format
+        .or_else(|| OutputFormat::from_path(&args.output))
+        .unwrap_or(OutputFormat::Svg);
+    if !format.is_supported() {
+        Arguments::command()
+            .error(
+                clap::error::ErrorKind::ValueValidation,
+                format!("unsupported output format: {}", format),
+            )
+            .exit();
+    }
+
+    // A canvas contains only the walls, so options affecting anything else
+    // would be silently ignored
+    if format == OutputFormat::Html {
+        let conflicting = [
+            ("--background", args.render_background.is_some()),
+            ("--text", args.render_text.is_some()),
+            ("--heat-map", args.render_heatmap.is_some()),
+            ("--grid", args.render_grid.is_some()),
+            ("--solve", args.render_solve.is_some()),
+            ("--mark-endpoints", args.render_endpoints.is_some()),
+            ("--label", args.render_label.is_some()),
+            ("--tile", args.tile.is_some()),
+            ("--wall-width", args.wall_width.is_some()),
+        ];
+        if let Some((name, _)) = conflicting.iter().find(|(_, set)| *set) {
+            Arguments::command()
+                .error(
+                    clap::error::ErrorKind::ArgumentConflict,
+                    format!("{} cannot be used with the html format", name),
+                )
+                .exit();
+        }
+    }
 
     // Parse maze information
     let (width, height) = args
         .render_background_ratio
         .and_then(|render_background_ratio| {
-            println!("RENDER BACKGROUND RATIO {}", render_background_ratio);
-            args.render_background.as_ref().map(|render_background| {
+            background_image.map(|image| {
                 args.shape.minimal_dimensions(
-                    render_background.image.width() as f32
-                        / render_background_ratio,
-                    render_background.image.height() as f32
-                        / render_background_ratio,
+                    image.width() as f32 / render_background_ratio,
+                    image.height() as f32 / render_background_ratio,
                 )
             })
         })
-        .unwrap_or_else(|| (args.width.unwrap(), args.height.unwrap()));
+        .unwrap_or_else(|| {
+            (
+                args.width.unwrap_or_default(),
+                args.height.unwrap_or_default(),
+            )
+        });
+
+    if let Err(e) = methods
+        .as_ref()
+        .map_or(Ok(()), |methods| methods.check_start(width, height))
+    {
+        Arguments::command()
+            .error(clap::error::ErrorKind::ValueValidation, e)
+            .exit();
+    }
+
+    if args.tile.is_some() && args.output.as_os_str() == STDIO {
+        Arguments::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "tiled output cannot be written to standard output",
+            )
+            .exit();
+    }
+
+    // A maze to render is read before doing any work
+    let loaded = args.load.as_ref().map(|path| {
+        if args.count > 1 {
+            Arguments::command()
+                .error(
+                    clap::error::ErrorKind::ArgumentConflict,
+                    "only one maze can be rendered when loading a maze",
+                )
+                .exit();
+        }
+        load_maze(path).unwrap_or_else(|e| {
+            Arguments::command()
+                .error(clap::error::ErrorKind::Io, e)
+                .exit()
+        })
+    });
 
-    let mut rng = args
+    // Generate one seed per maze; if a seed is needed for the output path but
+    // none is given, use random seeds
+    let template = args.output.to_string_lossy();
+    if args.count > 1
+        && !template.contains("{seed}")
+        && !template.contains("{index}")
+    {
+        Arguments::command()
+            .error(
+                clap::error::ErrorKind::ValueValidation,
+                "the output path must contain {seed} or {index} when \
+                generating several mazes",
+            )
+            .exit();
+    }
+    // A manifest records only the first seed, so when recording one, every
+    // seed must be derived from it
+    let base_seed = args
         .seed
-        .map(Random::from_seed)
-        .unwrap_or_else(Random::from_os);
-
-    // Make sure the maze is initialised
-    let maze = {
-        let mut maze = args.initialize_mask.initialize(
-            args.shape.create(width, height),
-            &mut rng,
-            args.methods,
-        );
+        .or_else(|| manifest.as_ref().map(|_| rand::random()));
+    let seeds = (0..args.count as u64)
+        .map(|i| {
+            base_seed.map(|seed| seed.wrapping_add(i)).or_else(|| {
+                if args.count > 1 || template.contains("{seed}") {
+                    Some(rand::random())
+                } else {
+                    None
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let generate = |(index, seed): (usize, Option<u64>)| {
+        let mut rng =
+            seed.map(Random::from_seed).unwrap_or_else(Random::from_os);
 
-        [&args.post_break as &dyn PostProcessor<_>]
+        // Make sure the maze is initialised, unless it was loaded
+        let maze = if let Some(maze) = &loaded {
+            maze.clone()
+        } else {
+            let methods = methods.clone().unwrap();
+            let mut events = vec![];
+            let initializer = args
+                .initialize_hidden_text
+                .as_ref()
+                .map(|i| i as &dyn Initializer<_>)
+                .unwrap_or(&args.initialize_mask);
+            let mut maze = initializer.initialize(
+                args.shape.create(width, height),
+                &mut rng,
+                methods,
+                &mut |event| {
+                    if args.animate.is_some() {
+                        events.push(event);
+                    }
+                },
+            );
+
+            if let Some(animation) = &args.animate {
+                animation
+                    .save(
+                        &maze,
+                        &events,
+                        maze_to_viewbox(&maze, args.scale, args.margin),
+                        args.scale,
+                        output_path(&animation.path, index, seed),
+                    )
+                    .expect("failed to write animation");
+            }
+
+            maze
+        };
+        let maze = [&args.post_break as &dyn PostProcessor<_>]
             .iter()
-            .fold(maze, |maze, a| a.post_process(maze, &mut rng))
+            .fold(maze, |maze, a| a.post_process(maze, &mut rng));
+
+        if let Some(path) = &args.export_json {
+            let (from, to) = endpoints(&maze);
+            let solution = maze.walk(from, to).ok_or_else(|| {
+                Arguments::command().error(
+                    clap::error::ErrorKind::ValueValidation,
+                    "cannot export the maze as JSON: its endpoints are \
+                    not connected",
+                )
+            })?;
+            let path = output_path(path, index, seed);
+            std::fs::write(&path, maze::io::puzzle_json(&maze, &solution))
+                .map_err(|e| {
+                    Arguments::command().error(
+                        clap::error::ErrorKind::Io,
+                        format!("failed to write {}: {}", path.display(), e),
+                    )
+                })?;
+        }
+
+        if let Some(export) = &args.export_heatmap {
+            export
+                .save(&maze, output_path(&export.path, index, seed))
+                .expect("failed to write heat map export");
+        }
+
+        let render_label =
+            args.render_label.as_ref().map(|label| match &methods {
+                Some(methods) => label.describe(seed, methods),
+                None => label.clone(),
+            });
+
+        run(
+            maze,
+            maze::physical::Transform::scale(args.scale, args.scale).then(
+                maze::physical::Transform::rotate(args.rotate.to_radians()),
+            ),
+            args.margin,
+            args.wall_width,
+            &[
+                ("Background", &args.render_background),
+                ("Text", &args.render_text),
+                ("Heat map", &args.render_heatmap),
+                ("Grid", &args.render_grid),
+                ("Solution", &args.render_solve),
+                ("Endpoints", &args.render_endpoints),
+                ("Label", &render_label),
+            ],
+            args.page,
+            args.tile,
+            format,
+            output_path(&args.output, index, seed),
+        );
+
+        Ok(())
+    };
+
+    // Errors are reported once all running jobs have stopped
+    let result: Result<(), clap::Error> = if args.parallel {
+        seeds.into_par_iter().enumerate().try_for_each(generate)
+    } else {
+        seeds.into_iter().enumerate().try_for_each(generate)
     };
+    if let Err(e) = result {
+        e.exit();
+    }
 
-    run(
-        maze,
-        args.scale,
-        args.margin,
-        &[
-            &args.render_background,
-            &args.render_text,
-            &args.render_heatmap,
-            &args.render_solve,
-        ],
-        &args.output,
-    );
+    if let (Some(manifest), Some(path)) = (manifest, &args.manifest) {
+        manifest
+            .save(base_seed, path)
+            .expect("failed to write manifest");
+    }
 }
@@ -0,0 +1,281 @@
+//! # Run manifests
+//!
+//! A manifest records how mazes were generated, so that they can be
+//! generated again byte for byte, for example when publishing puzzles.
+//!
+//! A manifest is a configuration file containing every parameter of the run,
+//! including default values and the resolved seed, followed by a `manifest`
+//! table describing the run:
+//!
+//! ```toml
+//! walls = 4
+//! width = 40
+//! height = 30
+//! method = "winding"
+//! seed = 8771593925735063719
+//!
+//! [manifest]
+//! started = 1760689200
+//! duration = 0.12
+//!
+//! [manifest.versions]
+//! maze = "3.1.1"
+//! maze-maker = "3.1.1"
+//! ```
+//!
+//! Passing a manifest with `--config` repeats the run. The output path is not
+//! part of the manifest.
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::Arguments;
+
+/// The arguments not recorded in manifests.
+const IGNORED: &[&str] = &["CONFIG", "MANIFEST", "SEED_PHRASE"];
+
+/// A description of a run.
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Run {
+    /// The time at which the run started, in seconds since the epoch.
+    started: u64,
+
+    /// The duration of the run, in seconds.
+    duration: f64,
+
+    /// The versions of the crates generating the mazes, by name.
+    versions: BTreeMap<String, String>,
+}
+
+impl Run {
+    /// The versions of the crates generating mazes.
+    fn versions() -> BTreeMap<String, String> {
+        [
+            ("maze", maze::VERSION),
+            ("maze-maker", env!("CARGO_PKG_VERSION")),
+        ]
+        .into_iter()
+        .map(|(name, version)| (name.into(), version.into()))
+        .collect()
+    }
+
+    /// Ensures that the run can be repeated by this version.
+    pub fn check(&self) -> Result<(), String> {
+        if self.versions == Self::versions() {
+            Ok(())
+        } else {
+            Err(format!(
+                "the manifest was written by different versions: {}",
+                self.versions
+                    .iter()
+                    .map(|(name, version)| format!("{} {}", name, version))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ))
+        }
+    }
+}
+
+/// A manifest being recorded.
+pub struct Manifest {
+    /// The parameters of the run, keyed by the names of the long options.
+    parameters: toml::Table,
+
+    /// The time at which the run started.
+    started: SystemTime,
+
+    /// The instant at which the run started, used to measure its duration.
+    start: Instant,
+}
+
+impl Manifest {
+    /// Starts recording a manifest.
+    ///
+    /// Values passed on the command line take precedence over values in the
+    /// configuration file, which take precedence over default values.
+    ///
+    /// # Arguments
+    /// *  `matches` - The matches of the command line.
+    /// *  `config` - The path of the configuration file, if any.
+    pub fn new(
+        matches: &ArgMatches,
+        config: Option<&Path>,
+    ) -> Result<Self, String> {
+        let mut configured = config
+            .map(|path| {
+                std::fs::read_to_string(path)
+                    .map_err(|e| {
+                        format!("failed to read {}: {}", path.display(), e)
+                    })
+                    .and_then(|data| {
+                        toml::from_str::<toml::Table>(&data)
+                            .map_err(|e| e.to_string())
+                    })
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let mut parameters = toml::Table::new();
+        for argument in Arguments::command().get_arguments() {
+            let id = argument.get_id().as_str();
+            let Some(name) = argument.get_long() else {
+                continue;
+            };
+            if IGNORED.contains(&id) {
+                continue;
+            }
+            let source = matches.value_source(id);
+            let value = if source == Some(ValueSource::CommandLine) {
+                raw(matches, id).map(|raw| value(name, &raw))
+            } else if let Some(value) = configured.remove(name) {
+                Some(value)
+            } else if source == Some(ValueSource::DefaultValue) {
+                raw(matches, id).map(|raw| value(name, &raw))
+            } else {
+                None
+            };
+            if let Some(value) = value {
+                parameters.insert(name.into(), value);
+            }
+        }
+
+        Ok(Self {
+            parameters,
+            started: SystemTime::now(),
+            start: Instant::now(),
+        })
+    }
+
+    /// Finishes recording this manifest and writes it.
+    ///
+    /// # Arguments
+    /// *  `seed` - The resolved seed of the first maze.
+    /// *  `path` - The path of the manifest.
+    pub fn save(
+        mut self,
+        seed: Option<u64>,
+        path: &Path,
+    ) -> Result<(), String> {
+        if let Some(seed) = seed {
+            // Seeds are unsigned, but TOML integers are signed
+            self.parameters.insert(
+                "seed".into(),
+                i64::try_from(seed)
+                    .map(toml::Value::Integer)
+                    .unwrap_or_else(|_| toml::Value::String(seed.to_string())),
+            );
+        }
+        let run = Run {
+            started: self
+                .started
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+            duration: self.start.elapsed().as_secs_f64(),
+            versions: Run::versions(),
+        };
+        self.parameters.insert(
+            "manifest".into(),
+            toml::Value::try_from(run).map_err(|e| e.to_string())?,
+        );
+
+        let data =
+            toml::to_string(&self.parameters).map_err(|e| e.to_string())?;
+        std::fs::write(path, data)
+            .map_err(|e| format!("failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// The raw value of an argument.
+///
+/// # Arguments
+/// *  `matches` - The matches of the command line.
+/// *  `id` - The ID of the argument.
+fn raw(matches: &ArgMatches, id: &str) -> Option<String> {
+    matches
+        .get_raw(id)
+        .and_then(|mut values| values.next())
+        .map(|value| value.to_string_lossy().into_owned())
+}
+
+/// Converts the raw value of an argument to a configuration value.
+///
+/// Numbers and booleans are used if the configuration accepts them for the
+/// argument, otherwise the value is kept as a string.
+///
+/// # Arguments
+/// *  `name` - The name of the argument.
+/// *  `raw` - The raw value.
+fn value(name: &str, raw: &str) -> toml::Value {
+    let candidates = [
+        raw.parse().ok().map(toml::Value::Integer),
+        raw.parse().ok().map(toml::Value::Float),
+        raw.parse().ok().map(toml::Value::Boolean),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .find(|candidate| {
+            let mut table = toml::Table::new();
+            table.insert(name.into(), candidate.clone());
+            table.try_into::<Config>().is_ok()
+        })
+        .unwrap_or_else(|| toml::Value::String(raw.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::FromArgMatches;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("maze-maker-manifest-{}.toml", std::process::id()));
+        let matches = Arguments::command().get_matches_from([
+            "maze-maker",
+            "--walls",
+            "6",
+            "--width",
+            "12",
+            "--height",
+            "8",
+            "--method",
+            "winding",
+            "--count",
+            "3",
+            "--manifest",
+            path.to_str().unwrap(),
+            "maze-{index}.svg",
+        ]);
+        Manifest::new(&matches, None)
+            .unwrap()
+            .save(Some(u64::MAX), &path)
+            .unwrap();
+
+        let matches = Arguments::command().get_matches_from([
+            "maze-maker",
+            "--config",
+            path.to_str().unwrap(),
+            "replayed-{index}.svg",
+        ]);
+        let mut args = Arguments::from_arg_matches(&matches).unwrap();
+        let config = Config::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        config.unwrap().apply(&mut args, &matches);
+
+        assert_eq!(maze::Shape::Hex, args.shape);
+        assert_eq!(Some(12), args.width);
+        assert_eq!(Some(8), args.height);
+        assert_eq!(3, args.count);
+        assert_eq!(Some(u64::MAX), args.seed);
+        assert_eq!(None, args.manifest);
+    }
+}
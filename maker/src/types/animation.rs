@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use svg::Node;
+
+use maze::initialize;
+use maze::WallPos;
+
+use crate::types::*;
+
+/// An animation of a maze being initialised.
+#[derive(Clone)]
+pub struct Animation {
+    /// The output path of the animation.
+    pub path: PathBuf,
+
+    /// The duration of the animation, in seconds.
+    pub duration: f32,
+}
+
+impl FromStr for Animation {
+    type Err = String;
+
+    /// Converts a string to an animation description.
+    ///
+    /// The string can be on two forms:
+    /// 1. `path`: If only a path is passed, the `duration` will be `10`.
+    /// 2. `path,duration`: If a duration is passed, it will be used as
+    ///    `duration`.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut parts = s.split(',').map(str::trim);
+        let path = parts.next().unwrap().into();
+
+        if let Some(part1) = parts.next() {
+            match part1.parse() {
+                Ok(duration) if duration > 0.0 => Ok(Self { path, duration }),
+                _ => Err(format!("invalid duration: {}", part1)),
+            }
+        } else {
+            Ok(Self {
+                path,
+                duration: 10.0,
+            })
+        }
+    }
+}
+
+impl Animation {
+    /// Writes an animation of a maze being initialised.
+    ///
+    /// Every wall is drawn as a separate line whose visibility is animated,
+    /// so that the walls disappear in the order they were opened. The
+    /// animation is an _SVG_ file using _SMIL_ animations.
+    ///
+    /// # Arguments
+    /// *  `maze` - The initialised maze.
+    /// *  `events` - The events reported while initialising the maze.
+    /// *  `viewbox` - The view box of the animation.
+    /// *  `scale` - A scale multiplier.
+    /// *  `path` - The output path.
+    pub fn save<P>(
+        &self,
+        maze: &Maze,
+        events: &[initialize::Event],
+        viewbox: (f32, f32, f32, f32),
+        scale: f32,
+        path: P,
+    ) -> Result<(), String>
+    where
+        P: AsRef<Path>,
+    {
+        // Collect the times at which each wall changes, using the same wall
+        // position for both sides of a wall
+        let modifications = events
+            .iter()
+            .filter_map(|event| match *event {
+                initialize::Event::Opened(wall_pos) => Some((wall_pos, true)),
+                initialize::Event::Closed(wall_pos) => Some((wall_pos, false)),
+                initialize::Event::Visited(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let mut changes = HashMap::<WallPos, Vec<(f32, bool)>>::new();
+        for (i, &(wall_pos, open)) in modifications.iter().enumerate() {
+            let time = (i + 1) as f32 / modifications.len() as f32;
+            changes
+                .entry(canonical(maze, wall_pos))
+                .or_default()
+                .push((time, open));
+        }
+
+        let mut container = svg::node::element::Group::new()
+            .set("transform", format!("scale({})", scale))
+            .set("fill", "none")
+            .set("stroke", "black")
+            .set("stroke-linecap", "round")
+            .set("stroke-width", 0.4)
+            .set("vector-effect", "non-scaling-stroke");
+
+        for (wall_pos, open) in maze.walls_iter() {
+            let (from, to) = maze.corners(wall_pos);
+            let mut line = svg::node::element::Line::new()
+                .set("x1", from.x)
+                .set("y1", from.y)
+                .set("x2", to.x)
+                .set("y2", to.y);
+
+            if let Some(changes) = changes.get(&wall_pos) {
+                line.append(self.visibility(changes));
+            } else if open || !visited(maze, wall_pos) {
+                continue;
+            }
+
+            container.append(line);
+        }
+
+        svg::save(
+            path,
+            &svg::Document::new().set("viewBox", viewbox).add(container),
+        )
+        .map_err(|e| format!("failed to write animation: {}", e))
+    }
+
+    /// Animates the visibility of a wall.
+    ///
+    /// # Arguments
+    /// *  `changes` - The relative times at which the wall was modified, and
+    ///    whether it was opened.
+    fn visibility(
+        &self,
+        changes: &[(f32, bool)],
+    ) -> svg::node::element::Animate {
+        let state = |open| if open { "hidden" } else { "visible" };
+        let values = std::iter::once("visible")
+            .chain(changes.iter().map(|&(_, open)| state(open)))
+            .collect::<Vec<_>>()
+            .join(";");
+        let key_times = std::iter::once(0.0)
+            .chain(changes.iter().map(|&(time, _)| time))
+            .map(|time| time.to_string())
+            .collect::<Vec<_>>()
+            .join(";");
+
+        svg::node::element::Animate::new()
+            .set("attributeName", "visibility")
+            .set("calcMode", "discrete")
+            .set("values", values)
+            .set("keyTimes", key_times)
+            .set("dur", format!("{}s", self.duration))
+            .set("fill", "freeze")
+    }
+}
+
+/// Whether either room on the sides of a wall has been visited.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+/// *  `wall_pos` - A wall position.
+fn visited(maze: &Maze, wall_pos: WallPos) -> bool {
+    let back = maze.back(wall_pos);
+    maze[wall_pos.0].visited || (maze.is_inside(back.0) && maze[back.0].visited)
+}
+
+/// Selects one side of a wall to represent both sides.
+///
+/// This is the side visited by [`maze::Maze::walls_iter`].
+///
+/// # Arguments
+/// *  `maze` - The maze.
+/// *  `wall_pos` - A wall position.
+fn canonical(maze: &Maze, wall_pos: WallPos) -> WallPos {
+    let back = maze.back(wall_pos);
+    if maze.is_inside(back.0)
+        && (back.0.row, back.0.col) < (wall_pos.0.row, wall_pos.0.col)
+    {
+        back
+    } else {
+        wall_pos
+    }
+}
@@ -3,17 +3,50 @@ use std::str::FromStr;
 
 use svg::Node;
 
+use maze::matrix;
 use maze::physical;
+use maze::render::svg::fill_rooms;
+use maze::render::Color;
 use maze_tools::cell::*;
-use maze_tools::image::Color;
 
 use crate::types::*;
 
-/// A background image.
+/// The threshold map used for ordered dithering.
+const BAYER: [[u8; 4]; 4] =
+    [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// The number of noise cells along the longest side of a maze.
+const NOISE_SCALE: f32 = 4.0;
+
+/// The number of octaves of noise to combine.
+const NOISE_OCTAVES: u32 = 4;
+
+/// The source of background colours.
+#[derive(Clone)]
+pub enum Source {
+    /// An image, which is stretched to cover the maze.
+    Image(image::RgbImage),
+
+    /// Greyscale procedural noise with a seed.
+    Noise(u64),
+
+    /// A horizontal gradient through a list of colours.
+    Gradient(Vec<Color>),
+}
+
+/// A background.
 #[derive(Clone)]
 pub struct BackgroundRenderer {
-    /// The background image.
-    pub image: image::RgbImage,
+    /// The source of background colours.
+    pub source: Source,
+
+    /// The colours to which to quantise room colours.
+    ///
+    /// If this is empty, room colours are not quantised.
+    pub palette: Vec<Color>,
+
+    /// Whether to apply ordered dithering before quantising room colours.
+    pub dither: bool,
 }
 
 impl FromStr for BackgroundRenderer {
@@ -21,47 +54,293 @@ impl FromStr for BackgroundRenderer {
 
     /// Converts a string to a background description.
     ///
-    /// The string must be a path.
+    /// The string is on the form `source,colour,...,dither`, where both the
+    /// colours and `dither` are optional. If colours are passed, they are
+    /// used as `palette`, and if `dither` is passed, `dither` will be `true`.
+    ///
+    /// The source is either `noise:seed`, `gradient:colour..colour`, with
+    /// any number of colours, or the path of an image.
     fn from_str(s: &str) -> Result<Self, String> {
+        let mut parts = s.split(',').map(str::trim);
+        let source = parts.next().map(Source::from_str).unwrap()?;
+
+        let mut palette = vec![];
+        let mut dither = false;
+        for part in parts {
+            if part == "dither" {
+                dither = true;
+            } else {
+                palette.push(Color::from_str(part)?);
+            }
+        }
+        if dither && palette.len() < 2 {
+            return Err("dithering requires at least two colours".into());
+        }
+
         Ok(Self {
-            image: image::open(s)
-                .map_err(|_| format!("failed to open {}", s))?
-                .to_rgb8(),
+            source,
+            palette,
+            dither,
         })
     }
 }
 
+impl FromStr for Source {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        if let Some(seed) = s.strip_prefix("noise:") {
+            seed.parse()
+                .map(Source::Noise)
+                .map_err(|_| format!("invalid noise seed: {}", seed))
+        } else if let Some(stops) = s.strip_prefix("gradient:") {
+            let stops = stops
+                .split("..")
+                .map(Color::from_str)
+                .collect::<Result<Vec<_>, _>>()?;
+            if stops.len() < 2 {
+                Err("a gradient requires at least two colours".into())
+            } else {
+                Ok(Source::Gradient(stops))
+            }
+        } else {
+            Ok(Source::Image(
+                image::open(s)
+                    .map_err(|_| format!("failed to open {}", s))?
+                    .to_rgb8(),
+            ))
+        }
+    }
+}
+
+impl BackgroundRenderer {
+    /// The background image, unless the background is procedural.
+    pub fn image(&self) -> Option<&image::RgbImage> {
+        match &self.source {
+            Source::Image(image) => Some(image),
+            _ => None,
+        }
+    }
+
+    /// The colour of every room, before quantisation.
+    ///
+    /// Images are sampled over the area of every room, while procedural
+    /// backgrounds are sampled at the centre of every room.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    fn colors(&self, maze: &Maze) -> matrix::Matrix<Color> {
+        let physical::ViewBox {
+            corner,
+            width,
+            height,
+        } = maze.viewbox();
+        let relative = |pos| {
+            let physical::Pos { x, y } = maze.center(pos);
+            ((x - corner.x) / width, (y - corner.y) / height)
+        };
+
+        match &self.source {
+            Source::Image(image) => {
+                let (cols, rows) = image.dimensions();
+                sample(maze, cols, rows, |x, y| {
+                    Intermediate::from(image.get_pixel(x, y))
+                })
+            }
+            Source::Noise(seed) => {
+                let size = width.max(height) / NOISE_SCALE;
+                matrix::Matrix::new_with_data(
+                    maze.width(),
+                    maze.height(),
+                    |pos| {
+                        let (u, v) = relative(pos);
+                        let value =
+                            noise(*seed, u * width / size, v * height / size);
+                        let grey =
+                            (127.5 * (1.0 + value.clamp(-1.0, 1.0))) as u8;
+                        Color {
+                            red: grey,
+                            green: grey,
+                            blue: grey,
+                            alpha: 255,
+                        }
+                    },
+                )
+            }
+            Source::Gradient(stops) => matrix::Matrix::new_with_data(
+                maze.width(),
+                maze.height(),
+                |pos| Color::gradient(stops, relative(pos).0),
+            ),
+        }
+    }
+
+    /// Maps the average colour of a room to its final colour.
+    ///
+    /// If a palette is set, the nearest colour of the palette is returned,
+    /// optionally after applying an offset from an ordered dithering
+    /// threshold map.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    /// *  `color` - The average colour of the room.
+    /// *  `spread` - The amplitude of dithering.
+    fn quantize(&self, pos: matrix::Pos, color: Color, spread: f32) -> Color {
+        if self.palette.is_empty() {
+            return color;
+        }
+
+        let offset = if self.dither {
+            let threshold = BAYER[pos.row.rem_euclid(4) as usize]
+                [pos.col.rem_euclid(4) as usize];
+            (f32::from(threshold) + 0.5) / 16.0 - 0.5
+        } else {
+            0.0
+        };
+        let target = [color.red, color.green, color.blue]
+            .map(|c| f32::from(c) + offset * spread);
+
+        self.palette
+            .iter()
+            .copied()
+            .min_by(|&a, &b| {
+                distance(a, target).total_cmp(&distance(b, target))
+            })
+            .unwrap()
+    }
+
+    /// The typical distance between colours of the palette.
+    ///
+    /// This is the average distance from each colour to its nearest
+    /// neighbour, and is used as the amplitude of dithering.
+    fn spread(&self) -> f32 {
+        self.palette
+            .iter()
+            .map(|&a| {
+                let target = [a.red, a.green, a.blue].map(f32::from);
+                self.palette
+                    .iter()
+                    .map(|&b| distance(b, target))
+                    .filter(|&d| d > 0.0)
+                    .fold(f32::MAX, f32::min)
+            })
+            .filter(|&d| d < f32::MAX)
+            .sum::<f32>()
+            / self.palette.len() as f32
+    }
+}
+
+/// Fractal gradient noise.
+///
+/// This is a sum of octaves of _Perlin_ noise, where every octave has twice
+/// the frequency and half the amplitude of the previous one. The result is
+/// roughly in the range _[-1, 1]_.
+///
+/// # Arguments
+/// *  `seed` - The seed of the noise.
+/// *  `x` - The horizontal coordinate, in noise cells.
+/// *  `y` - The vertical coordinate, in noise cells.
+fn noise(seed: u64, x: f32, y: f32) -> f32 {
+    let (sum, total) =
+        (0..NOISE_OCTAVES).fold((0.0, 0.0), |(sum, total), octave| {
+            let frequency = (1 << octave) as f32;
+            let amplitude = 1.0 / frequency;
+            (
+                sum + amplitude
+                    * perlin(
+                        seed.wrapping_add(u64::from(octave)),
+                        x * frequency,
+                        y * frequency,
+                    ),
+                total + amplitude,
+            )
+        });
+    sum / total
+}
+
+/// A single octave of _Perlin_ noise.
+///
+/// The result is in the range _[-1, 1]_.
+///
+/// # Arguments
+/// *  `seed` - The seed of the noise.
+/// *  `x` - The horizontal coordinate, in noise cells.
+/// *  `y` - The vertical coordinate, in noise cells.
+fn perlin(seed: u64, x: f32, y: f32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let corner = |dx: f32, dy: f32| {
+        let (gx, gy) = gradient(seed, (x0 + dx) as i64, (y0 + dy) as i64);
+        gx * (fx - dx) + gy * (fy - dy)
+    };
+    let fade = |t: f32| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+    let (sx, sy) = (fade(fx), fade(fy));
+    std::f32::consts::SQRT_2
+        * lerp(
+            lerp(corner(0.0, 0.0), corner(1.0, 0.0), sx),
+            lerp(corner(0.0, 1.0), corner(1.0, 1.0), sx),
+            sy,
+        )
+}
+
+/// The pseudo-random unit gradient at a lattice point.
+///
+/// # Arguments
+/// *  `seed` - The seed of the noise.
+/// *  `x` - The horizontal coordinate of the lattice point.
+/// *  `y` - The vertical coordinate of the lattice point.
+fn gradient(seed: u64, x: i64, y: i64) -> (f32, f32) {
+    // The finaliser of SplitMix64
+    let mut hash = seed
+        ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    hash ^= hash >> 31;
+
+    let angle =
+        (hash >> 40) as f32 / (1u64 << 24) as f32 * std::f32::consts::TAU;
+    (angle.cos(), angle.sin())
+}
+
+/// The distance between a colour and a target colour in _RGB_ space.
+///
+/// # Arguments
+/// *  `color` - The colour.
+/// *  `target` - The red, green and blue components of the target.
+fn distance(color: Color, target: [f32; 3]) -> f32 {
+    [color.red, color.green, color.blue]
+        .iter()
+        .zip(target)
+        .map(|(&c, t)| (f32::from(c) - t).powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
+
 impl Renderer for BackgroundRenderer {
     /// Applies the background action.
     ///
-    /// This action will use an image to sample the background colour of rooms.
+    /// This action will use an image or a procedural source to sample the
+    /// background colour of rooms, and optionally quantise the colours to a
+    /// palette.
     ///
     /// # Arguments
     /// *  `maze` - The maze.
     /// *  `group` - The group to which to add the rooms.
     fn render(&self, maze: &Maze, group: &mut svg::node::element::Group) {
-        let physical::ViewBox { width, height, .. } = maze.viewbox();
-        let (cols, rows) = self.image.dimensions();
-        let data = self
-            .image
-            .enumerate_pixels()
-            .map(|(x, y, pixel)| {
-                (
-                    physical::Pos {
-                        x: width * (x as f32 / cols as f32),
-                        y: height * (y as f32 / rows as f32),
-                    },
-                    Intermediate::from(pixel),
-                )
-            })
-            .split_by(&maze.shape(), maze.width(), maze.height());
+        let data = self.colors(maze);
 
-        group.append(draw_rooms(maze, |pos| data[pos]));
+        let spread = self.spread();
+        group.append(fill_rooms(maze, |pos| {
+            self.quantize(pos, data[pos], spread)
+        }));
     }
 }
 
 #[derive(Clone, Copy, Default)]
-struct Intermediate(u32, u32, u32);
+struct Intermediate(f32, f32, f32);
 
 impl<'a, P> From<&'a P> for Intermediate
 where
@@ -70,9 +349,9 @@ where
     fn from(source: &'a P) -> Self {
         let channels = source.channels();
         Intermediate(
-            u32::from(channels[0]),
-            u32::from(channels[1]),
-            u32::from(channels[2]),
+            f32::from(channels[0]),
+            f32::from(channels[1]),
+            f32::from(channels[2]),
         )
     }
 }
@@ -85,15 +364,108 @@ impl ops::Add<Intermediate> for Intermediate {
     }
 }
 
-impl ops::Div<usize> for Intermediate {
+impl ops::Mul<f32> for Intermediate {
+    type Output = Self;
+
+    fn mul(self, factor: f32) -> Self {
+        Intermediate(self.0 * factor, self.1 * factor, self.2 * factor)
+    }
+}
+
+impl ops::Div<f32> for Intermediate {
     type Output = Color;
 
-    fn div(self, divisor: usize) -> Self::Output {
+    fn div(self, divisor: f32) -> Self::Output {
         Color {
-            red: (self.0 / divisor as u32) as u8,
-            green: (self.1 / divisor as u32) as u8,
-            blue: (self.2 / divisor as u32) as u8,
+            red: (self.0 / divisor) as u8,
+            green: (self.1 / divisor) as u8,
+            blue: (self.2 / divisor) as u8,
             alpha: 255,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Describes a source in a comparable form.
+    ///
+    /// # Arguments
+    /// *  `source` - The source to describe.
+    fn describe(source: &Source) -> String {
+        match source {
+            Source::Image(image) => {
+                format!("image {}x{}", image.width(), image.height())
+            }
+            Source::Noise(seed) => format!("noise {}", seed),
+            Source::Gradient(stops) => format!("gradient {:?}", stops),
+        }
+    }
+
+    #[test]
+    fn from_str() {
+        let path = std::env::temp_dir()
+            .join(format!("maze-maker-background-{}.png", std::process::id(),));
+        image::RgbImage::new(3, 2).save(&path).unwrap();
+        let image = path.to_str().unwrap();
+
+        let black = Color::from_str("#000000").unwrap();
+        let white = Color::from_str("#FFFFFF").unwrap();
+        for (s, expected) in [
+            (
+                String::from("noise:42"),
+                Ok((String::from("noise 42"), vec![], false)),
+            ),
+            (
+                String::from("gradient:#000000..#FFFFFF, #000000"),
+                Ok((
+                    describe(&Source::Gradient(vec![black, white])),
+                    vec![black],
+                    false,
+                )),
+            ),
+            (
+                format!("{}, #000000, #FFFFFF, dither", image),
+                Ok((String::from("image 3x2"), vec![black, white], true)),
+            ),
+            (
+                String::from("noise:seed"),
+                Err(String::from("invalid noise seed: seed")),
+            ),
+            (
+                String::from("gradient:#000000"),
+                Err(String::from("a gradient requires at least two colours")),
+            ),
+            (
+                String::from("gradient:#000000..black"),
+                Err(String::from("unknown colour value: black")),
+            ),
+            (
+                String::from("noise:1,#000000,dither"),
+                Err(String::from("dithering requires at least two colours")),
+            ),
+            (
+                String::from("noise:1,grey"),
+                Err(String::from("unknown colour value: grey")),
+            ),
+            (
+                String::from("missing.png"),
+                Err(String::from("failed to open missing.png")),
+            ),
+        ] {
+            assert_eq!(
+                expected,
+                s.parse::<BackgroundRenderer>().map(|renderer| (
+                    describe(&renderer.source),
+                    renderer.palette,
+                    renderer.dither,
+                )),
+                "{}",
+                s,
+            );
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
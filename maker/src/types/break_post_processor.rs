@@ -1,6 +1,7 @@
 use std::str::FromStr;
 
 use maze::initialize;
+use maze_tools::mask;
 
 use crate::types::*;
 
@@ -12,6 +13,10 @@ pub struct BreakPostProcessor {
 
     /// The number of times to apply the operation.
     pub count: usize,
+
+    /// An image whose intensity is the probability of breaking walls in the
+    /// rooms it covers.
+    pub weights: Option<image::RgbImage>,
 }
 
 impl FromStr for BreakPostProcessor {
@@ -19,23 +24,35 @@ impl FromStr for BreakPostProcessor {
 
     /// Converts a string to a break description.
     ///
-    /// The string can be on two forms:
+    /// The string can be on three forms:
     /// 1. `map_type`: If only a value that can be made into a
     ///    [`HeatMapType`](HeatMapType) is passed, the `count` will be `1`.
     /// 2. `map_type,count`: If a count is passed, it will be used as `count`.
+    /// 3. `map_type,count,path`: If a path is passed, the image will be used
+    ///    as `weights`.
     fn from_str(s: &str) -> Result<Self, String> {
         let mut parts = s.split(',').map(str::trim);
         let map_type = parts.next().map(HeatMapType::from_str).unwrap()?;
+        let count = parts
+            .next()
+            .map(|part| {
+                part.parse().map_err(|_| format!("invalid count: {}", part))
+            })
+            .unwrap_or(Ok(1))?;
+        let weights = parts
+            .next()
+            .map(|path| {
+                image::open(path)
+                    .map(|image| image.to_rgb8())
+                    .map_err(|_| format!("failed to open {}", path))
+            })
+            .transpose()?;
 
-        if let Some(part1) = parts.next() {
-            if let Ok(count) = part1.parse() {
-                Ok(Self { map_type, count })
-            } else {
-                Err(format!("invalid count: {}", part1))
-            }
-        } else {
-            Ok(Self { map_type, count: 1 })
-        }
+        Ok(Self {
+            map_type,
+            count,
+            weights,
+        })
     }
 }
 
@@ -48,14 +65,45 @@ where
     /// This action will repeatedly calculate a heat map, and then open walls in
     /// rooms with higher probability in hot rooms.
     ///
+    /// If weights are used, rooms are only considered with the probability
+    /// given by their weight, and walls are chosen with a probability
+    /// proportional to the average weight of the rooms on either side.
+    ///
     /// # Arguments
     /// *  `maze` - The maze.
     /// *  `rng` - A random number generator.
     fn post_process(&self, mut maze: Maze, rng: &mut R) -> Maze {
+        let weights = self
+            .weights
+            .as_ref()
+            .map(|image| mask::intensities(image, &maze));
+
         for _ in 0..self.count {
-            let heat_map = self.map_type.generate(&maze);
+            let heat_map = heat_map(self.map_type, &maze);
             for pos in heat_map.positions() {
-                if 1.0 / (rng.random() * f64::from(heat_map[pos])) < 0.5 {
+                if 1.0 / (rng.random() * f64::from(heat_map[pos])) >= 0.5 {
+                    continue;
+                }
+
+                if let Some(weights) = &weights {
+                    if rng.random() >= f64::from(weights[pos]) {
+                        continue;
+                    }
+                    let candidates = maze
+                        .walls(pos)
+                        .iter()
+                        .map(|&wall| (pos, wall))
+                        .filter_map(|wall_pos| {
+                            let back = maze.back(wall_pos).0;
+                            maze.is_inside(back).then(|| {
+                                (wall_pos, 0.5 * (weights[pos] + weights[back]))
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    if let Some(wall_pos) = choose(&candidates, rng) {
+                        maze.open(wall_pos);
+                    }
+                } else {
                     loop {
                         let walls = maze.walls(pos);
                         let wall = walls[rng.range(0, walls.len())];
@@ -71,3 +119,36 @@ where
         maze
     }
 }
+
+/// Chooses a wall with a probability proportional to its weight.
+///
+/// If all weights are zero, the wall is chosen uniformly. If there are no
+/// walls, nothing is returned.
+///
+/// # Arguments
+/// *  `candidates` - The walls and their weights.
+/// *  `rng` - A random number generator.
+fn choose<R>(
+    candidates: &[(maze::WallPos, f32)],
+    rng: &mut R,
+) -> Option<maze::WallPos>
+where
+    R: initialize::Randomizer + Sized,
+{
+    let total = candidates.iter().map(|&(_, weight)| weight).sum::<f32>();
+    if candidates.is_empty() {
+        None
+    } else if total <= 0.0 {
+        Some(candidates[rng.range(0, candidates.len())].0)
+    } else {
+        let mut target = rng.random() as f32 * total;
+        candidates
+            .iter()
+            .find(|&&(_, weight)| {
+                target -= weight;
+                target < 0.0
+            })
+            .or(candidates.last())
+            .map(|&(wall_pos, _)| wall_pos)
+    }
+}
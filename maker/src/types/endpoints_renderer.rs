@@ -0,0 +1,272 @@
+use std::str::FromStr;
+
+use svg::Node;
+
+use maze::physical;
+use maze::render::Color;
+
+use crate::types::*;
+
+/// The size of markers, relative to the distance between the centre and the
+/// corners of rooms.
+const MARKER_SIZE: f32 = 0.6;
+
+/// A marker shape.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Marker {
+    /// A filled circle.
+    Circle,
+
+    /// An arrow pointing in the direction of the solution.
+    Arrow,
+
+    /// A five-pointed star.
+    Star,
+}
+
+impl FromStr for Marker {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "circle" => Ok(Marker::Circle),
+            "arrow" => Ok(Marker::Arrow),
+            "star" => Ok(Marker::Star),
+            _ => Err(format!("unknown marker: {}", s)),
+        }
+    }
+}
+
+impl Marker {
+    /// Draws this marker.
+    ///
+    /// # Arguments
+    /// *  `center` - The centre of the marker.
+    /// *  `radius` - The radius of the marker.
+    /// *  `direction` - The unit direction in which the marker points.
+    /// *  `color` - The colour of the marker.
+    fn draw(
+        self,
+        center: physical::Pos,
+        radius: f32,
+        direction: physical::Pos,
+        color: Color,
+    ) -> svg::node::element::Path {
+        let normal = physical::Pos {
+            x: -direction.y,
+            y: direction.x,
+        };
+        let points = match self {
+            Marker::Circle => (0..32)
+                .map(|i| {
+                    let angle = 2.0 * std::f32::consts::PI * i as f32 / 32.0;
+                    center
+                        + physical::Pos {
+                            x: angle.cos() * radius,
+                            y: angle.sin() * radius,
+                        }
+                })
+                .collect::<Vec<_>>(),
+            Marker::Arrow => vec![
+                center + direction * radius,
+                center - direction * radius + normal * (0.8 * radius),
+                center - direction * (0.4 * radius),
+                center - direction * radius - normal * (0.8 * radius),
+            ],
+            Marker::Star => (0..10)
+                .map(|i| {
+                    let angle = std::f32::consts::PI * (i as f32 / 5.0 - 0.5);
+                    let r = if i % 2 == 0 { radius } else { 0.4 * radius };
+                    center
+                        + physical::Pos {
+                            x: angle.cos() * r,
+                            y: angle.sin() * r,
+                        }
+                })
+                .collect::<Vec<_>>(),
+        };
+
+        let mut data = svg::node::element::path::Data::new()
+            .move_to((points[0].x, points[0].y));
+        for point in &points[1..] {
+            data = data.line_to((point.x, point.y));
+        }
+
+        svg::node::element::Path::new()
+            .set("fill", color.to_string())
+            .set("fill-opacity", f32::from(color.alpha) / 255.0)
+            .set("stroke", "none")
+            .set("d", data.close())
+    }
+}
+
+/// Markers for the endpoints of the solution.
+#[derive(Clone)]
+pub struct EndpointsRenderer {
+    /// The marker shape.
+    pub marker: Marker,
+
+    /// The colour of the start marker.
+    pub start: Color,
+
+    /// The colour of the finish marker.
+    pub finish: Color,
+}
+
+impl FromStr for EndpointsRenderer {
+    type Err = String;
+
+    /// Converts a string to an endpoint marker description.
+    ///
+    /// The string can be on three forms:
+    /// 1. `marker`: If only a value that can be made into a
+    ///    [`Marker`](Marker) is passed, the `start` and `finish` values will
+    ///    be `#008000FF` and `#FF0000FF`.
+    /// 2. `marker,colour`: If only one colour is passed, it is used for both
+    ///    `start` and `finish`.
+    /// 3. `marker,start,finish`: If two colours are passed, they are used as
+    ///    `start` and `finish` values.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut parts = s.split(',').map(str::trim);
+        let marker = parts.next().map(Marker::from_str).unwrap()?;
+
+        if let Some(part1) = parts.next() {
+            let start = Color::from_str(part1)?;
+            let finish =
+                parts.next().map(Color::from_str).unwrap_or(Ok(start))?;
+            Ok(Self {
+                marker,
+                start,
+                finish,
+            })
+        } else {
+            Ok(Self {
+                marker,
+                start: Color {
+                    red: 0,
+                    green: 128,
+                    blue: 0,
+                    alpha: 255,
+                },
+                finish: Color {
+                    red: 255,
+                    green: 0,
+                    blue: 0,
+                    alpha: 255,
+                },
+            })
+        }
+    }
+}
+
+impl Renderer for EndpointsRenderer {
+    /// Renders markers for the endpoints of the solution.
+    ///
+    /// Arrows point along the solution, or towards the finish if the maze
+    /// has no solution.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `group` - The group to which to add the markers.
+    fn render(&self, maze: &Maze, group: &mut svg::node::element::Group) {
+        let (from, to) = endpoints(maze);
+        let unit = |v: physical::Pos| {
+            let length = v.value().sqrt();
+            if length > 0.0 {
+                v * (1.0 / length)
+            } else {
+                physical::Pos { x: 1.0, y: 0.0 }
+            }
+        };
+        let (start_direction, finish_direction) = maze
+            .walk(from, to)
+            .map(|path| path.into_iter().collect::<Vec<_>>())
+            .filter(|positions| positions.len() > 1)
+            .map(|positions| {
+                let n = positions.len();
+                (
+                    maze.center(positions[1]) - maze.center(positions[0]),
+                    maze.center(positions[n - 1])
+                        - maze.center(positions[n - 2]),
+                )
+            })
+            .unwrap_or_else(|| {
+                let direction = maze.center(to) - maze.center(from);
+                (direction, direction)
+            });
+
+        for (pos, direction, color) in [
+            (from, start_direction, self.start),
+            (to, finish_direction, self.finish),
+        ] {
+            let center = maze.center(pos);
+            let radius = MARKER_SIZE
+                * (maze.corners((pos, maze.walls(pos)[0])).0 - center)
+                    .value()
+                    .sqrt();
+            group.append(self.marker.draw(
+                center,
+                radius,
+                unit(direction),
+                color,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marker_from_str() {
+        for (s, expected) in [
+            ("circle", Ok(Marker::Circle)),
+            ("arrow", Ok(Marker::Arrow)),
+            ("star", Ok(Marker::Star)),
+            ("dot", Err(String::from("unknown marker: dot"))),
+        ] {
+            assert_eq!(expected, s.parse::<Marker>(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn from_str() {
+        let color = |s: &str| s.parse::<Color>().unwrap();
+        for (s, expected) in [
+            (
+                "circle",
+                Ok((Marker::Circle, color("#008000FF"), color("#FF0000FF"))),
+            ),
+            (
+                "arrow,#0000FF",
+                Ok((Marker::Arrow, color("#0000FF"), color("#0000FF"))),
+            ),
+            (
+                "star, #000000, #FFFFFF",
+                Ok((Marker::Star, color("#000000"), color("#FFFFFF"))),
+            ),
+            ("", Err(String::from("unknown marker: "))),
+            ("#000000", Err(String::from("unknown marker: #000000"))),
+            (
+                "arrow,blue",
+                Err(String::from("unknown colour value: blue")),
+            ),
+            (
+                "arrow,#000000,white",
+                Err(String::from("unknown colour value: white")),
+            ),
+        ] {
+            assert_eq!(
+                expected,
+                s.parse::<EndpointsRenderer>().map(|renderer| (
+                    renderer.marker,
+                    renderer.start,
+                    renderer.finish,
+                )),
+                "{}",
+                s,
+            );
+        }
+    }
+}
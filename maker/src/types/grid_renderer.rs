@@ -0,0 +1,49 @@
+use svg::Node;
+
+use crate::types::*;
+
+/// The cell lattice underlying the maze.
+#[derive(Clone)]
+pub struct GridRenderer {
+    /// The colour of the grid lines.
+    color: String,
+}
+
+impl FromStr for GridRenderer {
+    type Err = String;
+
+    /// Converts a string to a grid renderer.
+    ///
+    /// The string is the colour of the grid lines.
+    fn from_str(s: &str) -> Result<Self, String> {
+        Ok(Self { color: s.into() })
+    }
+}
+
+impl Renderer for GridRenderer {
+    /// Renders the grid.
+    ///
+    /// This action will draw every wall of every room, regardless of whether
+    /// it is open. Walls shared by two rooms are drawn only once.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `group` - The group to which to add the grid.
+    fn render(&self, maze: &Maze, group: &mut svg::node::element::Group) {
+        let mut data = svg::node::element::path::Data::new();
+        for (wall_pos, _) in maze.walls_iter() {
+            let (start, end) = maze.corners(wall_pos);
+            data = data.move_to((start.x, start.y)).line_to((end.x, end.y));
+        }
+
+        group.append(
+            svg::node::element::Path::new()
+                .set("fill", "none")
+                .set("stroke", self.color.as_str())
+                .set("stroke-linecap", "round")
+                .set("stroke-width", 0.2)
+                .set("vector-effect", "non-scaling-stroke")
+                .set("d", data),
+        );
+    }
+}
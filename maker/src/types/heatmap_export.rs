@@ -0,0 +1,64 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::types::*;
+
+/// An export of a heat map for external tools.
+#[derive(Clone)]
+pub struct HeatMapExport {
+    /// The output path of the heat map.
+    pub path: PathBuf,
+
+    /// The heat map type.
+    pub map_type: HeatMapType,
+}
+
+impl FromStr for HeatMapExport {
+    type Err = String;
+
+    /// Converts a string to a heat map export description.
+    ///
+    /// The string can be on two forms:
+    /// 1. `path`: If only a path is passed, the `map_type` will be `full`.
+    /// 2. `path,map_type`: If a heat map type is passed, it will be used as
+    ///    `map_type`.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut parts = s.split(',').map(str::trim);
+        let path = parts.next().unwrap().into();
+        let map_type = parts
+            .next()
+            .map(HeatMapType::from_str)
+            .unwrap_or(Ok(HeatMapType::Full))?;
+
+        Ok(Self { path, map_type })
+    }
+}
+
+impl HeatMapExport {
+    /// Writes the heat map of a maze.
+    ///
+    /// If the extension of the path is `pgm`, the heat map is written as a
+    /// greymap image, otherwise as comma separated values.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `path` - The output path.
+    pub fn save<P>(&self, maze: &Maze, path: P) -> Result<(), String>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let heat_map = heat_map(self.map_type, maze);
+        let writer =
+            BufWriter::new(File::create(path).map_err(|e| format!("{}", e))?);
+
+        if path.extension().map(|ext| ext == "pgm").unwrap_or(false) {
+            heat_map.to_pgm(writer)
+        } else {
+            heat_map.to_csv(writer)
+        }
+        .map_err(|e| format!("{}", e))
+    }
+}
@@ -2,7 +2,8 @@ use std::str::FromStr;
 
 use svg::Node;
 
-use maze_tools::image::Color;
+use maze::render::svg::fill_rooms;
+use maze::render::Color;
 
 use crate::types::*;
 
@@ -12,11 +13,52 @@ pub struct HeatMapRenderer {
     /// The heat map type.
     pub map_type: HeatMapType,
 
-    /// The colour of cold regioins.
-    pub from: Color,
+    /// The colours of the gradient, from the colour of cold regions to the
+    /// colour of hot regions.
+    ///
+    /// This always contains at least two colours.
+    pub stops: Vec<Color>,
+
+    /// The scale used to map heat to the gradient.
+    pub scale: HeatMapScale,
+}
+
+/// A scale mapping heat to a gradient.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeatMapScale {
+    /// The heat is mapped linearly.
+    Linear,
+
+    /// The logarithm of the heat is mapped linearly.
+    ///
+    /// This prevents the few very hot rooms of large mazes from washing out
+    /// the rest of the heat map.
+    Logarithmic,
+}
+
+impl FromStr for HeatMapScale {
+    type Err = String;
 
-    /// The colour of hot regions.
-    pub to: Color,
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "linear" => Ok(HeatMapScale::Linear),
+            "log" => Ok(HeatMapScale::Logarithmic),
+            _ => Err(format!("unknown heat map scale: {}", s)),
+        }
+    }
+}
+
+impl HeatMapScale {
+    /// Maps all values of a heat map to the range _[0, 1]_.
+    ///
+    /// # Arguments
+    /// *  `heat_map` - The heat map.
+    pub fn map(self, heat_map: &maze::HeatMap) -> maze::matrix::Matrix<f32> {
+        match self {
+            HeatMapScale::Linear => heat_map.normalize(),
+            HeatMapScale::Logarithmic => heat_map.normalize_log(),
+        }
+    }
 }
 
 impl FromStr for HeatMapRenderer {
@@ -24,49 +66,66 @@ impl FromStr for HeatMapRenderer {
 
     /// Converts a string to a heat map description.
     ///
-    /// The string can be on three forms:
-    /// 1. `map_type`: If only a value that can be made into a
-    ///    [`HeatMapType`](HeatMapType) is passed, the `from` and `to` values
-    ///    will be `#000000FF` and `#FFFF0000`.
-    /// 2. `map_type,colour`: If only one colour is passed, the `from` and `to`
-    ///    values will be `#00000000` and the colour passed.
-    /// 3. `map_type,from,to`: If two colours are passed, they are used as
-    ///    `from` and `to` values.
+    /// The string is on the form `map_type,colour,...,scale`, where both the
+    /// colours and the scale are optional:
+    /// 1. `map_type`: If no colours are passed, the `stops` values will be
+    ///    `#0000FF00` and `#FF0000FF`.
+    /// 2. `map_type,colour`: If only one colour is passed, the `stops` values
+    ///    will be a transparent version of the colour and the colour passed.
+    /// 3. `map_type,from,...,to`: If two or more colours are passed, they are
+    ///    used as `stops`.
+    ///
+    /// The scale is either `linear` or `log`, and defaults to `linear`.
     fn from_str(s: &str) -> Result<Self, String> {
-        let mut parts = s.split(',').map(str::trim);
+        let mut parts = s.split(',').map(str::trim).peekable();
         let map_type = parts.next().map(HeatMapType::from_str).unwrap()?;
 
-        if let Some(part1) = parts.next() {
-            if let Some(part2) = parts.next() {
-                Ok(Self {
-                    map_type,
-                    from: Color::from_str(part1)?,
-                    to: Color::from_str(part2)?,
-                })
-            } else {
-                Ok(Self {
-                    map_type,
-                    from: Color::from_str(part1).map(Color::transparent)?,
-                    to: Color::from_str(part1)?,
-                })
+        let mut stops = vec![];
+        let mut scale = HeatMapScale::Linear;
+        while let Some(part) = parts.next() {
+            if parts.peek().is_none() {
+                if let Ok(value) = part.parse() {
+                    scale = value;
+                    break;
+                }
             }
-        } else {
-            Ok(Self {
-                map_type,
-                from: Color {
+            stops.push(Color::from_str(part)?);
+        }
+
+        let stops = match stops.len() {
+            0 => vec![
+                Color {
                     red: 0,
                     green: 0,
                     blue: 255,
                     alpha: 0,
                 },
-                to: Color {
+                Color {
                     red: 255,
                     green: 0,
                     blue: 0,
                     alpha: 255,
                 },
-            })
-        }
+            ],
+            1 => vec![stops[0].transparent(), stops[0]],
+            _ => stops,
+        };
+
+        Ok(Self {
+            map_type,
+            stops,
+            scale,
+        })
+    }
+}
+
+impl HeatMapRenderer {
+    /// Calculates the colour of a position along the gradient.
+    ///
+    /// # Arguments
+    /// *  `w` - The position along the gradient, in the range _[0, 1]_.
+    pub fn color(&self, w: f32) -> Color {
+        Color::gradient(&self.stops, w)
     }
 }
 
@@ -80,10 +139,83 @@ impl Renderer for HeatMapRenderer {
     /// *  `maze` - The maze.
     /// *  `group` - The group to which to add the rooms.
     fn render(&self, maze: &Maze, group: &mut svg::node::element::Group) {
-        let matrix = self.map_type.generate(maze);
-        let max = *matrix.values().max().unwrap() as f32;
-        group.append(draw_rooms(maze, |pos| {
-            self.to.fade(self.from, matrix[pos] as f32 / max)
-        }));
+        let heat = self.scale.map(&heat_map(self.map_type, maze));
+        group.append(fill_rooms(maze, |pos| self.color(heat[pos])));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale_from_str() {
+        for (s, expected) in [
+            ("linear", Ok(HeatMapScale::Linear)),
+            ("log", Ok(HeatMapScale::Logarithmic)),
+            ("Log", Err(String::from("unknown heat map scale: Log"))),
+            ("", Err(String::from("unknown heat map scale: "))),
+        ] {
+            assert_eq!(expected, s.parse::<HeatMapScale>(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn from_str() {
+        let color = |s: &str| s.parse::<Color>().unwrap();
+        for (s, expected) in [
+            (
+                "vertical",
+                Ok((
+                    HeatMapType::Vertical,
+                    vec![color("#0000FF00"), color("#FF0000FF")],
+                    HeatMapScale::Linear,
+                )),
+            ),
+            (
+                "full,#00FF00",
+                Ok((
+                    HeatMapType::Full,
+                    vec![color("#00FF0000"), color("#00FF00")],
+                    HeatMapScale::Linear,
+                )),
+            ),
+            (
+                "horizontal, #000000, #808080, #FFFFFF, log",
+                Ok((
+                    HeatMapType::Horizontal,
+                    vec![color("#000000"), color("#808080"), color("#FFFFFF")],
+                    HeatMapScale::Logarithmic,
+                )),
+            ),
+            (
+                "betweenness,log",
+                Ok((
+                    HeatMapType::Betweenness,
+                    vec![color("#0000FF00"), color("#FF0000FF")],
+                    HeatMapScale::Logarithmic,
+                )),
+            ),
+            (
+                "diagonal",
+                Err(String::from("unknown heat map type: diagonal")),
+            ),
+            ("full,red", Err(String::from("unknown colour value: red"))),
+            (
+                "full,log,#FFFFFF",
+                Err(String::from("unknown colour value: log")),
+            ),
+        ] {
+            assert_eq!(
+                expected,
+                s.parse::<HeatMapRenderer>().map(|renderer| (
+                    renderer.map_type,
+                    renderer.stops,
+                    renderer.scale,
+                )),
+                "{}",
+                s,
+            );
+        }
     }
 }
@@ -0,0 +1,80 @@
+use std::str::FromStr;
+
+use maze_tools::alphabet;
+
+use super::*;
+
+/// The intensity above which a room is considered part of the text.
+const THRESHOLD: f32 = 0.5;
+
+/// A text hidden in the solution of a maze.
+#[derive(Clone)]
+pub struct HiddenTextInitializer<R>
+where
+    R: initialize::Randomizer + Sized + Send + Sync,
+{
+    /// The text to hide.
+    pub text: String,
+
+    _marker: ::std::marker::PhantomData<R>,
+}
+
+impl<R> FromStr for HiddenTextInitializer<R>
+where
+    R: initialize::Randomizer + Sized + Send + Sync,
+{
+    type Err = String;
+
+    /// Converts a string to a text to hide.
+    fn from_str(s: &str) -> Result<Self, String> {
+        Ok(Self {
+            text: s.into(),
+            _marker: ::std::marker::PhantomData,
+        })
+    }
+}
+
+impl<R> Initializer<R> for HiddenTextInitializer<R>
+where
+    R: initialize::Randomizer + Sized + Send + Sync,
+{
+    /// Applies the initialise action.
+    ///
+    /// This action will first carve a solution passing through the rooms
+    /// covered by the text, and then use the methods to initialise the
+    /// remaining rooms.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to initialise.
+    /// *  `rng` - A random number generator.
+    /// *  `methods` - The initialisers to use to generate the maze.
+    /// *  `observer` - A function notified of every modified wall.
+    fn initialize(
+        &self,
+        mut maze: Maze,
+        rng: &mut R,
+        methods: Methods<R>,
+        observer: &mut dyn FnMut(initialize::Event),
+    ) -> Maze {
+        let targets =
+            alphabet::default::ALPHABET.rooms(&self.text, &maze, THRESHOLD);
+        let (start, end) = endpoints(&maze);
+        let path = initialize::carve_through_with_observer(
+            &mut maze, rng, &targets, start, end, observer,
+        );
+
+        let mut solution = matrix::Matrix::new(maze.width(), maze.height());
+        for pos in path {
+            solution[pos] = true;
+        }
+        let mut maze =
+            methods.initialize(maze, rng, |pos| !solution[pos], observer);
+        initialize::connect_all_with_observer(
+            &mut maze,
+            rng,
+            |_| true,
+            observer,
+        );
+        maze
+    }
+}
@@ -0,0 +1,276 @@
+use std::str::FromStr;
+
+use svg::Node;
+
+use crate::types::*;
+
+/// The size of label text, relative to the size of rooms.
+const FONT_SIZE: f32 = 0.5;
+
+/// A corner of the maze.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl FromStr for Corner {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top-left" => Ok(Corner::TopLeft),
+            "top-right" => Ok(Corner::TopRight),
+            "bottom-left" => Ok(Corner::BottomLeft),
+            "bottom-right" => Ok(Corner::BottomRight),
+            _ => Err(format!("unknown corner: {}", s)),
+        }
+    }
+}
+
+/// A piece of metadata to include in a label.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LabelField {
+    /// The seed used to generate the maze.
+    Seed,
+
+    /// The dimensions of the maze, in rooms.
+    Size,
+
+    /// The difficulty score of the maze.
+    Difficulty,
+
+    /// The initialisation methods used to generate the maze.
+    Method,
+}
+
+impl FromStr for LabelField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "seed" => Ok(LabelField::Seed),
+            "size" => Ok(LabelField::Size),
+            "difficulty" => Ok(LabelField::Difficulty),
+            "method" => Ok(LabelField::Method),
+            _ => Err(format!("unknown label field: {}", s)),
+        }
+    }
+}
+
+/// A label describing the maze.
+#[derive(Clone)]
+pub struct LabelRenderer {
+    /// The corner in which to draw the label.
+    pub corner: Corner,
+
+    /// The metadata to include.
+    pub fields: Vec<LabelField>,
+
+    /// The seed used to generate the maze, if known.
+    seed: Option<u64>,
+
+    /// The names of the initialisation methods, if known.
+    methods: Vec<String>,
+}
+
+impl FromStr for LabelRenderer {
+    type Err = String;
+
+    /// Converts a string to a label description.
+    ///
+    /// The string is a comma separated list of an optional corner, one of
+    /// `top-left`, `top-right`, `bottom-left` and `bottom-right`, and the
+    /// fields to include, any of `seed`, `size`, `difficulty` and `method`.
+    ///
+    /// If no corner is passed, the `corner` will be `bottom-right`, and if no
+    /// fields are passed, all fields are included.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut corner = Corner::BottomRight;
+        let mut fields = vec![];
+        for (i, part) in s.split(',').map(str::trim).enumerate() {
+            if i == 0 {
+                if let Ok(value) = part.parse() {
+                    corner = value;
+                    continue;
+                }
+            }
+            fields.push(part.parse()?);
+        }
+
+        if fields.is_empty() {
+            fields = vec![
+                LabelField::Seed,
+                LabelField::Size,
+                LabelField::Difficulty,
+                LabelField::Method,
+            ];
+        }
+
+        Ok(Self {
+            corner,
+            fields,
+            seed: None,
+            methods: vec![],
+        })
+    }
+}
+
+impl LabelRenderer {
+    /// Creates a copy of this label describing a specific maze.
+    ///
+    /// # Arguments
+    /// *  `seed` - The seed used to generate the maze, if any.
+    /// *  `methods` - The initialisation methods used to generate the maze.
+    pub fn describe<R>(&self, seed: Option<u64>, methods: &Methods<R>) -> Self
+    where
+        R: initialize::Randomizer + Sized + Send + Sync,
+    {
+        Self {
+            seed,
+            methods: methods
+                .0
+                .methods()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Calculates the difficulty score of a maze.
+    ///
+    /// This is the length of the solution divided by the distance between
+    /// its endpoints, so a maze whose solution winds back and forth scores
+    /// higher than one whose solution is straight.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    pub fn difficulty(maze: &Maze) -> Option<f32> {
+        let (from, to) = endpoints(maze);
+        let distance = (maze.center(to) - maze.center(from)).value().sqrt();
+        maze.walk(from, to)
+            .filter(|_| distance > 0.0)
+            .map(|path| path.physical_length() / distance)
+    }
+
+    /// Generates the text of this label.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    fn text(&self, maze: &Maze) -> String {
+        self.fields
+            .iter()
+            .filter_map(|field| match field {
+                LabelField::Seed => {
+                    self.seed.map(|seed| format!("seed {}", seed))
+                }
+                LabelField::Size => {
+                    Some(format!("{}×{}", maze.width(), maze.height()))
+                }
+                LabelField::Difficulty => Self::difficulty(maze)
+                    .map(|difficulty| format!("difficulty {:.1}", difficulty)),
+                LabelField::Method => Some(self.methods.join(", "))
+                    .filter(|methods| !methods.is_empty()),
+            })
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+impl Renderer for LabelRenderer {
+    /// Renders the label.
+    ///
+    /// The label is drawn just outside of the maze, in the margin, aligned
+    /// with the corner.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `group` - The group to which to add the label.
+    fn render(&self, maze: &Maze, group: &mut svg::node::element::Group) {
+        let viewbox = maze.viewbox();
+        let offset = FONT_SIZE / 2.0;
+        let (x, anchor) = match self.corner {
+            Corner::TopLeft | Corner::BottomLeft => (viewbox.corner.x, "start"),
+            Corner::TopRight | Corner::BottomRight => {
+                (viewbox.corner.x + viewbox.width, "end")
+            }
+        };
+        let (y, baseline) = match self.corner {
+            Corner::TopLeft | Corner::TopRight => {
+                (viewbox.corner.y - offset, "auto")
+            }
+            Corner::BottomLeft | Corner::BottomRight => {
+                (viewbox.corner.y + viewbox.height + offset, "hanging")
+            }
+        };
+
+        group.append(
+            svg::node::element::Text::new(self.text(maze))
+                .set("x", x)
+                .set("y", y)
+                .set("font-family", "sans-serif")
+                .set("font-size", FONT_SIZE)
+                .set("text-anchor", anchor)
+                .set("dominant-baseline", baseline),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corner_from_str() {
+        for (s, expected) in [
+            ("top-left", Ok(Corner::TopLeft)),
+            ("top-right", Ok(Corner::TopRight)),
+            ("bottom-left", Ok(Corner::BottomLeft)),
+            ("bottom-right", Ok(Corner::BottomRight)),
+            ("top", Err(String::from("unknown corner: top"))),
+        ] {
+            assert_eq!(expected, s.parse::<Corner>(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn from_str() {
+        let all = vec![
+            LabelField::Seed,
+            LabelField::Size,
+            LabelField::Difficulty,
+            LabelField::Method,
+        ];
+        for (s, expected) in [
+            ("top-left", Ok((Corner::TopLeft, all.clone()))),
+            ("seed", Ok((Corner::BottomRight, vec![LabelField::Seed]))),
+            (
+                "bottom-left, size, method",
+                Ok((
+                    Corner::BottomLeft,
+                    vec![LabelField::Size, LabelField::Method],
+                )),
+            ),
+            ("", Err(String::from("unknown label field: "))),
+            (
+                "size,top-left",
+                Err(String::from("unknown label field: top-left")),
+            ),
+            (
+                "top-left,colour",
+                Err(String::from("unknown label field: colour")),
+            ),
+        ] {
+            assert_eq!(
+                expected,
+                s.parse::<LabelRenderer>()
+                    .map(|renderer| (renderer.corner, renderer.fields)),
+                "{}",
+                s,
+            );
+        }
+    }
+}
@@ -1,15 +1,9 @@
-use std::ops;
 use std::str::FromStr;
 
-use maze::physical;
-use maze_tools::cell::*;
+use maze_tools::mask;
 
 use super::*;
 
-/// A constant used as multiplier for individual colour values to get an
-/// intensity
-const D: f32 = 1.0 / 255.0 / 3.0;
-
 /// A masking image.
 #[derive(Clone)]
 pub struct MaskInitializer<R>
@@ -22,6 +16,13 @@ where
     /// The intensity threshold
     pub threshold: f32,
 
+    /// Intensity bands, as upper intensity limits and the initialisation
+    /// methods used for rooms darker than the limit, in increasing order.
+    ///
+    /// If this is not empty, `threshold` is not used, and rooms brighter than
+    /// the last limit are not part of the maze.
+    pub bands: Vec<(f32, initialize::Method)>,
+
     _marker: ::std::marker::PhantomData<R>,
 }
 
@@ -35,28 +36,54 @@ where
     ///
     /// The string must be on the form `path,threshold`, where `path` is the
     /// path to an image and `threshold` is a value between 0 and 1.
+    ///
+    /// Instead of a single threshold, a list of bands on the form
+    /// `limit=method` may be passed, for example
+    /// `path,0.3=winding,0.6=branching`.
     fn from_str(s: &str) -> Result<Self, String> {
         let mut parts = s.split(',').map(str::trim);
         let path = parts
             .next()
             .map(|p| std::path::Path::new(p).to_path_buf())
             .unwrap();
-
-        if let Some(part1) = parts.next() {
-            if let Ok(threshold) = part1.parse() {
-                Ok(Self {
-                    image: image::open(path)
-                        .map_err(|_| format!("failed to open {}", s))?
-                        .to_rgb8(),
-                    threshold,
-                    _marker: ::std::marker::PhantomData,
-                })
-            } else {
-                Err(format!("invalid threshold: {}", part1))
+        let parts = parts.collect::<Vec<_>>();
+
+        let (threshold, bands) = match parts.as_slice() {
+            [] => return Err(format!("invalid mask: {}", s)),
+            [part1] if !part1.contains('=') => (
+                part1
+                    .parse()
+                    .map_err(|_| format!("invalid threshold: {}", part1))?,
+                Vec::new(),
+            ),
+            parts => {
+                let mut bands = parts
+                    .iter()
+                    .map(|part| {
+                        let (limit, method) = part
+                            .split_once('=')
+                            .ok_or_else(|| format!("invalid band: {}", part))?;
+                        Ok((
+                            limit.parse::<f32>().map_err(|_| {
+                                format!("invalid limit: {}", limit)
+                            })?,
+                            method.parse()?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                bands.sort_by(|a, b| a.0.total_cmp(&b.0));
+                (0.0, bands)
             }
-        } else {
-            Err(format!("invalid mask: {}", s))
-        }
+        };
+
+        Ok(Self {
+            image: image::open(path)
+                .map_err(|_| format!("failed to open {}", s))?
+                .to_rgb8(),
+            threshold,
+            bands,
+            _marker: ::std::marker::PhantomData,
+        })
     }
 }
 
@@ -67,58 +94,41 @@ where
     /// Applies the initialise action.
     ///
     /// This action will use the intensity of pixels to determine whether
-    /// rooms should be part of the maze.
+    /// rooms should be part of the maze, and, if bands are defined, which
+    /// initialisation method to use for them; `methods` is then not used.
     ///
     /// # Arguments
     /// *  `maze` - The maze to initialise.
     /// *  `rng` - A random number generator.
     /// *  `methods` - The initialisers to use to generate the maze.
-    fn initialize(&self, maze: Maze, rng: &mut R, methods: Methods<R>) -> Maze {
-        let physical::ViewBox { width, height, .. } = maze.viewbox();
-        let (cols, rows) = self.image.dimensions();
-        let data = self
-            .image
-            .enumerate_pixels()
-            .map(|(x, y, pixel)| {
-                (
-                    physical::Pos {
-                        x: width * (x as f32 / cols as f32),
-                        y: height * (y as f32 / rows as f32),
-                    },
-                    Intermediate::from(pixel),
-                )
-            })
-            .split_by(&maze.shape(), maze.width(), maze.height())
-            .map(|&v| v > self.threshold);
-
-        methods.initialize(maze, rng, |pos| data[pos])
-    }
-}
-
-#[derive(Clone, Copy, Default)]
-struct Intermediate(f32);
-
-impl<'a, P> From<&'a P> for Intermediate
-where
-    P: image::Pixel<Subpixel = u8>,
-{
-    fn from(source: &'a P) -> Self {
-        Intermediate(source.channels().iter().map(|&b| f32::from(b)).sum())
-    }
-}
-
-impl ops::Add<Intermediate> for Intermediate {
-    type Output = Self;
-
-    fn add(self, other: Self) -> Self {
-        Intermediate(self.0 + other.0)
-    }
-}
-
-impl ops::Div<usize> for Intermediate {
-    type Output = f32;
-
-    fn div(self, divisor: usize) -> Self::Output {
-        D * self.0 / divisor as f32
+    /// *  `observer` - A function notified of every modified wall.
+    fn initialize(
+        &self,
+        maze: Maze,
+        rng: &mut R,
+        methods: Methods<R>,
+        observer: &mut dyn FnMut(initialize::Event),
+    ) -> Maze {
+        if self.bands.is_empty() {
+            let data = mask::filter(&self.image, &maze, self.threshold);
+            methods.initialize(maze, rng, |pos| data[pos], observer)
+        } else {
+            let areas = mask::intensities(&self.image, &maze).map(|&v| {
+                self.bands
+                    .iter()
+                    .position(|&(limit, _)| v < limit)
+                    .unwrap_or(self.bands.len())
+            });
+            initialize::Mixed::new(
+                self.bands.iter().map(|&(_, method)| method).collect(),
+            )
+            .initialize_with_areas(
+                maze,
+                rng,
+                &areas,
+                |pos| areas[pos] < self.bands.len(),
+                observer,
+            )
+        }
     }
 }
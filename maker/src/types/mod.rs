@@ -1,27 +1,67 @@
+use std::io::Write;
 use std::str::FromStr;
 
 use rayon::prelude::*;
-use svg::Node;
 
 use maze::initialize;
 use maze::matrix;
-use maze_tools::image::Color;
 use maze_tools::voronoi;
 
+pub use maze_tools::heat_map::HeatMapType;
+
 pub type Maze = maze::Maze<()>;
 
+/// The path denoting standard input or standard output.
+pub const STDIO: &str = "-";
+
+/// Opens an output file for writing.
+///
+/// If the path is [`STDIO`], standard output is used.
+///
+/// # Arguments
+/// *  `path` - The output path.
+pub fn create_output<P>(path: P) -> Result<Box<dyn Write>, String>
+where
+    P: AsRef<std::path::Path>,
+{
+    let path = path.as_ref();
+    if path.as_os_str() == STDIO {
+        Ok(Box::new(std::io::stdout().lock()))
+    } else {
+        std::fs::File::create(path)
+            .map(|file| Box::new(std::io::BufWriter::new(file)) as Box<_>)
+            .map_err(|e| format!("failed to create {}: {}", path.display(), e))
+    }
+}
+
+pub mod animation;
+pub use self::animation::*;
 pub mod background_renderer;
 pub use self::background_renderer::*;
 pub mod break_post_processor;
 pub use self::break_post_processor::*;
+pub mod endpoints_renderer;
+pub use self::endpoints_renderer::*;
+pub mod grid_renderer;
+pub use self::grid_renderer::*;
+pub mod heatmap_export;
+pub use self::heatmap_export::*;
 pub mod heatmap_renderer;
 pub use self::heatmap_renderer::*;
+pub mod hidden_text_initializer;
+pub use self::hidden_text_initializer::*;
+pub mod label_renderer;
+pub use self::label_renderer::*;
 pub mod mask_initializer;
 pub use self::mask_initializer::*;
+pub mod page;
+pub use self::page::*;
 pub mod solve_renderer;
 pub use solve_renderer::*;
 pub mod text_renderer;
 pub use self::text_renderer::*;
+pub mod tiling;
+pub use self::tiling::*;
 
 /// A trait to initialise a maze.
 pub trait Initializer<R>
@@ -34,7 +74,14 @@ where
     /// *  `maze` - The maze to initialise.
     /// *  `rng` - A random number generator.
     /// *  `method` - The initialisation method to use.
-    fn initialize(&self, maze: Maze, rng: &mut R, method: Methods<R>) -> Maze;
+    /// *  `observer` - A function notified of every modified wall.
+    fn initialize(
+        &self,
+        maze: Maze,
+        rng: &mut R,
+        method: Methods<R>,
+        observer: &mut dyn FnMut(initialize::Event),
+    ) -> Maze;
 }
 
 impl<R, T> Initializer<R> for Option<T>
@@ -42,11 +89,17 @@ where
     R: initialize::Randomizer + Sized + Send + Sync,
     T: Initializer<R>,
 {
-    fn initialize(&self, maze: Maze, rng: &mut R, methods: Methods<R>) -> Maze {
+    fn initialize(
+        &self,
+        maze: Maze,
+        rng: &mut R,
+        methods: Methods<R>,
+        observer: &mut dyn FnMut(initialize::Event),
+    ) -> Maze {
         if let Some(action) = self {
-            action.initialize(maze, rng, methods)
+            action.initialize(maze, rng, methods, observer)
         } else {
-            methods.initialize(maze, rng, |_| true)
+            methods.initialize(maze, rng, |_| true, observer)
         }
     }
 }
@@ -79,7 +132,12 @@ where
 }
 
 #[derive(Clone)]
-pub struct Methods<R>(pub voronoi::initialize::Methods<R>)
+pub struct Methods<R>(
+    /// The initialisation methods.
+    pub voronoi::initialize::Methods<R>,
+    /// The room in which to start, if only a single method is used.
+    pub Option<matrix::Pos>,
+)
 where
     R: initialize::Randomizer + Sized + Send + Sync;
 
@@ -88,7 +146,7 @@ where
     R: initialize::Randomizer + Sized + Send + Sync,
 {
     fn default() -> Self {
-        Self(voronoi::initialize::Methods::default())
+        Self(voronoi::initialize::Methods::default(), None)
     }
 }
 
@@ -98,13 +156,42 @@ where
 {
     type Err = String;
 
+    /// Parses a comma separated list of methods.
+    ///
+    /// A single method may be followed by a starting room on the form
+    /// `@col,row`, for example `winding@0,0`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut methods = vec![];
-        for method in s.split(',') {
-            methods.push(method.parse()?)
+        let mut start = None;
+        let mut parts = s.split(',');
+        while let Some(part) = parts.next() {
+            if let Some((method, col)) = part.split_once('@') {
+                let row = parts.next();
+                let invalid = || {
+                    format!(
+                        "invalid starting room \"{}{}\": expected \
+                        <method>@<col>,<row>",
+                        part,
+                        row.map(|row| format!(",{}", row)).unwrap_or_default(),
+                    )
+                };
+                start = Some(matrix::Pos {
+                    col: col.parse().map_err(|_| invalid())?,
+                    row: row
+                        .and_then(|row| row.parse().ok())
+                        .ok_or_else(invalid)?,
+                });
+                methods.push(method.parse()?)
+            } else {
+                methods.push(part.parse()?)
+            }
         }
 
-        Ok(Self(voronoi::initialize::Methods::new(methods)))
+        if start.is_some() && methods.len() > 1 {
+            Err("a starting room requires a single method".to_owned())
+        } else {
+            Ok(Self(voronoi::initialize::Methods::new(methods), start))
+        }
     }
 }
 
@@ -112,23 +199,72 @@ impl<R> Methods<R>
 where
     R: initialize::Randomizer + Sized + Send + Sync,
 {
+    /// Ensures that the starting room, if any, is inside a maze.
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the maze.
+    /// *  `height` - The height of the maze.
+    pub fn check_start(
+        &self,
+        width: usize,
+        height: usize,
+    ) -> Result<(), String> {
+        match self.1 {
+            Some(start)
+                if start.col < 0
+                    || start.row < 0
+                    || start.col as usize >= width
+                    || start.row as usize >= height =>
+            {
+                Err(format!(
+                    "the starting room {},{} is outside of the {}x{} maze",
+                    start.col, start.row, width, height,
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Wraps the inner initialiser.
     ///
     /// # Arguments
     /// *  `maze` - The maze to initialise.
     /// *  `rng` - A random number generator.
     /// *  `filter` - An additional filter applied to all methods.
+    /// *  `observer` - A function notified of every modified wall.
     #[allow(clippy::needless_collect)] // TODO: Wait for Clippy #6066
-    pub fn initialize<F>(self, maze: Maze, rng: &mut R, filter: F) -> Maze
+    pub fn initialize<F>(
+        self,
+        maze: Maze,
+        rng: &mut R,
+        filter: F,
+        observer: &mut dyn FnMut(initialize::Event),
+    ) -> Maze
     where
         F: Fn(matrix::Pos) -> bool,
     {
+        if let Some(start) = self.1 {
+            return maze.initialize_from_with_observer(
+                self.0.methods()[0],
+                rng,
+                start,
+                filter,
+                observer,
+            );
+        }
+
         let points =
             voronoi::initialize::Methods::random_points(maze.viewbox(), rng)
                 .take(self.0.methods().len())
                 .collect::<Vec<_>>();
         self.0
-            .initialize(maze, rng, filter, points.into_iter())
+            .initialize_with_observer(
+                maze,
+                rng,
+                filter,
+                points.into_iter(),
+                observer,
+            )
             .into()
     }
 }
@@ -154,107 +290,149 @@ where
     }
 }
 
-/// A type of heat map.
-#[derive(Clone)]
-pub enum HeatMapType {
-    /// The heat map is generated by traversing vertically.
-    Vertical,
+/// Generates a heat map using all available threads.
+///
+/// # Arguments
+/// *  `map_type` - The type of heat map to generate.
+/// *  `maze` - The maze for which to generate a heat map.
+pub fn heat_map(map_type: HeatMapType, maze: &Maze) -> maze::HeatMap {
+    if map_type == HeatMapType::Betweenness {
+        return map_type.generate(maze);
+    }
+
+    let paths = map_type.paths(maze);
+    paths
+        .chunks((paths.len() / rayon::current_num_threads()).max(1))
+        .collect::<Vec<_>>()
+        .par_iter()
+        .map(|paths| maze::heatmap(maze, paths.iter().cloned()))
+        .reduce(
+            || maze::HeatMap::new(maze.width(), maze.height()),
+            std::ops::Add::add,
+        )
+}
+
+/// Loads a maze written by the json output format.
+///
+/// If the path is [`STDIO`], the maze is read from standard input.
+///
+/// # Arguments
+/// *  `path` - The path of the maze.
+pub fn load_maze<P>(path: P) -> Result<Maze, String>
+where
+    P: AsRef<std::path::Path>,
+{
+    let path = path.as_ref();
+    let result = if path.as_os_str() == STDIO {
+        serde_json::from_reader(std::io::stdin().lock())
+    } else {
+        serde_json::from_reader(std::io::BufReader::new(
+            std::fs::File::open(path).map_err(|e| {
+                format!("failed to open {}: {}", path.display(), e)
+            })?,
+        ))
+    };
+    result.map_err(|e| format!("failed to read maze: {}", e))
+}
+
+/// An output file format.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    /// Scalable Vector Graphics.
+    Svg,
+
+    /// Portable Network Graphics.
+    Png,
+
+    /// Portable Document Format.
+    Pdf,
 
-    /// The heat map is generated by traversing horisontally.
-    Horizontal,
+    /// Plain text.
+    Txt,
 
-    /// The heat map is generated by travesing from every edge room to the one
-    /// on the opposite side.
-    Full,
+    /// An _HTML_ document drawing the walls on a canvas.
+    Html,
+
+    /// The maze itself, which can be rendered later with `--render-only`.
+    Json,
 }
 
-impl FromStr for HeatMapType {
+impl FromStr for OutputFormat {
     type Err = String;
 
-    fn from_str(s: &str) -> Result<HeatMapType, Self::Err> {
-        match s {
-            "vertical" => Ok(HeatMapType::Vertical),
-            "horizontal" => Ok(HeatMapType::Horizontal),
-            "full" => Ok(HeatMapType::Full),
-            _ => Err(format!("unknown heat map type: {}", s)),
+    fn from_str(s: &str) -> Result<OutputFormat, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "svg" => Ok(OutputFormat::Svg),
+            "png" => Ok(OutputFormat::Png),
+            "pdf" => Ok(OutputFormat::Pdf),
+            "txt" => Ok(OutputFormat::Txt),
+            "html" => Ok(OutputFormat::Html),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("unknown output format: {}", s)),
         }
     }
 }
 
-impl HeatMapType {
-    /// Generates a heat map based on this heat map type.
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use OutputFormat::*;
+        match self {
+            Svg => write!(f, "svg"),
+            Png => write!(f, "png"),
+            Pdf => write!(f, "pdf"),
+            Txt => write!(f, "txt"),
+            Html => write!(f, "html"),
+            Json => write!(f, "json"),
+        }
+    }
+}
+
+impl OutputFormat {
+    /// Determines the output format from the extension of a path.
+    ///
+    /// If the path has no extension, or the extension is unknown, nothing is
+    /// returned.
     ///
     /// # Arguments
-    /// *  `maze` - The maze for which to generate a heat map.
-    pub fn generate(&self, maze: &Maze) -> maze::matrix::Matrix<u32> {
-        match *self {
-            HeatMapType::Vertical => self.create_heatmap(
-                maze,
-                (0..maze.width()).map(|col| {
-                    (
-                        maze::matrix::Pos {
-                            col: col as isize,
-                            row: 0,
-                        },
-                        maze::matrix::Pos {
-                            col: col as isize,
-                            row: maze.height() as isize - 1,
-                        },
-                    )
-                }),
-            ),
-            HeatMapType::Horizontal => self.create_heatmap(
-                maze,
-                (0..maze.height()).map(|row| {
-                    (
-                        maze::matrix::Pos {
-                            col: 0,
-                            row: row as isize,
-                        },
-                        maze::matrix::Pos {
-                            col: maze.width() as isize - 1,
-                            row: row as isize,
-                        },
-                    )
-                }),
-            ),
-            HeatMapType::Full => self.create_heatmap(
-                maze,
-                maze.positions()
-                    .filter(|&pos| pos.col == 0 || pos.row == 0)
-                    .map(|pos| {
-                        (
-                            pos,
-                            maze::matrix::Pos {
-                                col: maze.width() as isize - 1 - pos.col,
-                                row: maze.height() as isize - 1 - pos.row,
-                            },
-                        )
-                    }),
-            ),
-        }
+    /// *  `path` - The output path.
+    pub fn from_path<P>(path: P) -> Option<Self>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        path.as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| extension.parse().ok())
+    }
+
+    /// Whether a render backend exists for this format.
+    pub fn is_supported(self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Svg | OutputFormat::Html | OutputFormat::Json
+        )
     }
 
-    /// Generates a heat map for a maze and an iteration of positions.
+    /// Writes a document to a file using this format.
+    ///
+    /// If the path is [`STDIO`], the document is written to standard output.
     ///
     /// # Arguments
-    /// *  `maze` - The maze for which to generate a heat map.
-    /// *  `positions` - The positions for which to generate a heat map. These
-    ///   will be generated from the heat map type.
-    fn create_heatmap<I>(&self, maze: &Maze, positions: I) -> maze::HeatMap
+    /// *  `document` - The document to write.
+    /// *  `path` - The output path.
+    pub fn save<P>(
+        self,
+        document: &svg::Document,
+        path: P,
+    ) -> Result<(), String>
     where
-        I: Iterator<Item = (maze::matrix::Pos, maze::matrix::Pos)>,
+        P: AsRef<std::path::Path>,
     {
-        let collected = positions.collect::<Vec<_>>();
-        collected
-            .chunks(collected.len() / rayon::current_num_threads())
-            .collect::<Vec<_>>()
-            .par_iter()
-            .map(|positions| maze::heatmap(maze, positions.iter().cloned()))
-            .reduce(
-                || maze::HeatMap::new(maze.width(), maze.height()),
-                std::ops::Add::add,
-            )
+        match self {
+            OutputFormat::Svg => svg::write(create_output(path)?, document)
+                .map_err(|e| format!("failed to write SVG: {}", e)),
+            format => Err(format!("unsupported output format: {}", format)),
+        }
     }
 }
 
@@ -301,46 +479,120 @@ impl initialize::Randomizer for Random {
     }
 }
 
-/// Draws all rooms of a maze.
+/// The XML namespace of _Inkscape_ specific attributes.
+pub const INKSCAPE_NAMESPACE: &str =
+    "http://www.inkscape.org/namespaces/inkscape";
+
+/// Creates an empty group marked as an _Inkscape_ layer.
+///
+/// Documents containing layers must declare the `inkscape` namespace using
+/// [`INKSCAPE_NAMESPACE`].
 ///
 /// # Arguments
-/// *  `maze` - The maze to draw.
-/// *  `colors` - A function determining the colour of a room.
-pub fn draw_rooms<F>(maze: &Maze, colors: F) -> svg::node::element::Group
-where
-    F: Fn(maze::matrix::Pos) -> Color,
-{
-    let mut group = svg::node::element::Group::new();
-    for pos in maze.positions().filter(|&pos| maze[pos].visited) {
-        let color = colors(pos);
-        let mut commands = maze
-            .walls(pos)
-            .iter()
-            .enumerate()
-            .map(|(i, wall)| {
-                let (coords, _) = maze.corners((pos, wall));
-                if i == 0 {
-                    svg::node::element::path::Command::Move(
-                        svg::node::element::path::Position::Absolute,
-                        (coords.x, coords.y).into(),
-                    )
-                } else {
-                    svg::node::element::path::Command::Line(
-                        svg::node::element::path::Position::Absolute,
-                        (coords.x, coords.y).into(),
-                    )
-                }
-            })
-            .collect::<Vec<_>>();
-        commands.push(svg::node::element::path::Command::Close);
-
-        group.append(
-            svg::node::element::Path::new()
-                .set("fill", color.to_string())
-                .set("fill-opacity", f32::from(color.alpha) / 255.0)
-                .set("d", svg::node::element::path::Data::from(commands)),
-        );
+/// *  `label` - The label of the layer.
+pub fn layer(label: &str) -> svg::node::element::Group {
+    svg::node::element::Group::new()
+        .set("inkscape:groupmode", "layer")
+        .set("inkscape:label", label)
+}
+
+/// The endpoints of the solution of a maze.
+///
+/// The solution runs from the first to the last room on the edge of the maze,
+/// in row order. Unless rooms are excluded by a mask, these are the top left
+/// and bottom right rooms, which are also used for mazes not yet initialised.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+pub fn endpoints(maze: &Maze) -> (matrix::Pos, matrix::Pos) {
+    let mut edge = maze.edge_rooms();
+    match edge.next() {
+        Some(from) => (from, edge.last().unwrap_or(from)),
+        None => (
+            matrix::Pos { col: 0, row: 0 },
+            matrix::Pos {
+                col: maze.width() as isize - 1,
+                row: maze.height() as isize - 1,
+            },
+        ),
     }
+}
 
-    group
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn methods_from_str_start() {
+        for (s, expected) in [
+            ("winding@1,2", Ok(Some(matrix::Pos { col: 1, row: 2 }))),
+            ("winding", Ok(None)),
+            (
+                "winding@x,2",
+                Err(String::from(
+                    "invalid starting room \"winding@x,2\": expected \
+                    <method>@<col>,<row>",
+                )),
+            ),
+            (
+                "winding@1,y",
+                Err(String::from(
+                    "invalid starting room \"winding@1,y\": expected \
+                    <method>@<col>,<row>",
+                )),
+            ),
+            (
+                "winding@1",
+                Err(String::from(
+                    "invalid starting room \"winding@1\": expected \
+                    <method>@<col>,<row>",
+                )),
+            ),
+            (
+                "winding@1,2,braid",
+                Err(String::from("a starting room requires a single method")),
+            ),
+        ] {
+            assert_eq!(
+                expected,
+                s.parse::<Methods<Random>>().map(|methods| methods.1),
+                "{}",
+                s,
+            );
+        }
+    }
+
+    #[test]
+    fn methods_check_start() {
+        for (s, expected) in [
+            ("winding", Ok(())),
+            ("winding@0,0", Ok(())),
+            ("winding@4,2", Ok(())),
+            (
+                "winding@5,2",
+                Err(String::from(
+                    "the starting room 5,2 is outside of the 5x3 maze",
+                )),
+            ),
+            (
+                "winding@4,3",
+                Err(String::from(
+                    "the starting room 4,3 is outside of the 5x3 maze",
+                )),
+            ),
+            (
+                "winding@-1,0",
+                Err(String::from(
+                    "the starting room -1,0 is outside of the 5x3 maze",
+                )),
+            ),
+        ] {
+            assert_eq!(
+                expected,
+                s.parse::<Methods<Random>>().unwrap().check_start(5, 3),
+                "{}",
+                s,
+            );
+        }
+    }
 }
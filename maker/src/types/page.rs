@@ -0,0 +1,80 @@
+use std::str::FromStr;
+
+use maze::physical;
+
+/// A fixed page size onto which the maze is centred.
+#[derive(Clone, Copy)]
+pub struct Page {
+    /// The width of the page.
+    pub width: f32,
+
+    /// The height of the page.
+    pub height: f32,
+}
+
+impl FromStr for Page {
+    type Err = String;
+
+    /// Converts a string to a page size.
+    ///
+    /// The string can be on two forms:
+    /// 1. `widthxheight`: The page size in units.
+    /// 2. `a3`, `a4`, `a5` or `letter`: A common paper size, in millimetres.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (width, height) = match s.trim().to_lowercase().as_str() {
+            "a3" => (297.0, 420.0),
+            "a4" => (210.0, 297.0),
+            "a5" => (148.0, 210.0),
+            "letter" => (215.9, 279.4),
+            size => size
+                .split_once('x')
+                .and_then(|(width, height)| {
+                    Some((width.parse().ok()?, height.parse().ok()?))
+                })
+                .filter(|&(width, height): &(f32, f32)| {
+                    width > 0.0 && height > 0.0
+                })
+                .ok_or_else(|| format!("invalid page size: {}", s))?,
+        };
+
+        Ok(Self { width, height })
+    }
+}
+
+impl Page {
+    /// The view box of this page.
+    pub fn viewbox(self) -> physical::ViewBox {
+        physical::ViewBox {
+            corner: physical::Pos { x: 0.0, y: 0.0 },
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        for (s, expected) in [
+            ("a3", Ok((297.0, 420.0))),
+            ("A4", Ok((210.0, 297.0))),
+            (" a5 ", Ok((148.0, 210.0))),
+            ("letter", Ok((215.9, 279.4))),
+            ("100x50.5", Ok((100.0, 50.5))),
+            ("0x50", Err(String::from("invalid page size: 0x50"))),
+            ("100x-50", Err(String::from("invalid page size: 100x-50"))),
+            ("100", Err(String::from("invalid page size: 100"))),
+            ("a6", Err(String::from("invalid page size: a6"))),
+        ] {
+            assert_eq!(
+                expected,
+                s.parse::<Page>().map(|page| (page.width, page.height)),
+                "{}",
+                s,
+            );
+        }
+    }
+}
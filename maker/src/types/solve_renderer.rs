@@ -1,24 +1,45 @@
 use maze::render::svg::ToPath;
+use maze::render::Color;
 
 use svg::Node;
 
 use crate::types::*;
 
+/// The colour of the solution.
+#[derive(Clone)]
+enum SolveColor {
+    /// A single colour, either a colour name or a hex value.
+    Solid(String),
+
+    /// A gradient from the start colour to the finish colour.
+    Gradient(Color, Color),
+}
+
 /// The maze solution.
 #[derive(Clone)]
 pub struct SolveRenderer {
     /// The colour of the solution marker.
-    color: String,
+    color: SolveColor,
 }
 
 impl FromStr for SolveRenderer {
     type Err = String;
 
-    /// Converts a string to a string to render.
+    /// Converts a string to a solution renderer.
     ///
-    /// The string must be a path.
+    /// The string is either a single colour, or two hex encoded colours
+    /// separated by `..`, such as `#ff0000..#0000ff`, in which case the
+    /// solution is drawn as a gradient from the start to the finish.
     fn from_str(s: &str) -> Result<Self, String> {
-        Ok(Self { color: s.into() })
+        let color = if let Some((start, finish)) = s.split_once("..") {
+            SolveColor::Gradient(
+                Color::from_str(start.trim())?,
+                Color::from_str(finish.trim())?,
+            )
+        } else {
+            SolveColor::Solid(s.into())
+        };
+        Ok(Self { color })
     }
 }
 
@@ -29,26 +50,83 @@ impl Renderer for SolveRenderer {
     /// *  `maze` - The maze.
     /// *  `group` - The group to which to add the solution.
     fn render(&self, maze: &Maze, group: &mut svg::node::element::Group) {
-        group.append(
+        // A masked maze may not be solvable
+        let (from, to) = endpoints(maze);
+        let path = match maze.walk(from, to) {
+            Some(path) => path,
+            None => return,
+        };
+        let line = || {
             svg::node::element::Path::new()
                 .set("fill", "none")
-                .set("stroke", self.color.as_str())
                 .set("stroke-linecap", "round")
                 .set("stroke-linejoin", "round")
                 .set("stroke-width", 0.4)
                 .set("vector-effect", "non-scaling-stroke")
-                .set(
-                    "d",
-                    maze.walk(
-                        maze::matrix::Pos { col: 0, row: 0 },
-                        maze::matrix::Pos {
-                            col: maze.width() as isize - 1,
-                            row: maze.height() as isize - 1,
-                        },
-                    )
-                    .unwrap()
-                    .to_path_d(),
-                ),
+        };
+
+        match &self.color {
+            SolveColor::Solid(color) => group.append(
+                line()
+                    .set("stroke", color.as_str())
+                    .set("d", path.to_path_d()),
+            ),
+            SolveColor::Gradient(start, finish) => {
+                // Draw every step separately, coloured by its position along
+                // the path
+                let centers = path
+                    .into_iter()
+                    .map(|pos| maze.center(pos))
+                    .collect::<Vec<_>>();
+                let steps = centers.len().saturating_sub(1).max(1) as f32;
+                for (i, step) in centers.windows(2).enumerate() {
+                    let color = Color::gradient(
+                        &[*start, *finish],
+                        (i as f32 + 0.5) / steps,
+                    );
+                    group.append(
+                        line()
+                            .set("stroke", color.to_string())
+                            .set(
+                                "stroke-opacity",
+                                f32::from(color.alpha) / 255.0,
+                            )
+                            .set(
+                                "d",
+                                svg::node::element::path::Data::new()
+                                    .move_to((step[0].x, step[0].y))
+                                    .line_to((step[1].x, step[1].y)),
+                            ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze::initialize;
+
+    use super::*;
+
+    #[test]
+    fn render_unsolvable() {
+        // The masked column separates the endpoints
+        let maze = Maze::new(maze::Shape::Quad, 5, 5).initialize_filter(
+            initialize::Method::Winding(initialize::Bias::default()),
+            &mut initialize::LFSR::new(12345),
+            |pos| pos.col != 2,
         );
+        let (from, to) = endpoints(&maze);
+        assert!(maze.walk(from, to).is_none());
+
+        for s in ["black", "#ff0000..#0000ff"] {
+            let mut group = svg::node::element::Group::new();
+            s.parse::<SolveRenderer>()
+                .unwrap()
+                .render(&maze, &mut group);
+            assert!(group.get_children().is_empty(), "{}", s);
+        }
     }
 }
@@ -0,0 +1,231 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use svg::Node;
+
+use crate::types::INKSCAPE_NAMESPACE;
+
+/// A division of the rendered maze into pages.
+#[derive(Clone, Copy)]
+pub struct Tiling {
+    /// The number of pages horizontally.
+    pub columns: usize,
+
+    /// The number of pages vertically.
+    pub rows: usize,
+
+    /// The distance by which pages overlap their neighbours.
+    pub overlap: f32,
+}
+
+impl FromStr for Tiling {
+    type Err = String;
+
+    /// Converts a string to a tiling description.
+    ///
+    /// The string can be on two forms:
+    /// 1. `columnsxrows`: If only the number of pages is passed, the
+    ///    `overlap` will be `10`.
+    /// 2. `columnsxrows,overlap`: If an overlap is passed, it will be used as
+    ///    `overlap`.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut parts = s.split(',').map(str::trim);
+        let size = parts.next().unwrap();
+        let (columns, rows) = size
+            .split_once('x')
+            .and_then(|(columns, rows)| {
+                Some((columns.parse().ok()?, rows.parse().ok()?))
+            })
+            .filter(|&(columns, rows)| columns > 0 && rows > 0)
+            .ok_or_else(|| format!("invalid number of pages: {}", size))?;
+
+        if let Some(part1) = parts.next() {
+            if let Ok(overlap) = part1.parse() {
+                Ok(Self {
+                    columns,
+                    rows,
+                    overlap,
+                })
+            } else {
+                Err(format!("invalid overlap: {}", part1))
+            }
+        } else {
+            Ok(Self {
+                columns,
+                rows,
+                overlap: 10.0,
+            })
+        }
+    }
+}
+
+impl Tiling {
+    /// Splits a rendered maze into pages.
+    ///
+    /// Every page displays its part of the maze extended by the overlap on
+    /// all sides. Registration marks are drawn at the corners of the part,
+    /// so that the marks of neighbouring pages can be aligned when
+    /// assembling them.
+    ///
+    /// The pages are generated row by row, and each page is returned with
+    /// its column and row.
+    ///
+    /// # Arguments
+    /// *  `content` - The rendered maze.
+    /// *  `viewbox` - The view box of the rendered maze.
+    pub fn pages<'a>(
+        &'a self,
+        content: &'a svg::node::element::Group,
+        viewbox: (f32, f32, f32, f32),
+    ) -> impl Iterator<Item = (usize, usize, svg::Document)> + 'a {
+        let (x, y, width, height) = viewbox;
+        let page_width = width / self.columns as f32;
+        let page_height = height / self.rows as f32;
+
+        (0..self.rows).flat_map(move |row| {
+            (0..self.columns).map(move |column| {
+                let left = x + column as f32 * page_width;
+                let top = y + row as f32 * page_height;
+                let document = svg::Document::new()
+                    .set("xmlns:inkscape", INKSCAPE_NAMESPACE)
+                    .set(
+                        "viewBox",
+                        (
+                            left - self.overlap,
+                            top - self.overlap,
+                            page_width + 2.0 * self.overlap,
+                            page_height + 2.0 * self.overlap,
+                        ),
+                    )
+                    .add(content.clone())
+                    .add(self.registration_marks(
+                        left,
+                        top,
+                        page_width,
+                        page_height,
+                    ));
+                (column, row, document)
+            })
+        })
+    }
+
+    /// Generates the path of a page.
+    ///
+    /// The row and column are appended to the file stem of `path`.
+    ///
+    /// # Arguments
+    /// *  `path` - The output path of the full maze.
+    /// *  `column` - The column of the page.
+    /// *  `row` - The row of the page.
+    pub fn page_path(path: &Path, column: usize, row: usize) -> PathBuf {
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let name = if let Some(extension) = path.extension() {
+            format!(
+                "{}-{}-{}.{}",
+                stem,
+                row,
+                column,
+                extension.to_string_lossy(),
+            )
+        } else {
+            format!("{}-{}-{}", stem, row, column)
+        };
+        path.with_file_name(name)
+    }
+
+    /// Draws registration marks at the corners of a rectangle.
+    ///
+    /// The size of the marks is derived from the overlap, so that they are
+    /// visible on both pages sharing a corner.
+    ///
+    /// # Arguments
+    /// *  `left` - The left edge of the rectangle.
+    /// *  `top` - The top edge of the rectangle.
+    /// *  `width` - The width of the rectangle.
+    /// *  `height` - The height of the rectangle.
+    fn registration_marks(
+        &self,
+        left: f32,
+        top: f32,
+        width: f32,
+        height: f32,
+    ) -> svg::node::element::Group {
+        let radius = self.overlap / 2.0;
+        let mut group = svg::node::element::Group::new()
+            .set("fill", "none")
+            .set("stroke", "black")
+            .set("stroke-width", 0.4)
+            .set("vector-effect", "non-scaling-stroke");
+
+        for (x, y) in [
+            (left, top),
+            (left + width, top),
+            (left, top + height),
+            (left + width, top + height),
+        ] {
+            group.append(
+                svg::node::element::Circle::new()
+                    .set("cx", x)
+                    .set("cy", y)
+                    .set("r", radius / 2.0),
+            );
+            group.append(
+                svg::node::element::Path::new().set(
+                    "d",
+                    svg::node::element::path::Data::new()
+                        .move_to((x - radius, y))
+                        .line_to((x + radius, y))
+                        .move_to((x, y - radius))
+                        .line_to((x, y + radius)),
+                ),
+            );
+        }
+
+        group
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        for (s, expected) in [
+            ("2x3", Ok((2, 3, 10.0))),
+            ("2x3,5", Ok((2, 3, 5.0))),
+            (" 4x1 , 2.5 ", Ok((4, 1, 2.5))),
+            ("0x3", Err(String::from("invalid number of pages: 0x3"))),
+            ("2", Err(String::from("invalid number of pages: 2"))),
+            ("ax3", Err(String::from("invalid number of pages: ax3"))),
+            ("", Err(String::from("invalid number of pages: "))),
+            ("2x3,wide", Err(String::from("invalid overlap: wide"))),
+        ] {
+            assert_eq!(
+                expected,
+                s.parse::<Tiling>().map(|tiling| (
+                    tiling.columns,
+                    tiling.rows,
+                    tiling.overlap,
+                )),
+                "{}",
+                s,
+            );
+        }
+    }
+
+    #[test]
+    fn page_path() {
+        assert_eq!(
+            PathBuf::from("out/maze-1-2.svg"),
+            Tiling::page_path(Path::new("out/maze.svg"), 2, 1),
+        );
+        assert_eq!(
+            PathBuf::from("maze-0-0"),
+            Tiling::page_path(Path::new("maze"), 0, 0),
+        );
+    }
+}
@@ -1,20 +1,68 @@
 use criterion::{
     black_box, criterion_group, criterion_main, BenchmarkId, Criterion,
 };
-use maze::initialize::{Method, LFSR};
+use maze::initialize::{Bias, Method, LFSR};
 use maze::{Maze, Shape};
 
+/// The default texture parameters.
+const BIAS: Bias = Bias {
+    horizontal: 50,
+    branch: None,
+};
+
+/// The methods to benchmark.
+const METHODS: &[Method] = &[
+    Method::Braid,
+    Method::Branching(BIAS),
+    Method::Clear,
+    Method::Winding(BIAS),
+];
+
+/// The shapes to benchmark.
+const SHAPES: &[Shape] = &[Shape::Tri, Shape::Quad, Shape::Hex];
+
+/// The widths and heights of the mazes to benchmark.
+const SIZES: &[usize] = &[50, 100, 200];
+
 pub fn initialize(c: &mut Criterion) {
-    for &method in [Method::Braid, Method::Branching, Method::Winding].iter() {
+    for &method in METHODS {
         let mut group = c.benchmark_group(format!("initialize {}", method));
-        for shape in [Shape::Tri, Shape::Quad, Shape::Hex].iter() {
+        for &shape in SHAPES {
+            for &size in SIZES {
+                group.bench_with_input(
+                    BenchmarkId::new(shape.to_string(), size),
+                    &size,
+                    |b, &size| {
+                        b.iter(|| {
+                            Maze::<()>::new(black_box(shape), size, size)
+                                .initialize(method, &mut LFSR::new(65));
+                        });
+                    },
+                );
+            }
+        }
+        group.finish();
+    }
+}
+
+pub fn initialize_segmented(c: &mut Criterion) {
+    // Excluding every fourth column splits the maze into many areas, each of
+    // which requires a new random start room
+    for &method in [Method::Branching(BIAS), Method::Winding(BIAS)].iter() {
+        let mut group =
+            c.benchmark_group(format!("initialize segmented {}", method));
+        for &size in SIZES {
             group.bench_with_input(
-                BenchmarkId::from_parameter(shape),
-                shape,
-                |b, &shape| {
+                BenchmarkId::from_parameter(size),
+                &size,
+                |b, &size| {
                     b.iter(|| {
-                        Maze::<()>::new(black_box(shape), 100, 100)
-                            .initialize(method, &mut LFSR::new(65));
+                        Maze::<()>::new(black_box(Shape::Quad), size, size)
+                            .initialize_filter(
+                                method,
+                                &mut LFSR::new(65),
+                                |pos| pos.col % 4 != 3 && pos.row % 4 != 3,
+                            );
                     });
                 },
             );
@@ -23,5 +71,5 @@ pub fn initialize(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, initialize);
+criterion_group!(benches, initialize, initialize_segmented);
 criterion_main!(benches);
@@ -1,11 +1,17 @@
 use criterion::{
     black_box, criterion_group, criterion_main, BenchmarkId, Criterion,
 };
-use maze::initialize::{Method, LFSR};
+use maze::initialize::{Bias, Method, LFSR};
 use maze::{Maze, Shape};
 
 pub fn walk(c: &mut Criterion) {
-    for &method in [Method::Braid, Method::Branching, Method::Winding].iter() {
+    for &method in [
+        Method::Braid,
+        Method::Branching(Bias::default()),
+        Method::Winding(Bias::default()),
+    ]
+    .iter()
+    {
         let mut group = c.benchmark_group(format!("walk {}", method));
         for shape in [Shape::Tri, Shape::Quad, Shape::Hex].iter() {
             let maze = Maze::<()>::new(black_box(*shape), 100, 100)
@@ -15,7 +21,7 @@ pub fn walk(c: &mut Criterion) {
                 ((maze.width() - 1) as isize, (maze.height() - 1) as isize)
                     .into();
             group.bench_with_input(
-                BenchmarkId::from_parameter(shape),
+                BenchmarkId::new("walk", shape),
                 shape,
                 |b, _| {
                     b.iter(|| {
@@ -28,5 +34,27 @@ pub fn walk(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, walk);
+pub fn walk_large(c: &mut Criterion) {
+    let mut group = c.benchmark_group("walk large");
+    group.sample_size(10);
+    for &size in [250usize, 500].iter() {
+        let maze = Maze::<()>::new(black_box(Shape::Quad), size, size)
+            .initialize(Method::Braid, &mut LFSR::new(65));
+        let start = (0isize, 0isize).into();
+        let end =
+            ((maze.width() - 1) as isize, (maze.height() - 1) as isize).into();
+        group.bench_with_input(
+            BenchmarkId::new("walk", size),
+            &size,
+            |b, _| {
+                b.iter(|| {
+                    maze.walk(start, end);
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, walk, walk_large);
 criterion_main!(benches);
@@ -0,0 +1,207 @@
+//! # Maze texture analysis
+//!
+//! Generated mazes differ in texture depending on the initialisation method;
+//! some have long, straight corridors while others turn constantly. The
+//! metrics in this module describe such properties of a maze with a single
+//! number, which makes it possible to compare methods and parameters.
+
+use alloc::vec;
+
+use crate::matrix;
+use crate::Maze;
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// The ratio of open walls to all walls between two rooms inside of the
+    /// maze.
+    ///
+    /// Walls on the edge of the maze are not considered. A maze without any
+    /// interior walls has an openness of `0.0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut maze = maze::Shape::Quad.create::<()>(2, 1);
+    /// assert_eq!(maze.openness(), 0.0);
+    ///
+    /// maze.open(
+    ///     maze.connecting_wall((0isize, 0isize).into(), (1isize, 0isize).into())
+    ///         .unwrap(),
+    /// );
+    /// assert_eq!(maze.openness(), 1.0);
+    /// ```
+    pub fn openness(&self) -> f32 {
+        let (open, total) = self
+            .walls_iter()
+            .filter(|&(wall_pos, _)| self.is_inside(self.back(wall_pos).0))
+            .fold((0usize, 0usize), |(open, total), (_, is_open)| {
+                (open + usize::from(is_open), total + 1)
+            });
+
+        if total > 0 {
+            open as f32 / total as f32
+        } else {
+            0.0
+        }
+    }
+
+    /// The ratio of corridor rooms passed straight through to all corridor
+    /// rooms.
+    ///
+    /// A corridor room is a room connected to exactly two other rooms inside
+    /// of the maze, and it is passed straight through if the step into the
+    /// room has the same direction as the step out of it. Since the rooms of
+    /// a triangular maze alternate in orientation, only steps along a row can
+    /// be straight for such mazes.
+    ///
+    /// A maze without corridor rooms has a straightness of `0.0`.
+    pub fn corridor_straightness(&self) -> f32 {
+        let shape = self.shape();
+        let (straight, total) = self
+            .positions()
+            .filter_map(|pos| {
+                let mut neighbors =
+                    self.neighbors(pos).filter(|&p| self.is_inside(p));
+                match (neighbors.next(), neighbors.next(), neighbors.next()) {
+                    (Some(a), Some(b), None) => Some(
+                        shape.step_direction(a, pos)
+                            == shape.step_direction(pos, b),
+                    ),
+                    _ => None,
+                }
+            })
+            .fold((0usize, 0usize), |(straight, total), is_straight| {
+                (straight + usize::from(is_straight), total + 1)
+            });
+
+        if total > 0 {
+            straight as f32 / total as f32
+        } else {
+            0.0
+        }
+    }
+
+    /// The number of independent loops in this maze.
+    ///
+    /// This is the cycle rank of the graph of rooms inside of the maze, where
+    /// open walls are edges: the number of open walls that can be closed
+    /// without making any room unreachable from any room it can currently
+    /// reach. A perfect maze has no loops.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let maze = maze::Shape::Quad.create::<()>(3, 3).initialize(
+    ///     maze::initialize::Method::Clear,
+    ///     &mut maze::initialize::LFSR::new(12345),
+    /// );
+    /// assert_eq!(maze.loop_count(), 4);
+    /// ```
+    pub fn loop_count(&self) -> usize {
+        let edges = self
+            .walls_iter()
+            .filter(|&(wall_pos, open)| {
+                open && self.is_inside(self.back(wall_pos).0)
+            })
+            .count();
+
+        // Every component is a spanning tree of rooms plus its loops, so
+        // count the rooms and components to find the tree edges
+        let mut seen = matrix::Matrix::<bool>::new(self.width(), self.height());
+        let mut rooms = 0;
+        let mut components = 0;
+        for root in self.positions() {
+            if seen[root] {
+                continue;
+            }
+
+            components += 1;
+            seen[root] = true;
+            let mut stack = vec![root];
+            while let Some(pos) = stack.pop() {
+                rooms += 1;
+                for next in self.neighbors(pos) {
+                    if self.is_inside(next) && !seen[next] {
+                        seen[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+
+        edges + components - rooms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use crate::initialize;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn openness_closed(maze: TestMaze) {
+        assert_eq!(maze.openness(), 0.0);
+        assert_eq!(maze.corridor_straightness(), 0.0);
+        assert_eq!(maze.loop_count(), 0);
+    }
+
+    #[maze_test]
+    fn openness_clear(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Clear,
+            &mut initialize::LFSR::new(12345),
+        );
+        assert_eq!(maze.openness(), 1.0);
+        assert_eq!(
+            maze.loop_count(),
+            maze.walls_iter()
+                .filter(|&(wall_pos, _)| maze.is_inside(maze.back(wall_pos).0))
+                .count()
+                + 1
+                - maze.width() * maze.height(),
+        );
+    }
+
+    #[maze_test]
+    fn loop_count_branching(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Branching(initialize::Bias::default()),
+            &mut initialize::LFSR::new(12345),
+        );
+        assert_eq!(maze.loop_count(), 0);
+        assert!(maze.openness() > 0.0 && maze.openness() < 1.0);
+    }
+
+    #[maze_test(quad)]
+    fn loop_count_single(mut maze: TestMaze) {
+        Navigator::new(&mut maze)
+            .right(true)
+            .down(true)
+            .left(true)
+            .up(true);
+        assert_eq!(maze.loop_count(), 1);
+    }
+
+    #[maze_test(quad, hex)]
+    fn corridor_straightness_straight(mut maze: TestMaze) {
+        Navigator::new(&mut maze)
+            .right(true)
+            .right(true)
+            .right(true);
+        assert_eq!(maze.corridor_straightness(), 1.0);
+    }
+
+    #[maze_test(quad)]
+    fn corridor_straightness_turn(mut maze: TestMaze) {
+        Navigator::new(&mut maze)
+            .right(true)
+            .right(true)
+            .down(true)
+            .down(true);
+        assert_eq!(maze.corridor_straightness(), 2.0 / 3.0);
+    }
+}
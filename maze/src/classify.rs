@@ -0,0 +1,244 @@
+//! # Room classification
+//!
+//! Rooms of a generated maze can be classified by how they connect to the
+//! rest of the maze, which is useful when placing content in a maze used as a
+//! game map.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::matrix;
+use crate::Maze;
+
+/// The kind of a room, determined by the number of rooms reachable from it.
+///
+/// Openings leading out of the maze are not considered.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Kind {
+    /// A room not connected to any other room.
+    #[default]
+    Isolated,
+
+    /// A room connected to exactly one other room.
+    DeadEnd,
+
+    /// A room connected to exactly two other rooms.
+    Corridor,
+
+    /// A room connected to three or more other rooms.
+    Junction,
+}
+
+/// The classification of a room.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Class {
+    /// The kind of room.
+    pub kind: Kind,
+
+    /// Whether the room is part of a loop.
+    ///
+    /// A room is part of a loop if it is possible to leave it through one
+    /// opening and return through another without passing any room twice.
+    pub in_loop: bool,
+}
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// Classifies all rooms of this maze.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::classify::*;
+    /// # use maze::matrix;
+    /// let mut maze = maze::Shape::Quad.create::<()>(3, 1);
+    /// for col in 0..2 {
+    ///     let pos = matrix::Pos { col, row: 0 };
+    ///     let next = matrix::Pos { col: col + 1, row: 0 };
+    ///     maze.open(maze.connecting_wall(pos, next).unwrap());
+    /// }
+    ///
+    /// let classification = maze.classification();
+    /// assert_eq!(
+    ///     classification.values().map(|class| class.kind).collect::<Vec<_>>(),
+    ///     vec![Kind::DeadEnd, Kind::Corridor, Kind::DeadEnd],
+    /// );
+    /// assert!(classification.values().all(|class| !class.in_loop));
+    /// ```
+    pub fn classification(&self) -> matrix::Matrix<Class> {
+        let neighbors =
+            matrix::Matrix::new_with_data(self.width(), self.height(), |pos| {
+                self.neighbors(pos)
+                    .filter(|&p| self.is_inside(p))
+                    .collect::<Vec<_>>()
+            });
+        let in_loop = loops(&neighbors);
+
+        neighbors.map_with_pos(|pos, neighbors| Class {
+            kind: match neighbors.len() {
+                0 => Kind::Isolated,
+                1 => Kind::DeadEnd,
+                2 => Kind::Corridor,
+                _ => Kind::Junction,
+            },
+            in_loop: in_loop[pos],
+        })
+    }
+
+    /// Creates a copy of this maze with the classification of every room
+    /// added to its data.
+    ///
+    /// See [`classification`](Self::classification) for details.
+    pub fn classify_rooms(&self) -> Maze<(T, Class)> {
+        let classification = self.classification();
+        self.map(|pos, data| (data, classification[pos]))
+    }
+}
+
+/// Finds all cells that are part of a loop in a graph.
+///
+/// A cell is part of a loop if at least one of its edges is not a _bridge_,
+/// that is, an edge whose removal would split the graph. Bridges are found
+/// using an iterative version of _Tarjan's algorithm_.
+///
+/// # Arguments
+/// *  `neighbors` - The neighbours of every cell. The graph is assumed to be
+///    undirected.
+fn loops(neighbors: &matrix::Matrix<Vec<matrix::Pos>>) -> matrix::Matrix<bool> {
+    let mut result = matrix::Matrix::new(neighbors.width, neighbors.height);
+    let mut discovered =
+        matrix::Matrix::<Option<usize>>::new(neighbors.width, neighbors.height);
+    let mut low =
+        matrix::Matrix::<usize>::new(neighbors.width, neighbors.height);
+    let mut time = 0;
+
+    for root in neighbors.positions() {
+        if discovered[root].is_some() {
+            continue;
+        }
+
+        discovered[root] = Some(time);
+        low[root] = time;
+        time += 1;
+
+        // Every entry is a room, its parent and the index of the next
+        // neighbour to visit
+        let mut stack = vec![(root, None, 0)];
+        while let Some(&mut (pos, parent, ref mut index)) = stack.last_mut() {
+            if let Some(&next) = neighbors[pos].get(*index) {
+                *index += 1;
+                if Some(next) == parent {
+                    continue;
+                }
+                match discovered[next] {
+                    Some(d) => {
+                        // A back edge always closes a loop
+                        low[pos] = low[pos].min(d);
+                        result[pos] = true;
+                        result[next] = true;
+                    }
+                    None => {
+                        discovered[next] = Some(time);
+                        low[next] = time;
+                        time += 1;
+                        stack.push((next, Some(pos), 0));
+                    }
+                }
+            } else {
+                stack.pop();
+                if let Some(parent) = parent {
+                    low[parent] = low[parent].min(low[pos]);
+                    if discovered[parent].is_some_and(|d| low[pos] <= d) {
+                        result[parent] = true;
+                        result[pos] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn classification_closed(maze: TestMaze) {
+        assert!(maze
+            .classification()
+            .values()
+            .all(|&class| class == Class::default()));
+    }
+
+    #[maze_test]
+    fn classification_branching(maze: TestMaze) {
+        // A maze without loops has no rooms in loops
+        let maze = maze.initialize(
+            initialize::Method::Branching(initialize::Bias::default()),
+            &mut initialize::LFSR::new(12345),
+        );
+        let classification = maze.classification();
+        for pos in maze.positions() {
+            assert!(!classification[pos].in_loop);
+            assert_eq!(
+                match maze.degree_map()[pos] {
+                    0 => Kind::Isolated,
+                    1 => Kind::DeadEnd,
+                    2 => Kind::Corridor,
+                    _ => Kind::Junction,
+                },
+                classification[pos].kind,
+            );
+        }
+    }
+
+    #[test]
+    fn classification_loop() {
+        // A loop of four rooms with a dead end attached
+        let mut maze = crate::Shape::Quad.create::<u32>(3, 2);
+        for (pos1, pos2) in [
+            ((0, 0), (1, 0)),
+            ((1, 0), (1, 1)),
+            ((1, 1), (0, 1)),
+            ((0, 1), (0, 0)),
+            ((1, 0), (2, 0)),
+        ] {
+            let wall = maze
+                .connecting_wall(
+                    matrix_pos(pos1.0, pos1.1),
+                    matrix_pos(pos2.0, pos2.1),
+                )
+                .unwrap();
+            maze.open(wall);
+        }
+
+        let maze = maze.classify_rooms();
+        assert_eq!(
+            vec![
+                (Kind::Corridor, true),
+                (Kind::Junction, true),
+                (Kind::DeadEnd, false),
+                (Kind::Corridor, true),
+                (Kind::Corridor, true),
+                (Kind::Isolated, false),
+            ],
+            maze.positions()
+                .map(|pos| maze[pos].data.1)
+                .map(|class| (class.kind, class.in_loop))
+                .collect::<Vec<_>>(),
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! # Errors
+//!
+//! Most operations on mazes that may fail have a fallible variant returning
+//! an [`Error`], in addition to a variant that panics or returns nothing.
+use core::fmt;
+
+use crate::matrix;
+use crate::multi;
+
+/// An error occurring when operating on a maze.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// A position is outside of the maze or matrix.
+    OutOfBounds(matrix::Pos),
+
+    /// No path exists between two rooms.
+    Unreachable {
+        /// The start room.
+        from: matrix::Pos,
+
+        /// The room that could not be reached.
+        to: matrix::Pos,
+    },
+
+    /// No path exists between two rooms of a multi-level maze.
+    UnreachableLevel {
+        /// The start room.
+        from: multi::Pos,
+
+        /// The room that could not be reached.
+        to: multi::Pos,
+    },
+
+    /// A path is incomplete; the room at the position has no successor.
+    IncompletePath(matrix::Pos),
+
+    /// Two rooms expected to be adjacent are not.
+    NotAdjacent(matrix::Pos, matrix::Pos),
+
+    /// A level of a multi-level maze does not exist.
+    MissingLevel(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::OutOfBounds(pos) => {
+                write!(f, "({}, {}) is out of bounds", pos.col, pos.row)
+            }
+            Error::Unreachable { from, to } => write!(
+                f,
+                "({}, {}) is not reachable from ({}, {})",
+                to.col, to.row, from.col, from.row,
+            ),
+            Error::UnreachableLevel { from, to } => write!(
+                f,
+                "({}, {}) on level {} is not reachable from ({}, {}) on level \
+                {}",
+                to.pos.col,
+                to.pos.row,
+                to.level,
+                from.pos.col,
+                from.pos.row,
+                from.level,
+            ),
+            Error::IncompletePath(pos) => {
+                write!(f, "the path is broken at ({}, {})", pos.col, pos.row)
+            }
+            Error::NotAdjacent(pos1, pos2) => write!(
+                f,
+                "({}, {}) and ({}, {}) are not adjacent",
+                pos1.col, pos1.row, pos2.col, pos2.row,
+            ),
+            Error::MissingLevel(level) => {
+                write!(f, "level {} does not exist", level)
+            }
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let from = matrix::Pos { col: 1, row: 2 };
+        let to = matrix::Pos { col: 3, row: -4 };
+        assert_eq!(
+            "(3, -4) is out of bounds",
+            Error::OutOfBounds(to).to_string(),
+        );
+        assert_eq!(
+            "(3, -4) is not reachable from (1, 2)",
+            Error::Unreachable { from, to }.to_string(),
+        );
+        assert_eq!(
+            "(3, -4) on level 1 is not reachable from (1, 2) on level 0",
+            Error::UnreachableLevel {
+                from: multi::Pos {
+                    level: 0,
+                    pos: from
+                },
+                to: multi::Pos { level: 1, pos: to },
+            }
+            .to_string(),
+        );
+        assert_eq!(
+            "the path is broken at (1, 2)",
+            Error::IncompletePath(from).to_string(),
+        );
+        assert_eq!(
+            "(1, 2) and (3, -4) are not adjacent",
+            Error::NotAdjacent(from, to).to_string(),
+        );
+        assert_eq!(
+            "level 3 does not exist",
+            Error::MissingLevel(3).to_string(),
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! # Floating point functions
+//!
+//! Some functions of `f32` are only available when linking with `std`. This
+//! module provides them using _libm_ when building without `std`.
+
+/// Floating point functions not available in `core`.
+pub(crate) trait Float: Sized {
+    /// The square root of the value.
+    fn sqrt(self) -> Self;
+
+    /// The largest integer less than or equal to the value.
+    fn floor(self) -> Self;
+
+    /// The smallest integer greater than or equal to the value.
+    fn ceil(self) -> Self;
+
+    /// The fractional part of the value.
+    fn fract(self) -> Self;
+
+    /// The natural logarithm of one plus the value.
+    fn ln_1p(self) -> Self;
+
+    /// The sine and cosine of the value.
+    fn sin_cos(self) -> (Self, Self);
+}
+
+impl Float for f32 {
+    fn sqrt(self) -> Self {
+        libm::sqrtf(self)
+    }
+
+    fn floor(self) -> Self {
+        libm::floorf(self)
+    }
+
+    fn ceil(self) -> Self {
+        libm::ceilf(self)
+    }
+
+    fn fract(self) -> Self {
+        self - libm::truncf(self)
+    }
+
+    fn ln_1p(self) -> Self {
+        libm::log1pf(self)
+    }
+
+    fn sin_cos(self) -> (Self, Self) {
+        libm::sincosf(self)
+    }
+}
@@ -0,0 +1,99 @@
+//! # Arbitrary instances
+//!
+//! When the `arbitrary` feature is enabled, shapes, positions and mazes can
+//! be generated from unstructured data, which allows fuzzing the API of this
+//! crate.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::matrix;
+use crate::{Maze, Shape};
+
+/// The maximum width and height of arbitrary mazes.
+///
+/// This keeps the time spent on every fuzzing input low.
+pub const MAX_SIZE: usize = 32;
+
+impl<'a> Arbitrary<'a> for Shape {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(*u.choose(&[Shape::Tri, Shape::Quad, Shape::Hex])?)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        u32::size_hint(depth)
+    }
+}
+
+impl<'a> Arbitrary<'a> for matrix::Pos {
+    /// Generates an arbitrary position.
+    ///
+    /// The coordinates are limited to 16 bits, which covers positions both
+    /// inside and outside of arbitrary mazes without overflowing when
+    /// calculating neighbours.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            col: u.arbitrary::<i16>()?.into(),
+            row: u.arbitrary::<i16>()?.into(),
+        })
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <(i16, i16)>::size_hint(depth)
+    }
+}
+
+impl<'a, T> Arbitrary<'a> for Maze<T>
+where
+    T: Arbitrary<'a> + Clone + Default,
+{
+    /// Generates an arbitrary maze.
+    ///
+    /// The width and height are at most [`MAX_SIZE`]. Walls are opened
+    /// through [`Maze::open`], so the maze upholds all invariants checked by
+    /// [`Maze::validate`], except that walls leading out of the maze may be
+    /// open.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let shape = u.arbitrary::<Shape>()?;
+        let width = u.int_in_range(1..=MAX_SIZE)?;
+        let height = u.int_in_range(1..=MAX_SIZE)?;
+
+        let mut maze = Self::new(shape, width, height);
+        for pos in maze.positions() {
+            for &wall in maze.walls(pos) {
+                if u.arbitrary()? {
+                    maze.open((pos, wall));
+                }
+            }
+            if let Some(data) = maze.data_mut(pos) {
+                *data = u.arbitrary()?;
+            }
+        }
+
+        Ok(maze)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::Violation;
+
+    #[test]
+    fn arbitrary_maze() {
+        let data = (0..4096u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect::<Vec<_>>();
+        let mut u = Unstructured::new(&data);
+        for _ in 0..8 {
+            let maze = Maze::<u8>::arbitrary(&mut u).unwrap();
+            assert!((1..=MAX_SIZE).contains(&maze.width()));
+            assert!((1..=MAX_SIZE).contains(&maze.height()));
+            if let Err(violations) = maze.validate() {
+                assert!(violations.iter().all(|violation| matches!(
+                    violation,
+                    Violation::OpenToOutside(_),
+                )));
+            }
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! # Maze graphs
+//!
+//! A maze can be viewed as a graph where rooms are nodes and open walls
+//! between rooms are edges. This module allows exporting a maze to such a
+//! graph, so that arbitrary graph algorithms can be run on it, and creating
+//! a maze from a list of edges.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::matrix;
+use crate::{Error, Maze, Shape};
+
+/// A graph of the rooms of a maze.
+///
+/// The nodes are identified by their index, which corresponds to the order in
+/// which [`Maze::positions`] visits rooms.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Graph {
+    /// The width of the maze.
+    width: usize,
+
+    /// The positions of all rooms, in node order.
+    pub rooms: Vec<matrix::Pos>,
+
+    /// The neighbours of every node.
+    ///
+    /// Only rooms inside of the maze are included.
+    pub adjacency: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    /// The node index of a room.
+    ///
+    /// If the room is not part of the graph, nothing is returned.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn index(&self, pos: matrix::Pos) -> Option<usize> {
+        if pos.col >= 0 && pos.row >= 0 && (pos.col as usize) < self.width {
+            let index = pos.col as usize + pos.row as usize * self.width;
+            (index < self.rooms.len()).then_some(index)
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over all edges of this graph.
+    ///
+    /// Every edge is visited once, as the pair `(a, b)` where `a < b`.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.adjacency
+            .iter()
+            .enumerate()
+            .flat_map(|(a, neighbors)| {
+                neighbors
+                    .iter()
+                    .filter(move |&&b| a < b)
+                    .map(move |&b| (a, b))
+            })
+    }
+}
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// Creates a graph of the rooms of this maze and the open walls between
+    /// them.
+    ///
+    /// Openings leading out of the maze are not included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// let mut maze = maze::Shape::Quad.create::<()>(2, 2);
+    /// let (pos1, pos2) = (
+    ///     matrix::Pos { col: 0, row: 0 },
+    ///     matrix::Pos { col: 1, row: 1 },
+    /// );
+    /// maze.open(maze.connecting_wall(pos1, (1isize, 0isize).into()).unwrap());
+    /// maze.open(maze.connecting_wall((1isize, 0isize).into(), pos2).unwrap());
+    ///
+    /// let graph = maze.to_graph();
+    /// assert_eq!(graph.edges().collect::<Vec<_>>(), vec![(0, 1), (1, 3)]);
+    /// assert_eq!(graph.rooms[graph.index(pos2).unwrap()], pos2);
+    /// ```
+    pub fn to_graph(&self) -> Graph {
+        let rooms = self.positions().collect::<Vec<_>>();
+        let mut graph = Graph {
+            width: self.width(),
+            adjacency: Vec::with_capacity(rooms.len()),
+            rooms,
+        };
+        graph.adjacency = graph
+            .rooms
+            .iter()
+            .map(|&pos| {
+                self.neighbors(pos)
+                    .filter_map(|neighbor| graph.index(neighbor))
+                    .collect()
+            })
+            .collect();
+
+        graph
+    }
+}
+
+impl<T> Maze<T>
+where
+    T: Clone + Default,
+{
+    /// Creates a maze from a list of pairs of connected rooms.
+    ///
+    /// The wall between every pair of rooms is opened, and all other walls
+    /// are left closed. This is the inverse of [`Maze::to_graph`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// let (pos1, pos2) = (
+    ///     matrix::Pos { col: 0, row: 0 },
+    ///     matrix::Pos { col: 1, row: 0 },
+    /// );
+    /// let maze = maze::Maze::<()>::from_edges(
+    ///     maze::Shape::Quad,
+    ///     2,
+    ///     2,
+    ///     [(pos1, pos2)],
+    /// )
+    /// .unwrap();
+    /// assert!(maze.is_open(maze.connecting_wall(pos1, pos2).unwrap()));
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `shape` - The shape of the rooms.
+    /// *  `width` - The width, in rooms, of the maze.
+    /// *  `height` - The height, in rooms, of the maze.
+    /// *  `edges` - The pairs of rooms to connect.
+    ///
+    /// # Errors
+    /// If a room is outside of the maze, [`Error::OutOfBounds`] is returned,
+    /// and if two rooms of a pair are not adjacent, [`Error::NotAdjacent`].
+    pub fn from_edges<I>(
+        shape: Shape,
+        width: usize,
+        height: usize,
+        edges: I,
+    ) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (matrix::Pos, matrix::Pos)>,
+    {
+        let mut maze = Self::new(shape, width, height);
+        for (pos1, pos2) in edges {
+            if let Some(pos) =
+                [pos1, pos2].into_iter().find(|&pos| !maze.is_inside(pos))
+            {
+                return Err(Error::OutOfBounds(pos));
+            }
+            let wall_pos = maze
+                .connecting_wall(pos1, pos2)
+                .ok_or(Error::NotAdjacent(pos1, pos2))?;
+            maze.open(wall_pos);
+        }
+
+        Ok(maze)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn to_graph(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Branching(initialize::Bias::default()),
+            &mut initialize::LFSR::new(12345),
+        );
+        let graph = maze.to_graph();
+
+        assert_eq!(maze.positions().count(), graph.rooms.len());
+
+        // A maze without loops is a spanning tree
+        assert_eq!(graph.rooms.len() - 1, graph.edges().count());
+
+        for (a, b) in graph.edges() {
+            assert!(graph.adjacency[b].contains(&a));
+            assert!(maze
+                .connecting_wall(graph.rooms[a], graph.rooms[b])
+                .is_some_and(|wall_pos| maze.is_open(wall_pos)));
+        }
+    }
+
+    #[maze_test]
+    fn index(maze: TestMaze) {
+        let graph = maze.to_graph();
+        for (i, &pos) in graph.rooms.iter().enumerate() {
+            assert_eq!(Some(i), graph.index(pos));
+        }
+        assert_eq!(None, graph.index(matrix_pos(-1, 0)));
+        assert_eq!(None, graph.index(matrix_pos(maze.width() as isize, 0)));
+        assert_eq!(None, graph.index(matrix_pos(0, maze.height() as isize)));
+    }
+
+    #[maze_test]
+    fn from_edges_round_trip(maze: TestMaze) -> Result<(), Error> {
+        let maze = maze.initialize(
+            initialize::Method::Braid,
+            &mut initialize::LFSR::new(12345),
+        );
+        let graph = maze.to_graph();
+        let copy = Maze::<()>::from_edges(
+            maze.shape(),
+            maze.width(),
+            maze.height(),
+            graph.edges().map(|(a, b)| (graph.rooms[a], graph.rooms[b])),
+        )?;
+        assert_eq!(graph, copy.to_graph());
+        Ok(())
+    }
+
+    #[maze_test]
+    fn from_edges_invalid(maze: TestMaze) {
+        let (width, height) = (maze.width(), maze.height());
+        let outside = matrix_pos(width as isize, 0);
+        assert_eq!(
+            Err(Error::OutOfBounds(outside)),
+            Maze::<()>::from_edges(
+                maze.shape(),
+                width,
+                height,
+                [(matrix_pos(0, 0), outside)],
+            )
+            .map(|_| ()),
+        );
+
+        let (pos1, pos2) = (matrix_pos(0, 0), matrix_pos(2, 2));
+        assert_eq!(
+            Err(Error::NotAdjacent(pos1, pos2)),
+            Maze::<()>::from_edges(maze.shape(), width, height, [(pos1, pos2)])
+                .map(|_| ()),
+        );
+    }
+}
@@ -1,4 +1,5 @@
-use std::collections::BTreeSet;
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
 
 use crate::Maze;
 
@@ -13,10 +14,12 @@ use crate::matrix;
 /// *  `maze``- The maze to initialise.
 /// *  `rng` - A random number generator.
 /// *  `candidates` - A filter for the rooms to modify.
+/// *  `observer` - A function notified of every modified wall.
 pub(crate) fn initialize<R, T>(
     mut maze: Maze<T>,
     rng: &mut R,
     candidates: matrix::Matrix<bool>,
+    observer: &mut dyn FnMut(super::Event),
 ) -> Maze<T>
 where
     R: super::Randomizer + Sized,
@@ -27,7 +30,7 @@ where
         for wall in maze.walls(pos) {
             let (pos, wall) = maze.back((pos, wall));
             if *candidates.get(pos).unwrap_or(&false) {
-                maze.open((pos, wall));
+                super::open(&mut maze, (pos, wall), observer);
             }
         }
     }
@@ -63,13 +66,16 @@ where
     for &wall_pos in walls {
         let back = maze.back(wall_pos);
         if maze[wall_pos.0].open_walls() > 2 && maze[back.0].open_walls() > 2 {
-            maze.close(wall_pos);
+            super::close(&mut maze, wall_pos, observer);
         }
     }
 
-    super::connect_all(&mut maze, rng, |pos| {
-        *candidates.get(pos).unwrap_or(&false)
-    });
+    super::connect_all_with_observer(
+        &mut maze,
+        rng,
+        |pos| *candidates.get(pos).unwrap_or(&false),
+        observer,
+    );
 
     maze
 }
@@ -1,4 +1,7 @@
+use alloc::vec::Vec;
+
 use crate::Maze;
+use crate::WallPos;
 
 use crate::matrix;
 
@@ -8,63 +11,146 @@ use crate::matrix;
 /// *  `maze` - The maze to initialise.
 /// *  `rng` - A random number generator.
 /// *  `candidates` - A filter for the rooms to modify.
+/// *  `start` - The room in which to start, if it is a candidate.
+/// *  `bias` - Parameters tuning the texture.
+/// *  `observer` - A function notified of every modified wall.
 pub(crate) fn initialize<R, T>(
     mut maze: Maze<T>,
     rng: &mut R,
-    mut candidates: matrix::Matrix<bool>,
+    candidates: matrix::Matrix<bool>,
+    start: Option<matrix::Pos>,
+    bias: super::Bias,
+    observer: &mut dyn FnMut(super::Event),
 ) -> Maze<T>
 where
     R: super::Randomizer + Sized,
     T: Clone,
 {
-    loop {
-        // Start with all walls in a random room, except for those leading
-        // out of the maze
-        let mut walls = super::random_room(rng, &candidates)
-            // Get all walls not leading out of the maze
-            .map(|pos| {
-                maze.walls(pos)
-                    .iter()
-                    .filter(|wall| maze.is_inside(maze.back((pos, wall)).0))
-                    // Create a wall position
-                    .map(|wall| (pos, *wall))
-                    .collect::<Vec<_>>()
-            })
-            .unwrap_or_default();
+    let mut state = State::new(candidates, start, bias);
+    while state.step(&mut maze, rng, observer) {}
 
-        while !walls.is_empty() {
-            // Get a random wall
-            let index = rng.range(0, walls.len());
-            let wall_pos = walls.remove(index);
+    maze
+}
 
-            // Walk through the wall if we have not visited the room on the
-            // other side before
-            let (next_pos, _) = maze.back(wall_pos);
-            if candidates[next_pos] {
-                // Mark the rooms as visited and open the door
-                candidates[wall_pos.0] = false;
-                candidates[next_pos] = false;
-                maze.open(wall_pos);
+/// The state of an ongoing initialisation using the _Randomised Prim_
+/// algorithm.
+pub(crate) struct State {
+    /// The rooms not yet visited.
+    candidates: super::Candidates,
 
-                // Add all walls of the next room except those already
-                // visited and those outside of the maze
-                walls.extend(
-                    maze.walls(next_pos)
-                        .iter()
-                        .map(|w| maze.back((next_pos, w)))
-                        .filter(|&(pos, _)| {
-                            *candidates.get(pos).unwrap_or(&false)
-                        })
-                        .map(|wall_pos| maze.back(wall_pos))
-                        .filter(|&(pos, _)| candidates.is_inside(pos)),
-                );
-            }
-        }
+    /// The room in which to start, until the first room has been selected.
+    start: Option<matrix::Pos>,
 
-        if candidates.values().all(|v| !v) {
-            break;
+    /// The walls leading from visited rooms to rooms that may not have been
+    /// visited.
+    walls: Vec<WallPos>,
+
+    /// Parameters tuning the texture.
+    bias: super::Bias,
+}
+
+impl State {
+    /// Prepares an initialisation.
+    ///
+    /// # Arguments
+    /// *  `candidates` - A filter for the rooms to modify. At least one room
+    ///    must be a candidate.
+    /// *  `start` - The room in which to start, if it is a candidate.
+    /// *  `bias` - Parameters tuning the texture.
+    pub fn new(
+        candidates: matrix::Matrix<bool>,
+        start: Option<matrix::Pos>,
+        bias: super::Bias,
+    ) -> Self {
+        let candidates = super::Candidates::from(candidates);
+        let start = start.filter(|&pos| candidates.contains(pos));
+        Self {
+            candidates,
+            start,
+            walls: Vec::new(),
+            bias,
         }
     }
 
-    maze
+    /// Performs a single iteration of the algorithm.
+    ///
+    /// An iteration either selects a new room from which to start, or
+    /// considers a single wall. The return value is whether any iterations
+    /// remain.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to initialise.
+    /// *  `rng` - A random number generator.
+    /// *  `observer` - A function notified of every modified wall.
+    pub fn step<R, T>(
+        &mut self,
+        maze: &mut Maze<T>,
+        rng: &mut R,
+        observer: &mut dyn FnMut(super::Event),
+    ) -> bool
+    where
+        R: super::Randomizer + Sized,
+        T: Clone,
+    {
+        let Self {
+            candidates,
+            start,
+            walls,
+            bias,
+        } = self;
+
+        if walls.is_empty() {
+            // Start with all walls in the requested room, or in a random
+            // room, except for those leading out of the maze
+            return match start.take().or_else(|| candidates.random(rng)) {
+                Some(pos) => {
+                    // A room without any candidate neighbours would
+                    // otherwise be selected again forever
+                    candidates.remove(pos);
+                    walls.extend(
+                        maze.walls(pos)
+                            .iter()
+                            .filter(|wall| {
+                                maze.is_inside(maze.back((pos, wall)).0)
+                            })
+                            // Create a wall position
+                            .map(|wall| (pos, *wall)),
+                    );
+                    true
+                }
+                None => false,
+            };
+        }
+
+        // Get a random wall, or occasionally the most recently added one
+        let index = if bias.branches(rng) == Some(false) {
+            walls.len() - 1
+        } else {
+            bias.select(rng, walls.len(), |index| walls[index].1.dir.1 == 0)
+        };
+        let wall_pos = walls.remove(index);
+
+        // Walk through the wall if we have not visited the room on the other
+        // side before
+        let (next_pos, _) = maze.back(wall_pos);
+        if candidates.contains(next_pos) {
+            // Mark the rooms as visited and open the door
+            candidates.remove(wall_pos.0);
+            candidates.remove(next_pos);
+            super::open(maze, wall_pos, observer);
+
+            // Add all walls of the next room except those already visited and
+            // those outside of the maze
+            walls.extend(
+                maze.walls(next_pos)
+                    .iter()
+                    .map(|w| maze.back((next_pos, w)))
+                    .filter(|&(pos, _)| candidates.contains(pos))
+                    .map(|wall_pos| maze.back(wall_pos))
+                    .filter(|&(pos, _)| maze.is_inside(pos)),
+            );
+        }
+
+        !walls.is_empty() || !candidates.is_empty()
+    }
 }
@@ -0,0 +1,198 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::matrix;
+
+use super::Randomizer;
+
+/// A set of rooms that an initialiser may still modify.
+///
+/// The positions of all candidates are collected once, and rooms are then
+/// selected at random by index in _O(log n)_ time. This keeps initialisers
+/// linear in the number of rooms even when they select a new random room
+/// many times, as they do for segmented mazes.
+///
+/// A random room is the _n_th remaining room in row order, for a random _n_,
+/// so the room selected for a specific random value is the same as when
+/// scanning the rooms.
+pub(crate) struct Candidates {
+    /// The positions of all rooms that were initially candidates, in row
+    /// order.
+    positions: Vec<matrix::Pos>,
+
+    /// The index in `positions` of every remaining candidate.
+    indices: matrix::Matrix<Option<usize>>,
+
+    /// A _Fenwick tree_ of the number of remaining candidates, indexed by
+    /// the index in `positions` plus one.
+    tree: Vec<usize>,
+
+    /// The number of remaining candidates.
+    count: usize,
+}
+
+impl Candidates {
+    /// Whether a room is a candidate.
+    ///
+    /// Rooms outside of the matrix are never candidates.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn contains(&self, pos: matrix::Pos) -> bool {
+        self.indices.get(pos).is_some_and(|index| index.is_some())
+    }
+
+    /// Whether no candidates remain.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Removes a room from the set of candidates.
+    ///
+    /// Rooms that are not candidates are ignored.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn remove(&mut self, pos: matrix::Pos) {
+        if let Some(index) = self.indices.get_mut(pos).and_then(Option::take) {
+            self.count -= 1;
+
+            let mut i = index + 1;
+            while i < self.tree.len() {
+                self.tree[i] -= 1;
+                i += i & i.wrapping_neg();
+            }
+        }
+    }
+
+    /// Selects a random candidate.
+    ///
+    /// If no candidates remain, nothing is returned, and no random value is
+    /// consumed.
+    ///
+    /// # Arguments
+    /// *  `rng` - A random number generator.
+    pub fn random(&self, rng: &mut dyn Randomizer) -> Option<matrix::Pos> {
+        if self.is_empty() {
+            return None;
+        }
+
+        // Descend the tree to find the last index preceded by fewer than
+        // n + 1 candidates
+        let mut remaining = rng.range(0, self.count);
+        let mut index = 0;
+        let mut step = self.positions.len().next_power_of_two();
+        while step > 0 {
+            let next = index + step;
+            if next < self.tree.len() && self.tree[next] <= remaining {
+                index = next;
+                remaining -= self.tree[next];
+            }
+            step >>= 1;
+        }
+
+        Some(self.positions[index])
+    }
+}
+
+impl From<matrix::Matrix<bool>> for Candidates {
+    fn from(matrix: matrix::Matrix<bool>) -> Self {
+        let positions = matrix
+            .positions()
+            .filter(|&pos| matrix[pos])
+            .collect::<Vec<_>>();
+        let mut indices = matrix::Matrix::new(matrix.width, matrix.height);
+        for (index, &pos) in positions.iter().enumerate() {
+            indices[pos] = Some(index);
+        }
+
+        // Build the tree in place by propagating every node to its parent
+        let mut tree = vec![1; positions.len() + 1];
+        tree[0] = 0;
+        for i in 1..tree.len() {
+            let parent = i + (i & i.wrapping_neg());
+            if parent < tree.len() {
+                tree[parent] += tree[i];
+            }
+        }
+
+        Self {
+            count: positions.len(),
+            positions,
+            indices,
+            tree,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::initialize::LFSR;
+
+    #[test]
+    fn random_matches_scan() {
+        let (width, height) = (7, 5);
+        let (_, matrix) =
+            matrix::filter(width, height, |pos| (pos.col + pos.row) % 3 != 0);
+        let mut candidates = Candidates::from(matrix.clone());
+        let mut remaining = matrix;
+
+        let (mut rng1, mut rng2) = (LFSR::new(12345), LFSR::new(12345));
+        while let Some(pos) = candidates.random(&mut rng1) {
+            let expected = remaining
+                .positions()
+                .filter(|&pos| remaining[pos])
+                .collect::<Vec<_>>();
+            assert_eq!(expected[rng2.range(0, expected.len())], pos);
+
+            candidates.remove(pos);
+            remaining[pos] = false;
+            assert!(!candidates.contains(pos));
+        }
+        assert!(candidates.is_empty());
+        assert!(remaining.values().all(|&v| !v));
+    }
+
+    #[test]
+    fn random_none() {
+        let (_, matrix) = matrix::filter(5, 5, |_| false);
+        let candidates = Candidates::from(matrix);
+        let mut rng = LFSR::new(12345);
+        for _ in 0..100 {
+            assert!(candidates.random(&mut rng).is_none());
+        }
+    }
+
+    #[test]
+    fn random_distribution() {
+        let (width, height) = (5, 5);
+        let (_, matrix) =
+            matrix::filter(width, height, |pos| pos.col as usize == width - 1);
+        let candidates = Candidates::from(matrix);
+        let mut rng = LFSR::new(12345);
+
+        let buckets = height;
+        let iterations = 100 * 100 * buckets;
+        let hist = (0..iterations).fold(vec![0; buckets], |mut hist, _| {
+            hist[candidates.random(&mut rng).unwrap().row as usize] += 1;
+            hist
+        });
+
+        let mid = iterations / buckets;
+        let h = 400;
+        for v in hist {
+            assert!(mid - h < v && v < mid + h);
+        }
+    }
+
+    #[test]
+    fn remove_outside() {
+        let (_, matrix) = matrix::filter(2, 2, |_| true);
+        let mut candidates = Candidates::from(matrix);
+        candidates.remove(matrix::Pos { col: -1, row: 0 });
+        candidates.remove(matrix::Pos { col: 0, row: 0 });
+        candidates.remove(matrix::Pos { col: 0, row: 0 });
+        assert_eq!(3, candidates.count);
+    }
+}
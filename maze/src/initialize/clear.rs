@@ -8,10 +8,12 @@ use crate::matrix;
 /// *  `maze``- The maze to initialise.
 /// *  `_rng` - Not used.
 /// *  `candidates` - A filter for the rooms to modify.
+/// *  `observer` - A function notified of every modified wall.
 pub(crate) fn initialize<R, T>(
     mut maze: Maze<T>,
     _rng: &mut R,
     candidates: matrix::Matrix<bool>,
+    observer: &mut dyn FnMut(super::Event),
 ) -> Maze<T>
 where
     R: super::Randomizer + Sized,
@@ -21,7 +23,7 @@ where
         for wall in maze.walls(pos) {
             let (pos, wall) = maze.back((pos, wall));
             if *candidates.get(pos).unwrap_or(&false) {
-                maze.open((pos, wall));
+                super::open(&mut maze, (pos, wall), observer);
             }
         }
     }
@@ -0,0 +1,487 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::matrix;
+use crate::physical;
+use crate::Maze;
+
+use super::{connect_all_with_observer, Bias, Event, Method, Randomizer};
+
+/// A strategy for assigning rooms to the methods of a mixed initialisation.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Areas {
+    /// Every method is assigned a random, weighted point, and every room is
+    /// assigned to the method whose point is closest, which yields a Voronoi
+    /// diagram.
+    #[default]
+    Voronoi,
+
+    /// The maze is split into horizontal bands of equal height.
+    Rows,
+
+    /// The maze is split into vertical bands of equal width.
+    Columns,
+}
+
+/// A mixed initialisation, applying several methods to separate areas of a
+/// maze.
+///
+/// After every area has been initialised, the areas are connected to each
+/// other.
+///
+/// # Example
+///
+/// ```
+/// # use maze::initialize::*;
+/// let mixed = "braid,winding".parse::<Mixed>().unwrap();
+/// let maze = mixed.initialize(
+///     maze::Shape::Hex.create::<()>(10, 10),
+///     &mut LFSR::new(12345),
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Mixed {
+    /// The initialisation methods to use.
+    pub methods: Vec<Method>,
+
+    /// The strategy used to assign rooms to methods.
+    pub areas: Areas,
+}
+
+impl Mixed {
+    /// Creates a mixed initialisation using the default area strategy.
+    ///
+    /// # Arguments
+    /// *  `methods` - The initialisation methods to use.
+    pub fn new(methods: Vec<Method>) -> Self {
+        Self {
+            methods,
+            areas: Areas::default(),
+        }
+    }
+
+    /// Assigns every room of a maze to one of the methods.
+    ///
+    /// The values of the returned matrix are indices in `methods`.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to split.
+    /// *  `rng` - A random number generator.
+    pub fn areas<R, T>(
+        &self,
+        maze: &Maze<T>,
+        rng: &mut R,
+    ) -> matrix::Matrix<usize>
+    where
+        R: Randomizer + Sized,
+        T: Clone,
+    {
+        let count = self.methods.len().max(1);
+        let (width, height) = (maze.width(), maze.height());
+        match self.areas {
+            Areas::Voronoi => {
+                let viewbox = maze.viewbox();
+                let points = (0..count)
+                    .map(|_| {
+                        (
+                            physical::Pos {
+                                x: viewbox.corner.x
+                                    + rng.random() as f32 * viewbox.width,
+                                y: viewbox.corner.y
+                                    + rng.random() as f32 * viewbox.height,
+                            },
+                            (rng.random() as f32) + 0.5,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                voronoi(maze, &points)
+            }
+            Areas::Rows => {
+                matrix::Matrix::new_with_data(width, height, |pos| {
+                    pos.row as usize * count / height
+                })
+            }
+            Areas::Columns => {
+                matrix::Matrix::new_with_data(width, height, |pos| {
+                    pos.col as usize * count / width
+                })
+            }
+        }
+    }
+
+    /// Initialises a maze by applying all methods to their areas.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to initialise.
+    /// *  `rng` - A random number generator.
+    pub fn initialize<R, T>(&self, maze: Maze<T>, rng: &mut R) -> Maze<T>
+    where
+        R: Randomizer + Sized,
+        T: Clone,
+    {
+        let areas = self.areas(&maze, rng);
+        self.initialize_with_areas(maze, rng, &areas, |_| true, |_| {})
+    }
+
+    /// Initialises a maze by applying all methods to predetermined areas,
+    /// and reports every modification to an observer.
+    ///
+    /// Rooms assigned to an index not corresponding to a method are left
+    /// untouched, but are still connected to the rest of the maze unless
+    /// excluded by `filter`.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to initialise.
+    /// *  `rng` - A random number generator.
+    /// *  `areas` - The index of the method to use for every room.
+    /// *  `filter` - An additional filter applied to all methods.
+    /// *  `observer` - A function notified of every modified wall.
+    pub fn initialize_with_areas<R, T, F, O>(
+        &self,
+        maze: Maze<T>,
+        rng: &mut R,
+        areas: &matrix::Matrix<usize>,
+        filter: F,
+        mut observer: O,
+    ) -> Maze<T>
+    where
+        R: Randomizer + Sized,
+        T: Clone,
+        F: Fn(matrix::Pos) -> bool,
+        O: FnMut(Event),
+    {
+        // Use a different initialisation method for each area
+        let mut maze =
+            self.methods
+                .iter()
+                .enumerate()
+                .fold(maze, |maze, (i, &method)| {
+                    maze.initialize_filter_with_observer(
+                        method,
+                        rng,
+                        |pos| filter(pos) && areas[pos] == i,
+                        &mut observer,
+                    )
+                });
+
+        // Make sure all areas are connected
+        connect_all_with_observer(&mut maze, rng, filter, &mut observer);
+
+        maze
+    }
+}
+
+impl fmt::Display for Mixed {
+    /// The opposite of [`str::FromStr`].
+    ///
+    /// The methods are formatted as a comma separated list.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, method) in self.methods.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", method)?;
+        }
+        Ok(())
+    }
+}
+
+impl str::FromStr for Mixed {
+    type Err = String;
+
+    /// Converts a comma separated list of methods to a mixed initialisation
+    /// using the default area strategy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use maze::initialize::*;
+    ///
+    /// assert_eq!(
+    ///     "braid,winding".parse(),
+    ///     Ok(Mixed::new(vec![
+    ///         Method::Braid,
+    ///         Method::Winding(Bias::default()),
+    ///     ])),
+    /// );
+    /// ```
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        source
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self::new)
+    }
+}
+
+/// Assigns every room of a maze to the closest of a set of weighted points.
+///
+/// The distance to a point is divided by its weight, so points with larger
+/// weights cover larger areas. The values of the returned matrix are indices
+/// in `points`.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+/// *  `points` - The points and their weights.
+pub fn voronoi<T>(
+    maze: &Maze<T>,
+    points: &[(physical::Pos, f32)],
+) -> matrix::Matrix<usize>
+where
+    T: Clone,
+{
+    matrix::Matrix::new_with_data(maze.width(), maze.height(), |pos| {
+        let center = maze.center(pos);
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, &(p, w))| ((p - center).value() / w, i))
+            // We assume that that the weights are not exotic enough to cause
+            // this to fail
+            .min_by(|v1, v2| v1.0.partial_cmp(&v2.0).unwrap())
+            .map(|(_, i)| i)
+            .unwrap_or(0)
+    })
+}
+
+/// Initialises a maze using a separate method for every region.
+///
+/// Every room is initialised using the method of the corresponding cell in
+/// `regions`, and the regions are then connected to each other. Rooms outside
+/// of `regions` are left untouched, but are still connected to the rest of
+/// the maze.
+///
+/// # Arguments
+/// *  `maze` - The maze to initialise.
+/// *  `regions` - The method to use for every room.
+/// *  `rng` - A random number generator.
+///
+/// # Example
+///
+/// ```
+/// # use maze::initialize::*;
+/// # use maze::matrix;
+/// let maze = maze::Shape::Quad.create::<()>(10, 10);
+/// let regions = matrix::Matrix::new_with_data(10, 10, |pos| {
+///     if pos.col < 5 {
+///         Method::Braid
+///     } else {
+///         Method::Winding(Bias::default())
+///     }
+/// });
+/// let maze = by_regions(maze, &regions, &mut LFSR::new(12345));
+/// ```
+pub fn by_regions<R, T>(
+    maze: Maze<T>,
+    regions: &matrix::Matrix<Method>,
+    rng: &mut R,
+) -> Maze<T>
+where
+    R: Randomizer + Sized,
+    T: Clone,
+{
+    // Assign an index to every distinct method, in order of first appearance
+    let mut methods = Vec::new();
+    for &method in regions.values() {
+        if !methods.contains(&method) {
+            methods.push(method);
+        }
+    }
+    let areas =
+        matrix::Matrix::new_with_data(maze.width(), maze.height(), |pos| {
+            regions
+                .get(pos)
+                .and_then(|method| methods.iter().position(|m| m == method))
+                .unwrap_or(usize::MAX)
+        });
+
+    Mixed::new(methods).initialize_with_areas(
+        maze,
+        rng,
+        &areas,
+        |_| true,
+        |_| {},
+    )
+}
+
+/// Initialises a maze with corridors following a skeleton.
+///
+/// Every room covered by `skeleton` is initialised using
+/// [`Method::Winding`], and the remaining rooms using `method`. Since the
+/// skeleton rooms are initialised separately, every room of a connected part
+/// of the skeleton can be reached from every other room of the same part
+/// without ever leaving the skeleton. This makes it possible to hide a shape,
+/// such as a logo, as paths that must be travelled rather than just as
+/// colours. The areas are then connected to each other.
+///
+/// The skeleton is typically of a lower resolution than the maze, for example
+/// a thresholded image, and is scaled to cover the entire maze.
+///
+/// # Arguments
+/// *  `maze` - The maze to initialise.
+/// *  `skeleton` - The rooms through which corridors must pass.
+/// *  `method` - The method used for rooms not covered by the skeleton.
+/// *  `rng` - A random number generator.
+///
+/// # Example
+///
+/// ```
+/// # use maze::initialize::*;
+/// # use maze::matrix;
+/// // A cross in the middle of the maze
+/// let skeleton =
+///     matrix::Matrix::new_with_data(5, 5, |pos| pos.col == 2 || pos.row == 2);
+/// let maze = by_skeleton(
+///     maze::Shape::Quad.create::<()>(10, 10),
+///     &skeleton,
+///     Method::Braid,
+///     &mut LFSR::new(12345),
+/// );
+/// ```
+pub fn by_skeleton<R, T>(
+    maze: Maze<T>,
+    skeleton: &matrix::Matrix<bool>,
+    method: Method,
+    rng: &mut R,
+) -> Maze<T>
+where
+    R: Randomizer + Sized,
+    T: Clone,
+{
+    let (width, height) = (maze.width(), maze.height());
+    let areas = matrix::Matrix::new_with_data(width, height, |pos| {
+        let cell = matrix::Pos {
+            col: pos.col * skeleton.width as isize / width as isize,
+            row: pos.row * skeleton.height as isize / height as isize,
+        };
+        if skeleton.get(cell).copied().unwrap_or(false) {
+            0
+        } else {
+            1
+        }
+    });
+
+    Mixed::new(vec![Method::Winding(Bias::default()), method])
+        .initialize_with_areas(maze, rng, &areas, |_| true, |_| {})
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize::LFSR;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn initialize_connected(maze: TestMaze) {
+        for areas in [Areas::Voronoi, Areas::Rows, Areas::Columns] {
+            let mixed = Mixed {
+                methods: vec![
+                    Method::Braid,
+                    Method::Winding(Bias::default()),
+                    Method::Clear,
+                ],
+                areas,
+            };
+            let maze = mixed.initialize(maze.clone(), &mut LFSR::new(12345));
+            let from = matrix_pos(0, 0);
+            for pos in maze.positions() {
+                assert!(maze.walk(from, pos).is_some());
+            }
+        }
+    }
+
+    #[maze_test]
+    fn areas_bands(maze: TestMaze) {
+        let mixed = Mixed {
+            methods: vec![Method::Braid, Method::Winding(Bias::default())],
+            areas: Areas::Rows,
+        };
+        let areas = mixed.areas(&maze, &mut LFSR::new(12345));
+        assert_eq!(0, areas[matrix_pos(0, 0)]);
+        assert_eq!(1, areas[matrix_pos(0, maze.height() as isize - 1)]);
+
+        let mixed = Mixed {
+            areas: Areas::Columns,
+            ..mixed
+        };
+        let areas = mixed.areas(&maze, &mut LFSR::new(12345));
+        assert_eq!(0, areas[matrix_pos(0, 0)]);
+        assert_eq!(1, areas[matrix_pos(maze.width() as isize - 1, 0)]);
+    }
+
+    #[maze_test]
+    fn by_regions_connected(maze: TestMaze) {
+        let regions =
+            matrix::Matrix::new_with_data(maze.width(), maze.height(), |pos| {
+                if pos.col % 2 == 0 {
+                    Method::Branching(Bias::default())
+                } else {
+                    Method::Clear
+                }
+            });
+        let maze = by_regions(maze, &regions, &mut LFSR::new(12345));
+        let from = matrix_pos(0, 0);
+        for pos in maze.positions() {
+            assert!(maze.walk(from, pos).is_some());
+        }
+    }
+
+    #[maze_test]
+    fn by_skeleton_corridors(maze: TestMaze) {
+        // A frame and a diagonal line, at half the resolution of the maze
+        let (width, height) = (maze.width() / 2, maze.height() / 2);
+        let skeleton = matrix::Matrix::new_with_data(width, height, |pos| {
+            pos.col == 0
+                || pos.row == 0
+                || pos.col == width as isize - 1
+                || pos.row == height as isize - 1
+                || pos.col == pos.row
+        });
+        let rooms =
+            matrix::Matrix::new_with_data(maze.width(), maze.height(), |pos| {
+                skeleton[matrix_pos(
+                    pos.col * width as isize / maze.width() as isize,
+                    pos.row * height as isize / maze.height() as isize,
+                )]
+            });
+
+        for method in [Method::Braid, Method::Branching(Bias::default())] {
+            let maze = by_skeleton(
+                maze.clone(),
+                &skeleton,
+                method,
+                &mut LFSR::new(12345),
+            );
+
+            // All skeleton rooms are reachable through the skeleton
+            let start = matrix_pos(0, 0);
+            let mut reached = matrix::Matrix::new(maze.width(), maze.height());
+            reached.fill(start, true, |pos| {
+                maze.neighbors(pos)
+                    .filter(|&next| rooms.get(next).copied().unwrap_or(false))
+            });
+            for pos in maze.positions() {
+                assert_eq!(rooms[pos], reached[pos], "for {:?}", method);
+                assert!(maze.walk(start, pos).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn display() {
+        let mixed =
+            Mixed::new(vec![Method::Braid, Method::Winding(Bias::default())]);
+        assert_eq!(Ok(mixed.clone()), mixed.to_string().parse());
+        assert!("braid,unknown".parse::<Mixed>().is_err());
+    }
+}
@@ -3,19 +3,37 @@
 //! This module contains implementations of initialisation methods. These are
 //! used to open walls in a fully closed maze to make it navigable.
 
-use std::iter;
-use std::str;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::iter;
+use core::str;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use crate::Maze;
+use crate::WallPos;
 
 use crate::matrix;
 
 mod braid;
 mod branching;
+mod candidates;
+use self::candidates::Candidates;
 mod clear;
+mod mixed;
+pub use self::mixed::{by_regions, by_skeleton, voronoi, Areas, Mixed};
+mod pcg;
+pub use self::pcg::Pcg32;
+mod solution;
+pub use self::solution::{
+    carve_through, carve_through_with_observer, solution_through,
+};
+mod splitmix;
+pub use self::splitmix::SplitMix64;
+mod stepped;
+pub use self::stepped::{Initializer, Status};
 mod winding;
 
 /// The various supported initialisation method.
@@ -43,7 +61,7 @@ pub enum Method {
     /// See [Wikipedia] for a description of the algorithm.
     ///
     /// [Wikipedia]: https://en.wikipedia.org/wiki/Maze_generation_algorithm#Randomized_Prim's_algorithm
-    Branching,
+    Branching(Bias),
 
     /// Initialises a maze using a winding algorithm.
     ///
@@ -54,17 +72,178 @@ pub enum Method {
     /// See [Wikipedia] for a description of the algorithm.
     ///
     /// [Wikipedia]: https://en.wikipedia.org/wiki/Maze_generation_algorithm#Depth-first_search
-    Winding,
+    Winding(Bias),
+}
+
+/// Parameters tuning the texture of mazes generated by a method.
+///
+/// The default parameters leave the characteristic texture of a method
+/// unchanged.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Bias {
+    /// The preference for horizontal passages over vertical ones, as a
+    /// percentage.
+    ///
+    /// At `50` there is no preference. Higher values yield long horizontal
+    /// passages, like rivers, and lower values long vertical ones. Values
+    /// above `100` are treated as `100`.
+    pub horizontal: u8,
+
+    /// The probability, as a percentage, of carving onward from a random
+    /// visited room instead of from the most recently visited one.
+    ///
+    /// If not specified, this is `0` for [`Method::Winding`], which never
+    /// branches until it reaches a dead end, and `100` for
+    /// [`Method::Branching`]. Values above `100` are treated as `100`.
+    pub branch: Option<u8>,
+}
+
+impl Default for Bias {
+    fn default() -> Self {
+        Self {
+            horizontal: 50,
+            branch: None,
+        }
+    }
+}
+
+impl Bias {
+    /// Randomly selects the index of the next step among a number of
+    /// candidate steps.
+    ///
+    /// Without a horizontal preference, all candidates are equally likely.
+    ///
+    /// # Arguments
+    /// *  `rng` - A random number generator.
+    /// *  `count` - The number of candidates. This must not be `0`.
+    /// *  `is_horizontal` - Whether the step with a specific index stays on
+    ///    the same row.
+    fn select<R, F>(self, rng: &mut R, count: usize, is_horizontal: F) -> usize
+    where
+        R: Randomizer + Sized,
+        F: Fn(usize) -> bool,
+    {
+        let horizontal = usize::from(self.horizontal.min(100));
+        if horizontal == 50 {
+            return rng.range(0, count);
+        }
+
+        let weight = |index| {
+            if is_horizontal(index) {
+                horizontal
+            } else {
+                100 - horizontal
+            }
+        };
+        let total = (0..count).map(weight).sum::<usize>();
+        if total == 0 {
+            return rng.range(0, count);
+        }
+
+        let mut target = rng.range(0, total);
+        (0..count)
+            .find(|&index| {
+                let weight = weight(index);
+                if target < weight {
+                    true
+                } else {
+                    target -= weight;
+                    false
+                }
+            })
+            .unwrap()
+    }
+
+    /// Randomly decides whether to branch, if a branch probability is
+    /// specified.
+    ///
+    /// The random number generator is only used if a probability is
+    /// specified, so mazes generated with default parameters remain
+    /// unchanged.
+    ///
+    /// # Arguments
+    /// *  `rng` - A random number generator.
+    fn branches<R>(self, rng: &mut R) -> Option<bool>
+    where
+        R: Randomizer + Sized,
+    {
+        self.branch
+            .map(|branch| rng.range(0, 100) < usize::from(branch))
+    }
+}
+
+impl core::fmt::Display for Bias {
+    /// Formats the parameters that differ from the defaults, each preceded by
+    /// a colon.
+    ///
+    /// Percentages above `100` are formatted as `100`, which has the same
+    /// effect, so that the result can always be parsed.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let default = Self::default();
+        let horizontal = self.horizontal.min(100);
+        if horizontal != default.horizontal {
+            write!(f, ":horizontal={}", horizontal)?;
+        }
+        if let Some(branch) = self.branch {
+            write!(f, ":branch={}", branch.min(100))?;
+        }
+        Ok(())
+    }
+}
+
+impl str::FromStr for Bias {
+    type Err = String;
+
+    /// Parses a colon separated list of parameters on the form
+    /// `name=value`.
+    ///
+    /// Parameters not present keep their default values.
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut result = Self::default();
+        for part in source.split(':').filter(|part| !part.is_empty()) {
+            let value = |value: &str| {
+                value
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|&value| value <= 100)
+                    .ok_or_else(|| part.to_owned())
+            };
+            match part.split_once('=') {
+                Some(("horizontal", v)) => result.horizontal = value(v)?,
+                Some(("branch", v)) => result.branch = Some(value(v)?),
+                _ => return Err(part.to_owned()),
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// An event reported while initialising a maze.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    /// A wall was opened.
+    Opened(WallPos),
+
+    /// A wall was closed.
+    Closed(WallPos),
+
+    /// A room was visited for the first time.
+    ///
+    /// This is reported after the event for the wall whose opening caused
+    /// the room to be visited.
+    Visited(matrix::Pos),
 }
 
 impl Default for Method {
-    /// The default initialisation method is [`Branching`](Method::Branchin).
+    /// The default initialisation method is [`Branching`](Method::Branching)
+    /// with default parameters.
     fn default() -> Self {
-        Method::Branching
+        Method::Branching(Bias::default())
     }
 }
 
-impl std::fmt::Display for Method {
+impl core::fmt::Display for Method {
     /// The opposite of [std::str::FromStr].
     ///
     /// # Examples
@@ -77,25 +256,33 @@ impl std::fmt::Display for Method {
     ///     Ok(Method::Braid),
     /// );
     /// assert_eq!(
-    ///     Method::Branching.to_string().parse::<Method>(),
-    ///     Ok(Method::Branching),
+    ///     Method::Branching(Bias::default()).to_string().parse::<Method>(),
+    ///     Ok(Method::Branching(Bias::default())),
     /// );
     /// assert_eq!(
     ///     Method::Clear.to_string().parse::<Method>(),
     ///     Ok(Method::Clear),
     /// );
     /// assert_eq!(
-    ///     Method::Winding.to_string().parse::<Method>(),
-    ///     Ok(Method::Winding),
+    ///     Method::Winding(Bias::default()).to_string().parse::<Method>(),
+    ///     Ok(Method::Winding(Bias::default())),
+    /// );
+    /// let bias = Bias {
+    ///     horizontal: 80,
+    ///     branch: Some(10),
+    /// };
+    /// assert_eq!(
+    ///     Method::Winding(bias).to_string().parse::<Method>(),
+    ///     Ok(Method::Winding(bias)),
     /// );
     /// ```
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         use Method::*;
         match self {
             Braid => write!(f, "braid"),
             Clear => write!(f, "clear"),
-            Branching => write!(f, "branching"),
-            Winding => write!(f, "winding"),
+            Branching(bias) => write!(f, "branching{}", bias),
+            Winding(bias) => write!(f, "winding{}", bias),
         }
     }
 }
@@ -106,7 +293,9 @@ impl str::FromStr for Method {
     /// Converts a string to an initialiser.
     ///
     /// The source strings are the lower case names of the initialisation
-    /// methods.
+    /// methods. The names of methods accepting a [`Bias`] may be followed by
+    /// parameters on the form `:name=value`, for example
+    /// `winding:horizontal=80:branch=10`.
     ///
     /// # Examples
     ///
@@ -119,7 +308,7 @@ impl str::FromStr for Method {
     /// );
     /// assert_eq!(
     ///     "branching".parse::<Method>(),
-    ///     Ok(Method::Branching),
+    ///     Ok(Method::Branching(Bias::default())),
     /// );
     /// assert_eq!(
     ///     "clear".parse::<Method>(),
@@ -127,16 +316,24 @@ impl str::FromStr for Method {
     /// );
     /// assert_eq!(
     ///     "winding".parse::<Method>(),
-    ///     Ok(Method::Winding),
+    ///     Ok(Method::Winding(Bias::default())),
+    /// );
+    /// assert_eq!(
+    ///     "winding:horizontal=80".parse::<Method>(),
+    ///     Ok(Method::Winding(Bias {
+    ///         horizontal: 80,
+    ///         ..Default::default()
+    ///     })),
     /// );
     /// ```
     fn from_str(source: &str) -> Result<Self, Self::Err> {
-        match source {
-            "braid" => Ok(Method::Braid),
-            "clear" => Ok(Method::Clear),
-            "branching" => Ok(Method::Branching),
-            "winding" => Ok(Method::Winding),
-            e => Err(e.to_owned()),
+        let (name, bias) = source.split_once(':').unwrap_or((source, ""));
+        match name {
+            "braid" if bias.is_empty() => Ok(Method::Braid),
+            "clear" if bias.is_empty() => Ok(Method::Clear),
+            "branching" => bias.parse().map(Method::Branching),
+            "winding" => bias.parse().map(Method::Winding),
+            _ => Err(source.to_owned()),
         }
     }
 }
@@ -172,7 +369,50 @@ where
     }
 }
 
+/// Generates an unbiased random value in the range `[low, high)`, where
+/// `low` and `high` are the low and high values of `a` and `b`.
+///
+/// Values from `next` that would favour the low end of the range are
+/// rejected, so every value in the range is equally likely.
+///
+/// # Arguments
+/// *  `a` - A number.
+/// *  `b` - A number.
+/// *  `next` - A source of uniformly distributed 64 bit values.
+fn unbiased_range<F>(a: usize, b: usize, mut next: F) -> usize
+where
+    F: FnMut() -> u64,
+{
+    let (low, high) = if a < b { (a, b) } else { (b, a) };
+    let span = (high - low) as u64;
+    if span == 0 {
+        return low;
+    }
+
+    // The number of values at the start of the full range that would make the
+    // result biased
+    let threshold = span.wrapping_neg() % span;
+    loop {
+        let value = next();
+        if value >= threshold {
+            break low + (value % span) as usize;
+        }
+    }
+}
+
+/// Maps a uniformly distributed 64 bit value to the range `[0, 1)`.
+///
+/// # Arguments
+/// *  `value` - The value to map.
+fn unit_interval(value: u64) -> f64 {
+    (value >> 11) as f64 / (1u64 << 53) as f64
+}
+
 /// A linear feedback shift register.
+///
+/// The low bits of consecutive values are correlated, which biases
+/// [`Randomizer::range`]. This generator is kept so that existing seeds
+/// generate the same mazes; prefer [`SplitMix64`] for new uses.
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct LFSR(u64);
@@ -232,6 +472,33 @@ impl Randomizer for LFSR {
     }
 }
 
+/// Derives a seed from a phrase.
+///
+/// The phrase is hashed using _FNV-1a_, so the same phrase always yields the
+/// same seed on all platforms. This allows memorable phrases to be used to
+/// identify mazes.
+///
+/// # Example
+///
+/// ```
+/// # use maze::initialize::*;
+/// assert_eq!(
+///     seed_from_phrase("my birthday maze"),
+///     seed_from_phrase("my birthday maze"),
+/// );
+/// assert_ne!(seed_from_phrase("a maze"), seed_from_phrase("another maze"));
+/// ```
+///
+/// # Arguments
+/// *  `phrase` - The phrase.
+pub fn seed_from_phrase(phrase: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    phrase.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
 impl<T> Maze<T>
 where
     T: Clone,
@@ -257,6 +524,50 @@ where
         self.initialize_filter(method, rng, |_| true)
     }
 
+    /// Initialises a maze using the selected algorithm, and reports every
+    /// modification to an observer.
+    ///
+    /// This is the equivalent of [`Self::initialize`], except that `observer`
+    /// is called every time a wall is opened or closed, and every time a room
+    /// is visited for the first time. This can be used to report progress or
+    /// to capture the generation of a maze.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::initialize::*;
+    /// # use maze::Shape;
+    /// let mut visited = 0;
+    /// let maze = Shape::Quad.create::<()>(5, 5).initialize_with_observer(
+    ///     Method::Branching(Bias::default()),
+    ///     &mut LFSR::new(12345),
+    ///     |event| {
+    ///         if let Event::Visited(_) = event {
+    ///             visited += 1;
+    ///         }
+    ///     },
+    /// );
+    ///
+    /// assert_eq!(visited, 25);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `method` - The initialisation method to use.
+    /// *  `rng` - A random number generator.
+    /// *  `observer` - A function notified of every modification.
+    pub fn initialize_with_observer<R, O>(
+        self,
+        method: Method,
+        rng: &mut R,
+        observer: O,
+    ) -> Self
+    where
+        R: Randomizer + Sized,
+        O: FnMut(Event),
+    {
+        self.initialize_filter_with_observer(method, rng, |_| true, observer)
+    }
+
     /// Initialises a maze using the selected algorithm.
     ///
     /// See [here](https://en.wikipedia.org/wiki/Maze_generation_algorithm) for
@@ -278,42 +589,227 @@ where
         rng: &mut R,
         filter: F,
     ) -> Self
+    where
+        F: Fn(matrix::Pos) -> bool,
+        R: Randomizer + Sized,
+    {
+        self.initialize_filter_with_observer(method, rng, filter, |_| {})
+    }
+
+    /// Initialises a maze using the selected algorithm, and reports every
+    /// modification to an observer.
+    ///
+    /// This is the equivalent of [`Self::initialize_filter`], except that
+    /// `observer` is called every time a wall is opened or closed, and every
+    /// time a room is visited for the first time, in the order the
+    /// modifications are made. Walls opened but later closed by the algorithm
+    /// are reported twice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::initialize::*;
+    /// # use maze::Shape;
+    /// let mut opened = 0;
+    /// let maze = Shape::Quad.create::<()>(5, 5).initialize_filter_with_observer(
+    ///     Method::Winding(Bias::default()),
+    ///     &mut LFSR::new(12345),
+    ///     |_| true,
+    ///     |event| {
+    ///         if let Event::Opened(_) = event {
+    ///             opened += 1;
+    ///         }
+    ///     },
+    /// );
+    ///
+    /// // A perfect maze with 25 rooms has 24 open walls
+    /// assert_eq!(opened, 24);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `method` - The initialisation method to use.
+    /// *  `rng` - A random number generator.
+    /// *  `filter` - A filter function used to ignore rooms.
+    /// *  `observer` - A function notified of every modified wall.
+    pub fn initialize_filter_with_observer<R, F, O>(
+        self,
+        method: Method,
+        rng: &mut R,
+        filter: F,
+        mut observer: O,
+    ) -> Self
+    where
+        F: Fn(matrix::Pos) -> bool,
+        R: Randomizer + Sized,
+        O: FnMut(Event),
+    {
+        self.initialize_start(method, rng, filter, None, &mut observer)
+    }
+
+    /// Initialises a maze using the selected algorithm, starting in a
+    /// specific room.
+    ///
+    /// The starting room strongly affects the texture of mazes generated with
+    /// [`Method::Winding`] and [`Method::Branching`], since corridors radiate
+    /// from it. The other methods ignore it.
+    ///
+    /// If `start` is not a room of the maze, a random room is used instead,
+    /// just like for [`Self::initialize`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::initialize::*;
+    /// # use maze::matrix;
+    /// # use maze::Shape;
+    /// let start = matrix::Pos { col: 2, row: 2 };
+    /// let mut events = vec![];
+    /// let maze = Shape::Quad.create::<()>(5, 5).initialize_from_with_observer(
+    ///     Method::Winding(Bias::default()),
+    ///     &mut LFSR::new(12345),
+    ///     start,
+    ///     |_| true,
+    ///     |event| events.push(event),
+    /// );
+    ///
+    /// match events[0] {
+    ///     Event::Opened((pos, _)) => assert_eq!(pos, start),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `method` - The initialisation method to use.
+    /// *  `rng` - A random number generator.
+    /// *  `start` - The room in which to start.
+    pub fn initialize_from<R>(
+        self,
+        method: Method,
+        rng: &mut R,
+        start: matrix::Pos,
+    ) -> Self
+    where
+        R: Randomizer + Sized,
+    {
+        self.initialize_from_with_observer(method, rng, start, |_| true, |_| {})
+    }
+
+    /// Initialises a maze using the selected algorithm, starting in a
+    /// specific room, and reports every modification to an observer.
+    ///
+    /// This is the equivalent of [`Self::initialize_from`] combined with
+    /// [`Self::initialize_filter_with_observer`].
+    ///
+    /// # Arguments
+    /// *  `method` - The initialisation method to use.
+    /// *  `rng` - A random number generator.
+    /// *  `start` - The room in which to start.
+    /// *  `filter` - A filter function used to ignore rooms.
+    /// *  `observer` - A function notified of every modified wall.
+    pub fn initialize_from_with_observer<R, F, O>(
+        self,
+        method: Method,
+        rng: &mut R,
+        start: matrix::Pos,
+        filter: F,
+        mut observer: O,
+    ) -> Self
+    where
+        F: Fn(matrix::Pos) -> bool,
+        R: Randomizer + Sized,
+        O: FnMut(Event),
+    {
+        self.initialize_start(method, rng, filter, Some(start), &mut observer)
+    }
+
+    /// Initialises a maze using the selected algorithm, optionally starting
+    /// in a specific room.
+    ///
+    /// # Arguments
+    /// *  `method` - The initialisation method to use.
+    /// *  `rng` - A random number generator.
+    /// *  `filter` - A filter function used to ignore rooms.
+    /// *  `start` - The room in which to start, if any.
+    /// *  `observer` - A function notified of every modified wall.
+    fn initialize_start<R, F>(
+        self,
+        method: Method,
+        rng: &mut R,
+        filter: F,
+        start: Option<matrix::Pos>,
+        observer: &mut dyn FnMut(Event),
+    ) -> Self
     where
         F: Fn(matrix::Pos) -> bool,
         R: Randomizer + Sized,
     {
         match matrix::filter(self.width(), self.height(), filter) {
             (count, filter) if count > 0 => match method {
-                Method::Braid => braid::initialize(self, rng, filter),
-                Method::Clear => clear::initialize(self, rng, filter),
-                Method::Branching => branching::initialize(self, rng, filter),
-                Method::Winding => winding::initialize(self, rng, filter),
+                Method::Braid => braid::initialize(self, rng, filter, observer),
+                Method::Clear => clear::initialize(self, rng, filter, observer),
+                Method::Branching(bias) => branching::initialize(
+                    self, rng, filter, start, bias, observer,
+                ),
+                Method::Winding(bias) => winding::initialize(
+                    self, rng, filter, start, bias, observer,
+                ),
             },
             _ => self,
         }
     }
-}
 
-/// Returns a random unvisited room.
-///
-/// # Arguments
-/// *  `rng` - A random number generator.
-/// *  `filter_matrix` - A matrix containing the rooms to consider.
-fn random_room(
-    rng: &mut dyn Randomizer,
-    filter_matrix: &matrix::Matrix<bool>,
-) -> Option<matrix::Pos> {
-    let count = filter_matrix
-        .positions()
-        .filter(|&pos| filter_matrix[pos])
-        .count();
-    if count > 0 {
-        filter_matrix
-            .positions()
-            .filter(|&pos| filter_matrix[pos])
-            .nth(rng.range(0, count))
-    } else {
-        None
+    /// Returns a random room that is part of the maze.
+    ///
+    /// Only rooms that have been visited are considered, so rooms excluded
+    /// when initialising the maze, for example by a mask, are never returned.
+    ///
+    /// If no room has been visited, nothing is returned.
+    ///
+    /// # Arguments
+    /// *  `rng` - A random number generator.
+    pub fn random_room<R>(&self, rng: &mut R) -> Option<matrix::Pos>
+    where
+        R: Randomizer + Sized,
+    {
+        Candidates::from(self.rooms.map(|room| room.visited)).random(rng)
+    }
+
+    /// Returns a random pair of distinct rooms between which a path exists.
+    ///
+    /// The first room is selected among all rooms that are part of the maze
+    /// and that are connected to at least one other room, and the second room
+    /// is selected among all rooms reachable from the first one.
+    ///
+    /// If no rooms are connected, nothing is returned.
+    ///
+    /// # Arguments
+    /// *  `rng` - A random number generator.
+    pub fn random_reachable_pair<R>(
+        &self,
+        rng: &mut R,
+    ) -> Option<(matrix::Pos, matrix::Pos)>
+    where
+        R: Randomizer + Sized,
+    {
+        let candidates = self.rooms.map_with_pos(|pos, room| {
+            room.visited
+                && self.neighbors(pos).any(|next| {
+                    self.rooms.get(next).map(|r| r.visited).unwrap_or(false)
+                })
+        });
+        let from = Candidates::from(candidates).random(rng)?;
+
+        let reachable = self
+            .flood(from)
+            .skip(1)
+            .map(|(pos, _)| pos)
+            .filter(|&pos| self.rooms[pos].visited)
+            .collect::<Vec<_>>();
+        if reachable.is_empty() {
+            None
+        } else {
+            Some((from, reachable[rng.range(0, reachable.len())]))
+        }
     }
 }
 
@@ -330,6 +826,29 @@ where
     F: Fn(matrix::Pos) -> bool,
     R: Randomizer + Sized,
     T: Clone,
+{
+    connect_all_with_observer(maze, rng, filter, &mut |_| {});
+}
+
+/// Ensures all rooms are connected, and reports every opened wall to an
+/// observer.
+///
+/// This is the equivalent of [`connect_all`], except that `observer` is
+/// called every time a wall is opened.
+///
+/// # Arguments
+/// *  `maze` - The maze to modify.
+/// *  `filter` - A filter for rooms to consider.
+/// *  `observer` - A function notified of every modified wall.
+pub fn connect_all_with_observer<F, R, T>(
+    maze: &mut Maze<T>,
+    rng: &mut R,
+    filter: F,
+    observer: &mut dyn FnMut(Event),
+) where
+    F: Fn(matrix::Pos) -> bool,
+    R: Randomizer + Sized,
+    T: Clone,
 {
     // First find all non-connected areas by visiting all rooms and filling for
     // each filtered, non-filled room and then incrementing the area index
@@ -349,7 +868,7 @@ where
 
     // Then find all edges between separate areas and open a random wall
     for (_, edge) in areas
-        .edges(|pos| maze.adjacent(pos))
+        .edges(|pos| areas.neighbors_by(pos, maze.shape()))
         .iter()
         .filter(|&((source, _), _)| *source > 0)
     {
@@ -357,20 +876,91 @@ where
             .iter()
             .flat_map(|&(pos1, pos2)| maze.connecting_wall(pos1, pos2))
             .collect::<Vec<_>>();
-        maze.open(wall_positions[rng.range(0, wall_positions.len())])
+        open(
+            maze,
+            wall_positions[rng.range(0, wall_positions.len())],
+            observer,
+        );
+    }
+}
+
+/// Opens a wall and reports it to an observer.
+///
+/// Walls that are already open are left untouched and not reported. Rooms
+/// visited for the first time when the wall is opened are reported after the
+/// wall.
+///
+/// # Arguments
+/// *  `maze` - The maze to modify.
+/// *  `wall_pos` - The wall to open.
+/// *  `observer` - A function notified of every modified wall.
+fn open<T>(
+    maze: &mut Maze<T>,
+    wall_pos: WallPos,
+    observer: &mut dyn FnMut(Event),
+) where
+    T: Clone,
+{
+    if !maze.is_open(wall_pos) {
+        let back = maze.back(wall_pos);
+        let visited = |maze: &Maze<T>, pos| {
+            maze.rooms.get(pos).map(|room| room.visited).unwrap_or(true)
+        };
+        let unvisited =
+            [wall_pos.0, back.0].map(|pos| (pos, !visited(maze, pos)));
+
+        maze.open(wall_pos);
+        observer(Event::Opened(wall_pos));
+        for (pos, unvisited) in unvisited {
+            if unvisited && visited(maze, pos) {
+                observer(Event::Visited(pos));
+            }
+        }
+    }
+}
+
+/// Closes a wall and reports it to an observer.
+///
+/// Walls that are already closed are left untouched and not reported.
+///
+/// # Arguments
+/// *  `maze` - The maze to modify.
+/// *  `wall_pos` - The wall to close.
+/// *  `observer` - A function notified of every modified wall.
+fn close<T>(
+    maze: &mut Maze<T>,
+    wall_pos: WallPos,
+    observer: &mut dyn FnMut(Event),
+) where
+    T: Clone,
+{
+    if maze.is_open(wall_pos) {
+        maze.close(wall_pos);
+        observer(Event::Closed(wall_pos));
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use maze_test::maze_test;
 
     use super::*;
     use crate::test_utils::*;
 
+    /// The default texture parameters.
+    const BIAS: Bias = Bias {
+        horizontal: 50,
+        branch: None,
+    };
+
     /// The various initialisation methods tested.
-    const INITIALIZERS: &[Method] =
-        &[Method::Braid, Method::Branching, Method::Winding];
+    const INITIALIZERS: &[Method] = &[
+        Method::Braid,
+        Method::Branching(BIAS),
+        Method::Winding(BIAS),
+    ];
 
     /// Tests that range works as advertised.
     #[test]
@@ -413,42 +1003,136 @@ mod tests {
     }
 
     #[test]
-    fn random_room_none() {
-        let width = 5;
-        let height = 5;
-        let mut rng = LFSR::new(12345);
-        let (count, filter_matrix) = matrix::filter(width, height, |_| false);
+    fn seed_from_phrase_values() {
+        // These values must never change, since they identify mazes
+        assert_eq!(0xcbf29ce484222325, seed_from_phrase(""));
+        assert_eq!(0xaf63dc4c8601ec8c, seed_from_phrase("a"));
+        assert_eq!(0x85944171f73967e8, seed_from_phrase("foobar"));
+    }
+
+    #[test]
+    fn bias_parse() {
+        assert_eq!(Ok(Bias::default()), "".parse());
+        assert_eq!(
+            Ok(Bias {
+                horizontal: 0,
+                branch: Some(100),
+            }),
+            ":branch=100:horizontal=0".parse(),
+        );
+        assert!("horizontal=101".parse::<Bias>().is_err());
+        assert!("unknown=1".parse::<Bias>().is_err());
+        assert!("braid:horizontal=10".parse::<Method>().is_err());
+        assert_eq!("winding", Method::Winding(Bias::default()).to_string());
+    }
+
+    #[test]
+    fn bias_round_trip() {
+        for horizontal in [0, 50, 80, 100, 101, u8::MAX] {
+            for branch in [None, Some(0), Some(100), Some(101), Some(u8::MAX)] {
+                let bias = Bias { horizontal, branch };
+                assert_eq!(
+                    Ok(Bias {
+                        horizontal: horizontal.min(100),
+                        branch: branch.map(|branch| branch.min(100)),
+                    }),
+                    bias.to_string().parse(),
+                    "{:?}",
+                    bias,
+                );
+            }
+        }
+    }
 
-        assert_eq!(0, count);
+    #[maze_test]
+    fn initialize_bias_horizontal(maze: TestMaze) {
+        for method in [Method::Branching, Method::Winding] {
+            let horizontal = |horizontal| {
+                maze.clone()
+                    .initialize(
+                        method(Bias {
+                            horizontal,
+                            ..Default::default()
+                        }),
+                        &mut LFSR::new(12345),
+                    )
+                    .walls_iter()
+                    .filter(|&((_, wall), open)| open && wall.dir.1 == 0)
+                    .count()
+            };
 
-        let iterations = width * height * 100;
-        for _ in 0..iterations {
-            assert!(random_room(&mut rng, &filter_matrix).is_none());
+            assert!(horizontal(90) > horizontal(10));
         }
     }
 
-    #[test]
-    fn random_room_some() {
-        let width = 5;
-        let height = 5;
+    #[maze_test]
+    fn initialize_bias_branch(maze: TestMaze) {
+        for method in [Method::Branching, Method::Winding] {
+            for branch in [0, 50, 100] {
+                let maze = maze.clone().initialize(
+                    method(Bias {
+                        branch: Some(branch),
+                        ..Default::default()
+                    }),
+                    &mut LFSR::new(12345),
+                );
+
+                assert_eq!(maze.loop_count(), 0);
+                assert!(maze.positions().all(|pos| maze[pos].visited));
+            }
+        }
+    }
+
+    #[maze_test]
+    fn maze_random_room(maze: TestMaze) {
         let mut rng = LFSR::new(12345);
-        let (count, filter_matrix) =
-            matrix::filter(width, height, |pos| pos.col as usize == width - 1);
+        assert!(maze.random_room(&mut rng).is_none());
 
-        assert_eq!(height, count);
+        let excluded = matrix_pos(0, 0);
+        let maze = maze.initialize_filter(
+            Method::Branching(Bias::default()),
+            &mut rng,
+            |pos| pos != excluded,
+        );
+        for _ in 0..1000 {
+            let pos = maze.random_room(&mut rng).unwrap();
+            assert!(maze.is_inside(pos));
+            assert_ne!(pos, excluded);
+        }
+    }
 
-        let buckets = height;
-        let iterations = 100 * 100 * buckets;
-        let hist = (0..iterations).fold(vec![0; buckets], |mut hist, _| {
-            hist[random_room(&mut rng, &filter_matrix).unwrap().row
-                as usize] += 1;
-            hist
-        });
+    #[maze_test]
+    fn maze_random_reachable_pair(mut maze: TestMaze) {
+        let mut rng = LFSR::new(12345);
+        assert!(maze.random_reachable_pair(&mut rng).is_none());
 
-        let mid = iterations / buckets;
-        let h = 400;
-        for v in hist {
-            assert!(mid - h < v && v < mid + h);
+        let log = Navigator::new(&mut maze).down(true).stop();
+        for _ in 0..100 {
+            let (from, to) = maze.random_reachable_pair(&mut rng).unwrap();
+            assert_ne!(from, to);
+            assert!(log.contains(&from));
+            assert!(log.contains(&to));
+        }
+
+        let maze = maze.initialize(Method::Winding(Bias::default()), &mut rng);
+        for _ in 0..100 {
+            let (from, to) = maze.random_reachable_pair(&mut rng).unwrap();
+            assert_ne!(from, to);
+            assert!(maze.walk(from, to).is_some());
+        }
+    }
+
+    #[maze_test]
+    fn initialize_braid_no_dead_ends(maze: TestMaze) {
+        let maze = maze.initialize(Method::Braid, &mut LFSR::new(12345));
+
+        // Only rooms with a single neighbour, such as the corners of some
+        // shapes, may be dead ends
+        let degree_map = maze.degree_map();
+        for pos in maze.positions() {
+            let neighbors =
+                maze.adjacent(pos).filter(|&p| maze.is_inside(p)).count();
+            assert!(degree_map[pos] != 1 || neighbors == 1);
         }
     }
 
@@ -467,6 +1151,23 @@ mod tests {
         }
     }
 
+    #[maze_test(sizes(1x1, 2x7, 7x2, 3..6), seeds(3))]
+    fn initialize_sizes(maze: TestMaze, seed: u64) {
+        for method in INITIALIZERS {
+            let maze =
+                maze.clone().initialize(*method, &mut SplitMix64::new(seed));
+
+            let from = matrix_pos(0, 0);
+            for to in maze.positions() {
+                assert!(
+                    maze.walk(from, to).is_some(),
+                    "for method {:?}",
+                    method,
+                );
+            }
+        }
+    }
+
     #[maze_test]
     fn initialize_lfsr_stable(maze: TestMaze) {
         for method in INITIALIZERS {
@@ -480,6 +1181,118 @@ mod tests {
         }
     }
 
+    #[maze_test]
+    fn initialize_filter_with_observer_replay(maze: TestMaze) {
+        for method in INITIALIZERS {
+            let mut events = vec![];
+            let initialized = maze.clone().initialize_filter_with_observer(
+                *method,
+                &mut LFSR::new(12345),
+                |_| true,
+                |event| events.push(event),
+            );
+
+            let mut replayed = maze.clone();
+            for event in events {
+                match event {
+                    Event::Opened(wall_pos) => {
+                        assert!(!replayed.is_open(wall_pos));
+                        replayed.open(wall_pos);
+                    }
+                    Event::Closed(wall_pos) => {
+                        assert!(replayed.is_open(wall_pos));
+                        replayed.close(wall_pos);
+                    }
+                    Event::Visited(pos) => {
+                        assert!(replayed[pos].visited);
+                    }
+                }
+            }
+
+            for pos in maze.positions() {
+                for wall_pos in maze.wall_positions(pos) {
+                    assert_eq!(
+                        replayed.is_open(wall_pos),
+                        initialized.is_open(wall_pos),
+                        "for method {:?}",
+                        method,
+                    );
+                }
+            }
+        }
+    }
+
+    #[maze_test]
+    fn initialize_with_observer_visited(maze: TestMaze) {
+        for method in INITIALIZERS {
+            let mut visited = HashSet::new();
+            let initialized = maze.clone().initialize_with_observer(
+                *method,
+                &mut LFSR::new(12345),
+                |event| {
+                    if let Event::Visited(pos) = event {
+                        assert!(visited.insert(pos), "for method {:?}", method);
+                    }
+                },
+            );
+
+            for pos in maze.positions() {
+                assert_eq!(
+                    visited.contains(&pos),
+                    initialized[pos].visited,
+                    "for method {:?}",
+                    method,
+                );
+            }
+        }
+    }
+
+    #[maze_test]
+    fn initialize_from_start(maze: TestMaze) {
+        for method in [
+            Method::Branching(Bias::default()),
+            Method::Winding(Bias::default()),
+        ] {
+            for start in maze.positions() {
+                let mut events = vec![];
+                let initialized = maze.clone().initialize_from_with_observer(
+                    method,
+                    &mut LFSR::new(12345),
+                    start,
+                    |_| true,
+                    |event| events.push(event),
+                );
+
+                match events.first() {
+                    Some(&Event::Opened((pos, _))) => {
+                        assert_eq!(pos, start, "for method {:?}", method)
+                    }
+                    event => panic!("unexpected first event {:?}", event),
+                }
+                assert!(initialized
+                    .positions()
+                    .all(|pos| { initialized.walk(start, pos).is_some() }));
+            }
+        }
+    }
+
+    #[maze_test]
+    fn initialize_from_outside(maze: TestMaze) {
+        for method in INITIALIZERS {
+            let initialized = maze.clone().initialize_from(
+                *method,
+                &mut LFSR::new(12345),
+                matrix_pos(-1, -1),
+            );
+
+            assert!(
+                initialized.positions().all(|pos| initialized[pos].visited),
+                "for method {:?}",
+                method,
+            );
+        }
+    }
+
     #[maze_test]
     fn initialize_filter_most(maze: TestMaze) {
         for method in INITIALIZERS {
@@ -559,4 +1372,28 @@ mod tests {
             }
         }
     }
+
+    #[maze_test]
+    fn initialize_filter_isolated(maze: TestMaze) {
+        for method in INITIALIZERS {
+            // The first room is a candidate, but none of its neighbours are
+            let isolated = matrix_pos(0, 0);
+            let neighbors = maze
+                .walls(isolated)
+                .iter()
+                .map(|wall| maze.back((isolated, wall)).0)
+                .collect::<Vec<_>>();
+            let filter = |pos| pos == isolated || !neighbors.contains(&pos);
+            let maze = maze.clone().initialize_filter(
+                *method,
+                &mut rand::thread_rng(),
+                filter,
+            );
+
+            assert!(maze
+                .walls(isolated)
+                .iter()
+                .all(|wall| { !maze.is_open((isolated, wall)) }));
+        }
+    }
 }
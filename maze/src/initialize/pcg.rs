@@ -0,0 +1,159 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{unbiased_range, unit_interval, Randomizer};
+
+/// The multiplier of the underlying linear congruential generator.
+const MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+
+/// The stream used by [`Pcg32::new`].
+const DEFAULT_STREAM: u64 = 0xda3e_39cb_94b9_5bdb;
+
+/// A _PCG_ random number generator.
+///
+/// This is the _PCG-XSH-RR_ variant with 64 bits of state and 32 bits of
+/// output described by [O'Neill]. Unlike random number generators provided
+/// by external crates, the sequence of values generated for a seed, and the
+/// way they are mapped to ranges, is part of the interface of this type, and
+/// will remain the same on all platforms and in all versions of this crate.
+///
+/// [O'Neill]: https://www.pcg-random.org/
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Pcg32 {
+    /// The current state.
+    state: u64,
+
+    /// The increment, which selects the stream. This is always odd.
+    increment: u64,
+}
+
+impl Pcg32 {
+    /// Creates a random number generator using the default stream.
+    ///
+    /// # Arguments
+    /// *  `seed` - The seed.
+    pub fn new(seed: u64) -> Self {
+        Self::with_stream(seed, DEFAULT_STREAM)
+    }
+
+    /// Creates a random number generator using a specific stream.
+    ///
+    /// Generators with the same seed but different streams yield unrelated
+    /// sequences.
+    ///
+    /// # Arguments
+    /// *  `seed` - The seed.
+    /// *  `stream` - The stream. Only the lower 63 bits are used.
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut result = Self {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        result.next_u32();
+        result.state = result.state.wrapping_add(seed);
+        result.next_u32();
+        result
+    }
+
+    /// Creates a random number generator seeded by the operating system.
+    #[cfg(feature = "rand")]
+    pub fn from_entropy() -> Self {
+        Self::with_stream(rand::random(), rand::random())
+    }
+
+    /// Generates the next 32 bit value.
+    pub fn next_u32(&mut self) -> u32 {
+        let state = self.state;
+        self.state =
+            state.wrapping_mul(MULTIPLIER).wrapping_add(self.increment);
+
+        let xorshifted = (((state >> 18) ^ state) >> 27) as u32;
+        let rotation = (state >> 59) as u32;
+        xorshifted.rotate_right(rotation)
+    }
+
+    /// Generates the next 64 bit value.
+    ///
+    /// This consumes two 32 bit values, the first of which makes up the
+    /// high bits.
+    pub fn next_u64(&mut self) -> u64 {
+        let high = self.next_u32() as u64;
+        let low = self.next_u32() as u64;
+        (high << 32) | low
+    }
+}
+
+impl Randomizer for Pcg32 {
+    fn range(&mut self, a: usize, b: usize) -> usize {
+        unbiased_range(a, b, || self.next_u64())
+    }
+
+    fn random(&mut self) -> f64 {
+        unit_interval(self.next_u64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_values() {
+        // The output of the reference implementation for these parameters
+        let mut rng = Pcg32::with_stream(42, 54);
+        assert_eq!(
+            [
+                0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b,
+                0xcbed606e,
+            ],
+            [(); 6].map(|_| rng.next_u32()),
+        );
+    }
+
+    #[test]
+    fn range_values() {
+        // These values must never change, since they determine the mazes
+        // generated for a seed
+        let mut rng = Pcg32::new(1);
+        assert_eq!(
+            [13, 25, 87, 10, 14, 20, 82, 23],
+            [(); 8].map(|_| rng.range(0, 100)),
+        );
+    }
+
+    #[test]
+    fn random_values() {
+        let mut rng = Pcg32::new(1);
+        assert_eq!(
+            [0.9390237507624452, 0.9697715497226064, 0.30470737855931396],
+            [(); 3].map(|_| rng.random()),
+        );
+    }
+
+    #[test]
+    fn range() {
+        let mut rng = Pcg32::new(12345);
+        assert_eq!(3, rng.range(3, 3));
+        for (a, b) in [(0, 1), (0, 7), (10, 3), (0, usize::MAX)] {
+            let (low, high) = if a < b { (a, b) } else { (b, a) };
+            for _ in 0..1000 {
+                assert!((low..high).contains(&rng.range(a, b)));
+            }
+        }
+
+        let mut counts = [0usize; 6];
+        for _ in 0..6000 {
+            counts[rng.range(0, counts.len())] += 1;
+        }
+        assert!(counts.iter().all(|&count| (900..1100).contains(&count)));
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = Pcg32::new(12345);
+        for _ in 0..1000 {
+            assert!((0.0..1.0).contains(&rng.random()));
+        }
+    }
+}
@@ -0,0 +1,351 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::matrix;
+use crate::Maze;
+
+use super::{connect_all, open, Event, Method, Randomizer};
+
+/// Carves a path between two rooms that passes through as many of a set of
+/// target rooms as possible.
+///
+/// See [`carve_through_with_observer`] for details.
+///
+/// # Arguments
+/// *  `maze` - The maze to modify.
+/// *  `rng` - A random number generator.
+/// *  `targets` - The rooms through which the path should pass.
+/// *  `start` - The first room of the path.
+/// *  `end` - The last room of the path.
+pub fn carve_through<R, T>(
+    maze: &mut Maze<T>,
+    rng: &mut R,
+    targets: &matrix::Matrix<bool>,
+    start: matrix::Pos,
+    end: matrix::Pos,
+) -> Vec<matrix::Pos>
+where
+    R: Randomizer + Sized,
+    T: Clone,
+{
+    carve_through_with_observer(maze, rng, targets, start, end, &mut |_| {})
+}
+
+/// Carves a path between two rooms that passes through as many of a set of
+/// target rooms as possible, and reports every opened wall to an observer.
+///
+/// The path repeatedly moves to the closest target room not yet on the path,
+/// and then follows the target rooms for as long as possible, preferring
+/// rooms with few remaining target neighbours so that thin shapes are traced
+/// from one end to the other. Target rooms that cannot be reached without
+/// crossing the path are skipped.
+///
+/// Only the walls along the path are opened, so if the rest of the maze is
+/// then initialised without touching the rooms of the path, and every area is
+/// connected to the path exactly once, the path is the only solution. See
+/// [`solution_through`].
+///
+/// The rooms of the path, from `start` to `end`, are returned.
+///
+/// # Arguments
+/// *  `maze` - The maze to modify.
+/// *  `rng` - A random number generator.
+/// *  `targets` - The rooms through which the path should pass.
+/// *  `start` - The first room of the path.
+/// *  `end` - The last room of the path. This must differ from `start`.
+/// *  `observer` - A function notified of every modified wall.
+pub fn carve_through_with_observer<R, T>(
+    maze: &mut Maze<T>,
+    rng: &mut R,
+    targets: &matrix::Matrix<bool>,
+    start: matrix::Pos,
+    end: matrix::Pos,
+    observer: &mut dyn FnMut(Event),
+) -> Vec<matrix::Pos>
+where
+    R: Randomizer + Sized,
+    T: Clone,
+{
+    let is_target = |pos| targets.get(pos).copied().unwrap_or(false);
+
+    // The end is reserved until the path is completed
+    let mut used = matrix::Matrix::new(maze.width(), maze.height());
+    used[start] = true;
+    used[end] = true;
+    let mut path = vec![start];
+
+    loop {
+        // Find the closest remaining target room
+        let current = path[path.len() - 1];
+        let Some(steps) = route(maze, &used, current, is_target) else {
+            break;
+        };
+        for &pos in &steps {
+            used[pos] = true;
+        }
+        path.extend(steps);
+
+        // Follow the target rooms for as long as possible
+        loop {
+            let current = path[path.len() - 1];
+            let free = |pos: matrix::Pos, used: &matrix::Matrix<bool>| {
+                maze.is_inside(pos) && is_target(pos) && !used[pos]
+            };
+            let candidates = maze
+                .adjacent(current)
+                .filter(|&pos| free(pos, &used))
+                .map(|pos| {
+                    (
+                        pos,
+                        maze.adjacent(pos).filter(|&p| free(p, &used)).count(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let Some(fewest) = candidates.iter().map(|&(_, c)| c).min() else {
+                break;
+            };
+            let candidates = candidates
+                .into_iter()
+                .filter(|&(_, count)| count == fewest)
+                .collect::<Vec<_>>();
+            let (next, _) = candidates[rng.range(0, candidates.len())];
+            used[next] = true;
+            path.push(next);
+        }
+    }
+
+    // Finish at the end room, backing up if the path has trapped itself
+    used[end] = false;
+    while path.len() > 1 {
+        let current = path[path.len() - 1];
+        if let Some(steps) = route(maze, &used, current, |pos| pos == end) {
+            path.extend(steps);
+            break;
+        }
+        used[current] = false;
+        path.pop();
+    }
+    if path.len() == 1 && start != end {
+        path.extend(route(maze, &used, start, |pos| pos == end).unwrap());
+    }
+
+    for rooms in path.windows(2) {
+        if let Some(wall_pos) = maze.connecting_wall(rooms[0], rooms[1]) {
+            open(maze, wall_pos, observer);
+        }
+    }
+
+    path
+}
+
+/// Initialises a maze whose only solution passes through as many of a set
+/// of target rooms as possible.
+///
+/// A path is first carved using [`carve_through`], and the remaining rooms
+/// are then initialised using `method` and connected to the path. If the
+/// target rooms spell a word, the solution reveals the text.
+///
+/// Unless `method` creates loops, the carved path is the only path between
+/// `start` and `end`.
+///
+/// # Example
+///
+/// ```
+/// # use maze::initialize::*;
+/// # use maze::matrix;
+/// let targets = matrix::Matrix::new_with_data(10, 10, |pos| pos.row == 5);
+/// let (start, end) = ((0isize, 0isize).into(), (9isize, 9isize).into());
+/// let maze = solution_through(
+///     maze::Shape::Quad.create::<()>(10, 10),
+///     &targets,
+///     start,
+///     end,
+///     Method::default(),
+///     &mut LFSR::new(12345),
+/// );
+///
+/// let solution = maze.walk(start, end).unwrap();
+/// assert!(solution.into_iter().filter(|&pos| targets[pos]).count() >= 8);
+/// ```
+///
+/// # Arguments
+/// *  `maze` - The maze to initialise.
+/// *  `targets` - The rooms through which the solution should pass.
+/// *  `start` - The first room of the solution.
+/// *  `end` - The last room of the solution.
+/// *  `method` - The method used for the rooms not on the solution.
+/// *  `rng` - A random number generator.
+pub fn solution_through<R, T>(
+    mut maze: Maze<T>,
+    targets: &matrix::Matrix<bool>,
+    start: matrix::Pos,
+    end: matrix::Pos,
+    method: Method,
+    rng: &mut R,
+) -> Maze<T>
+where
+    R: Randomizer + Sized,
+    T: Clone,
+{
+    let path = carve_through(&mut maze, rng, targets, start, end);
+    let mut solution = matrix::Matrix::new(maze.width(), maze.height());
+    for pos in path {
+        solution[pos] = true;
+    }
+
+    // The areas not on the solution only neighbour the solution, so every
+    // area is connected to it exactly once
+    let mut maze = maze.initialize_filter(method, rng, |pos| !solution[pos]);
+    connect_all(&mut maze, rng, |_| true);
+    maze
+}
+
+/// Finds the shortest route from a room to the closest room matching a
+/// predicate, ignoring walls but avoiding rooms already used.
+///
+/// The route, excluding `from`, is returned, or nothing if no matching room
+/// can be reached.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+/// *  `used` - The rooms to avoid.
+/// *  `from` - The starting room.
+/// *  `goal` - The predicate for the destination room.
+fn route<F, T>(
+    maze: &Maze<T>,
+    used: &matrix::Matrix<bool>,
+    from: matrix::Pos,
+    goal: F,
+) -> Option<Vec<matrix::Pos>>
+where
+    F: Fn(matrix::Pos) -> bool,
+    T: Clone,
+{
+    let mut came_from =
+        matrix::Matrix::<Option<matrix::Pos>>::new(maze.width(), maze.height());
+    let mut queue = VecDeque::from([from]);
+    while let Some(current) = queue.pop_front() {
+        for next in maze.adjacent(current) {
+            if !maze.is_inside(next)
+                || next == from
+                || used[next]
+                || came_from[next].is_some()
+            {
+                continue;
+            }
+            came_from[next] = Some(current);
+
+            if goal(next) {
+                let mut result = vec![next];
+                let mut pos = next;
+                while let Some(previous) = came_from[pos].filter(|&p| p != from)
+                {
+                    result.push(previous);
+                    pos = previous;
+                }
+                result.reverse();
+                return Some(result);
+            }
+            queue.push_back(next);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize::LFSR;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn carve_through_simple(mut maze: TestMaze) {
+        let targets =
+            matrix::Matrix::new_with_data(maze.width(), maze.height(), |pos| {
+                pos.row == maze.height() as isize / 2
+            });
+        let (start, end) = (
+            matrix_pos(0, 0),
+            matrix_pos(maze.width() as isize - 1, maze.height() as isize - 1),
+        );
+        let path = carve_through(
+            &mut maze,
+            &mut LFSR::new(12345),
+            &targets,
+            start,
+            end,
+        );
+
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        assert_eq!(path.iter().collect::<HashSet<_>>().len(), path.len());
+        for rooms in path.windows(2) {
+            assert!(maze.connecting_wall(rooms[0], rooms[1]).is_some());
+        }
+        assert_eq!(
+            maze.walk(start, end)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            path,
+        );
+    }
+
+    #[maze_test(quad)]
+    fn carve_through_line(mut maze: TestMaze) {
+        let row = maze.height() as isize / 2;
+        let targets =
+            matrix::Matrix::new_with_data(maze.width(), maze.height(), |pos| {
+                pos.row == row
+            });
+        let end = matrix_pos(0, maze.height() as isize - 1);
+        let path = carve_through(
+            &mut maze,
+            &mut LFSR::new(12345),
+            &targets,
+            matrix_pos(0, 0),
+            end,
+        );
+
+        assert!(targets
+            .positions()
+            .filter(|&pos| targets[pos])
+            .all(|pos| { path.contains(&pos) }));
+    }
+
+    #[maze_test]
+    fn solution_through_unique(maze: TestMaze) {
+        let targets =
+            matrix::Matrix::new_with_data(maze.width(), maze.height(), |pos| {
+                pos.col == pos.row
+            });
+        let (start, end) = (
+            matrix_pos(maze.width() as isize - 1, 0),
+            matrix_pos(0, maze.height() as isize - 1),
+        );
+        let maze = solution_through(
+            maze,
+            &targets,
+            start,
+            end,
+            Method::default(),
+            &mut LFSR::new(12345),
+        );
+
+        assert_eq!(maze.loop_count(), 0);
+        for pos in maze.positions() {
+            assert!(maze.walk(start, pos).is_some());
+        }
+        assert!(maze
+            .walk(start, end)
+            .unwrap()
+            .into_iter()
+            .any(|pos| targets[pos]));
+    }
+}
@@ -132,9 +132,9 @@ pub(crate) fn parse_method(s: &str) -> Result<super::Method, ()> {
 }
 
 pub(crate) fn display_method(
-    f: &mut std::fmt::Formatter,
+    f: &mut core::fmt::Formatter,
     instructions: &Instructions,
-) -> std::fmt::Result {
+) -> core::fmt::Result {
     write!(f, "{}{}{}", PREFIX, instructions, SUFFIX)
 }
 
@@ -144,8 +144,8 @@ impl From<Vec<Instruction>> for Instructions {
     }
 }
 
-impl std::fmt::Display for Instructions {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Instructions {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         for i in &self.0 {
             write!(f, "{}", i.char())?;
         }
@@ -153,7 +153,7 @@ impl std::fmt::Display for Instructions {
     }
 }
 
-impl std::str::FromStr for Instructions {
+impl core::str::FromStr for Instructions {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
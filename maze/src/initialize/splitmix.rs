@@ -0,0 +1,99 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{unbiased_range, unit_interval, Randomizer};
+
+/// The increment of the state for every value generated.
+const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// A _SplitMix64_ random number generator.
+///
+/// This is a small and fast generator with 64 bits of state, passing common
+/// statistical test suites. Like [`Pcg32`](super::Pcg32), the sequence of
+/// values generated for a seed, and the way they are mapped to ranges, will
+/// remain the same on all platforms and in all versions of this crate.
+///
+/// Unlike [`LFSR`](super::LFSR), the low bits of the generated values are not
+/// correlated, so this is the preferred generator for seeded mazes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+    /// Creates a new random number generator.
+    ///
+    /// # Arguments
+    /// *  `seed` - The seed.
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Generates the next 64 bit value.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(GAMMA);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl<T> From<T> for SplitMix64
+where
+    T: Into<u64>,
+{
+    fn from(source: T) -> Self {
+        Self(source.into())
+    }
+}
+
+impl Randomizer for SplitMix64 {
+    fn range(&mut self, a: usize, b: usize) -> usize {
+        unbiased_range(a, b, || self.next_u64())
+    }
+
+    fn random(&mut self) -> f64 {
+        unit_interval(self.next_u64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_values() {
+        // The output of the reference implementation for this seed
+        let mut rng = SplitMix64::new(0);
+        assert_eq!(
+            [0xe220a8397b1dcdaf, 0x6e789e6aa1b965f4, 0x06c45d188009454f],
+            [(); 3].map(|_| rng.next_u64()),
+        );
+    }
+
+    #[test]
+    fn range() {
+        let mut rng = SplitMix64::new(12345);
+        assert_eq!(3, rng.range(3, 3));
+        for (a, b) in [(0, 1), (0, 7), (10, 3), (0, usize::MAX)] {
+            let (low, high) = if a < b { (a, b) } else { (b, a) };
+            for _ in 0..1000 {
+                assert!((low..high).contains(&rng.range(a, b)));
+            }
+        }
+
+        // The low bits are not correlated, so alternating values are evenly
+        // distributed
+        let mut counts = [[0usize; 2]; 2];
+        let mut previous = rng.range(0, 2);
+        for _ in 0..4000 {
+            let value = rng.range(0, 2);
+            counts[previous][value] += 1;
+            previous = value;
+        }
+        assert!(counts
+            .iter()
+            .flatten()
+            .all(|&count| (900..1100).contains(&count)));
+    }
+}
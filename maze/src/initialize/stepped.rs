@@ -0,0 +1,237 @@
+use crate::matrix;
+use crate::Maze;
+
+use super::{braid, branching, clear, winding, Method, Randomizer};
+
+/// The status of a stepped initialisation.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Status {
+    /// More steps remain.
+    Incomplete,
+
+    /// The maze is fully initialised.
+    Complete,
+}
+
+/// The algorithm specific state of an initialisation.
+enum State {
+    /// An initialisation using [`Method::Branching`].
+    Branching(branching::State),
+
+    /// An initialisation using [`Method::Winding`].
+    Winding(winding::State),
+
+    /// An initialisation using a method that cannot be stepped, which is
+    /// performed all at once by the first step.
+    Pending(Method, matrix::Matrix<bool>),
+
+    /// The initialisation is complete.
+    Complete,
+}
+
+/// An initialisation performed in slices.
+///
+/// This allows a large maze to be generated gradually, for example over
+/// several frames of a game, without blocking. The resulting maze is the same
+/// as the one generated by [`Maze::initialize`] with the same method and
+/// random number generator.
+///
+/// Only [`Method::Branching`] and [`Method::Winding`] can be stepped; the
+/// other methods complete in a single step.
+///
+/// # Example
+///
+/// ```
+/// # use maze::initialize::*;
+/// # use maze::Shape;
+/// let mut initializer = Initializer::new(
+///     Shape::Quad.create::<()>(5, 5),
+///     Method::default(),
+///     LFSR::new(12345),
+/// );
+/// while initializer.step(10) == Status::Incomplete {
+///     // Render the partially initialised maze
+///     let _ = initializer.maze();
+/// }
+///
+/// let expected = Shape::Quad
+///     .create::<()>(5, 5)
+///     .initialize(Method::default(), &mut LFSR::new(12345));
+/// assert!(initializer.finish().diff(&expected).is_empty());
+/// ```
+pub struct Initializer<R, T>
+where
+    R: Randomizer + Sized,
+    T: Clone,
+{
+    /// The maze being initialised.
+    ///
+    /// This is only empty while a method that cannot be stepped is applied.
+    maze: Option<Maze<T>>,
+
+    /// The random number generator.
+    rng: R,
+
+    /// The state of the initialisation.
+    state: State,
+}
+
+impl<R, T> Initializer<R, T>
+where
+    R: Randomizer + Sized,
+    T: Clone,
+{
+    /// Prepares the initialisation of a maze.
+    ///
+    /// No walls are opened until [`step`](Self::step) is called.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to initialise. This should be fully closed.
+    /// *  `method` - The initialisation method to use.
+    /// *  `rng` - A random number generator.
+    pub fn new(maze: Maze<T>, method: Method, mut rng: R) -> Self {
+        let candidates =
+            matrix::Matrix::new_with_data(maze.width(), maze.height(), |_| {
+                true
+            });
+        let state = if maze.width() == 0 || maze.height() == 0 {
+            State::Complete
+        } else {
+            match method {
+                Method::Branching(bias) => State::Branching(
+                    branching::State::new(candidates, None, bias),
+                ),
+                Method::Winding(bias) => State::Winding(winding::State::new(
+                    &mut rng, candidates, None, bias,
+                )),
+                method => State::Pending(method, candidates),
+            }
+        };
+
+        Self {
+            maze: Some(maze),
+            rng,
+            state,
+        }
+    }
+
+    /// Performs at most a number of steps of the initialisation.
+    ///
+    /// A step either opens a wall or moves on to a different room, so the
+    /// time taken by every step is small and independent of the size of the
+    /// maze.
+    ///
+    /// # Arguments
+    /// *  `steps` - The maximum number of steps to perform.
+    pub fn step(&mut self, steps: usize) -> Status {
+        let observer = &mut |_| {};
+        for _ in 0..steps {
+            let maze = self.maze.as_mut().unwrap();
+            let incomplete = match &mut self.state {
+                State::Branching(state) => {
+                    state.step(maze, &mut self.rng, observer)
+                }
+                State::Winding(state) => {
+                    state.step(maze, &mut self.rng, observer)
+                }
+                State::Pending(method, candidates) => {
+                    let (method, candidates) = (*method, candidates.clone());
+                    let maze = self.maze.take().unwrap();
+                    let rng = &mut self.rng;
+                    self.maze = Some(match method {
+                        Method::Braid => {
+                            braid::initialize(maze, rng, candidates, observer)
+                        }
+                        // Only braid and clear are pending
+                        _ => clear::initialize(maze, rng, candidates, observer),
+                    });
+                    false
+                }
+                State::Complete => false,
+            };
+
+            if !incomplete {
+                self.state = State::Complete;
+                break;
+            }
+        }
+
+        self.status()
+    }
+
+    /// The status of this initialisation.
+    pub fn status(&self) -> Status {
+        match self.state {
+            State::Complete => Status::Complete,
+            _ => Status::Incomplete,
+        }
+    }
+
+    /// The maze being initialised.
+    ///
+    /// Until the initialisation is complete, only some of its rooms have been
+    /// visited.
+    pub fn maze(&self) -> &Maze<T> {
+        self.maze.as_ref().unwrap()
+    }
+
+    /// Completes the initialisation and returns the maze.
+    pub fn finish(mut self) -> Maze<T> {
+        while self.step(usize::MAX) == Status::Incomplete {}
+        self.maze.take().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize::{Bias, LFSR};
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn step_single(maze: TestMaze) {
+        for method in [
+            Method::Braid,
+            Method::Branching(Bias::default()),
+            Method::Clear,
+            Method::Winding(Bias::default()),
+        ] {
+            let expected =
+                maze.clone().initialize(method, &mut LFSR::new(12345));
+
+            let mut initializer =
+                Initializer::new(maze.clone(), method, LFSR::new(12345));
+            let mut steps = 0;
+            while initializer.step(1) == Status::Incomplete {
+                steps += 1;
+            }
+
+            assert_eq!(initializer.status(), Status::Complete);
+            assert!(
+                initializer.finish().diff(&expected).is_empty(),
+                "for {:?}",
+                method,
+            );
+            if matches!(method, Method::Branching(_) | Method::Winding(_)) {
+                assert!(steps >= maze.width() * maze.height() - 1);
+            }
+        }
+    }
+
+    #[maze_test]
+    fn step_partial(maze: TestMaze) {
+        let mut initializer = Initializer::new(
+            maze.clone(),
+            Method::Winding(Bias::default()),
+            LFSR::new(12345),
+        );
+
+        assert_eq!(initializer.step(3), Status::Incomplete);
+        assert_eq!(
+            initializer.maze().walls_iter().filter(|&(_, o)| o).count(),
+            3,
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use crate::Maze;
 
 use crate::matrix;
@@ -16,31 +18,119 @@ use crate::matrix;
 /// *  `maze``- The maze to initialise.
 /// *  `rng` - A random number generator.
 /// *  `candidates` - A filter for the rooms to modify.
+/// *  `start` - The room in which to start, if it is a candidate.
+/// *  `bias` - Parameters tuning the texture.
+/// *  `observer` - A function notified of every modified wall.
 pub(crate) fn initialize<R, T>(
     mut maze: Maze<T>,
     rng: &mut R,
-    mut candidates: matrix::Matrix<bool>,
+    candidates: matrix::Matrix<bool>,
+    start: Option<matrix::Pos>,
+    bias: super::Bias,
+    observer: &mut dyn FnMut(super::Event),
 ) -> Maze<T>
 where
     R: super::Randomizer + Sized,
     T: Clone,
 {
-    // The backracking path is initially empty
-    let mut path = Vec::new();
+    let mut state = State::new(rng, candidates, start, bias);
+    while state.step(&mut maze, rng, observer) {}
+
+    maze
+}
+
+/// The state of an ongoing initialisation using the _Depth First_ algorithm.
+pub(crate) struct State {
+    /// The rooms not yet visited.
+    candidates: super::Candidates,
+
+    /// The backtracking path.
+    path: Vec<matrix::Pos>,
+
+    /// The current room.
+    current: matrix::Pos,
+
+    /// Parameters tuning the texture.
+    bias: super::Bias,
+}
 
-    // Start in a random room; we know that at least one candidate exists
-    let mut current = super::random_room(rng, &candidates).unwrap();
+impl State {
+    /// Prepares an initialisation.
+    ///
+    /// # Arguments
+    /// *  `rng` - A random number generator.
+    /// *  `candidates` - A filter for the rooms to modify. At least one room
+    ///    must be a candidate.
+    /// *  `start` - The room in which to start, if it is a candidate.
+    /// *  `bias` - Parameters tuning the texture.
+    pub fn new<R>(
+        rng: &mut R,
+        candidates: matrix::Matrix<bool>,
+        start: Option<matrix::Pos>,
+        bias: super::Bias,
+    ) -> Self
+    where
+        R: super::Randomizer + Sized,
+    {
+        let candidates = super::Candidates::from(candidates);
 
-    loop {
-        candidates[current] = false;
+        // Start in the requested room, or in a random room; we know that at
+        // least one candidate exists
+        let current = start
+            .filter(|&pos| candidates.contains(pos))
+            .or_else(|| candidates.random(rng))
+            .unwrap();
+
+        Self {
+            candidates,
+            // The backracking path is initially empty
+            path: Vec::new(),
+            current,
+            bias,
+        }
+    }
+
+    /// Performs a single iteration of the algorithm.
+    ///
+    /// An iteration either opens a wall, or backtracks to a previous room.
+    /// The return value is whether any iterations remain.
+    ///
+    /// # Arguments
+    /// *  `maze``- The maze to initialise.
+    /// *  `rng` - A random number generator.
+    /// *  `observer` - A function notified of every modified wall.
+    pub fn step<R, T>(
+        &mut self,
+        maze: &mut Maze<T>,
+        rng: &mut R,
+        observer: &mut dyn FnMut(super::Event),
+    ) -> bool
+    where
+        R: super::Randomizer + Sized,
+        T: Clone,
+    {
+        let Self {
+            candidates,
+            path,
+            current,
+            bias,
+        } = self;
+        candidates.remove(*current);
+
+        // Occasionally continue from a random room on the path, which keeps
+        // the current room on the path to backtrack to later
+        if !path.is_empty() && bias.branches(rng) == Some(true) {
+            let index = rng.range(0, path.len());
+            core::mem::swap(current, &mut path[index]);
+        }
 
         // Find all non-visited neighbours as the tuple (neighbour-position,
         // wall-from-current)
         let neighbors = maze
-            .walls(current)
+            .walls(*current)
             .iter()
-            .map(|wall| maze.back((current, wall)))
-            .filter(|&(pos, _)| *candidates.get(pos).unwrap_or(&false))
+            .map(|wall| maze.back((*current, wall)))
+            .filter(|&(pos, _)| candidates.contains(pos))
             .map(|(pos, wall)| (pos, maze.back((pos, wall)).1))
             .collect::<Vec<_>>();
 
@@ -48,18 +138,20 @@ where
         // backtrack to  the previous room; since the maze may be segmented, we
         // must also attempt to find a new random room
         if !neighbors.is_empty() {
-            let (next, wall) = neighbors[rng.range(0, neighbors.len())];
-            maze.open((current, wall));
-            path.push(current);
-            current = next;
-        } else if let Some(next) =
-            path.pop().or_else(|| super::random_room(rng, &candidates))
+            let index = bias.select(rng, neighbors.len(), |index| {
+                neighbors[index].0.row == current.row
+            });
+            let (next, wall) = neighbors[index];
+            super::open(maze, (*current, wall), observer);
+            path.push(*current);
+            *current = next;
+            true
+        } else if let Some(next) = path.pop().or_else(|| candidates.random(rng))
         {
-            current = next;
+            *current = next;
+            true
         } else {
-            break;
+            false
         }
     }
-
-    maze
 }
@@ -0,0 +1,605 @@
+//! # Exchange formats
+//!
+//! Mazes are often generated once and then played in a different program,
+//! and existing mazes are often drawn by other generators. This module writes
+//! mazes in formats that such programs can read without depending on this
+//! crate, and reads mazes in formats commonly used by puzzle collections.
+//!
+//! ## Puzzle JSON
+//!
+//! The format generated by [`puzzle_json`] describes a maze with a single
+//! puzzle: a start room, a goal room and the solution between them. It is
+//! intended for puzzle apps, which only need to draw walls and check moves.
+//! The document is an object with the following fields:
+//!
+//! *  `shape` - The shape of the rooms: `"tri"`, `"quad"` or `"hex"`.
+//! *  `width`, `height` - The dimensions of the maze, in rooms.
+//! *  `viewbox` - The area covered by the maze as `[x, y, width, height]`.
+//! *  `rooms` - Every room as an object with the fields `col` and `row`,
+//!    its matrix position, `center`, its physical centre as `[x, y]`, and
+//!    `neighbors`, the rooms reachable through an open wall as
+//!    `[col, row]`.
+//! *  `walls` - Every wall as an object with the fields `col` and `row`, the
+//!    room of the wall, `name`, the name of the wall, `open`, whether the
+//!    wall is open, and `span`, the end points of the wall as
+//!    `[x1, y1, x2, y2]`. Every wall is listed once, even though it has two
+//!    sides.
+//! *  `start`, `goal` - The first and last rooms of the solution as
+//!    `[col, row]`.
+//! *  `solution` - The rooms of the solution, from `start` to `goal`, as
+//!    `[col, row]`.
+//!
+//! All physical coordinates use the same unit as [`Maze::center`].
+//!
+//! ## Plain text
+//!
+//! Mazes drawn with characters, as found in many puzzle collections, are read
+//! by [`parse_text`]. Every other line contains horizontal walls, and the
+//! lines between them contain the rooms and vertical walls:
+//!
+//! ```text
+//! +--+--+     ┌─────┐     #####
+//! |     |     │     │     #   #
+//! +--+  +     ├──╴  │     ### #
+//! |     |     │     │     #   #
+//! +--+--+     └─────┘     #####
+//! ```
+//!
+//! The characters `+`, `-`, `|`, `#` and box-drawing characters are walls;
+//! all other characters are ignored. The width of rooms is determined from
+//! the columns of the vertical walls and corners, so any width is supported
+//! as long as all rooms have the same width.
+//!
+//! ## `.maze` files
+//!
+//! The simple format read by [`parse_maze`] is used by several generators.
+//! Lines starting with `#` are comments. The first remaining line contains
+//! the width and height of the maze, and it is followed by one line per row
+//! of rooms, each containing one number per room. The number is a bit mask
+//! of the open walls of the room, where `1` is north, `2` is south, `4` is
+//! east and `8` is west.
+//!
+//! Only mazes with quadratic rooms can be imported.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Write;
+
+use crate::matrix;
+use crate::shape::quad;
+use crate::validate::Violation;
+use crate::walk::Path;
+use crate::wall;
+use crate::Maze;
+use crate::Shape;
+
+/// An error occurring when importing a maze.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The source contains no maze.
+    Empty,
+
+    /// The dimensions of the maze are missing or invalid.
+    InvalidDimensions,
+
+    /// A line does not fit the layout of the maze.
+    ///
+    /// The line number starts at `1`.
+    InvalidLine(usize),
+
+    /// The imported maze breaks the invariants of a maze.
+    Invalid(Vec<Violation>),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "no maze found"),
+            ParseError::InvalidDimensions => {
+                write!(f, "invalid maze dimensions")
+            }
+            ParseError::InvalidLine(line) => write!(f, "invalid line {}", line),
+            ParseError::Invalid(violations) => {
+                write!(f, "invalid maze: ")?;
+                for (i, violation) in violations.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", violation)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// Generates a _JSON_ document describing a maze and its solution.
+///
+/// See the [module documentation](self) for a description of the format.
+///
+/// # Example
+///
+/// ```
+/// # use maze::initialize::*;
+/// let maze = maze::Shape::Quad
+///     .create::<()>(5, 5)
+///     .initialize(Method::default(), &mut LFSR::new(12345));
+/// let path = maze
+///     .walk((0isize, 0isize).into(), (4isize, 4isize).into())
+///     .unwrap();
+///
+/// let json = maze::io::puzzle_json(&maze, &path);
+/// assert!(json.starts_with(r#"{"shape":"quad","width":5,"height":5,"#));
+/// assert!(json.contains(r#""start":[0,0],"goal":[4,4],"#));
+/// ```
+///
+/// # Arguments
+/// *  `maze` - The maze.
+/// *  `solution` - The solution of the puzzle.
+pub fn puzzle_json<T>(maze: &Maze<T>, solution: &Path<'_, T>) -> String
+where
+    T: Clone,
+{
+    let pos = |pos: matrix::Pos| format!("[{},{}]", pos.col, pos.row);
+    let list = |items: Vec<String>| format!("[{}]", items.join(","));
+
+    let viewbox = maze.viewbox();
+    let rooms = maze
+        .positions()
+        .map(|room| {
+            let center = maze.center(room);
+            format!(
+                r#"{{"col":{},"row":{},"center":[{},{}],"neighbors":{}}}"#,
+                room.col,
+                room.row,
+                center.x,
+                center.y,
+                list(
+                    maze.neighbors(room)
+                        .filter(|&next| maze.is_inside(next))
+                        .map(pos)
+                        .collect(),
+                ),
+            )
+        })
+        .collect();
+    let walls = maze
+        .walls_iter()
+        .map(|(wall_pos, open)| {
+            let (from, to) = maze.corners(wall_pos);
+            format!(
+                concat!(
+                    r#"{{"col":{},"row":{},"name":"{}","open":{},"#,
+                    r#""span":[{},{},{},{}]}}"#,
+                ),
+                wall_pos.0.col,
+                wall_pos.0.row,
+                wall_pos.1.name,
+                open,
+                from.x,
+                from.y,
+                to.x,
+                to.y,
+            )
+        })
+        .collect();
+    let rooms_of_solution = solution.into_iter().collect::<Vec<_>>();
+
+    let mut json = String::new();
+    let _ = write!(
+        json,
+        concat!(
+            r#"{{"shape":"{}","width":{},"height":{},"#,
+            r#""viewbox":[{},{},{},{}],"rooms":{},"walls":{},"#,
+            r#""start":{},"goal":{},"solution":{}}}"#,
+        ),
+        maze.shape(),
+        maze.width(),
+        maze.height(),
+        viewbox.corner.x,
+        viewbox.corner.y,
+        viewbox.width,
+        viewbox.height,
+        list(rooms),
+        list(walls),
+        pos(rooms_of_solution[0]),
+        pos(rooms_of_solution[rooms_of_solution.len() - 1]),
+        list(rooms_of_solution.into_iter().map(pos).collect()),
+    );
+
+    json
+}
+
+/// Reads a maze drawn with characters.
+///
+/// See the [module documentation](self) for a description of the format.
+/// Openings in the outer wall, such as entrances, are ignored.
+///
+/// # Example
+///
+/// ```
+/// let maze = maze::io::parse_text(
+///     "+--+--+\n\
+///      |     |\n\
+///      +--+  +\n\
+///      |     |\n\
+///      +--+--+\n",
+/// )
+/// .unwrap();
+///
+/// assert_eq!((maze.width(), maze.height()), (2, 2));
+/// assert!(maze.connected((0isize, 0isize).into(), (1isize, 0isize).into()));
+/// assert!(maze.connected((1isize, 0isize).into(), (1isize, 1isize).into()));
+/// assert!(!maze.connected((0isize, 0isize).into(), (0isize, 1isize).into()));
+/// ```
+///
+/// # Arguments
+/// *  `text` - The text to parse.
+pub fn parse_text(text: &str) -> Result<Maze<()>, ParseError> {
+    let lines = text
+        .lines()
+        .map(|line| line.trim_end().chars().collect::<Vec<_>>())
+        .skip_while(|line| line.is_empty())
+        .collect::<Vec<_>>();
+    let lines = &lines[..lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map(|i| i + 1)
+        .unwrap_or(0)];
+    if lines.len() < 3 {
+        return Err(ParseError::Empty);
+    }
+    if lines.len() % 2 == 0 {
+        return Err(ParseError::InvalidDimensions);
+    }
+
+    // Lines may have had trailing spaces removed
+    let length = lines.iter().map(Vec::len).max().unwrap_or(0);
+    let wall_at = |line: usize, column: usize| {
+        lines[line]
+            .get(column)
+            .copied()
+            .map(is_wall)
+            .unwrap_or(false)
+    };
+
+    // The vertical walls of every room line, and the corners of every wall
+    // line, are at multiples of the room width; corners are either drawn
+    // explicitly or found next to an opening
+    let step = (0..lines.len())
+        .flat_map(|line| {
+            (0..lines[line].len()).filter(move |&column| {
+                if line % 2 == 0 {
+                    wall_at(line, column)
+                        && (is_corner(lines[line][column])
+                            || column == 0
+                            || !wall_at(line, column - 1)
+                            || !wall_at(line, column + 1))
+                } else {
+                    wall_at(line, column)
+                }
+            })
+        })
+        .fold(length - 1, gcd);
+    if step < 2 {
+        return Err(ParseError::InvalidDimensions);
+    }
+    for line in (1..lines.len()).step_by(2) {
+        if !wall_at(line, 0) {
+            return Err(ParseError::InvalidLine(line + 1));
+        }
+    }
+
+    let width = (length - 1) / step;
+    let height = (lines.len() - 1) / 2;
+    let mut maze = Shape::Quad.create::<()>(width, height);
+    for pos in maze.positions() {
+        let line = 2 * pos.row as usize + 1;
+        let column = pos.col as usize * step;
+        if pos.col as usize + 1 < width && !wall_at(line, column + step) {
+            maze.open((pos, &quad::walls::RIGHT));
+        }
+        if pos.row as usize + 1 < height
+            && !(column + 1..column + step).any(|c| wall_at(line + 1, c))
+        {
+            maze.open((pos, &quad::walls::DOWN));
+        }
+    }
+
+    maze.validate().map_err(ParseError::Invalid)?;
+    Ok(maze)
+}
+
+/// Reads a maze in the `.maze` format.
+///
+/// See the [module documentation](self) for a description of the format.
+/// Unlike [`parse_text`], the walls of every room are listed separately, so
+/// a wall open on only one side, or leading out of the maze, is an error.
+///
+/// # Example
+///
+/// ```
+/// let maze = maze::io::parse_maze(
+///     "# A maze with two rooms\n\
+///      2 1\n\
+///      4 8\n",
+/// )
+/// .unwrap();
+///
+/// assert!(maze.connected((0isize, 0isize).into(), (1isize, 0isize).into()));
+/// assert!(maze::io::parse_maze("2 1\n4 0\n").is_err());
+/// ```
+///
+/// # Arguments
+/// *  `text` - The text to parse.
+pub fn parse_maze(text: &str) -> Result<Maze<()>, ParseError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    let (_, header) = lines.next().ok_or(ParseError::Empty)?;
+    let dimensions = header
+        .split_whitespace()
+        .map(str::parse::<usize>)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ParseError::InvalidDimensions)?;
+    let (width, height) = match dimensions.as_slice() {
+        &[width, height] if width > 0 && height > 0 => (width, height),
+        _ => return Err(ParseError::InvalidDimensions),
+    };
+
+    /// The walls corresponding to the bits of a room.
+    const WALLS: [&wall::Wall; 4] = [
+        &quad::walls::UP,
+        &quad::walls::DOWN,
+        &quad::walls::RIGHT,
+        &quad::walls::LEFT,
+    ];
+
+    let mut maze = Shape::Quad.create::<()>(width, height);
+    let mut rows = 0;
+    for (row, (number, line)) in lines.enumerate() {
+        let masks = line
+            .split_whitespace()
+            .map(str::parse::<u8>)
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+            .filter(|masks| row < height && masks.len() == width)
+            .ok_or(ParseError::InvalidLine(number))?;
+        for (col, mask) in masks.into_iter().enumerate() {
+            if mask >= 1 << WALLS.len() {
+                return Err(ParseError::InvalidLine(number));
+            }
+            let pos = matrix::Pos {
+                col: col as isize,
+                row: row as isize,
+            };
+            for (bit, &wall) in WALLS.iter().enumerate() {
+                if mask & (1 << bit) != 0 {
+                    maze.rooms[pos].open(wall);
+                }
+            }
+        }
+        rows += 1;
+    }
+    if rows != height {
+        return Err(ParseError::InvalidDimensions);
+    }
+
+    maze.validate().map_err(ParseError::Invalid)?;
+    Ok(maze)
+}
+
+/// Whether a character is part of a wall in a maze drawn with characters.
+///
+/// # Arguments
+/// *  `c` - The character.
+fn is_wall(c: char) -> bool {
+    matches!(c, '+' | '-' | '|' | '#' | '\u{2500}'..='\u{259f}')
+}
+
+/// Whether a character may be drawn where walls meet in a maze drawn with
+/// characters.
+///
+/// Horizontal lines and blocks are excluded, since they are also drawn
+/// between corners.
+///
+/// # Arguments
+/// *  `c` - The character.
+fn is_corner(c: char) -> bool {
+    matches!(c, '+' | '\u{2500}'..='\u{257f}')
+        && !matches!(
+            c,
+            '\u{2500}'
+                | '\u{2501}'
+                | '\u{2504}'
+                | '\u{2505}'
+                | '\u{2508}'
+                | '\u{2509}'
+                | '\u{254c}'
+                | '\u{254d}'
+                | '\u{2550}'
+        )
+}
+
+/// The greatest common divisor of two numbers.
+///
+/// # Arguments
+/// *  `a` - The first number.
+/// *  `b` - The second number.
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    /// Draws a quadratic maze using `+`, `-` and `|`.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to draw.
+    fn draw(maze: &TestMaze) -> String {
+        let mut text = String::new();
+        let line = |text: &mut String, row: isize, wall| {
+            for col in 0..maze.width() as isize {
+                text.push('+');
+                text.push_str(if maze.is_open((matrix_pos(col, row), wall)) {
+                    "  "
+                } else {
+                    "--"
+                });
+            }
+            text.push_str("+\n");
+        };
+
+        line(&mut text, 0, &quad::walls::UP);
+        for row in 0..maze.height() as isize {
+            for col in 0..maze.width() as isize {
+                let pos = matrix_pos(col, row);
+                text.push(if maze.is_open((pos, &quad::walls::LEFT)) {
+                    ' '
+                } else {
+                    '|'
+                });
+                text.push_str("  ");
+            }
+            text.push_str("|\n");
+            line(&mut text, row, &quad::walls::DOWN);
+        }
+
+        text
+    }
+
+    #[maze_test(quad)]
+    fn parse_text_round_trip(maze: TestMaze) {
+        let maze = maze.initialize(
+            crate::initialize::Method::default(),
+            &mut crate::initialize::LFSR::new(12345),
+        );
+        let parsed = parse_text(&draw(&maze)).unwrap();
+
+        assert_eq!(
+            (maze.width(), maze.height()),
+            (parsed.width(), parsed.height()),
+        );
+        assert!(maze.diff(&parsed).is_empty());
+    }
+
+    #[test]
+    fn parse_text_styles() {
+        let expected =
+            parse_text("+--+--+\n|     |\n+--+  +\n|     |\n+--+--+").unwrap();
+        for text in [
+            "┌─────┐\n│     │\n├──╴  │\n│     │\n└─────┘\n",
+            "#####\n#   #\n### #\n#   #\n#####\n",
+            "\n+---+---+\n| S     |\n+---+   +\n|     E |\n+---+---+\n\n",
+        ] {
+            let maze = parse_text(text).unwrap();
+            assert_eq!((2, 2), (maze.width(), maze.height()), "for {}", text);
+            assert!(maze.diff(&expected).is_empty(), "for {}", text);
+        }
+    }
+
+    #[test]
+    fn parse_text_invalid() {
+        assert_eq!(Err(ParseError::Empty), parse_text("").map(|_| ()));
+        assert_eq!(
+            Err(ParseError::InvalidDimensions),
+            parse_text("+--+\n|  |\n+--+\n|  |").map(|_| ()),
+        );
+        assert_eq!(
+            Err(ParseError::InvalidLine(2)),
+            parse_text("+--+\n   |\n+--+").map(|_| ()),
+        );
+    }
+
+    #[test]
+    fn parse_maze_valid() {
+        let maze = parse_maze("# Comment\n3 2\n6 12 10\n1 4 9\n").unwrap();
+
+        assert_eq!((3, 2), (maze.width(), maze.height()));
+        assert_eq!(Ok(()), maze.validate());
+        assert!(maze.walk(matrix_pos(0, 1), matrix_pos(2, 1)).is_some());
+        assert!(!maze.connected(matrix_pos(1, 0), matrix_pos(1, 1)));
+    }
+
+    #[test]
+    fn parse_maze_invalid() {
+        assert_eq!(Err(ParseError::Empty), parse_maze("# Nothing").map(|_| ()));
+        assert_eq!(
+            Err(ParseError::InvalidDimensions),
+            parse_maze("2 x\n0 0").map(|_| ()),
+        );
+        assert_eq!(
+            Err(ParseError::InvalidDimensions),
+            parse_maze("2 2\n0 0").map(|_| ()),
+        );
+        assert_eq!(
+            Err(ParseError::InvalidLine(2)),
+            parse_maze("2 1\n0 0 0").map(|_| ()),
+        );
+        assert_eq!(
+            Err(ParseError::InvalidLine(2)),
+            parse_maze("2 1\n0 16").map(|_| ()),
+        );
+        assert_eq!(
+            Err(ParseError::Invalid(vec![
+                Violation::Asymmetric((matrix_pos(0, 0), &quad::walls::RIGHT)),
+                Violation::OpenToOutside((
+                    matrix_pos(1, 0),
+                    &quad::walls::RIGHT,
+                )),
+            ])),
+            parse_maze("2 1\n4 4").map(|_| ()),
+        );
+    }
+
+    #[maze_test]
+    fn puzzle_json_valid(mut maze: TestMaze) {
+        let log = Navigator::new(&mut maze).down(true).right(true).stop();
+        let path = maze.walk(log[0], *log.last().unwrap()).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&puzzle_json(&maze, &path)).unwrap();
+        assert_eq!(
+            maze.width() * maze.height(),
+            json["rooms"].as_array().unwrap().len(),
+        );
+        assert_eq!(
+            maze.walls_iter().count(),
+            json["walls"].as_array().unwrap().len(),
+        );
+        assert_eq!(
+            log.len() - 1,
+            json["walls"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|wall| wall["open"].as_bool().unwrap())
+                .count(),
+        );
+        assert_eq!(serde_json::json!([log[0].col, log[0].row]), json["start"]);
+        assert_eq!(
+            log.iter()
+                .map(|pos| serde_json::json!([pos.col, pos.row]))
+                .collect::<Vec<_>>(),
+            *json["solution"].as_array().unwrap(),
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! # Typed room layers
+//!
+//! The data parameter of a maze holds a single value per room. Subsystems
+//! that need to attach their own data to rooms, such as colours, movement
+//! costs or triggers, can instead use a layer: a matrix of values with the
+//! same dimensions as the maze, identified by the type of its values.
+//!
+//! Every type has at most one layer, so subsystems should use their own
+//! types, for example newtypes, to avoid conflicts. Since mazes may be shared
+//! between threads, the values must be [`Send`] and [`Sync`]. Layers are
+//! copied when a maze is cloned or mapped, but they are not serialised.
+
+use alloc::boxed::Box;
+use core::any::{Any, TypeId};
+use core::fmt;
+
+use crate::matrix;
+use crate::HashMap;
+use crate::Maze;
+
+/// A type erased layer.
+trait Layer: Any + Send + Sync {
+    /// Clones this layer.
+    fn clone_layer(&self) -> Box<dyn Layer>;
+
+    /// This layer as a reference to [`Any`].
+    fn as_any(&self) -> &dyn Any;
+
+    /// This layer as a mutable reference to [`Any`].
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Converts this layer to a boxed [`Any`].
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<K> Layer for matrix::Matrix<K>
+where
+    K: Clone + Send + Sync + 'static,
+{
+    fn clone_layer(&self) -> Box<dyn Layer> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// The layers of a maze, keyed by the type of their values.
+#[derive(Default)]
+pub(crate) struct Layers(HashMap<TypeId, Box<dyn Layer>>);
+
+impl Clone for Layers {
+    fn clone(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|(&key, layer)| (key, layer.clone_layer()))
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Debug for Layers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.keys()).finish()
+    }
+}
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// The layer with values of a specific type.
+    ///
+    /// If no such layer has been created, nothing is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// /// The cost of entering a room.
+    /// #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    /// struct Cost(u32);
+    ///
+    /// let mut maze = maze::Shape::Quad.create::<()>(5, 5);
+    /// assert!(maze.layer::<Cost>().is_none());
+    ///
+    /// let pos = matrix::Pos { col: 2, row: 3 };
+    /// maze.layer_mut::<Cost>()[pos] = Cost(10);
+    /// assert_eq!(
+    ///     maze.layer::<Cost>().map(|costs| costs[pos]),
+    ///     Some(Cost(10)),
+    /// );
+    /// ```
+    pub fn layer<K>(&self) -> Option<&matrix::Matrix<K>>
+    where
+        K: Clone + Send + Sync + 'static,
+    {
+        self.layers
+            .0
+            .get(&TypeId::of::<K>())
+            .and_then(|layer| layer.as_any().downcast_ref())
+    }
+
+    /// The mutable layer with values of a specific type.
+    ///
+    /// If no such layer has been created, a layer with default values and
+    /// the dimensions of this maze is created. The dimensions of the layer
+    /// should not be changed.
+    pub fn layer_mut<K>(&mut self) -> &mut matrix::Matrix<K>
+    where
+        K: Clone + Default + Send + Sync + 'static,
+    {
+        let (width, height) = (self.width(), self.height());
+        self.layers
+            .0
+            .entry(TypeId::of::<K>())
+            .or_insert_with(|| {
+                Box::new(matrix::Matrix::<K>::new(width, height))
+            })
+            .as_any_mut()
+            .downcast_mut()
+            .unwrap()
+    }
+
+    /// Removes the layer with values of a specific type.
+    ///
+    /// The removed layer is returned, or nothing if no such layer exists.
+    pub fn remove_layer<K>(&mut self) -> Option<matrix::Matrix<K>>
+    where
+        K: Clone + Send + Sync + 'static,
+    {
+        self.layers
+            .0
+            .remove(&TypeId::of::<K>())
+            .and_then(|layer| layer.into_any().downcast().ok())
+            .map(|layer| *layer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use crate::test_utils::*;
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    struct Cost(u32);
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    struct Trap(bool);
+
+    #[maze_test]
+    fn layer_types(mut maze: TestMaze) {
+        let pos = matrix_pos(1, 2);
+        assert!(maze.layer::<Cost>().is_none());
+
+        maze.layer_mut::<Cost>()[pos] = Cost(3);
+        maze.layer_mut::<Trap>()[pos] = Trap(true);
+        let costs = maze.layer::<Cost>().unwrap();
+        assert_eq!((maze.width(), maze.height()), (costs.width, costs.height));
+        assert_eq!(Cost(3), costs[pos]);
+        assert_eq!(Cost(0), costs[matrix_pos(0, 0)]);
+        assert_eq!(Trap(true), maze.layer::<Trap>().unwrap()[pos]);
+    }
+
+    #[maze_test]
+    fn layer_clone(mut maze: TestMaze) {
+        let pos = matrix_pos(1, 2);
+        maze.layer_mut::<Cost>()[pos] = Cost(3);
+
+        let mut clone = maze.clone();
+        clone.layer_mut::<Cost>()[pos] = Cost(4);
+        assert_eq!(Cost(3), maze.layer::<Cost>().unwrap()[pos]);
+        assert_eq!(Cost(4), clone.layer::<Cost>().unwrap()[pos]);
+        assert_eq!(Cost(3), maze.map(|_, _| 1u8).layer::<Cost>().unwrap()[pos]);
+
+        let removed = maze.remove_layer::<Cost>().unwrap();
+        assert_eq!(Cost(3), removed[pos]);
+        assert!(maze.layer::<Cost>().is_none());
+        assert!(maze.remove_layer::<Trap>().is_none());
+    }
+}
@@ -1,34 +1,80 @@
 #![deny(clippy::all)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+#[cfg(all(test, not(feature = "std")))]
+extern crate std;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use hashbrown::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+
 #[cfg(test)]
 mod test_utils;
 
 #[macro_use]
 mod macros;
 
+#[cfg(not(feature = "std"))]
+mod float;
+
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+
 pub mod wall;
 
 pub mod shape;
 pub use self::shape::Shape;
 
+mod error;
+pub use self::error::Error;
+
+mod layer;
+
+mod sight;
+
+pub mod analysis;
+pub mod classify;
+pub mod graph;
 pub mod initialize;
+pub mod io;
 pub mod matrix;
+pub mod multi;
+pub mod overlay;
 pub mod physical;
 pub mod render;
 pub mod room;
+pub mod validate;
 pub mod walk;
 
+/// The version of this crate.
+///
+/// Mazes generated with the same seed are only guaranteed to be identical
+/// for the same version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// A wall of a room.
 pub type WallPos = (matrix::Pos, &'static wall::Wall);
 
 /// A matrix of rooms.
 type Rooms<T> = matrix::Matrix<room::Room<T>>;
 
+/// The layer tracking whether rooms have been explored.
+#[derive(Clone, Copy, Debug, Default)]
+struct Explored(bool);
+
 /// A maze contains rooms and has methods for managing paths and doors.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Maze<T>
 where
@@ -39,6 +85,10 @@ where
 
     /// The actual rooms.
     rooms: Rooms<T>,
+
+    /// Additional room data, keyed by type.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    layers: layer::Layers,
 }
 
 impl<T> Maze<T>
@@ -53,7 +103,11 @@ where
     /// *  `height` - The height, in rooms, of the maze.
     pub fn new(shape: Shape, width: usize, height: usize) -> Self {
         let rooms = Rooms::new(width, height);
-        Self { shape, rooms }
+        Self {
+            shape,
+            rooms,
+            layers: Default::default(),
+        }
     }
 }
 
@@ -80,7 +134,11 @@ where
         F: FnMut(matrix::Pos) -> T,
     {
         let rooms = Rooms::new_with_data(width, height, |pos| data(pos).into());
-        Self { shape, rooms }
+        Self {
+            shape,
+            rooms,
+            layers: Default::default(),
+        }
     }
 
     /// Maps each room, yielding a maze with the same layout but with
@@ -98,6 +156,7 @@ where
             rooms: self.rooms.map_with_pos(|pos, value| {
                 value.with_data(data(pos, value.data.clone()))
             }),
+            layers: self.layers.clone(),
         }
     }
 
@@ -126,6 +185,16 @@ where
         self.rooms.get(pos).map(|room| &room.data)
     }
 
+    /// The room at a specific position.
+    ///
+    /// This is the fallible variant of indexing a maze.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn get(&self, pos: matrix::Pos) -> Result<&room::Room<T>, Error> {
+        self.rooms.get(pos).ok_or(Error::OutOfBounds(pos))
+    }
+
     /// The mutable data for a specific room.
     ///
     /// If the position is out of bounds, nothing is returned.
@@ -144,6 +213,78 @@ where
         self.rooms.is_inside(pos)
     }
 
+    /// Whether a room has been visited.
+    ///
+    /// Opening a wall marks the rooms on both sides as visited, and the
+    /// initialisation methods use this flag to determine which rooms are part
+    /// of the maze. Rooms not visited are not rendered.
+    ///
+    /// To track the rooms explored by a player, use
+    /// [`explored`](Self::explored) instead.
+    ///
+    /// If the position is out of bounds, `false` is returned.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn visited(&self, pos: matrix::Pos) -> bool {
+        self.rooms
+            .get(pos)
+            .map(|room| room.visited)
+            .unwrap_or(false)
+    }
+
+    /// Whether a room has been explored.
+    ///
+    /// The exploration state is kept separately from the room data and the
+    /// [`visited`](Self::visited) flag, so it may be used freely, for example
+    /// to track the rooms seen by a player, without affecting initialisation
+    /// or rendering. No room is explored initially.
+    ///
+    /// If the position is out of bounds, `false` is returned.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn explored(&self, pos: matrix::Pos) -> bool {
+        self.layer::<Explored>()
+            .and_then(|explored| explored.get(pos))
+            .is_some_and(|&Explored(value)| value)
+    }
+
+    /// Sets whether a room has been explored.
+    ///
+    /// If the position is out of bounds, nothing is changed.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    /// *  `value` - Whether the room has been explored.
+    pub fn set_explored(&mut self, pos: matrix::Pos, value: bool) {
+        if self.is_inside(pos) {
+            self.layer_mut::<Explored>()[pos] = Explored(value);
+        }
+    }
+
+    /// Marks all rooms as not explored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::initialize::*;
+    /// let mut maze = maze::Shape::Quad
+    ///     .create::<()>(5, 5)
+    ///     .initialize(Method::default(), &mut LFSR::new(12345));
+    ///
+    /// let start = (0isize, 0isize).into();
+    /// maze.set_explored(start, true);
+    /// assert_eq!(maze.positions().filter(|&pos| maze.explored(pos)).count(), 1);
+    ///
+    /// maze.reset_explored();
+    /// assert_eq!(maze.positions().filter(|&pos| maze.explored(pos)).count(), 0);
+    /// assert!(maze.positions().all(|pos| maze.visited(pos)));
+    /// ```
+    pub fn reset_explored(&mut self) {
+        self.remove_layer::<Explored>();
+    }
+
     /// Whether a wall is open.
     ///
     /// If the position is out of bounds, `false` is returned.
@@ -230,6 +371,32 @@ where
         self.set_open(wall_pos, false);
     }
 
+    /// Opens a wall if it is closed, and closes it if it is open.
+    ///
+    /// The new state of the wall is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// let mut maze = maze::Shape::Quad.create::<()>(5, 5);
+    /// let pos = matrix::Pos { col: 2, row: 2 };
+    /// let wall_pos = (pos, maze.walls(pos)[0]);
+    ///
+    /// assert!(maze.toggle(wall_pos));
+    /// assert!(maze.is_open(maze.back(wall_pos)));
+    /// assert!(!maze.toggle(wall_pos));
+    /// assert!(!maze.is_open(wall_pos));
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `wall_pos` - The wall position.
+    pub fn toggle(&mut self, wall_pos: WallPos) -> bool {
+        let value = !self.is_open(wall_pos);
+        self.set_open(wall_pos, value);
+        value
+    }
+
     /// Iterates over all room positions.
     ///
     /// The positions are visited row by row, starting from `(0, 0)` and ending
@@ -247,6 +414,61 @@ where
         (center + wall_pos.1.span.0, center + wall_pos.1.span.1)
     }
 
+    /// The physical positions of the corners of both faces of a thick wall.
+    ///
+    /// The first pair of corners is the inner face, facing the room of the
+    /// wall position, and the second pair the outer face, facing the room
+    /// on the back of the wall. Each face is parallel to the wall and half of
+    /// `thickness` away from it, and the corners are moved along the lines
+    /// through the centre of the room, so the faces of neighbouring walls
+    /// meet at their corners.
+    ///
+    /// With a thickness of `0.0`, both faces are equal to
+    /// [`corners`](Self::corners).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// let maze = maze::Shape::Quad.create::<()>(2, 2);
+    /// let wall_pos = (
+    ///     matrix::Pos { col: 0, row: 0 },
+    ///     &maze::shape::quad::walls::RIGHT,
+    /// );
+    /// let ((inner, _), (outer, _)) = maze.wall_faces(wall_pos, 0.2);
+    ///
+    /// let (corner, _) = maze.corners(wall_pos);
+    /// assert!((corner.x - inner.x - 0.1).abs() < 1e-5);
+    /// assert!((outer.x - corner.x - 0.1).abs() < 1e-5);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `wall_pos` - The wall position.
+    /// *  `thickness` - The thickness of the wall.
+    pub fn wall_faces(
+        &self,
+        wall_pos: WallPos,
+        thickness: f32,
+    ) -> (
+        (physical::Pos, physical::Pos),
+        (physical::Pos, physical::Pos),
+    ) {
+        let center = self.center(wall_pos.0);
+        let offset = self.shape.corner_offset(thickness);
+        let corner = |angle: wall::Angle, scale: f32| {
+            center
+                + physical::Pos {
+                    x: angle.dx * scale,
+                    y: angle.dy * scale,
+                }
+        };
+        let (start, end) = wall_pos.1.span;
+        (
+            (corner(start, 1.0 - offset), corner(end, 1.0 - offset)),
+            (corner(start, 1.0 + offset), corner(end, 1.0 + offset)),
+        )
+    }
+
     /// See [`Self::corner_walls_start`].
     #[deprecated]
     pub fn corner_walls(
@@ -271,7 +493,7 @@ where
         wall_pos: WallPos,
     ) -> impl DoubleEndedIterator<Item = WallPos> {
         let (matrix::Pos { col, row }, wall) = wall_pos;
-        std::iter::once(wall_pos).chain(wall.corner_wall_offsets.iter().map(
+        core::iter::once(wall_pos).chain(wall.corner_wall_offsets.iter().map(
             move |&wall::Offset { dx, dy, wall }| {
                 (
                     matrix::Pos {
@@ -300,7 +522,7 @@ where
     ) -> impl DoubleEndedIterator<Item = WallPos> {
         let shape = self.shape;
         let (matrix::Pos { col, row }, wall) = shape.back(wall_pos);
-        std::iter::once(wall_pos).chain(
+        core::iter::once(wall_pos).chain(
             wall.corner_wall_offsets.iter().rev().map(
                 move |&wall::Offset { dx, dy, wall }| {
                     shape.back((
@@ -326,6 +548,127 @@ where
         self.walls(pos).iter().map(move |&wall| (pos, wall))
     }
 
+    /// Iterates over every wall of the maze, and whether it is open.
+    ///
+    /// Every wall is visited only once, even though it has two sides. Of the
+    /// two sides, the one in the room with the lowest row, and then column,
+    /// is used, unless the other side is outside of the maze.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::Shape;
+    /// let mut maze = Shape::Quad.create::<()>(2, 1);
+    /// maze.open(
+    ///     maze.connecting_wall((0isize, 0isize).into(), (1isize, 0isize).into())
+    ///         .unwrap(),
+    /// );
+    ///
+    /// assert_eq!(maze.walls_iter().count(), 7);
+    /// assert_eq!(maze.walls_iter().filter(|&(_, open)| open).count(), 1);
+    /// ```
+    pub fn walls_iter(&self) -> impl Iterator<Item = (WallPos, bool)> + '_ {
+        self.positions()
+            .flat_map(move |pos| self.wall_positions(pos))
+            .filter(move |&wall_pos| {
+                let (back, _) = self.back(wall_pos);
+                !self.is_inside(back)
+                    || (wall_pos.0.row, wall_pos.0.col) < (back.row, back.col)
+            })
+            .map(move |wall_pos| (wall_pos, self.is_open(wall_pos)))
+    }
+
+    /// Lists all walls whose open state differs between this maze and
+    /// another.
+    ///
+    /// Every wall is listed once, using the same side as
+    /// [`walls_iter`](Self::walls_iter). The mazes are expected to have the
+    /// same shape; rooms of this maze missing from `other` are considered
+    /// closed, and rooms of `other` missing from this maze are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::Shape;
+    /// let maze = Shape::Quad.create::<()>(2, 1);
+    /// let mut other = maze.clone();
+    /// let wall_pos = other
+    ///     .connecting_wall((0isize, 0isize).into(), (1isize, 0isize).into())
+    ///     .unwrap();
+    /// other.open(wall_pos);
+    ///
+    /// assert_eq!(maze.diff(&maze), vec![]);
+    /// assert_eq!(maze.diff(&other), vec![wall_pos]);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `other` - The maze to compare with.
+    pub fn diff<U>(&self, other: &Maze<U>) -> Vec<WallPos>
+    where
+        U: Clone,
+    {
+        self.walls_iter()
+            .filter(|&(wall_pos, open)| other.is_open(wall_pos) != open)
+            .map(|(wall_pos, _)| wall_pos)
+            .collect()
+    }
+
+    /// Iterates over all walls on the outer boundary of this maze.
+    ///
+    /// Only visited rooms are considered part of the maze, so rooms excluded
+    /// by a mask when initialising the maze are outside of it. An edge wall
+    /// is a wall of a visited room leading out of the maze or to a room that
+    /// has not been visited. The wall position is in the visited room.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::Shape;
+    /// # use maze::initialize::{LFSR, Method};
+    /// let maze = Shape::Quad
+    ///     .create::<()>(3, 3)
+    ///     .initialize_filter(Method::Clear, &mut LFSR::new(1), |pos| {
+    ///         pos.col < 2
+    ///     });
+    ///
+    /// assert_eq!(maze.edge_walls().count(), 10);
+    /// ```
+    pub fn edge_walls(&self) -> impl Iterator<Item = WallPos> + '_ {
+        let visited = |pos| self.visited(pos);
+        self.positions()
+            .filter(move |&pos| visited(pos))
+            .flat_map(move |pos| self.wall_positions(pos))
+            .filter(move |&wall_pos| !visited(self.back(wall_pos).0))
+    }
+
+    /// Iterates over all rooms on the outer boundary of this maze.
+    ///
+    /// These are the rooms with at least one wall returned by
+    /// [`edge_walls`](Self::edge_walls). Every room is visited once, in row
+    /// order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::Shape;
+    /// # use maze::initialize::{LFSR, Method};
+    /// let maze = Shape::Quad
+    ///     .create::<()>(3, 3)
+    ///     .initialize(Method::Clear, &mut LFSR::new(1));
+    ///
+    /// // All rooms except for the centre one
+    /// assert_eq!(maze.edge_rooms().count(), 8);
+    /// ```
+    pub fn edge_rooms(&self) -> impl Iterator<Item = matrix::Pos> + '_ {
+        let visited = |pos| self.visited(pos);
+        self.positions().filter(move |&pos| {
+            visited(pos)
+                && self
+                    .wall_positions(pos)
+                    .any(|wall_pos| !visited(self.back(wall_pos).0))
+        })
+    }
+
     /// Iterates over all open walls of a room.
     ///
     /// # Arguments
@@ -370,14 +713,98 @@ where
     ) -> impl DoubleEndedIterator<Item = matrix::Pos> + '_ {
         self.doors(pos).map(move |wall| self.back((pos, wall)).0)
     }
+
+    /// Generates a matrix containing the number of open walls of every room.
+    ///
+    /// Openings leading out of the maze are included.
+    pub fn degree_map(&self) -> matrix::Matrix<u8> {
+        self.rooms
+            .map_with_pos(|pos, _| self.doors(pos).count() as u8)
+    }
+
+    /// Counts the rooms with every number of open walls.
+    ///
+    /// The value at index _n_ of the returned vector is the number of rooms
+    /// with _n_ open walls, so a maze without dead ends has the value `0` at
+    /// index `1`. The length of the vector is one more than the largest
+    /// number of walls of any room.
+    pub fn degree_histogram(&self) -> Vec<usize> {
+        let len = self
+            .positions()
+            .map(|pos| self.walls(pos).len() + 1)
+            .max()
+            .unwrap_or(0);
+        self.degree_map().values().fold(
+            vec![0; len],
+            |mut histogram, &degree| {
+                histogram[degree as usize] += 1;
+                histogram
+            },
+        )
+    }
+
+    /// Creates a larger maze by turning every room into a block of rooms.
+    ///
+    /// Every room becomes a square block of `factor` × `factor` rooms with all
+    /// inner walls open. The walls between two blocks are open if the wall
+    /// between the corresponding rooms of this maze is open, so the resulting
+    /// maze has the same layout as this one on a finer grid. All rooms of a
+    /// block share the data and visited state of the original room. Layers are
+    /// not copied to the result.
+    ///
+    /// The result is typically used as a template for further modifications.
+    ///
+    /// Only quad mazes can be subdivided, since a block of hex or tri rooms
+    /// does not have the shape of a single room. For other shapes, and if
+    /// `factor` is `0`, nothing is returned.
+    ///
+    /// # Arguments
+    /// *  `factor` - The number of rooms in each direction to create from
+    ///    every room.
+    pub fn subdivide(&self, factor: usize) -> Option<Self> {
+        if self.shape != Shape::Quad || factor == 0 {
+            return None;
+        }
+
+        let original = |pos: matrix::Pos| matrix::Pos {
+            col: pos.col.div_euclid(factor as isize),
+            row: pos.row.div_euclid(factor as isize),
+        };
+        let mut result = self.shape.create_with_data(
+            self.width() * factor,
+            self.height() * factor,
+            |pos| self.rooms[original(pos)].data.clone(),
+        );
+        for pos in result.positions() {
+            result.rooms[pos].visited = self.rooms[original(pos)].visited;
+            for &wall in result.walls(pos) {
+                let (back, _) = result.back((pos, wall));
+                if original(pos) == original(back)
+                    || self.is_open((original(pos), wall))
+                {
+                    result.open((pos, wall));
+                }
+            }
+        }
+
+        Some(result)
+    }
 }
 
-impl<T> std::ops::Index<matrix::Pos> for Maze<T>
+impl<T> core::ops::Index<matrix::Pos> for Maze<T>
 where
     T: Clone,
 {
     type Output = room::Room<T>;
 
+    /// Retrieves the room at a specific position.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    ///
+    /// # Panics
+    /// Accessing a room outside of the maze will cause a panic. Use
+    /// [`get`](Self::get) to avoid this.
     fn index(&self, pos: matrix::Pos) -> &Self::Output {
         &self.rooms[pos]
     }
@@ -386,6 +813,147 @@ where
 /// A matrix of scores for rooms.
 pub type HeatMap = matrix::Matrix<u32>;
 
+impl HeatMap {
+    /// The largest value of this heat map.
+    ///
+    /// An empty heat map has the maximum value `0`.
+    pub fn max(&self) -> u32 {
+        self.values().copied().max().unwrap_or(0)
+    }
+
+    /// The value below which a given percentage of all values fall.
+    ///
+    /// This uses the _nearest rank_ method, so the value returned is always
+    /// one of the values of this heat map. An empty heat map has all
+    /// percentiles `0`.
+    ///
+    /// # Arguments
+    /// *  `p` - The percentile, in the range _[0, 100]_. Values outside of
+    ///    this range are clamped.
+    pub fn percentile(&self, p: f32) -> u32 {
+        let mut values = self.values().copied().collect::<Vec<_>>();
+        if values.is_empty() {
+            return 0;
+        }
+        values.sort_unstable();
+        let rank = (p.clamp(0.0, 100.0) / 100.0 * values.len() as f32).ceil();
+        values[(rank as usize).clamp(1, values.len()) - 1]
+    }
+
+    /// Scales all values linearly to the range _[0, 1]_.
+    ///
+    /// The largest value is mapped to `1`. If all values are `0`, so are all
+    /// values of the result.
+    pub fn normalize(&self) -> matrix::Matrix<f32> {
+        self.scaled(|value| value as f32)
+    }
+
+    /// Scales all values logarithmically to the range _[0, 1]_.
+    ///
+    /// This prevents the few very hot rooms of large mazes from washing out
+    /// the rest of the heat map. The largest value is mapped to `1`. If all
+    /// values are `0`, so are all values of the result.
+    pub fn normalize_log(&self) -> matrix::Matrix<f32> {
+        self.scaled(|value| (value as f32).ln_1p())
+    }
+
+    /// Scales all values to the range _[0, 1]_ after applying a mapping
+    /// function.
+    ///
+    /// # Arguments
+    /// *  `mapper` - A monotonic function mapping `0` to `0`.
+    fn scaled<F>(&self, mapper: F) -> matrix::Matrix<f32>
+    where
+        F: Fn(u32) -> f32,
+    {
+        let max = mapper(self.max());
+        self.map(|&value| if max > 0.0 { mapper(value) / max } else { 0.0 })
+    }
+
+    /// Writes this heat map as comma separated values.
+    ///
+    /// Every row of the heat map is written as a line of values, from the
+    /// top down.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::HeatMap;
+    /// let heat_map = HeatMap::new_with_data(3, 2, |pos| {
+    ///     (pos.col + 3 * pos.row) as u32
+    /// });
+    /// let mut csv = Vec::new();
+    /// heat_map.to_csv(&mut csv).unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "0,1,2\n3,4,5\n");
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `writer` - The writer to which to write the values.
+    #[cfg(feature = "std")]
+    pub fn to_csv<W>(&self, mut writer: W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        for row in 0..self.height as isize {
+            for col in 0..self.width as isize {
+                if col > 0 {
+                    write!(writer, ",")?;
+                }
+                write!(writer, "{}", self[matrix::Pos { col, row }])?;
+            }
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes this heat map as a binary greymap image.
+    ///
+    /// Every room becomes a single pixel, where the largest value is white.
+    /// Values are written with 8 bits when the largest value fits, and with
+    /// 16 bits otherwise; heat maps with values larger than `65535` are
+    /// scaled linearly to fit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::HeatMap;
+    /// let heat_map = HeatMap::new_with_data(2, 1, |pos| pos.col as u32 * 7);
+    /// let mut pgm = Vec::new();
+    /// heat_map.to_pgm(&mut pgm).unwrap();
+    ///
+    /// assert_eq!(pgm, b"P5\n2 1\n7\n\x00\x07");
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `writer` - The writer to which to write the image.
+    #[cfg(feature = "std")]
+    pub fn to_pgm<W>(&self, mut writer: W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        const LIMIT: u32 = u16::MAX as u32;
+        let max = self.max();
+        let maxval = max.clamp(1, LIMIT);
+        write!(writer, "P5\n{} {}\n{}\n", self.width, self.height, maxval)?;
+        for value in self.values().copied() {
+            let value = if max > LIMIT {
+                (u64::from(value) * u64::from(LIMIT) / u64::from(max)) as u16
+            } else {
+                value as u16
+            };
+            if maxval > u32::from(u8::MAX) {
+                writer.write_all(&value.to_be_bytes())?;
+            } else {
+                writer.write_all(&[value as u8])?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Generates a heat map where the value for each cell is the number of times it
 /// has been traversed when walking between the positions.
 ///
@@ -393,7 +961,7 @@ pub type HeatMap = matrix::Matrix<u32>;
 ///
 /// # Arguments
 /// *  `positions` - The positions as the tuple `(from, to)`. These are used as
-///   positions between which to walk.
+///    positions between which to walk.
 pub fn heatmap<I, T>(maze: &crate::Maze<T>, positions: I) -> HeatMap
 where
     I: Iterator<Item = (matrix::Pos, matrix::Pos)>,
@@ -412,9 +980,140 @@ where
     result
 }
 
+/// Generates a heat map where the value for each room is its _betweenness
+/// centrality_.
+///
+/// The betweenness centrality of a room is the number of shortest paths
+/// between pairs of other rooms that pass through it. If there are several
+/// shortest paths between a pair of rooms, each path contributes only its
+/// share, so the values are rounded to the nearest integer. Unlike
+/// [`heatmap`], this does not depend on a selection of endpoints, which makes
+/// it suitable for finding choke points of mazes with loops.
+///
+/// This requires a breadth-first search from every room of the maze. When the
+/// feature `parallel` is enabled, the searches are run in parallel, which
+/// requires `T` to be `Sync`.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+#[cfg(feature = "parallel")]
+pub fn betweenness<T>(maze: &crate::Maze<T>) -> HeatMap
+where
+    T: Clone + Sync,
+{
+    use rayon::prelude::*;
+    centrality(
+        maze.positions()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|source| dependencies(maze, source))
+            .reduce(
+                || matrix::Matrix::new(maze.width(), maze.height()),
+                core::ops::Add::add,
+            ),
+    )
+}
+
+/// Generates a heat map where the value for each room is its _betweenness
+/// centrality_.
+///
+/// The betweenness centrality of a room is the number of shortest paths
+/// between pairs of other rooms that pass through it. If there are several
+/// shortest paths between a pair of rooms, each path contributes only its
+/// share, so the values are rounded to the nearest integer. Unlike
+/// [`heatmap`], this does not depend on a selection of endpoints, which makes
+/// it suitable for finding choke points of mazes with loops.
+///
+/// This requires a breadth-first search from every room of the maze. When the
+/// feature `parallel` is enabled, the searches are run in parallel, which
+/// requires `T` to be `Sync`.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+#[cfg(not(feature = "parallel"))]
+pub fn betweenness<T>(maze: &crate::Maze<T>) -> HeatMap
+where
+    T: Clone,
+{
+    centrality(
+        maze.positions()
+            .map(|source| dependencies(maze, source))
+            .fold(
+                matrix::Matrix::new(maze.width(), maze.height()),
+                core::ops::Add::add,
+            ),
+    )
+}
+
+/// Converts the summed dependencies of all rooms to betweenness centrality.
+///
+/// # Arguments
+/// *  `dependencies` - The sum of the dependencies of all source rooms.
+fn centrality(dependencies: matrix::Matrix<f64>) -> HeatMap {
+    // Every pair of rooms has been visited in both directions; the values are
+    // never negative, so adding one half rounds them
+    dependencies.map(|&dependency| (dependency / 2.0 + 0.5) as u32)
+}
+
+/// Calculates the dependency of a single source room on all other rooms.
+///
+/// This is one step of _Brandes' algorithm_: the value for each room is the
+/// sum of the fractions of the shortest paths from `source` to all other
+/// rooms that pass through it.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+/// *  `source` - The room from which to search.
+fn dependencies<T>(
+    maze: &crate::Maze<T>,
+    source: matrix::Pos,
+) -> matrix::Matrix<f64>
+where
+    T: Clone,
+{
+    let (width, height) = (maze.width(), maze.height());
+    let mut distances = matrix::Matrix::<Option<usize>>::new(width, height);
+    let mut counts = matrix::Matrix::<f64>::new(width, height);
+    let mut order = Vec::with_capacity(width * height);
+    let mut queue = alloc::collections::VecDeque::new();
+
+    // Find the number of shortest paths to every room in order of distance
+    distances[source] = Some(0);
+    counts[source] = 1.0;
+    queue.push_back(source);
+    while let Some(pos) = queue.pop_front() {
+        order.push(pos);
+        let distance = distances[pos].unwrap_or(0) + 1;
+        for next in maze.neighbors(pos).filter(|&p| maze.is_inside(p)) {
+            if distances[next].is_none() {
+                distances[next] = Some(distance);
+                queue.push_back(next);
+            }
+            if distances[next] == Some(distance) {
+                counts[next] += counts[pos];
+            }
+        }
+    }
+
+    // Accumulate the dependencies backwards from the most distant rooms
+    let mut result = matrix::Matrix::<f64>::new(width, height);
+    for &pos in order.iter().rev() {
+        let distance = distances[pos].unwrap_or(0);
+        for previous in maze.neighbors(pos).filter(|&p| maze.is_inside(p)) {
+            if distances[previous].map(|d| d + 1) == Some(distance) {
+                result[previous] +=
+                    counts[previous] / counts[pos] * (1.0 + result[pos]);
+            }
+        }
+    }
+    result[source] = 0.0;
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
-    use std::iter::once;
+    use core::iter::once;
 
     use maze_test::maze_test;
 
@@ -444,6 +1143,133 @@ mod tests {
         )));
     }
 
+    #[maze_test]
+    fn explored(maze: TestMaze) {
+        let mut maze = maze.initialize(
+            initialize::Method::Winding(initialize::Bias::default()),
+            &mut initialize::LFSR::new(12345),
+        );
+        let pos = matrix_pos(1, 1);
+        assert!(!maze.explored(pos));
+        maze.set_explored(pos, true);
+        assert!(maze.explored(pos));
+        assert!(!maze.explored(matrix_pos(-1, -1)));
+        maze.set_explored(matrix_pos(-1, -1), true);
+        assert!(!maze.explored(matrix_pos(-1, -1)));
+
+        let segments = maze.wall_segments().collect::<Vec<_>>();
+        maze.reset_explored();
+        assert!(maze.positions().all(|pos| !maze.explored(pos)));
+        assert!(maze.positions().all(|pos| maze.visited(pos)));
+        assert_eq!(segments, maze.wall_segments().collect::<Vec<_>>());
+    }
+
+    #[maze_test]
+    fn wall_faces(maze: TestMaze) {
+        let thickness = 0.2;
+        let distance =
+            |p: physical::Pos, (a, b): (physical::Pos, physical::Pos)| {
+                let edge = b - a;
+                (edge.x * (p.y - a.y) - edge.y * (p.x - a.x)).abs()
+                    / edge.value().sqrt()
+            };
+        for pos in maze.positions() {
+            let center = maze.center(pos);
+            for &wall in maze.walls(pos) {
+                let wall_pos = (pos, wall);
+                let corners = maze.corners(wall_pos);
+                let (inner, outer) = maze.wall_faces(wall_pos, thickness);
+                let (next_inner, next_outer) =
+                    maze.wall_faces((pos, wall.next), thickness);
+
+                let apothem = distance(center, corners);
+                assert!(
+                    (distance(center, inner) - (apothem - thickness / 2.0))
+                        .abs()
+                        < 1e-5
+                );
+                assert!(
+                    (distance(center, outer) - (apothem + thickness / 2.0))
+                        .abs()
+                        < 1e-5
+                );
+                assert!(is_close(inner.1, next_inner.0));
+                assert!(is_close(outer.1, next_outer.0));
+                assert_eq!(maze.wall_faces(wall_pos, 0.0), (corners, corners));
+            }
+        }
+    }
+
+    #[maze_test]
+    fn get(maze: TestMaze) {
+        assert!(maze.get(matrix_pos(0, 0)).is_ok());
+        assert_eq!(
+            Some(Error::OutOfBounds(matrix_pos(-1, -1))),
+            maze.get(matrix_pos(-1, -1)).err(),
+        );
+    }
+
+    #[maze_test]
+    fn edge_walls(maze: TestMaze) {
+        assert_eq!(0, maze.edge_walls().count());
+
+        let maze = maze.initialize(
+            initialize::Method::Winding(initialize::Bias::default()),
+            &mut initialize::LFSR::new(12345),
+        );
+        let outside = maze
+            .walls_iter()
+            .filter(|&(wall_pos, _)| !maze.is_inside(maze.back(wall_pos).0))
+            .count();
+        assert_eq!(outside, maze.edge_walls().count());
+        for wall_pos in maze.edge_walls() {
+            assert!(!maze.is_inside(maze.back(wall_pos).0));
+        }
+    }
+
+    #[maze_test]
+    fn edge_rooms_masked(maze: TestMaze) {
+        let maze = maze.initialize_filter(
+            initialize::Method::Branching(initialize::Bias::default()),
+            &mut initialize::LFSR::new(12345),
+            |pos| pos.col > 2 && pos.row > 0,
+        );
+        let rooms = maze.edge_rooms().collect::<Vec<_>>();
+        assert!(rooms.contains(&matrix_pos(3, 1)));
+        assert!(!rooms.contains(&matrix_pos(0, 0)));
+        assert!(!rooms.contains(&matrix_pos(5, 2)));
+        for pos in rooms {
+            assert!(maze.edge_walls().any(|wall_pos| wall_pos.0 == pos));
+        }
+    }
+
+    #[maze_test]
+    #[should_panic]
+    fn index_outside(maze: TestMaze) {
+        let _ = &maze[matrix_pos(-1, -1)];
+    }
+
+    #[maze_test(seeds(2))]
+    async fn initialize_async(maze: TestMaze, seed: u64) {
+        // Yield once to ensure that pending futures are resumed
+        let mut yielded = false;
+        core::future::poll_fn(|context| {
+            if core::mem::replace(&mut yielded, true) {
+                core::task::Poll::Ready(())
+            } else {
+                context.waker().wake_by_ref();
+                core::task::Poll::Pending
+            }
+        })
+        .await;
+
+        let maze = maze.initialize(
+            initialize::Method::Branching(initialize::Bias::default()),
+            &mut initialize::LFSR::new(seed),
+        );
+        assert!(maze.positions().all(|pos| maze.visited(pos)));
+    }
+
     #[maze_test]
     fn can_open(mut maze: TestMaze) {
         let log = Navigator::new(&mut maze).down(true).stop();
@@ -582,7 +1408,8 @@ mod tests {
         let walls = maze
             .walls(pos)
             .iter()
-            .filter(|wall| maze.is_inside(maze.back((pos, wall)).0)).copied()
+            .filter(|wall| maze.is_inside(maze.back((pos, wall)).0))
+            .copied()
             .collect::<Vec<_>>();
         walls.iter().for_each(|wall| maze.open((pos, wall)));
         assert_eq!(maze.doors(pos).collect::<Vec<_>>(), walls);
@@ -618,4 +1445,220 @@ mod tests {
                 .collect::<Vec<_>>(),
         );
     }
+
+    #[test]
+    fn heatmap_statistics() {
+        let heat_map =
+            HeatMap::new_with_data(4, 1, |pos| [0, 1, 3, 7][pos.col as usize]);
+        assert_eq!(7, heat_map.max());
+        assert_eq!(0, heat_map.percentile(0.0));
+        assert_eq!(1, heat_map.percentile(50.0));
+        assert_eq!(3, heat_map.percentile(75.0));
+        assert_eq!(7, heat_map.percentile(100.0));
+        assert_eq!(7, heat_map.percentile(200.0));
+        assert_eq!(
+            vec![0.0, 1.0 / 7.0, 3.0 / 7.0, 1.0],
+            heat_map.normalize().values().copied().collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            vec![0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0],
+            heat_map
+                .normalize_log()
+                .values()
+                .copied()
+                .collect::<Vec<_>>(),
+        );
+
+        let empty = HeatMap::new(2, 2);
+        assert_eq!(0, empty.max());
+        assert_eq!(0, empty.percentile(50.0));
+        assert!(empty.normalize().values().all(|&v| v == 0.0));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn heatmap_export() {
+        let heat_map = HeatMap::new_with_data(2, 2, |pos| {
+            [0, 1, 300, 70000][(pos.col + 2 * pos.row) as usize]
+        });
+        let mut csv = Vec::new();
+        heat_map.to_csv(&mut csv).unwrap();
+        assert_eq!("0,1\n300,70000\n", String::from_utf8(csv).unwrap());
+
+        let mut pgm = Vec::new();
+        heat_map.to_pgm(&mut pgm).unwrap();
+        assert_eq!(b"P5\n2 2\n65535\n", &pgm[..13]);
+        assert_eq!(vec![0, 0, 0, 0, 1, 24, 255, 255], pgm[13..].to_vec());
+
+        let mut pgm = Vec::new();
+        HeatMap::new(1, 1).to_pgm(&mut pgm).unwrap();
+        assert_eq!(b"P5\n1 1\n1\n\x00".to_vec(), pgm);
+    }
+
+    #[maze_test]
+    fn betweenness_corridor(mut maze: TestMaze) {
+        // Open a single corridor along the first row
+        let width = maze.width() as isize;
+        for col in 0..width - 1 {
+            let pos1 = matrix_pos(col, 0);
+            let pos2 = matrix_pos(col + 1, 0);
+            if let Some(wall) = maze.connecting_wall(pos1, pos2) {
+                maze.open(wall);
+            }
+        }
+
+        let heat_map = betweenness(&maze);
+        assert_eq!(0, heat_map[matrix_pos(0, 0)]);
+        assert_eq!(0, heat_map[matrix_pos(width - 1, 0)]);
+        for col in 1..width - 1 {
+            assert_eq!(
+                (col * (width - 1 - col)) as u32,
+                heat_map[matrix_pos(col, 0)],
+            );
+        }
+        assert_eq!(0, heat_map[matrix_pos(0, 1)]);
+    }
+
+    #[test]
+    fn betweenness_loop() {
+        // A loop of four rooms with a dead end attached, and an isolated room
+        let mut maze = Shape::Quad.create::<()>(3, 2);
+        for (pos1, pos2) in [
+            ((0, 0), (1, 0)),
+            ((1, 0), (1, 1)),
+            ((1, 1), (0, 1)),
+            ((0, 1), (0, 0)),
+            ((1, 0), (2, 0)),
+        ] {
+            let wall = maze
+                .connecting_wall(
+                    matrix_pos(pos1.0, pos1.1),
+                    matrix_pos(pos2.0, pos2.1),
+                )
+                .unwrap();
+            maze.open(wall);
+        }
+
+        let heat_map = betweenness(&maze);
+        assert_eq!(
+            vec![1, 4, 0, 1, 1, 0],
+            maze.positions()
+                .map(|pos| heat_map[pos])
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[maze_test]
+    fn degree_map(mut maze: TestMaze) {
+        let pos = matrix_pos(0, 0);
+        let walls = maze.walls(pos);
+        maze.open((pos, walls[0]));
+        maze.open((pos, walls[1]));
+
+        let degree_map = maze.degree_map();
+        assert_eq!(2, degree_map[pos]);
+        assert_eq!(
+            maze.positions()
+                .map(|pos| maze.doors(pos).count() as u8)
+                .collect::<Vec<_>>(),
+            degree_map.values().copied().collect::<Vec<_>>(),
+        );
+
+        let histogram = maze.degree_histogram();
+        assert_eq!(
+            maze.positions()
+                .map(|pos| maze.walls(pos).len())
+                .max()
+                .unwrap()
+                + 1,
+            histogram.len(),
+        );
+        assert_eq!(maze.positions().count(), histogram.iter().sum::<usize>());
+        assert_eq!(1, histogram[2]);
+    }
+
+    #[test]
+    fn subdivide() {
+        use shape::quad::walls;
+
+        let mut maze = Shape::Quad.create::<u32>(2, 1);
+        *maze.data_mut(matrix_pos(1, 0)).unwrap() = 1;
+        maze.open((matrix_pos(0, 0), &walls::RIGHT));
+        maze.open((matrix_pos(0, 0), &walls::LEFT));
+
+        let subdivided = maze.subdivide(2).unwrap();
+        assert_eq!((4, 2), (subdivided.width(), subdivided.height()));
+        assert_eq!(Some(&1), subdivided.data(matrix_pos(3, 1)));
+        for row in 0..2 {
+            assert!(subdivided.is_open((matrix_pos(0, row), &walls::LEFT)));
+            assert!(subdivided.is_open((matrix_pos(1, row), &walls::RIGHT)));
+            assert!(!subdivided.is_open((matrix_pos(3, row), &walls::RIGHT)));
+        }
+        assert!(subdivided.is_open((matrix_pos(2, 0), &walls::DOWN)));
+        assert!(!subdivided.is_open((matrix_pos(2, 1), &walls::DOWN)));
+
+        assert!(maze.subdivide(0).is_none());
+        assert!(Shape::Hex.create::<()>(2, 2).subdivide(2).is_none());
+    }
+
+    #[maze_test]
+    fn diff(maze: TestMaze) {
+        let mut rng = initialize::LFSR::new(12345);
+        let maze = maze.initialize(
+            initialize::Method::Winding(initialize::Bias::default()),
+            &mut rng,
+        );
+        assert!(maze.diff(&maze).is_empty());
+
+        // A different seed yields a different maze
+        let other = maze
+            .shape()
+            .create::<()>(maze.width(), maze.height())
+            .initialize(
+                initialize::Method::Winding(initialize::Bias::default()),
+                &mut initialize::LFSR::new(54321),
+            );
+        let diff = maze.diff(&other);
+        assert!(!diff.is_empty());
+        for (wall_pos, open) in maze.walls_iter() {
+            assert_eq!(
+                diff.contains(&wall_pos),
+                other.is_open(wall_pos) != open,
+            );
+        }
+
+        let mut copy = maze.clone();
+        let wall_pos = diff[0];
+        copy.set_open(wall_pos, other.is_open(wall_pos));
+        assert_eq!(diff[1..], copy.diff(&other));
+    }
+
+    #[maze_test]
+    fn walls_iter(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Branching(initialize::Bias::default()),
+            &mut initialize::LFSR::new(12345),
+        );
+        let walls = maze.walls_iter().collect::<Vec<_>>();
+
+        // Every wall is visited from exactly one side
+        for pos in maze.positions() {
+            for wall_pos in maze.wall_positions(pos) {
+                let back = maze.back(wall_pos);
+                assert_eq!(
+                    1,
+                    walls
+                        .iter()
+                        .filter(|&&(w, _)| w == wall_pos || w == back)
+                        .count(),
+                );
+            }
+        }
+
+        // A maze without loops has one open wall less than it has rooms
+        assert_eq!(
+            maze.positions().count() - 1,
+            walls.iter().filter(|&&(_, open)| open).count(),
+        );
+    }
 }
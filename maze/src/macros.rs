@@ -11,6 +11,87 @@ macro_rules! dispatch {
     }
 }
 
+/// Evaluates an expression with a type alias for the [`ShapeKind`] of the
+/// current maze.
+///
+/// Unlike [`dispatch!`], the shape is only matched once, so the expression is
+/// compiled separately for every shape, without dispatching in inner loops.
+///
+/// This is an internal library macro.
+///
+/// [`ShapeKind`]: crate::shape::ShapeKind
+macro_rules! monomorphise {
+    ($on:expr, $kind:ident => $body:expr) => {
+        match $on {
+            crate::Shape::Hex => {
+                type $kind = crate::shape::Hex;
+                $body
+            }
+            crate::Shape::Quad => {
+                type $kind = crate::shape::Quad;
+                $body
+            }
+            crate::Shape::Tri => {
+                type $kind = crate::shape::Tri;
+                $body
+            }
+        }
+    };
+}
+
+/// Defines a shape kind, forwarding all functions to a shape module.
+///
+/// This is an internal library macro.
+macro_rules! define_shape_kind {
+    ($(#[$attr:meta])* $name:ident => $module:ident) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Default, Hash, PartialEq)]
+        pub struct $name;
+
+        impl ShapeKind for $name {
+            const SHAPE: Shape = Shape::$name;
+
+            #[inline]
+            fn all_walls() -> &'static [&'static wall::Wall] {
+                $module::all_walls()
+            }
+
+            #[inline]
+            fn back(wall_pos: WallPos) -> WallPos {
+                $module::back(wall_pos)
+            }
+
+            #[inline]
+            fn walls(pos: matrix::Pos) -> &'static [&'static wall::Wall] {
+                $module::walls(pos)
+            }
+
+            #[inline]
+            fn graph_distance_lower_bound(
+                a: matrix::Pos,
+                b: matrix::Pos,
+            ) -> usize {
+                $module::graph_distance_lower_bound(a, b)
+            }
+
+            #[inline]
+            fn physical_to_cell(pos: physical::Pos) -> matrix::Pos {
+                $module::physical_to_cell(pos)
+            }
+
+            #[inline]
+            fn physical_to_wall_pos(pos: physical::Pos) -> WallPos {
+                $module::physical_to_wall_pos(pos)
+            }
+
+            #[inline]
+            fn cell_to_physical(pos: matrix::Pos) -> physical::Pos {
+                $module::cell_to_physical(pos)
+            }
+        }
+    };
+}
+
 /// Defines a wall module.
 ///
 /// This is an internal library macro.
@@ -47,6 +128,7 @@ macro_rules! define_shape {
         ///
         /// # Arguments
         /// *  `wall_pos` - The wall for which to find the back.
+        #[inline]
         pub fn back(wall_pos: WallPos) -> WallPos {
             let (pos, wall) = wall_pos;
             let other = matrix::Pos {
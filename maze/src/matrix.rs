@@ -2,13 +2,18 @@
 //!
 //! A matrix is a two-dimensional array of data. A maze is a matrix of rooms.
 
-use std::cmp::Ordering;
-use std::collections::BTreeMap;
-use std::collections::BTreeSet;
+use alloc::collections::BTreeMap;
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+
 /// A matrix position.
 ///
 /// The coordinates of this type are signed, but valid matrix positions never
@@ -215,6 +220,69 @@ where
         })
     }
 
+    /// Creates a copy of this matrix with different dimensions.
+    ///
+    /// Cells present in both matrices keep their values, and new cells are
+    /// assigned `fill`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix::*;
+    ///
+    /// let matrix = Matrix::new_with_data(2, 2, |pos| pos.col + 2 * pos.row);
+    /// assert_eq!(
+    ///     matrix.resized(3, 1, 9).values().cloned().collect::<Vec<_>>(),
+    ///     vec![0, 1, 9],
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the new matrix.
+    /// *  `height` - The height of the new matrix.
+    /// *  `fill` - The value of cells not present in this matrix.
+    pub fn resized(&self, width: usize, height: usize, fill: T) -> Self {
+        Matrix::new_with_data(width, height, |pos| {
+            self.get(pos).cloned().unwrap_or_else(|| fill.clone())
+        })
+    }
+
+    /// Copies the values of another matrix into this one.
+    ///
+    /// The cell `(0, 0)` of `other` is copied to `offset`, and cells ending up
+    /// outside of this matrix are ignored. The offset may be negative.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix::*;
+    ///
+    /// let mut matrix = Matrix::new_with_data(3, 2, |_| 0);
+    /// matrix.embed(&Matrix::new_with_data(2, 2, |_| 1), Pos { col: 2, row: -1 });
+    /// assert_eq!(
+    ///     matrix.values().cloned().collect::<Vec<_>>(),
+    ///     vec![
+    ///         0, 0, 1,
+    ///         0, 0, 0,
+    ///     ],
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `other` - The matrix to copy.
+    /// *  `offset` - The position in this matrix of the first cell of
+    ///    `other`.
+    pub fn embed(&mut self, other: &Matrix<T>, offset: Pos) {
+        for pos in other.positions() {
+            if let Some(value) = self.get_mut(Pos {
+                col: pos.col + offset.col,
+                row: pos.row + offset.row,
+            }) {
+                *value = other[pos].clone();
+            }
+        }
+    }
+
     /// Whether a position is inside of the matrix.
     ///
     /// # Example
@@ -305,6 +373,47 @@ where
         }
     }
 
+    /// Iterates over all cells adjacent to a cell in a maze of a specific
+    /// shape.
+    ///
+    /// Only cells inside of this matrix are visited. This is useful as the
+    /// `neighbors` argument of [`edges`](Self::edges) and
+    /// [`fill`](Self::fill) when the matrix corresponds to the rooms of a
+    /// maze.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix::*;
+    /// # type Matrix = maze::matrix::Matrix<u32>;
+    ///
+    /// let matrix = Matrix::new(3, 3);
+    /// assert_eq!(
+    ///     matrix
+    ///         .neighbors_by(Pos { col: 0, row: 0 }, maze::Shape::Quad)
+    ///         .collect::<Vec<_>>(),
+    ///     vec![Pos { col: 1, row: 0 }, Pos { col: 0, row: 1 }],
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `pos` - The matrix position.
+    /// *  `shape` - The shape of the maze.
+    pub fn neighbors_by(
+        &self,
+        pos: Pos,
+        shape: crate::Shape,
+    ) -> impl Iterator<Item = Pos> + '_ {
+        shape
+            .walls(pos)
+            .iter()
+            .map(move |wall| Pos {
+                col: pos.col + wall.dir.0,
+                row: pos.row + wall.dir.1,
+            })
+            .filter(move |&pos| self.is_inside(pos))
+    }
+
     /// Iterates over all cell positions.
     ///
     /// The positions are visited row by row, starting with `(0, 0)` and ending
@@ -510,9 +619,9 @@ where
     }
 }
 
-impl<T> std::ops::Add for Matrix<T>
+impl<T> core::ops::Add for Matrix<T>
 where
-    T: std::ops::AddAssign + Clone + Copy,
+    T: core::ops::AddAssign + Clone + Copy,
 {
     type Output = Self;
 
@@ -554,8 +663,8 @@ where
     /// # Arguments
     /// *  `other` - The matrix to add.
     fn add(mut self, other: Self) -> Self {
-        let width = std::cmp::min(self.width, other.width);
-        let height = std::cmp::min(self.height, other.height);
+        let width = core::cmp::min(self.width, other.width);
+        let height = core::cmp::min(self.height, other.height);
         for row in 0..height {
             for col in 0..width {
                 let pos = Pos {
@@ -659,7 +768,7 @@ where
     }
 }
 
-impl<T> std::ops::Index<Pos> for Matrix<T>
+impl<T> core::ops::Index<Pos> for Matrix<T>
 where
     T: Clone,
 {
@@ -677,12 +786,12 @@ where
         if self.is_inside(pos) {
             &self.data[(pos.col + pos.row * self.width as isize) as usize]
         } else {
-            panic!()
+            panic!("{}", crate::Error::OutOfBounds(pos))
         }
     }
 }
 
-impl<T> std::ops::IndexMut<Pos> for Matrix<T>
+impl<T> core::ops::IndexMut<Pos> for Matrix<T>
 where
     T: Clone,
 {
@@ -698,7 +807,7 @@ where
         if self.is_inside(pos) {
             &mut self.data[(pos.col + pos.row * self.width as isize) as usize]
         } else {
-            panic!()
+            panic!("{}", crate::Error::OutOfBounds(pos))
         }
     }
 }
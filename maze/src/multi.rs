@@ -0,0 +1,378 @@
+//! # Multi-level mazes
+//!
+//! A multi-level maze is a stack of ordinary mazes, where stairs lead from a
+//! room on one level to a room on the level above. Every level is generated
+//! and modified as a separate maze, and the stairs are added explicitly.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::matrix;
+use crate::walk;
+use crate::Error;
+use crate::Maze;
+
+/// A room position in a multi-level maze.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Pos {
+    /// The level, where `0` is the bottom level.
+    pub level: usize,
+
+    /// The room position on the level.
+    pub pos: matrix::Pos,
+}
+
+/// A stair between rooms on adjacent levels.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Stair {
+    /// The level of the lower room.
+    pub level: usize,
+
+    /// The room on the lower level.
+    pub lower: matrix::Pos,
+
+    /// The room on the level above.
+    pub upper: matrix::Pos,
+}
+
+impl Stair {
+    /// The room at the bottom of this stair.
+    pub fn bottom(&self) -> Pos {
+        Pos {
+            level: self.level,
+            pos: self.lower,
+        }
+    }
+
+    /// The room at the top of this stair.
+    pub fn top(&self) -> Pos {
+        Pos {
+            level: self.level + 1,
+            pos: self.upper,
+        }
+    }
+}
+
+/// A stack of mazes connected by stairs.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct MultiMaze<T>
+where
+    T: Clone,
+{
+    /// The levels, from the bottom up.
+    levels: Vec<Maze<T>>,
+
+    /// The stairs between levels.
+    stairs: Vec<Stair>,
+}
+
+impl<T> MultiMaze<T>
+where
+    T: Clone,
+{
+    /// Creates a multi-level maze without stairs.
+    ///
+    /// # Arguments
+    /// *  `levels` - The levels, from the bottom up.
+    pub fn new(levels: Vec<Maze<T>>) -> Self {
+        Self {
+            levels,
+            stairs: Vec::new(),
+        }
+    }
+
+    /// The levels of this maze, from the bottom up.
+    pub fn levels(&self) -> &[Maze<T>] {
+        &self.levels
+    }
+
+    /// A specific level.
+    ///
+    /// If the level does not exist, nothing is returned.
+    ///
+    /// # Arguments
+    /// *  `level` - The level.
+    pub fn level(&self, level: usize) -> Option<&Maze<T>> {
+        self.levels.get(level)
+    }
+
+    /// A specific mutable level.
+    ///
+    /// If the level does not exist, nothing is returned.
+    ///
+    /// # Arguments
+    /// *  `level` - The level.
+    pub fn level_mut(&mut self, level: usize) -> Option<&mut Maze<T>> {
+        self.levels.get_mut(level)
+    }
+
+    /// All stairs of this maze.
+    pub fn stairs(&self) -> &[Stair] {
+        &self.stairs
+    }
+
+    /// Whether a position is inside of the maze.
+    ///
+    /// # Arguments
+    /// *  `pos` - The position.
+    pub fn is_inside(&self, pos: Pos) -> bool {
+        self.level(pos.level)
+            .map(|maze| maze.is_inside(pos.pos))
+            .unwrap_or(false)
+    }
+
+    /// Adds a stair leading from a room to a room on the level above.
+    ///
+    /// If either level does not exist, [`Error::MissingLevel`] is returned,
+    /// and if either room is outside of its level, [`Error::OutOfBounds`].
+    ///
+    /// # Arguments
+    /// *  `level` - The level of the lower room.
+    /// *  `lower` - The room on the lower level.
+    /// *  `upper` - The room on the level above.
+    pub fn add_stair(
+        &mut self,
+        level: usize,
+        lower: matrix::Pos,
+        upper: matrix::Pos,
+    ) -> Result<Stair, Error> {
+        let stair = Stair {
+            level,
+            lower,
+            upper,
+        };
+        for Pos { level, pos } in [stair.bottom(), stair.top()] {
+            let maze = self.level(level).ok_or(Error::MissingLevel(level))?;
+            if !maze.is_inside(pos) {
+                return Err(Error::OutOfBounds(pos));
+            }
+        }
+
+        if !self.stairs.contains(&stair) {
+            self.stairs.push(stair);
+        }
+        Ok(stair)
+    }
+
+    /// The rooms reachable from a room by taking a single stair.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room.
+    pub fn stairs_from(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.stairs.iter().filter_map(move |stair| {
+            if stair.bottom() == pos {
+                Some(stair.top())
+            } else if stair.top() == pos {
+                Some(stair.bottom())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The rooms reachable from a room in a single step, either through an
+    /// open wall or by taking a stair.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room.
+    pub fn neighbors(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.level(pos.level)
+            .filter(|maze| maze.is_inside(pos.pos))
+            .into_iter()
+            .flat_map(move |maze| {
+                maze.neighbors(pos.pos)
+                    .filter(|&next| maze.is_inside(next))
+                    .map(move |next| Pos {
+                        level: pos.level,
+                        pos: next,
+                    })
+            })
+            .chain(self.stairs_from(pos))
+    }
+
+    /// Walks from `from` to `to` along the shortest path, taking stairs
+    /// between levels as required.
+    ///
+    /// Taking a stair counts as a single step. If the rooms are connected,
+    /// the rooms passed through, including `from` and `to`, are returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::initialize::*;
+    /// # use maze::multi::*;
+    /// # use maze::Shape;
+    /// let level = |seed| {
+    ///     Shape::Quad
+    ///         .create::<()>(5, 5)
+    ///         .initialize(Method::default(), &mut LFSR::new(seed))
+    /// };
+    /// let mut maze = MultiMaze::new(vec![level(1), level(2)]);
+    /// let corner = (4isize, 4isize).into();
+    /// maze.add_stair(0, corner, corner).unwrap();
+    ///
+    /// let from = Pos { level: 0, pos: (0isize, 0isize).into() };
+    /// let to = Pos { level: 1, pos: (0isize, 0isize).into() };
+    /// let path = maze.walk(from, to).unwrap();
+    ///
+    /// assert_eq!(path.first(), Some(&from));
+    /// assert_eq!(path.last(), Some(&to));
+    /// assert!(path.contains(&Pos { level: 1, pos: corner }));
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `from` - The starting position.
+    /// *  `to` - The desired goal.
+    pub fn walk(&self, from: Pos, to: Pos) -> Option<Vec<Pos>> {
+        if !self.is_inside(from) || !self.is_inside(to) {
+            return None;
+        }
+
+        walk::astar(
+            from,
+            |pos| pos == to,
+            |pos| self.neighbors(pos),
+            |_, _| 1.0,
+            |_| 0.0,
+        )
+    }
+
+    /// Walks from `from` to `to` along the shortest path, taking stairs
+    /// between levels as required.
+    ///
+    /// This is the fallible variant of [`walk`](Self::walk), which describes
+    /// why no path was found.
+    ///
+    /// # Arguments
+    /// *  `from` - The starting position.
+    /// *  `to` - The desired goal.
+    pub fn try_walk(&self, from: Pos, to: Pos) -> Result<Vec<Pos>, Error> {
+        for pos in [from, to] {
+            let maze = self
+                .level(pos.level)
+                .ok_or(Error::MissingLevel(pos.level))?;
+            if !maze.is_inside(pos.pos) {
+                return Err(Error::OutOfBounds(pos.pos));
+            }
+        }
+
+        self.walk(from, to)
+            .ok_or(Error::UnreachableLevel { from, to })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize::{Method, LFSR};
+    use crate::test_utils::*;
+
+    /// Creates an initialised multi-level maze with one stair between every
+    /// pair of levels.
+    ///
+    /// # Arguments
+    /// *  `maze` - The template for every level.
+    /// *  `count` - The number of levels.
+    fn stacked(maze: &TestMaze, count: usize) -> MultiMaze<()> {
+        let mut result = MultiMaze::new(
+            (0..count)
+                .map(|level| {
+                    maze.clone().initialize(
+                        Method::default(),
+                        &mut LFSR::new(12345 + level as u64),
+                    )
+                })
+                .collect(),
+        );
+        let corner =
+            matrix_pos(maze.width() as isize - 1, maze.height() as isize - 1);
+        for level in 0..count - 1 {
+            result.add_stair(level, corner, corner).unwrap();
+        }
+
+        result
+    }
+
+    #[maze_test]
+    fn add_stair(maze: TestMaze) {
+        let mut maze = stacked(&maze, 2);
+        let pos = matrix_pos(0, 0);
+
+        assert_eq!(Err(Error::MissingLevel(2)), maze.add_stair(1, pos, pos),);
+        assert_eq!(
+            Err(Error::OutOfBounds(matrix_pos(-1, 0))),
+            maze.add_stair(0, pos, matrix_pos(-1, 0)),
+        );
+        assert_eq!(1, maze.stairs().len());
+
+        maze.add_stair(0, pos, pos).unwrap();
+        maze.add_stair(0, pos, pos).unwrap();
+        assert_eq!(2, maze.stairs().len());
+        assert_eq!(
+            vec![Pos { level: 1, pos }],
+            maze.stairs_from(Pos { level: 0, pos }).collect::<Vec<_>>(),
+        );
+    }
+
+    #[maze_test]
+    fn walk_levels(maze: TestMaze) {
+        let maze = stacked(&maze, 3);
+        let from = Pos {
+            level: 0,
+            pos: matrix_pos(0, 0),
+        };
+        let to = Pos {
+            level: 2,
+            pos: matrix_pos(0, 0),
+        };
+        let path = maze.walk(from, to).unwrap();
+
+        assert_eq!(Some(&from), path.first());
+        assert_eq!(Some(&to), path.last());
+        for pair in path.windows(2) {
+            assert!(maze.neighbors(pair[0]).any(|pos| pos == pair[1]));
+        }
+        assert_eq!(
+            2,
+            path.windows(2)
+                .filter(|pair| pair[0].level != pair[1].level)
+                .count(),
+        );
+    }
+
+    #[maze_test]
+    fn walk_without_stairs(maze: TestMaze) {
+        let maze = MultiMaze::new(vec![
+            maze.clone()
+                .initialize(Method::default(), &mut LFSR::new(12345)),
+            maze.initialize(Method::default(), &mut LFSR::new(54321)),
+        ]);
+        let from = Pos {
+            level: 0,
+            pos: matrix_pos(0, 0),
+        };
+        let to = Pos {
+            level: 1,
+            pos: matrix_pos(0, 0),
+        };
+
+        assert_eq!(None, maze.walk(from, to));
+        assert_eq!(
+            Err(Error::UnreachableLevel { from, to }),
+            maze.try_walk(from, to),
+        );
+        assert_eq!(
+            Err(Error::MissingLevel(2)),
+            maze.try_walk(from, Pos { level: 2, ..to }),
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! # Maze composition
+//!
+//! Independently generated mazes with the same layout can be combined wall by
+//! wall. This makes it possible to, for example, carve the solution of one
+//! maze into another, or to merge several sparse layers into a single maze.
+
+use alloc::vec::Vec;
+
+use crate::Maze;
+
+/// An operation combining the state of a wall in two mazes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Operation {
+    /// A wall is open if it is open in either maze.
+    Union,
+
+    /// A wall is open only if it is open in both mazes.
+    Intersection,
+
+    /// A wall is open if it is open in exactly one of the mazes.
+    Xor,
+}
+
+impl Operation {
+    /// Combines the states of a wall.
+    ///
+    /// # Arguments
+    /// *  `a` - Whether the wall is open in the first maze.
+    /// *  `b` - Whether the wall is open in the second maze.
+    pub fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            Operation::Union => a || b,
+            Operation::Intersection => a && b,
+            Operation::Xor => a != b,
+        }
+    }
+}
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// Combines the walls of another maze with the walls of this maze.
+    ///
+    /// Every wall of this maze is opened or closed depending on the result
+    /// of `operation` applied to its current state and its state in `other`.
+    /// As with [`diff`](Self::diff), the mazes are expected to have the same
+    /// shape; walls of rooms missing from `other` are considered closed.
+    ///
+    /// Closing walls does not mark rooms as not visited.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::initialize::*;
+    /// # use maze::overlay::Operation;
+    /// # use maze::Shape;
+    /// let mut maze = Shape::Quad
+    ///     .create::<()>(5, 5)
+    ///     .initialize(Method::default(), &mut LFSR::new(12345));
+    ///
+    /// // Carve a straight corridor through the maze
+    /// let mut corridor = Shape::Quad.create::<()>(5, 5);
+    /// let pos = |col| (col, 2isize).into();
+    /// for col in 0..4 {
+    ///     let wall_pos = corridor.connecting_wall(pos(col), pos(col + 1));
+    ///     corridor.open(wall_pos.unwrap());
+    /// }
+    /// maze.overlay(&corridor, Operation::Union);
+    ///
+    /// assert!((0..4).all(|col| maze.connected(pos(col), pos(col + 1))));
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `other` - The maze to combine with this one.
+    /// *  `operation` - The operation to apply to every wall.
+    pub fn overlay<U>(&mut self, other: &Maze<U>, operation: Operation)
+    where
+        U: Clone,
+    {
+        let walls = self
+            .walls_iter()
+            .map(|(wall_pos, open)| {
+                (wall_pos, operation.apply(open, other.is_open(wall_pos)))
+            })
+            .collect::<Vec<_>>();
+        for (wall_pos, open) in walls {
+            if self.is_open(wall_pos) != open {
+                self.set_open(wall_pos, open);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize::{Bias, LFSR};
+    use crate::test_utils::*;
+
+    const METHOD: crate::initialize::Method =
+        crate::initialize::Method::Winding(Bias {
+            horizontal: 50,
+            branch: None,
+        });
+
+    #[maze_test]
+    fn overlay_self(maze: TestMaze) {
+        let maze = maze.initialize(METHOD, &mut LFSR::new(12345));
+
+        for operation in [Operation::Union, Operation::Intersection] {
+            let mut combined = maze.clone();
+            combined.overlay(&maze, operation);
+            assert!(combined.diff(&maze).is_empty());
+        }
+
+        let mut combined = maze.clone();
+        combined.overlay(&maze, Operation::Xor);
+        assert_eq!(combined.openness(), 0.0);
+    }
+
+    #[maze_test]
+    fn overlay_operations(maze: TestMaze) {
+        let a = maze.clone().initialize(METHOD, &mut LFSR::new(12345));
+        let b = maze.initialize(METHOD, &mut LFSR::new(54321));
+
+        let mut union = a.clone();
+        union.overlay(&b, Operation::Union);
+        let mut intersection = a.clone();
+        intersection.overlay(&b, Operation::Intersection);
+        let mut xor = a.clone();
+        xor.overlay(&b, Operation::Xor);
+
+        for (wall_pos, open) in a.walls_iter() {
+            let other = b.is_open(wall_pos);
+            assert_eq!(union.is_open(wall_pos), open || other);
+            assert_eq!(intersection.is_open(wall_pos), open && other);
+            assert_eq!(xor.is_open(wall_pos), open != other);
+        }
+        assert!(union.loop_count() > 0);
+    }
+}
@@ -2,11 +2,14 @@
 //!
 //! When physically laying out the maze, rooms and edges have certain
 //! attributes. These are collected in this module.
-use std::ops;
+use core::fmt;
+use core::ops;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
 use crate::wall::Angle;
 
 /// A physical position.
@@ -387,6 +390,217 @@ impl ViewBox {
             && pos.x <= self.corner.x + self.width
             && pos.y <= self.corner.x + self.height
     }
+
+    /// The smallest view box containing both this view box and another.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical::*;
+    ///
+    /// let (a, b) = (
+    ///     ViewBox::centered_at(Pos { x: 0.0, y: 0.0 }, 2.0, 2.0),
+    ///     ViewBox::centered_at(Pos { x: 2.0, y: 1.0 }, 2.0, 2.0),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     a.union(b),
+    ///     ViewBox {
+    ///         corner: Pos { x: -1.0, y: -1.0 },
+    ///         width: 4.0,
+    ///         height: 3.0,
+    ///     },
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `other` - The other view box.
+    pub fn union(self, other: Self) -> Self {
+        let corner = Pos {
+            x: self.corner.x.min(other.corner.x),
+            y: self.corner.y.min(other.corner.y),
+        };
+        Self {
+            corner,
+            width: (self.corner.x + self.width)
+                .max(other.corner.x + other.width)
+                - corner.x,
+            height: (self.corner.y + self.height)
+                .max(other.corner.y + other.height)
+                - corner.y,
+        }
+    }
+
+    /// The area covered by both this view box and another.
+    ///
+    /// View boxes sharing only an edge intersect in a view box with zero
+    /// width or height. If the view boxes do not overlap, nothing is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical::*;
+    ///
+    /// let (a, b, c) = (
+    ///     ViewBox::centered_at(Pos { x: 0.0, y: 0.0 }, 2.0, 2.0),
+    ///     ViewBox::centered_at(Pos { x: 2.0, y: 1.0 }, 2.0, 2.0),
+    ///     ViewBox::centered_at(Pos { x: 4.0, y: 0.0 }, 2.0, 2.0),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     a.intersect(b),
+    ///     Some(ViewBox {
+    ///         corner: Pos { x: 1.0, y: 0.0 },
+    ///         width: 0.0,
+    ///         height: 1.0,
+    ///     }),
+    /// );
+    /// assert_eq!(a.intersect(c), None);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `other` - The other view box.
+    pub fn intersect(self, other: Self) -> Option<Self> {
+        let corner = Pos {
+            x: self.corner.x.max(other.corner.x),
+            y: self.corner.y.max(other.corner.y),
+        };
+        let width = (self.corner.x + self.width)
+            .min(other.corner.x + other.width)
+            - corner.x;
+        let height = (self.corner.y + self.height)
+            .min(other.corner.y + other.height)
+            - corner.y;
+        (width >= 0.0 && height >= 0.0).then_some(Self {
+            corner,
+            width,
+            height,
+        })
+    }
+
+    /// The largest factor by which this view box can be scaled while still
+    /// fitting inside another view box.
+    ///
+    /// The aspect ratio is maintained, so the scaled view box will fill
+    /// `other` along one axis only, unless their aspect ratios are equal.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical::*;
+    ///
+    /// let viewbox = ViewBox::centered_at(Pos { x: 0.0, y: 0.0 }, 4.0, 2.0);
+    ///
+    /// assert_eq!(
+    ///     viewbox.scale_to_fit(ViewBox::centered_at(
+    ///         Pos { x: 0.0, y: 0.0 },
+    ///         10.0,
+    ///         10.0,
+    ///     )),
+    ///     2.5,
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `other` - The view box to fit into.
+    pub fn scale_to_fit(self, other: Self) -> f32 {
+        (other.width / self.width).min(other.height / self.height)
+    }
+
+    /// A transform scaling and moving this view box to fit inside another
+    /// one.
+    ///
+    /// The aspect ratio is maintained, and the transformed view box is
+    /// centred in `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical::*;
+    ///
+    /// let viewbox = ViewBox::centered_at(Pos { x: 0.0, y: 0.0 }, 4.0, 2.0);
+    /// let page = ViewBox {
+    ///     corner: Pos { x: 0.0, y: 0.0 },
+    ///     width: 10.0,
+    ///     height: 10.0,
+    /// };
+    ///
+    /// assert_eq!(
+    ///     viewbox.transform(viewbox.fit_into(page)),
+    ///     ViewBox {
+    ///         corner: Pos { x: 0.0, y: 2.5 },
+    ///         width: 10.0,
+    ///         height: 5.0,
+    ///     },
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `other` - The view box to fit into.
+    pub fn fit_into(self, other: Self) -> Transform {
+        let scale = self.scale_to_fit(other);
+        let (from, to) = (self.center(), other.center());
+        Transform::translate(-from.x, -from.y)
+            .then(Transform::scale(scale, scale))
+            .then(Transform::translate(to.x, to.y))
+    }
+
+    /// The bounding box of this view box after applying a transform.
+    ///
+    /// If the transform contains a rotation, the result will be larger than
+    /// the transformed view box.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical::*;
+    ///
+    /// let viewbox = ViewBox {
+    ///     corner: Pos { x: 0.0, y: 0.0 },
+    ///     width: 2.0,
+    ///     height: 1.0,
+    /// };
+    ///
+    /// assert_eq!(
+    ///     viewbox.transform(
+    ///         Transform::scale(2.0, 2.0).then(Transform::translate(1.0, 0.0)),
+    ///     ),
+    ///     ViewBox {
+    ///         corner: Pos { x: 1.0, y: 0.0 },
+    ///         width: 4.0,
+    ///         height: 2.0,
+    ///     },
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `transform` - The transform to apply.
+    pub fn transform(self, transform: Transform) -> Self {
+        let (x, y) = (self.corner.x, self.corner.y);
+        let corners = [
+            (x, y),
+            (x + self.width, y),
+            (x, y + self.height),
+            (x + self.width, y + self.height),
+        ]
+        .map(|corner| transform.apply(corner.into()));
+        let (min, max) =
+            corners
+                .iter()
+                .fold((corners[0], corners[0]), |(min, max), pos| {
+                    (
+                        (min.x.min(pos.x), min.y.min(pos.y)).into(),
+                        (max.x.max(pos.x), max.y.max(pos.y)).into(),
+                    )
+                });
+
+        Self {
+            corner: min,
+            width: max.x - min.x,
+            height: max.y - min.y,
+        }
+    }
 }
 
 impl ops::Mul<ViewBox> for f32 {
@@ -435,3 +649,248 @@ impl ops::Mul<f32> for ViewBox {
         rhs * self
     }
 }
+
+/// An affine transform.
+///
+/// The transform maps the position _(x, y)_ to
+/// _(a * x + c * y + e, b * x + d * y + f)_, which is the same convention as
+/// the _SVG_ `matrix` transform function. Its string representation can thus
+/// be used directly as the value of an _SVG_ `transform` attribute.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Transform {
+    /// The contribution of _x_ to the X coordinate.
+    pub a: f32,
+
+    /// The contribution of _x_ to the Y coordinate.
+    pub b: f32,
+
+    /// The contribution of _y_ to the X coordinate.
+    pub c: f32,
+
+    /// The contribution of _y_ to the Y coordinate.
+    pub d: f32,
+
+    /// The translation along the X axis.
+    pub e: f32,
+
+    /// The translation along the Y axis.
+    pub f: f32,
+}
+
+impl Transform {
+    /// The transform leaving all positions unchanged.
+    pub const IDENTITY: Self = Self {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    /// A transform scaling positions along the axes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical::*;
+    ///
+    /// assert_eq!(
+    ///     Transform::scale(2.0, 3.0).apply(Pos { x: 1.0, y: 1.0 }),
+    ///     Pos { x: 2.0, y: 3.0 },
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `sx` - The scale factor along the X axis.
+    /// *  `sy` - The scale factor along the Y axis.
+    pub fn scale(sx: f32, sy: f32) -> Self {
+        Self {
+            a: sx,
+            d: sy,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// A transform rotating positions around _(0, 0)_.
+    ///
+    /// Since the Y axis points downwards, positive angles rotate clockwise as
+    /// displayed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical::*;
+    ///
+    /// let pos = Transform::rotate(std::f32::consts::FRAC_PI_2)
+    ///     .apply(Pos { x: 1.0, y: 0.0 });
+    /// assert!((pos - Pos { x: 0.0, y: 1.0 }).value() < 1e-6);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `angle` - The angle, in radians.
+    pub fn rotate(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    /// A transform moving positions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical::*;
+    ///
+    /// assert_eq!(
+    ///     Transform::translate(2.0, 3.0).apply(Pos { x: 1.0, y: 1.0 }),
+    ///     Pos { x: 3.0, y: 4.0 },
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `dx` - The distance to move along the X axis.
+    /// *  `dy` - The distance to move along the Y axis.
+    pub fn translate(dx: f32, dy: f32) -> Self {
+        Self {
+            e: dx,
+            f: dy,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Combines this transform with another one.
+    ///
+    /// The resulting transform first applies this transform, and then
+    /// `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical::*;
+    ///
+    /// let pos = Pos { x: 1.0, y: 1.0 };
+    /// let (scale, translate) =
+    ///     (Transform::scale(2.0, 2.0), Transform::translate(1.0, 0.0));
+    ///
+    /// assert_eq!(scale.then(translate).apply(pos), Pos { x: 3.0, y: 2.0 });
+    /// assert_eq!(translate.then(scale).apply(pos), Pos { x: 4.0, y: 2.0 });
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `other` - The transform to apply after this one.
+    pub fn then(self, other: Self) -> Self {
+        Self {
+            a: other.a * self.a + other.c * self.b,
+            b: other.b * self.a + other.d * self.b,
+            c: other.a * self.c + other.c * self.d,
+            d: other.b * self.c + other.d * self.d,
+            e: other.a * self.e + other.c * self.f + other.e,
+            f: other.b * self.e + other.d * self.f + other.f,
+        }
+    }
+
+    /// The inverse of this transform.
+    ///
+    /// If this transform cannot be inverted, which is the case when it
+    /// scales an axis to zero, nothing is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical::*;
+    ///
+    /// let transform =
+    ///     Transform::scale(2.0, 4.0).then(Transform::translate(1.0, 2.0));
+    /// let pos = Pos { x: 3.0, y: 5.0 };
+    ///
+    /// assert_eq!(
+    ///     transform.inverse().unwrap().apply(transform.apply(pos)),
+    ///     pos,
+    /// );
+    /// assert_eq!(Transform::scale(0.0, 1.0).inverse(), None);
+    /// ```
+    pub fn inverse(self) -> Option<Self> {
+        let determinant = self.a * self.d - self.b * self.c;
+        if determinant == 0.0 {
+            None
+        } else {
+            let (a, b, c, d) = (
+                self.d / determinant,
+                -self.b / determinant,
+                -self.c / determinant,
+                self.a / determinant,
+            );
+            Some(Self {
+                a,
+                b,
+                c,
+                d,
+                e: -(a * self.e + c * self.f),
+                f: -(b * self.e + d * self.f),
+            })
+        }
+    }
+
+    /// Applies this transform to a position.
+    ///
+    /// # Arguments
+    /// *  `pos` - The position to transform.
+    pub fn apply(self, pos: Pos) -> Pos {
+        Pos {
+            x: self.a * pos.x + self.c * pos.y + self.e,
+            y: self.b * pos.x + self.d * pos.y + self.f,
+        }
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl ops::Mul<Pos> for Transform {
+    type Output = Pos;
+
+    /// Applies this transform to a position.
+    ///
+    /// See [`Transform::apply`].
+    ///
+    /// # Arguments
+    /// *  `rhs` - The position to transform.
+    fn mul(self, rhs: Pos) -> Self::Output {
+        self.apply(rhs)
+    }
+}
+
+impl fmt::Display for Transform {
+    /// Formats this transform as an _SVG_ `matrix` transform function.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical::*;
+    ///
+    /// assert_eq!(
+    ///     Transform::scale(2.0, 3.0)
+    ///         .then(Transform::translate(4.0, 5.0))
+    ///         .to_string(),
+    ///     "matrix(2 0 0 3 4 5)",
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "matrix({} {} {} {} {} {})",
+            self.a, self.b, self.c, self.d, self.e, self.f,
+        )
+    }
+}
@@ -0,0 +1,350 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str;
+
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+
+/// A colour.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Color {
+    /// The red component.
+    pub red: u8,
+
+    /// The green component.
+    pub green: u8,
+
+    /// The blue component.
+    pub blue: u8,
+
+    /// The alpha component.
+    pub alpha: u8,
+}
+
+impl Color {
+    /// Returns a fully transparent version of this colour.
+    pub fn transparent(self) -> Self {
+        Self { alpha: 0, ..self }
+    }
+
+    /// Fades one colour to another.
+    ///
+    /// # Arguments
+    /// *  `other` - The other colour.
+    /// *  `w` - The weight of this colour. If this is `1.0` or greater, `self`
+    ///    colour is returned; if this is 0.0 or less, `other` is returned;
+    ///    otherwise a linear interpolation between the colours is returned.
+    pub fn fade(self, other: Self, w: f32) -> Color {
+        if w >= 1.0 {
+            self
+        } else if w <= 0.0 {
+            other
+        } else {
+            let n = 1.0 - w;
+            Color {
+                red: (f32::from(self.red) * w + f32::from(other.red) * n) as u8,
+                green: (f32::from(self.green) * w + f32::from(other.green) * n)
+                    as u8,
+                blue: (f32::from(self.blue) * w + f32::from(other.blue) * n)
+                    as u8,
+                alpha: (f32::from(self.alpha) * w + f32::from(other.alpha) * n)
+                    as u8,
+            }
+        }
+    }
+
+    /// Creates an opaque colour from _hue_, _saturation_ and _lightness_.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::render::Color;
+    /// assert_eq!(
+    ///     Color::from_hsl(120.0, 1.0, 0.25),
+    ///     "#008000".parse().unwrap(),
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `hue` - The hue, in degrees. Values outside of _[0, 360)_ wrap
+    ///    around.
+    /// *  `saturation` - The saturation, in the range _[0, 1]_.
+    /// *  `lightness` - The lightness, in the range _[0, 1]_.
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+        let sector = (hue - 360.0 * (hue / 360.0).floor()) / 60.0;
+
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let x = chroma
+            * (1.0 - ((sector - 2.0 * (sector / 2.0).floor()) - 1.0).abs());
+        let (r, g, b) = match sector as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = lightness - chroma / 2.0;
+        let component =
+            |v: f32| ((v + m) * 255.0 + 0.5).clamp(0.0, 255.0) as u8;
+
+        Self {
+            red: component(r),
+            green: component(g),
+            blue: component(b),
+            alpha: 255,
+        }
+    }
+
+    /// Converts this colour to _hue_, _saturation_ and _lightness_.
+    ///
+    /// The hue is in the range _[0, 360)_, and the saturation and lightness in
+    /// the range _[0, 1]_. The alpha component is ignored.
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let (r, g, b) = (
+            f32::from(self.red) / 255.0,
+            f32::from(self.green) / 255.0,
+            f32::from(self.blue) / 255.0,
+        );
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+        let lightness = (max + min) / 2.0;
+
+        if chroma == 0.0 {
+            (0.0, 0.0, lightness)
+        } else {
+            let saturation = chroma / (1.0 - (2.0 * lightness - 1.0).abs());
+            let sector = if max == r {
+                (g - b) / chroma
+            } else if max == g {
+                (b - r) / chroma + 2.0
+            } else {
+                (r - g) / chroma + 4.0
+            };
+            let hue = 60.0 * sector;
+            (
+                if hue < 0.0 { hue + 360.0 } else { hue },
+                saturation.min(1.0),
+                lightness,
+            )
+        }
+    }
+
+    /// Generates a palette of colours with evenly distributed hues.
+    ///
+    /// The first colour is this colour, and the following colours have the
+    /// same saturation, lightness and alpha, but with the hue rotated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::render::Color;
+    /// let red = "#FF0000".parse::<Color>().unwrap();
+    /// let palette = red.palette(3).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(palette[0], red);
+    /// assert_eq!(palette[1], "#00FF00".parse().unwrap());
+    /// assert_eq!(palette[2], "#0000FF".parse().unwrap());
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `count` - The number of colours to generate.
+    pub fn palette(self, count: usize) -> impl Iterator<Item = Self> {
+        let (hue, saturation, lightness) = self.to_hsl();
+        let step = 360.0 / count.max(1) as f32;
+        (0..count).map(move |i| {
+            if i == 0 {
+                self
+            } else {
+                Self {
+                    alpha: self.alpha,
+                    ..Self::from_hsl(
+                        hue + step * i as f32,
+                        saturation,
+                        lightness,
+                    )
+                }
+            }
+        })
+    }
+
+    /// Calculates the colour of a position along a gradient.
+    ///
+    /// The stops are evenly distributed along the gradient, and the colour is
+    /// a linear interpolation between the closest stops.
+    ///
+    /// If `stops` is empty, the default colour is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::render::Color;
+    /// let stops = ["#000000", "#FF0000", "#FFFFFF"]
+    ///     .iter()
+    ///     .map(|s| s.parse().unwrap())
+    ///     .collect::<Vec<Color>>();
+    ///
+    /// assert_eq!(Color::gradient(&stops, 0.0), stops[0]);
+    /// assert_eq!(Color::gradient(&stops, 0.5), stops[1]);
+    /// assert_eq!(Color::gradient(&stops, 2.0), stops[2]);
+    /// assert_eq!(
+    ///     Color::gradient(&stops, 0.75),
+    ///     "#FF7F7F".parse().unwrap(),
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `stops` - The colours of the gradient.
+    /// *  `w` - The position along the gradient, in the range _[0, 1]_.
+    pub fn gradient(stops: &[Self], w: f32) -> Self {
+        match stops.len() {
+            0 => Self::default(),
+            1 => stops[0],
+            count => {
+                let segments = count - 1;
+                let position = w.clamp(0.0, 1.0) * segments as f32;
+                let index = (position as usize).min(segments - 1);
+                stops[index + 1].fade(stops[index], position - index as f32)
+            }
+        }
+    }
+}
+
+impl str::FromStr for Color {
+    type Err = String;
+
+    /// Converts a string to a colour.
+    ///
+    /// This method supports colours on the form `#RRGGBB` and `#RRGGBBAA`,
+    /// where `RR`, `GG`, `BB` and `AA` are the red, green, blue and alpha
+    /// components hex encoded.
+    ///
+    /// # Arguments
+    /// *  `s` - The string to convert.
+    fn from_str(s: &str) -> Result<Color, String> {
+        if !s.starts_with('#') || s.len().is_multiple_of(2) {
+            Err(format!("unknown colour value: {}", s))
+        } else {
+            let data = s
+                .bytes()
+                // Skip the initial '#'
+                .skip(1)
+                // Hex decode and create list
+                .map(|c| {
+                    if c.is_ascii_digit() {
+                        Some(c - b'0')
+                    } else if (b'A'..=b'F').contains(&c) {
+                        Some(c - b'A' + 10)
+                    } else if (b'a'..=b'f').contains(&c) {
+                        Some(c - b'a' + 10)
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+                // Join every byte
+                .chunks(2)
+                .map(|c| {
+                    if let (Some(msb), Some(lsb)) = (c[0], c[1]) {
+                        Some(msb << 4 | lsb)
+                    } else {
+                        None
+                    }
+                })
+                // Ensure all values are valid
+                .take_while(Option::is_some)
+                .map(Option::unwrap)
+                .collect::<Vec<_>>();
+
+            match data.len() {
+                3 => Ok(Color {
+                    red: data[0],
+                    green: data[1],
+                    blue: data[2],
+                    alpha: 255,
+                }),
+                4 => Ok(Color {
+                    red: data[0],
+                    green: data[1],
+                    blue: data[2],
+                    alpha: data[3],
+                }),
+                _ => Err(format!("invalid colour format: {}", s)),
+            }
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    /// Converts a colour to a string.
+    ///
+    /// This method ignores the alpha component.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02.X}{:02.X}{:02.X}", self.red, self.green, self.blue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            Ok(Color {
+                red: 0x12,
+                green: 0x34,
+                blue: 0xAB,
+                alpha: 255,
+            }),
+            "#1234ab".parse::<Color>(),
+        );
+        assert_eq!(
+            Ok(Color {
+                red: 0x00,
+                green: 0x00,
+                blue: 0xFF,
+                alpha: 0x80,
+            }),
+            "#0000FF80".parse::<Color>(),
+        );
+        assert!("#1234a".parse::<Color>().is_err());
+        assert!("1234ab".parse::<Color>().is_err());
+        assert!("#1234ag".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            "#0A0B0C",
+            Color {
+                red: 10,
+                green: 11,
+                blue: 12,
+                alpha: 13,
+            }
+            .to_string(),
+        );
+    }
+
+    #[test]
+    fn hsl_round_trip() {
+        for value in (0..=0xFFFFFFu32).step_by(0x010305) {
+            let color = Color {
+                red: (value >> 16) as u8,
+                green: (value >> 8) as u8,
+                blue: value as u8,
+                alpha: 255,
+            };
+            let (h, s, l) = color.to_hsl();
+            assert_eq!(color, Color::from_hsl(h, s, l), "{:?}", (h, s, l));
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! # DXF rendering
+//!
+//! A maze can be exported to CAD software and CNC tool chains as an ASCII
+//! _DXF_ drawing. The walls are split into separate layers, so that the outer
+//! border can be handled differently from the inner walls:
+//!
+//! *  [`LAYER_WALLS`] contains walls between two rooms of the maze.
+//! *  [`LAYER_BORDER`] contains walls between the maze and its surroundings.
+//! *  [`LAYER_SOLUTION`] contains the solution, if any.
+//!
+//! One unit in the drawing corresponds to the distance from the centre of a
+//! room to its corners. The Y axis of _DXF_ points upwards, so the drawing is
+//! flipped vertically to match other renderers.
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::walk::Path;
+use crate::HashSet;
+use crate::Maze;
+
+use super::segments::{key, merge, Segment};
+
+/// The layer containing walls between rooms.
+pub const LAYER_WALLS: &str = "WALLS";
+
+/// The layer containing walls along the edge of the maze.
+pub const LAYER_BORDER: &str = "BORDER";
+
+/// The layer containing the solution.
+pub const LAYER_SOLUTION: &str = "SOLUTION";
+
+/// The layers of a drawing, and their _AutoCAD Color Index_.
+const LAYERS: &[(&str, u8)] =
+    &[(LAYER_WALLS, 7), (LAYER_BORDER, 5), (LAYER_SOLUTION, 1)];
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// Generates an ASCII _DXF_ drawing of the walls of this maze, and
+    /// optionally a path through it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::initialize;
+    /// # use maze::render::dxf;
+    /// # use maze::Shape;
+    /// let maze = Shape::Quad.create::<()>(5, 5).initialize(
+    ///     initialize::Method::Winding(initialize::Bias::default()),
+    ///     &mut initialize::LFSR::new(12345),
+    /// );
+    /// let solution = maze
+    ///     .walk((0isize, 0isize).into(), (4isize, 4isize).into())
+    ///     .unwrap();
+    /// let dxf = maze.to_dxf(Some(&solution));
+    ///
+    /// assert!(dxf.contains(dxf::LAYER_SOLUTION));
+    /// assert!(dxf.ends_with("EOF\n"));
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `solution` - A path to draw on the solution layer.
+    pub fn to_dxf(&self, solution: Option<&Path<'_, T>>) -> String {
+        // Walls along the edge of the maze are edge walls of its rooms
+        let edges = self
+            .edge_walls()
+            .map(|wall_pos| segment_key(self.corners(wall_pos)))
+            .collect::<HashSet<_>>();
+        let (border, walls): (Vec<_>, Vec<_>) = self
+            .wall_segments()
+            .partition(|&segment| edges.contains(&segment_key(segment)));
+
+        let mut dxf = String::new();
+        group(&mut dxf, 0, "SECTION");
+        group(&mut dxf, 2, "TABLES");
+        group(&mut dxf, 0, "TABLE");
+        group(&mut dxf, 2, "LAYER");
+        group(&mut dxf, 70, LAYERS.len());
+        for &(name, color) in LAYERS {
+            group(&mut dxf, 0, "LAYER");
+            group(&mut dxf, 2, name);
+            group(&mut dxf, 70, 0);
+            group(&mut dxf, 62, color);
+            group(&mut dxf, 6, "CONTINUOUS");
+        }
+        group(&mut dxf, 0, "ENDTAB");
+        group(&mut dxf, 0, "ENDSEC");
+
+        group(&mut dxf, 0, "SECTION");
+        group(&mut dxf, 2, "ENTITIES");
+        for (layer, segments) in [(LAYER_WALLS, walls), (LAYER_BORDER, border)]
+        {
+            for segment in merge(segments) {
+                line(&mut dxf, layer, segment);
+            }
+        }
+        if let Some(solution) = solution {
+            let centers = solution
+                .into_iter()
+                .map(|pos| self.center(pos))
+                .collect::<Vec<_>>();
+            for step in centers.windows(2) {
+                line(&mut dxf, LAYER_SOLUTION, (step[0], step[1]));
+            }
+        }
+        group(&mut dxf, 0, "ENDSEC");
+        group(&mut dxf, 0, "EOF");
+
+        dxf
+    }
+}
+
+/// A hashable key for a segment, independent of its direction.
+///
+/// # Arguments
+/// *  `segment` - The segment.
+fn segment_key((from, to): Segment) -> ((i64, i64), (i64, i64)) {
+    let (from, to) = (key(from), key(to));
+    if from < to {
+        (from, to)
+    } else {
+        (to, from)
+    }
+}
+
+/// Writes a group code and its value.
+///
+/// # Arguments
+/// *  `dxf` - The drawing being generated.
+/// *  `code` - The group code.
+/// *  `value` - The value.
+fn group<V>(dxf: &mut String, code: u16, value: V)
+where
+    V: core::fmt::Display,
+{
+    let _ = write!(dxf, "{}\n{}\n", code, value);
+}
+
+/// Writes a line entity.
+///
+/// # Arguments
+/// *  `dxf` - The drawing being generated.
+/// *  `layer` - The layer of the line.
+/// *  `segment` - The end points of the line.
+fn line(dxf: &mut String, layer: &str, segment: Segment) {
+    let (from, to) = segment;
+    group(dxf, 0, "LINE");
+    group(dxf, 8, layer);
+    group(dxf, 10, from.x);
+    group(dxf, 20, -from.y);
+    group(dxf, 30, 0.0);
+    group(dxf, 11, to.x);
+    group(dxf, 21, -to.y);
+    group(dxf, 31, 0.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize;
+    use crate::test_utils::*;
+
+    /// Counts the lines of a layer.
+    ///
+    /// # Arguments
+    /// *  `dxf` - The drawing.
+    /// *  `layer` - The layer name.
+    fn lines(dxf: &str, layer: &str) -> usize {
+        dxf.matches(&alloc::format!("LINE\n8\n{}\n", layer)).count()
+    }
+
+    #[maze_test]
+    fn to_dxf(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Clear,
+            &mut initialize::LFSR::new(12345),
+        );
+        let dxf = maze.to_dxf(None);
+
+        // A cleared maze has only border walls
+        assert_eq!(lines(&dxf, LAYER_WALLS), 0);
+        assert_eq!(
+            lines(&dxf, LAYER_BORDER),
+            maze.merged_wall_segments().len(),
+        );
+        assert_eq!(lines(&dxf, LAYER_SOLUTION), 0);
+        assert!(dxf.ends_with("0\nEOF\n"));
+    }
+
+    #[maze_test]
+    fn to_dxf_solution(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Winding(initialize::Bias::default()),
+            &mut initialize::LFSR::new(12345),
+        );
+        let from = matrix_pos(0, 0);
+        let to = matrix_pos(maze.width() as isize - 1, 0);
+        let solution = maze.walk(from, to).unwrap();
+        let dxf = maze.to_dxf(Some(&solution));
+
+        assert!(lines(&dxf, LAYER_WALLS) > 0);
+        assert_eq!(
+            lines(&dxf, LAYER_SOLUTION) + 1,
+            solution.into_iter().count(),
+        );
+    }
+}
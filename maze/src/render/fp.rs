@@ -0,0 +1,237 @@
+//! # First person rendering
+//!
+//! A maze can be viewed from the inside by casting one ray per column of a
+//! view, and drawing a vertical line for every wall hit, scaled by its
+//! distance from the camera.
+use alloc::vec::Vec;
+use core::f32::consts::FRAC_PI_3;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+use crate::matrix;
+use crate::physical;
+use crate::{Maze, WallPos};
+
+/// The default horizontal field of view, 60°.
+pub const DEFAULT_FOV: f32 = FRAC_PI_3;
+
+/// A camera placed inside of a maze.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Camera {
+    /// The position of the camera.
+    pub pos: physical::Pos,
+
+    /// The direction in which the camera is looking, in radians.
+    pub angle: f32,
+
+    /// The horizontal field of view, in radians.
+    pub fov: f32,
+}
+
+impl Camera {
+    /// Creates a camera with the default field of view.
+    ///
+    /// # Arguments
+    /// *  `pos` - The position of the camera.
+    /// *  `angle` - The direction in which the camera is looking, in radians.
+    pub fn new(pos: physical::Pos, angle: f32) -> Self {
+        Self {
+            pos,
+            angle,
+            fov: DEFAULT_FOV,
+        }
+    }
+
+    /// Replaces the field of view of this camera.
+    ///
+    /// # Arguments
+    /// *  `fov` - The horizontal field of view, in radians.
+    pub fn with_fov(self, fov: f32) -> Self {
+        Self { fov, ..self }
+    }
+}
+
+/// A column of a first person view.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Column {
+    /// The distance to the wall, measured along the direction of the camera.
+    ///
+    /// Using this distance rather than the distance to the camera position
+    /// prevents a _fish eye_ effect.
+    pub distance: f32,
+
+    /// The height of the wall, relative to the width of the view.
+    ///
+    /// Walls are one unit tall, which is the distance between the centre and
+    /// the corners of a room.
+    pub height: f32,
+
+    /// The wall hit.
+    pub wall_pos: WallPos,
+
+    /// The position along the wall where it was hit, from `0.0` at its first
+    /// corner to `1.0` at its second.
+    ///
+    /// This is useful to apply textures.
+    pub offset: f32,
+}
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// Renders a first person view of this maze as a list of columns.
+    ///
+    /// The columns are ordered from left to right. A column is `None` if its
+    /// ray does not hit a wall, which is the case if the camera is outside of
+    /// the maze.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// # use maze::render::fp::*;
+    /// let maze = maze::Shape::Quad.create::<()>(1, 1);
+    /// let pos = matrix::Pos { col: 0, row: 0 };
+    /// let camera = Camera::new(maze.center(pos), 0.0);
+    ///
+    /// let columns = maze.first_person(camera, 3);
+    /// let center = columns[1].unwrap();
+    /// assert!((center.offset - 0.5).abs() < 1e-5);
+    /// assert!(columns[0].unwrap().distance == columns[2].unwrap().distance);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `camera` - The camera.
+    /// *  `columns` - The number of columns.
+    pub fn first_person(
+        &self,
+        camera: Camera,
+        columns: usize,
+    ) -> Vec<Option<Column>> {
+        let (sin, cos) = camera.angle.sin_cos();
+        let (forward, right) = (
+            physical::Pos { x: cos, y: sin },
+            physical::Pos { x: -sin, y: cos },
+        );
+        let (sin, cos) = (0.5 * camera.fov).sin_cos();
+        let half_width = sin / cos;
+
+        (0..columns)
+            .map(|i| {
+                // The direction has a unit length along the camera direction,
+                // so the ray parameter is the perpendicular distance
+                let x = 2.0 * (i as f32 + 0.5) / columns as f32 - 1.0;
+                let direction = forward + right * (x * half_width);
+                let (distance, wall_pos) = self.trace(camera.pos, direction)?;
+
+                let (a, b) = self.corners(wall_pos);
+                let hit = camera.pos + direction * distance;
+                let offset = ((hit - a).value() / (b - a).value()).sqrt();
+                Some(Column {
+                    distance,
+                    height: 1.0 / (2.0 * half_width * distance),
+                    wall_pos,
+                    offset: offset.min(1.0),
+                })
+            })
+            .collect()
+    }
+
+    /// Renders a first person view of this maze as an image.
+    ///
+    /// Every pixel of the returned matrix is the brightness of the wall
+    /// drawn there, decreasing with distance, or `0.0` for the floor and
+    /// ceiling.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// # use maze::render::fp::*;
+    /// let maze = maze::Shape::Quad.create::<()>(1, 1);
+    /// let pos = matrix::Pos { col: 0, row: 0 };
+    /// let camera = Camera::new(maze.center(pos), 0.0);
+    ///
+    /// let image = maze.first_person_image(camera, 16, 12);
+    /// assert_eq!((image.width, image.height), (16, 12));
+    /// assert!(image[matrix::Pos { col: 8, row: 6 }] > 0.0);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `camera` - The camera.
+    /// *  `width` - The width of the image, in pixels.
+    /// *  `height` - The height of the image, in pixels.
+    pub fn first_person_image(
+        &self,
+        camera: Camera,
+        width: usize,
+        height: usize,
+    ) -> matrix::Matrix<f32> {
+        let columns = self.first_person(camera, width);
+        matrix::Matrix::new_with_data(width, height, |pos| {
+            columns[pos.col as usize]
+                .filter(|column| {
+                    let y = (pos.row as f32 + 0.5 - 0.5 * height as f32).abs();
+                    y <= 0.5 * column.height * width as f32
+                })
+                .map(|column| 1.0 / (1.0 + column.distance))
+                .unwrap_or(0.0)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn first_person_closed(maze: TestMaze) {
+        for pos in maze.positions() {
+            let camera = Camera::new(maze.center(pos), 0.3);
+            for column in maze.first_person(camera, 16) {
+                let column = column.unwrap();
+                assert_eq!(pos, column.wall_pos.0);
+                assert!(column.distance > 0.0);
+                assert!((0.0..=1.0).contains(&column.offset));
+            }
+        }
+    }
+
+    #[maze_test]
+    fn first_person_outside(maze: TestMaze) {
+        let camera = Camera::new(physical::Pos { x: -10.0, y: -10.0 }, 0.0);
+        assert!(maze
+            .first_person(camera, 16)
+            .into_iter()
+            .all(|column| column.is_none()));
+    }
+
+    #[test]
+    fn first_person_corridor() {
+        let maze = crate::Shape::Quad.create::<()>(8, 1).initialize(
+            initialize::Method::Clear,
+            &mut initialize::LFSR::new(12345),
+        );
+        let camera = Camera::new(maze.center(matrix_pos(0, 0)), 0.0);
+
+        // Looking down the corridor, the far wall is smaller than the sides
+        let columns = maze.first_person(camera, 9);
+        let (center, side) = (columns[4].unwrap(), columns[0].unwrap());
+        assert_eq!(matrix_pos(7, 0), center.wall_pos.0);
+        assert!(center.height < side.height);
+
+        let image = maze.first_person_image(camera, 9, 9);
+        assert_eq!(0.0, image[matrix_pos(4, 0)]);
+        assert!(image[matrix_pos(4, 4)] > 0.0);
+        assert!(image[matrix_pos(0, 2)] > 0.0);
+    }
+}
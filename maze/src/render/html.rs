@@ -0,0 +1,145 @@
+//! # HTML canvas rendering
+//!
+//! A maze can be embedded in a web page without _SVG_ by drawing its walls on
+//! a `<canvas>`. The generated document is self-contained: the wall segments
+//! are embedded as a _JavaScript_ array, which a small script draws.
+use alloc::string::String;
+use core::fmt::Write;
+
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+use crate::physical;
+use crate::Maze;
+
+/// The width of walls, in pixels.
+const LINE_WIDTH: f32 = 1.0;
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// Generates a self-contained _HTML_ document drawing this maze on a
+    /// canvas.
+    ///
+    /// The walls are transformed by `transform`, and the canvas covers
+    /// `viewbox`, which is expressed in transformed coordinates. One unit
+    /// corresponds to one pixel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::initialize;
+    /// # use maze::physical;
+    /// # use maze::Shape;
+    /// let maze = Shape::Quad.create::<()>(5, 5)
+    ///     .initialize(initialize::Method::Clear, &mut initialize::LFSR::new(12345));
+    /// let transform = physical::Transform::scale(10.0, 10.0);
+    /// let viewbox = maze.viewbox().transform(transform).expand(5.0);
+    /// let html = maze.to_canvas_html(transform, viewbox);
+    ///
+    /// assert!(html.contains(&format!(
+    ///     r#"<canvas id="maze" width="{}" height="{}">"#,
+    ///     viewbox.width.ceil(),
+    ///     viewbox.height.ceil(),
+    /// )));
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `transform` - The transform to apply to the maze.
+    /// *  `viewbox` - The area of the transformed maze to draw.
+    pub fn to_canvas_html(
+        &self,
+        transform: physical::Transform,
+        viewbox: physical::ViewBox,
+    ) -> String {
+        let mut walls = String::new();
+        for (i, (from, to)) in
+            self.merged_wall_segments().into_iter().enumerate()
+        {
+            if i > 0 {
+                walls.push_str(",\n");
+            }
+            let _ =
+                write!(walls, "  [{}, {}, {}, {}]", from.x, from.y, to.x, to.y);
+        }
+
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Maze</title>
+</head>
+<body>
+<canvas id="maze" width="{width}" height="{height}"></canvas>
+<script>
+const walls = [
+{walls}
+];
+
+const canvas = document.getElementById("maze");
+const context = canvas.getContext("2d");
+
+function draw() {{
+  context.setTransform(1, 0, 0, 1, 0, 0);
+  context.clearRect(0, 0, canvas.width, canvas.height);
+
+  // Build the path in maze coordinates
+  context.setTransform({a}, {b}, {c}, {d}, {e}, {f});
+  context.beginPath();
+  for (const [x1, y1, x2, y2] of walls) {{
+    context.moveTo(x1, y1);
+    context.lineTo(x2, y2);
+  }}
+
+  // Stroke the path in canvas coordinates, so that the line width is not
+  // scaled
+  context.setTransform(1, 0, 0, 1, 0, 0);
+  context.lineCap = "round";
+  context.lineWidth = {line_width};
+  context.strokeStyle = "black";
+  context.stroke();
+}}
+
+draw();
+</script>
+</body>
+</html>
+"#,
+            width = viewbox.width.ceil(),
+            height = viewbox.height.ceil(),
+            walls = walls,
+            a = transform.a,
+            b = transform.b,
+            c = transform.c,
+            d = transform.d,
+            e = transform.e - viewbox.corner.x,
+            f = transform.f - viewbox.corner.y,
+            line_width = LINE_WIDTH,
+        );
+
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn to_canvas_html(maze: TestMaze) {
+        let html =
+            maze.to_canvas_html(physical::Transform::IDENTITY, maze.viewbox());
+
+        assert_eq!(
+            html.matches("  [").count(),
+            maze.merged_wall_segments().len(),
+        );
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+}
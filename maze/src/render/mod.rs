@@ -14,5 +14,16 @@ where
     }
 }
 
+mod color;
+pub use self::color::Color;
+
+pub mod dxf;
+pub mod fp;
+pub mod html;
+mod outline;
+mod segments;
+pub use self::segments::Segment;
+
 #[cfg(feature = "svg")]
 pub mod svg;
+pub mod tikz;
@@ -0,0 +1,276 @@
+//! # Wall outlines
+//!
+//! When walls are drawn as lines, their thickness is left to the renderer.
+//! This module calculates the actual outline of the walls for a specific
+//! thickness, which is required when the walls are to be cut from a physical
+//! material.
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+use crate::physical;
+use crate::HashSet;
+use crate::Maze;
+use crate::WallPos;
+
+/// The maximum distance, relative to half the wall thickness, between a
+/// corner and its offset corner before the corner is bevelled.
+///
+/// This corresponds to the default value of the _SVG_ attribute
+/// `stroke-miterlimit`.
+const MITER_LIMIT: f32 = 4.0;
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// Calculates the outline of all walls of this maze given a thickness.
+    ///
+    /// Every wall is extended by half of `width` on both sides, and the
+    /// resulting shapes are joined. The return value is a list of closed
+    /// polygons; a point is part of a wall if it is enclosed by an odd number
+    /// of polygons.
+    ///
+    /// Loose wall ends are extended by half of `width`, and sharp corners are
+    /// bevelled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::Shape;
+    /// let mut maze = Shape::Quad.create::<()>(2, 1);
+    /// maze.open(
+    ///     maze.connecting_wall((0isize, 0isize).into(), (1isize, 0isize).into())
+    ///         .unwrap(),
+    /// );
+    ///
+    /// // The outside and the inside of the walls
+    /// let outline = maze.outline(0.2);
+    /// assert_eq!(outline.len(), 2);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `width` - The thickness of the walls.
+    pub fn outline(&self, width: f32) -> Vec<Vec<physical::Pos>> {
+        let mut visited = HashSet::new();
+        let mut result = Vec::new();
+
+        // The walls seen from inside the maze, and the walls along its edge
+        // seen from the outside
+        let sides = self
+            .positions()
+            .flat_map(|pos| self.wall_positions(pos))
+            .chain(self.edge_walls().map(|wall_pos| self.back(wall_pos)));
+        for side in sides {
+            if self.is_solid(side) && !visited.contains(&side) {
+                result.push(self.outline_polygon(
+                    side,
+                    width / 2.0,
+                    &mut visited,
+                ));
+            }
+        }
+
+        result
+    }
+
+    /// Whether a wall is part of the outline.
+    ///
+    /// A wall is solid if it is closed from both sides and at least one of
+    /// its rooms has been visited.
+    ///
+    /// # Arguments
+    /// *  `wall_pos` - The wall position.
+    fn is_solid(&self, wall_pos: WallPos) -> bool {
+        let back = self.back(wall_pos);
+        let visited = |pos| self.visited(pos);
+
+        !self.is_open(wall_pos)
+            && !self.is_open(back)
+            && (visited(wall_pos.0) || visited(back.0))
+    }
+
+    /// Calculates the polygon following the walls of a cavity.
+    ///
+    /// All wall positions making up the polygon are marked as visited.
+    ///
+    /// # Arguments
+    /// *  `start` - The first wall of the polygon.
+    /// *  `distance` - The distance by which to offset the polygon towards
+    ///    the centre of the cavity.
+    /// *  `visited` - The wall positions already part of a polygon.
+    fn outline_polygon(
+        &self,
+        start: WallPos,
+        distance: f32,
+        visited: &mut HashSet<WallPos>,
+    ) -> Vec<physical::Pos> {
+        let mut sides = vec![start];
+        visited.insert(start);
+        loop {
+            let next = self.next_solid(*sides.last().unwrap());
+            if next == start {
+                break;
+            } else {
+                visited.insert(next);
+                sides.push(next);
+            }
+        }
+
+        let mut result = Vec::with_capacity(sides.len());
+        let mut previous = *sides.last().unwrap();
+        for current in sides {
+            let corner = self.corners(current).0;
+            let (pdir, pnormal) = self.direction_normal(previous);
+            let (_, cnormal) = self.direction_normal(current);
+
+            if current == self.back(previous) {
+                // A loose wall end; extend the wall
+                let cap = corner + pdir * distance;
+                result.push(cap + pnormal * distance);
+                result.push(cap + cnormal * distance);
+            } else {
+                let sum = pnormal + cnormal;
+                let miter = sum * (1.0 / sum.value().sqrt());
+                let cos = miter.x * pnormal.x + miter.y * pnormal.y;
+                if cos * MITER_LIMIT < 1.0 {
+                    result.push(corner + pnormal * distance);
+                    result.push(corner + cnormal * distance);
+                } else {
+                    result.push(corner + miter * (distance / cos));
+                }
+            }
+
+            previous = current;
+        }
+
+        result
+    }
+
+    /// Finds the next solid wall when following the walls of a cavity.
+    ///
+    /// This is the equivalent of [`Self::follow_wall`], except that walls
+    /// between non-visited rooms are ignored.
+    ///
+    /// # Arguments
+    /// *  `wall_pos` - The current wall position.
+    fn next_solid(&self, wall_pos: WallPos) -> WallPos {
+        self.corner_walls_start((wall_pos.0, wall_pos.1.next))
+            .find(|&next| self.is_solid(next))
+            .unwrap_or_else(|| self.back(wall_pos))
+    }
+
+    /// Calculates the unit direction of a wall and its unit normal pointing
+    /// into its room.
+    ///
+    /// # Arguments
+    /// *  `wall_pos` - The wall position.
+    fn direction_normal(
+        &self,
+        wall_pos: WallPos,
+    ) -> (physical::Pos, physical::Pos) {
+        let (start, end) = self.corners(wall_pos);
+        let direction = end - start;
+        let direction = direction * (1.0 / direction.value().sqrt());
+        let normal = physical::Pos {
+            x: -direction.y,
+            y: direction.x,
+        };
+        let inwards = self.center(wall_pos.0) - start;
+        if normal.x * inwards.x + normal.y * inwards.y < 0.0 {
+            (direction, normal * -1.0)
+        } else {
+            (direction, normal)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn outline_empty(maze: TestMaze) {
+        assert!(maze.outline(0.1).is_empty());
+    }
+
+    #[maze_test]
+    fn outline_single_room(mut maze: TestMaze) {
+        let pos = matrix_pos(1, 1);
+        maze.rooms[pos].visited = true;
+        let outline = maze.outline(0.0);
+
+        assert_eq!(outline.len(), 2);
+        for polygon in outline {
+            assert_eq!(polygon.len(), maze.walls(pos).len());
+            for wall_pos in maze.wall_positions(pos) {
+                let corner = maze.corners(wall_pos).0;
+                assert!(polygon.iter().any(|&p| is_close(p, corner)));
+            }
+        }
+    }
+
+    #[maze_test]
+    fn outline_loose_end(maze: TestMaze) {
+        let mut maze =
+            maze.initialize(initialize::Method::Clear, &mut rand::thread_rng());
+        let pos = matrix_pos(2, 2);
+        maze.close((pos, maze.walls(pos)[0]));
+        let outline = maze.outline(0.2);
+
+        // The outside, the inside and the wall in the middle, whose two
+        // corners have been extended
+        assert_eq!(outline.len(), 3);
+        assert!(outline.iter().any(|polygon| polygon.len() == 4));
+    }
+
+    #[maze_test]
+    fn outline_perfect_maze(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Branching(initialize::Bias::default()),
+            &mut rand::thread_rng(),
+        );
+
+        // The walls of a perfect maze are all connected, and there is only
+        // one cavity
+        assert_eq!(maze.outline(0.2).len(), 2);
+    }
+
+    #[maze_test]
+    fn outline_distance(maze: TestMaze) {
+        let width = 0.2;
+        let maze =
+            maze.initialize(initialize::Method::Braid, &mut rand::thread_rng());
+        let segments = maze
+            .positions()
+            .flat_map(|pos| maze.wall_positions(pos))
+            .filter(|&wall_pos| maze.is_solid(wall_pos))
+            .map(|wall_pos| maze.corners(wall_pos))
+            .collect::<Vec<_>>();
+
+        for point in maze.outline(width).into_iter().flatten() {
+            let distance = segments
+                .iter()
+                .map(|&(a, b)| segment_distance(point, a, b))
+                .fold(f32::MAX, f32::min);
+            assert!(distance > width / 2.0 - 0.001);
+        }
+    }
+
+    /// The distance between a point and a line segment.
+    fn segment_distance(
+        point: physical::Pos,
+        a: physical::Pos,
+        b: physical::Pos,
+    ) -> f32 {
+        let ab = b - a;
+        let ap = point - a;
+        let t = ((ap.x * ab.x + ap.y * ab.y) / ab.value()).clamp(0.0, 1.0);
+        (point - (a + ab * t)).value().sqrt()
+    }
+}
@@ -0,0 +1,279 @@
+//! # Wall segments
+//!
+//! Renderers that draw walls as individual lines need only the end points of
+//! every wall. This module extracts them, optionally merging walls along the
+//! same line into longer segments.
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+use crate::physical;
+use crate::HashMap;
+use crate::Maze;
+
+/// The number of subdivisions per unit used when comparing positions.
+///
+/// Corners shared by several walls are calculated from different rooms, so
+/// they may differ slightly.
+const RESOLUTION: f32 = 1024.0;
+
+/// The maximum sine of the angle between two segments considered collinear.
+const TOLERANCE: f32 = 1e-3;
+
+/// A line segment.
+pub type Segment = (physical::Pos, physical::Pos);
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// Iterates over the end points of all walls to draw.
+    ///
+    /// Every closed wall next to a visited room is visited once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::Shape;
+    /// let mut maze = Shape::Quad.create::<()>(2, 1);
+    /// maze.open(
+    ///     maze.connecting_wall((0isize, 0isize).into(), (1isize, 0isize).into())
+    ///         .unwrap(),
+    /// );
+    ///
+    /// assert_eq!(maze.wall_segments().count(), 6);
+    /// ```
+    pub fn wall_segments(&self) -> impl Iterator<Item = Segment> + '_ {
+        let visited = |pos| self.visited(pos);
+        self.walls_iter()
+            .filter(move |&(wall_pos, open)| {
+                !open && (visited(wall_pos.0) || visited(self.back(wall_pos).0))
+            })
+            .map(move |(wall_pos, _)| self.corners(wall_pos))
+    }
+
+    /// Calculates the end points of all walls to draw, with walls along the
+    /// same line merged.
+    ///
+    /// This yields the same lines as [`wall_segments`](Self::wall_segments),
+    /// but with fewer segments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::Shape;
+    /// let mut maze = Shape::Quad.create::<()>(2, 1);
+    /// maze.open(
+    ///     maze.connecting_wall((0isize, 0isize).into(), (1isize, 0isize).into())
+    ///         .unwrap(),
+    /// );
+    ///
+    /// // The top and bottom walls are merged
+    /// assert_eq!(maze.merged_wall_segments().len(), 4);
+    /// ```
+    pub fn merged_wall_segments(&self) -> Vec<Segment> {
+        merge(self.wall_segments())
+    }
+
+    /// The total length of all walls to draw.
+    ///
+    /// Only closed walls are included, and walls shared by two rooms are
+    /// counted once. The unit of length is the distance from the centre of a
+    /// room to its corners.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::Shape;
+    /// let mut maze = Shape::Quad.create::<()>(2, 1);
+    /// maze.open(
+    ///     maze.connecting_wall((0isize, 0isize).into(), (1isize, 0isize).into())
+    ///         .unwrap(),
+    /// );
+    ///
+    /// // Six walls remain, each with the length √2
+    /// assert!((maze.total_wall_length() - 6.0 * 2f32.sqrt()).abs() < 1e-5);
+    /// ```
+    pub fn total_wall_length(&self) -> f32 {
+        self.wall_segments()
+            .map(|(from, to)| (to - from).value().sqrt())
+            .sum()
+    }
+}
+
+/// Merges collinear segments sharing an end point.
+///
+/// # Arguments
+/// *  `segments` - The segments to merge.
+pub(crate) fn merge<I>(segments: I) -> Vec<Segment>
+where
+    I: IntoIterator<Item = Segment>,
+{
+    let segments = segments.into_iter().collect::<Vec<_>>();
+    let mut ends = HashMap::<_, Vec<usize>>::new();
+    for (i, &(from, to)) in segments.iter().enumerate() {
+        ends.entry(key(from)).or_default().push(i);
+        ends.entry(key(to)).or_default().push(i);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut result = Vec::new();
+    for i in 0..segments.len() {
+        if used[i] {
+            continue;
+        }
+        used[i] = true;
+
+        // Extend the segment as far as possible in both directions
+        let (mut from, mut to) = segments[i];
+        while let Some(next) = extend(&segments, &ends, &mut used, from, to) {
+            to = next;
+        }
+        while let Some(next) = extend(&segments, &ends, &mut used, to, from) {
+            from = next;
+        }
+        result.push((from, to));
+    }
+
+    result
+}
+
+/// Finds an unused segment continuing a line beyond its end point.
+///
+/// If one is found, it is marked as used and its other end point is returned.
+///
+/// # Arguments
+/// *  `segments` - All segments.
+/// *  `ends` - The indices of the segments ending at every point.
+/// *  `used` - Whether every segment has been used.
+/// *  `from` - The start of the line.
+/// *  `to` - The end of the line.
+fn extend(
+    segments: &[Segment],
+    ends: &HashMap<(i64, i64), Vec<usize>>,
+    used: &mut [bool],
+    from: physical::Pos,
+    to: physical::Pos,
+) -> Option<physical::Pos> {
+    let (index, next) = ends
+        .get(&key(to))?
+        .iter()
+        .filter(|&&j| !used[j])
+        .map(|&j| {
+            let (a, b) = segments[j];
+            (j, if key(a) == key(to) { b } else { a })
+        })
+        .find(|&(_, next)| collinear(from, to, next))?;
+    used[index] = true;
+    Some(next)
+}
+
+/// Whether a line continues in the same direction through a point.
+///
+/// # Arguments
+/// *  `from` - The start of the line.
+/// *  `via` - The point through which the line passes.
+/// *  `to` - The end of the line.
+pub(crate) fn collinear(
+    from: physical::Pos,
+    via: physical::Pos,
+    to: physical::Pos,
+) -> bool {
+    let (d1, d2) = (via - from, to - via);
+    let cross = d1.x * d2.y - d1.y * d2.x;
+    let dot = d1.x * d2.x + d1.y * d2.y;
+    dot > 0.0 && cross.abs() <= TOLERANCE * (d1.value() * d2.value()).sqrt()
+}
+
+/// A hashable key for a position.
+///
+/// # Arguments
+/// *  `pos` - The position.
+pub(crate) fn key(pos: physical::Pos) -> (i64, i64) {
+    (
+        (pos.x * RESOLUTION + 0.5).floor() as i64,
+        (pos.y * RESOLUTION + 0.5).floor() as i64,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn total_wall_length(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Winding(initialize::Bias::default()),
+            &mut initialize::LFSR::new(12345),
+        );
+        let expected = maze
+            .merged_wall_segments()
+            .into_iter()
+            .map(|(from, to)| (to - from).value().sqrt())
+            .sum::<f32>();
+        assert!((maze.total_wall_length() - expected).abs() < 1e-2);
+    }
+
+    #[maze_test]
+    fn merged_wall_segments(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Winding(initialize::Bias::default()),
+            &mut initialize::LFSR::new(12345),
+        );
+        let segments = maze.wall_segments().collect::<Vec<_>>();
+        let merged = maze.merged_wall_segments();
+        assert!(merged.len() <= segments.len());
+
+        // Walls of hex rooms are never collinear
+        if maze.shape() != crate::Shape::Hex {
+            assert!(merged.len() < segments.len());
+        }
+
+        // Every wall lies on a merged segment
+        for (from, to) in segments {
+            let midpoint = (from + to) / 2.0;
+            assert!(merged.iter().any(|&(a, b)| {
+                [from, midpoint, to]
+                    .iter()
+                    .all(|&pos| on_segment(a, b, pos))
+            }));
+        }
+    }
+
+    #[test]
+    fn merge_collinear() {
+        let pos = |x, y| physical::Pos { x, y };
+        assert_eq!(
+            vec![(pos(0.0, 0.0), pos(3.0, 0.0))],
+            merge([
+                (pos(1.0, 0.0), pos(2.0, 0.0)),
+                (pos(1.0, 0.0), pos(0.0, 0.0)),
+                (pos(2.0, 0.0), pos(3.0, 0.0)),
+            ]),
+        );
+        assert_eq!(
+            2,
+            merge([
+                (pos(0.0, 0.0), pos(1.0, 0.0)),
+                (pos(1.0, 0.0), pos(1.0, 1.0)),
+            ])
+            .len(),
+        );
+    }
+
+    /// Whether a position lies on a segment.
+    fn on_segment(
+        a: physical::Pos,
+        b: physical::Pos,
+        pos: physical::Pos,
+    ) -> bool {
+        let (d, p) = (b - a, pos - a);
+        let t = (d.x * p.x + d.y * p.y) / d.value();
+        (-1e-3..=1.0 + 1e-3).contains(&t) && (a + d * t - pos).value() < 1e-6
+    }
+}
@@ -1,19 +1,74 @@
 use svg::node::element::path::{Command, Position};
+use svg::Node;
 
+use crate::multi::MultiMaze;
 use crate::Maze;
 use crate::WallPos;
 
 use crate::matrix;
 use crate::physical;
+use crate::shape::COS_30;
 use crate::wall;
 
 use crate::walk::*;
 
+use super::segments;
+use super::Color;
+
 pub trait ToPath {
     /// Generates an _SVG path d_ attribute value.
     fn to_path_d(&self) -> svg::node::element::path::Data;
 }
 
+pub trait ToOutline {
+    /// Generates an _SVG path d_ attribute value for the outline of an
+    /// object with a specific line width.
+    ///
+    /// The path should be filled using the _evenodd_ fill rule.
+    ///
+    /// # Arguments
+    /// *  `width` - The line width.
+    fn to_outline_d(&self, width: f32) -> svg::node::element::path::Data;
+}
+
+pub trait ToDiffPath {
+    /// Generates an _SVG path d_ attribute value for all walls whose open
+    /// state differs between this object and another.
+    ///
+    /// Drawn on top of a rendered maze, this highlights the differences.
+    ///
+    /// # Arguments
+    /// *  `other` - The object to compare with.
+    fn to_diff_path_d(&self, other: &Self) -> svg::node::element::path::Data;
+}
+
+pub trait ToSmoothPath {
+    /// Generates an _SVG path d_ attribute value for a smooth curve through
+    /// the centres of all rooms.
+    ///
+    /// # Arguments
+    /// *  `curvature` - The curvature of the line. A value of `0.0` yields
+    ///    straight lines and a value of `1.0` yields a _Catmull-Rom spline_.
+    fn to_smooth_path_d(
+        &self,
+        curvature: f32,
+    ) -> svg::node::element::path::Data;
+
+    /// Generates an _SVG path d_ attribute value for a smooth curve through
+    /// the centres of the walls between rooms.
+    ///
+    /// Unlike [`Self::to_smooth_path_d`], the curve passes through the doors
+    /// between rooms, so it will not cut corners.
+    ///
+    /// # Arguments
+    /// *  `curvature` - The curvature of the line. A value of `0.0` yields
+    ///    straight lines and a value of `1.0` yields a _Catmull-Rom spline_.
+    fn to_smooth_door_path_d(
+        &self,
+        curvature: f32,
+    ) -> svg::node::element::path::Data;
+}
+
 impl<T> ToPath for Maze<T>
 where
     T: Clone,
@@ -61,7 +116,7 @@ where
         }
 
         svg::node::element::path::Data::from(
-            commands
+            simplify(commands)
                 .into_iter()
                 .map(Into::into)
                 .collect::<Vec<Command>>(),
@@ -69,6 +124,57 @@ where
     }
 }
 
+impl<T> ToOutline for Maze<T>
+where
+    T: Clone,
+{
+    fn to_outline_d(&self, width: f32) -> svg::node::element::path::Data {
+        svg::node::element::path::Data::from(
+            self.outline(width)
+                .into_iter()
+                .flat_map(|polygon| {
+                    polygon
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, pos)| {
+                            if i == 0 {
+                                Operation::Move(pos).into()
+                            } else {
+                                Operation::Line(pos).into()
+                            }
+                        })
+                        .chain(core::iter::once(Command::Close))
+                })
+                .collect::<Vec<Command>>(),
+        )
+    }
+}
+
+impl<T> ToDiffPath for Maze<T>
+where
+    T: Clone,
+{
+    fn to_diff_path_d(&self, other: &Self) -> svg::node::element::path::Data {
+        let walls = self
+            .diff(other)
+            .into_iter()
+            .map(|wall_pos| self.corners(wall_pos));
+        svg::node::element::path::Data::from(
+            simplify(
+                segments::merge(walls)
+                    .into_iter()
+                    .flat_map(|(from, to)| {
+                        [Operation::Move(from), Operation::Line(to)]
+                    })
+                    .collect(),
+            )
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<Command>>(),
+        )
+    }
+}
+
 impl<'a, T> ToPath for Path<'a, T>
 where
     T: Clone,
@@ -90,6 +196,50 @@ where
     }
 }
 
+impl<'a, T> ToSmoothPath for Path<'a, T>
+where
+    T: Clone,
+{
+    fn to_smooth_path_d(
+        &self,
+        curvature: f32,
+    ) -> svg::node::element::path::Data {
+        smooth_path_d(
+            &self
+                .into_iter()
+                .map(|pos| self.maze.center(pos))
+                .collect::<Vec<_>>(),
+            curvature,
+        )
+    }
+
+    fn to_smooth_door_path_d(
+        &self,
+        curvature: f32,
+    ) -> svg::node::element::path::Data {
+        let rooms = self.into_iter().collect::<Vec<_>>();
+
+        // A path through a single room has no doors, and its first and last
+        // room are the same
+        if rooms.len() < 2 {
+            return self.to_smooth_path_d(curvature);
+        }
+
+        let doors = self.doors().map(|wall_pos| center(self.maze, wall_pos));
+
+        smooth_path_d(
+            &rooms
+                .first()
+                .map(|&pos| self.maze.center(pos))
+                .into_iter()
+                .chain(doors)
+                .chain(rooms.last().map(|&pos| self.maze.center(pos)))
+                .collect::<Vec<_>>(),
+            curvature,
+        )
+    }
+}
+
 /// A visitor for wall positions.
 ///
 /// This struct provides means to visit all wall positions of a maze.
@@ -188,13 +338,7 @@ where
                 row: (self.index / self.maze.width()) as isize,
             };
 
-            if self
-                .maze
-                .rooms
-                .get(pos)
-                .map(|room| room.visited)
-                .unwrap_or(false)
-            {
+            if self.maze.visited(pos) {
                 return Some(pos);
             } else {
                 self.index += 1;
@@ -205,6 +349,315 @@ where
     }
 }
 
+/// Generates an _SVG group_ with one filled path for every visited room of a
+/// maze.
+///
+/// # Example
+///
+/// ```
+/// # use maze::initialize;
+/// # use maze::render::svg::fill_rooms;
+/// # use maze::render::Color;
+/// # use maze::Shape;
+/// let maze = Shape::Hex.create::<()>(5, 5)
+///     .initialize(initialize::Method::Clear, &mut initialize::LFSR::new(12345));
+///
+/// let group = fill_rooms(&maze, |pos| Color {
+///     red: (pos.col * 50) as u8,
+///     green: (pos.row * 50) as u8,
+///     blue: 0,
+///     alpha: 255,
+/// });
+///
+/// assert_eq!(group.to_string().matches("<path").count(), 25);
+/// ```
+///
+/// # Arguments
+/// *  `maze` - The maze whose rooms to fill.
+/// *  `colors` - A function determining the colour of a room.
+pub fn fill_rooms<T, F>(maze: &Maze<T>, colors: F) -> svg::node::element::Group
+where
+    T: Clone,
+    F: Fn(matrix::Pos) -> Color,
+{
+    room_paths(maze, colors, |_, path| path)
+}
+
+/// Generates an _SVG group_ with one filled path for every visited room of a
+/// maze, like [`fill_rooms`], and identifies the rooms.
+///
+/// Every path is given an `id` on the form `room-<col>-<row>`, and the
+/// attributes `data-col` and `data-row`, which allows scripts to find the
+/// rooms of a rendered maze.
+///
+/// # Example
+///
+/// ```
+/// # use maze::initialize;
+/// # use maze::render::svg::fill_rooms_identified;
+/// # use maze::render::Color;
+/// # use maze::Shape;
+/// let maze = Shape::Hex.create::<()>(5, 5)
+///     .initialize(initialize::Method::Clear, &mut initialize::LFSR::new(12345));
+///
+/// let group = fill_rooms_identified(&maze, |_| Color::default());
+///
+/// assert!(group.to_string().contains(r#"id="room-2-3""#));
+/// ```
+///
+/// # Arguments
+/// *  `maze` - The maze whose rooms to fill.
+/// *  `colors` - A function determining the colour of a room.
+pub fn fill_rooms_identified<T, F>(
+    maze: &Maze<T>,
+    colors: F,
+) -> svg::node::element::Group
+where
+    T: Clone,
+    F: Fn(matrix::Pos) -> Color,
+{
+    room_paths(maze, colors, |pos, path| {
+        path.set("id", format!("room-{}-{}", pos.col, pos.row))
+            .set("data-col", pos.col)
+            .set("data-row", pos.row)
+    })
+}
+
+/// Generates an _SVG group_ with one filled path for every visited room of a
+/// maze.
+///
+/// # Arguments
+/// *  `maze` - The maze whose rooms to fill.
+/// *  `colors` - A function determining the colour of a room.
+/// *  `decorate` - A function applied to the path of every room before it is
+///    added to the group.
+fn room_paths<T, F, D>(
+    maze: &Maze<T>,
+    colors: F,
+    decorate: D,
+) -> svg::node::element::Group
+where
+    T: Clone,
+    F: Fn(matrix::Pos) -> Color,
+    D: Fn(matrix::Pos, svg::node::element::Path) -> svg::node::element::Path,
+{
+    let mut group = svg::node::element::Group::new();
+    for pos in maze.positions().filter(|&pos| maze.visited(pos)) {
+        let color = colors(pos);
+        let mut commands = maze
+            .shape()
+            .cell_polygon(pos)
+            .enumerate()
+            .map(|(i, coords)| {
+                if i == 0 {
+                    Command::Move(
+                        Position::Absolute,
+                        (coords.x, coords.y).into(),
+                    )
+                } else {
+                    Command::Line(
+                        Position::Absolute,
+                        (coords.x, coords.y).into(),
+                    )
+                }
+            })
+            .collect::<Vec<_>>();
+        commands.push(Command::Close);
+
+        group.append(decorate(
+            pos,
+            svg::node::element::Path::new()
+                .set("fill", color.to_string())
+                .set("fill-opacity", f32::from(color.alpha) / 255.0)
+                .set("d", svg::node::element::path::Data::from(commands)),
+        ));
+    }
+
+    group
+}
+
+/// The distance from the centre of a stair marker to its corners.
+const STAIR_MARKER_SIZE: f32 = 0.4;
+
+/// Generates an _SVG group_ with a marker for every stair of a level of a
+/// multi-level maze.
+///
+/// Stairs leading up are drawn as triangles pointing up, with the class
+/// `stair-up`, and stairs leading down as triangles pointing down, with the
+/// class `stair-down`. If the level does not exist, the group is empty.
+///
+/// # Arguments
+/// *  `maze` - The multi-level maze.
+/// *  `level` - The level whose stairs to mark.
+pub fn stair_markers<T>(
+    maze: &MultiMaze<T>,
+    level: usize,
+) -> svg::node::element::Group
+where
+    T: Clone,
+{
+    let mut group = svg::node::element::Group::new();
+    let Some(current) = maze.level(level) else {
+        return group;
+    };
+
+    let markers = maze.stairs().iter().flat_map(|stair| {
+        [
+            (stair.bottom(), "stair-up", -1.0),
+            (stair.top(), "stair-down", 1.0),
+        ]
+    });
+    for (pos, class, direction) in markers {
+        if pos.level != level {
+            continue;
+        }
+
+        let center = current.center(pos.pos);
+        let tip = STAIR_MARKER_SIZE * direction;
+        let base = -0.5 * tip;
+        let half_width = STAIR_MARKER_SIZE * COS_30;
+        group.append(
+            svg::node::element::Path::new().set("class", class).set(
+                "d",
+                svg::node::element::path::Data::new()
+                    .move_to((center.x, center.y + tip))
+                    .line_to((center.x + half_width, center.y + base))
+                    .line_to((center.x - half_width, center.y + base))
+                    .close(),
+            ),
+        );
+    }
+
+    group
+}
+
+/// Generates one _SVG document_ for every level of a multi-level maze.
+///
+/// Every document contains the walls of its level and the markers generated
+/// by [`stair_markers`].
+///
+/// # Example
+///
+/// ```
+/// # use maze::initialize::*;
+/// # use maze::multi::MultiMaze;
+/// # use maze::render::svg::level_documents;
+/// # use maze::Shape;
+/// let level = |seed| {
+///     Shape::Hex
+///         .create::<()>(5, 5)
+///         .initialize(Method::default(), &mut LFSR::new(seed))
+/// };
+/// let mut maze = MultiMaze::new(vec![level(1), level(2)]);
+/// maze.add_stair(0, (2isize, 2isize).into(), (2isize, 2isize).into())
+///     .unwrap();
+///
+/// let documents = level_documents(&maze);
+/// assert_eq!(documents.len(), 2);
+/// assert!(documents[0].to_string().contains("stair-up"));
+/// assert!(documents[1].to_string().contains("stair-down"));
+/// ```
+///
+/// # Arguments
+/// *  `maze` - The multi-level maze.
+pub fn level_documents<T>(maze: &MultiMaze<T>) -> Vec<svg::Document>
+where
+    T: Clone,
+{
+    maze.levels()
+        .iter()
+        .enumerate()
+        .map(|(level, current)| {
+            svg::Document::new()
+                .set("viewBox", current.viewbox().tuple())
+                .add(
+                    svg::node::element::Path::new()
+                        .set("fill", "none")
+                        .set("stroke", "black")
+                        .set("stroke-linecap", "round")
+                        .set("stroke-linejoin", "round")
+                        .set("stroke-width", 0.4)
+                        .set("vector-effect", "non-scaling-stroke")
+                        .set("d", current.to_path_d()),
+                )
+                .add(stair_markers(maze, level).set("fill", "black"))
+        })
+        .collect()
+}
+
+/// Simplifies a list of move and line operations.
+///
+/// Subpaths continuing where the previous subpath starts or ends are joined
+/// with it, and consecutive lines in the same direction are merged. This
+/// greatly reduces the size of the generated path for mazes with long,
+/// straight walls.
+///
+/// # Arguments
+/// *  `commands` - The operations to simplify.
+fn simplify(commands: Vec<Operation>) -> Vec<Operation> {
+    let mut subpaths: Vec<Vec<physical::Pos>> = Vec::new();
+    for operation in commands {
+        match (operation, subpaths.last_mut()) {
+            (Operation::Line(pos), Some(subpath)) => subpath.push(pos),
+            (operation, _) => subpaths.push(vec![operation.pos()]),
+        }
+    }
+
+    // Join every subpath with the previous one if they share an end point
+    let mut joined: Vec<Vec<physical::Pos>> = Vec::new();
+    for mut subpath in subpaths {
+        if let Some(previous) = joined.last_mut() {
+            let same = |a, b| segments::key(a) == segments::key(b);
+            let (first, last) = (subpath[0], subpath[subpath.len() - 1]);
+            let (start, end) = (previous[0], previous[previous.len() - 1]);
+            let join = if same(first, end) {
+                true
+            } else if same(last, end) {
+                subpath.reverse();
+                true
+            } else if same(last, start) {
+                previous.reverse();
+                subpath.reverse();
+                true
+            } else if same(first, start) {
+                previous.reverse();
+                true
+            } else {
+                false
+            };
+            if join {
+                previous.extend(subpath.into_iter().skip(1));
+                continue;
+            }
+        }
+        joined.push(subpath);
+    }
+
+    joined
+        .into_iter()
+        .flat_map(|subpath| {
+            // Keep only points where the direction changes
+            let mut points: Vec<physical::Pos> = Vec::new();
+            for pos in subpath {
+                match points.as_slice() {
+                    [.., a, b] if segments::collinear(*a, *b, pos) => {
+                        *points.last_mut().unwrap() = pos;
+                    }
+                    _ => points.push(pos),
+                }
+            }
+
+            points.into_iter().enumerate().map(|(i, pos)| {
+                if i == 0 {
+                    Operation::Move(pos)
+                } else {
+                    Operation::Line(pos)
+                }
+            })
+        })
+        .collect()
+}
+
 /// A line drawing operation.
 enum Operation {
     /// Move the current position without drawing a line.
@@ -212,13 +665,19 @@ enum Operation {
 
     /// Draw a line from the old position to this position.
     Line(physical::Pos),
+
+    /// Draw a cubic Bézier curve from the old position to the last position,
+    /// using the first two positions as control points.
+    Curve(physical::Pos, physical::Pos, physical::Pos),
 }
 
 impl Operation {
     /// Extracts the position from this operation regardless of type.
     fn pos(&self) -> physical::Pos {
         match *self {
-            Operation::Move(pos) | Operation::Line(pos) => pos,
+            Operation::Move(pos)
+            | Operation::Line(pos)
+            | Operation::Curve(_, _, pos) => pos,
         }
     }
 }
@@ -233,10 +692,54 @@ impl From<Operation> for Command {
             Operation::Line(pos) => {
                 Command::Line(Position::Absolute, (pos.x, pos.y).into())
             }
+            Operation::Curve(control1, control2, pos) => Command::CubicCurve(
+                Position::Absolute,
+                (control1.x, control1.y, control2.x, control2.y, pos.x, pos.y)
+                    .into(),
+            ),
         }
     }
 }
 
+/// Generates an _SVG path d_ attribute value for a smooth curve through a
+/// sequence of points.
+///
+/// The curve is a _Catmull-Rom spline_ converted to cubic Bézier curves, with
+/// the tangents scaled by `curvature`.
+///
+/// # Arguments
+/// *  `points` - The points through which to draw the curve.
+/// *  `curvature` - The curvature of the line.
+fn smooth_path_d(
+    points: &[physical::Pos],
+    curvature: f32,
+) -> svg::node::element::path::Data {
+    let factor = curvature / 6.0;
+    let last = points.len().saturating_sub(1);
+
+    svg::node::element::path::Data::from(
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, &pos)| {
+                if i == 0 {
+                    Operation::Move(pos).into()
+                } else {
+                    let p0 = points[i.saturating_sub(2)];
+                    let p1 = points[i - 1];
+                    let p3 = points[(i + 1).min(last)];
+                    Operation::Curve(
+                        p1 + (pos - p0) * factor,
+                        pos - (p3 - p1) * factor,
+                        pos,
+                    )
+                    .into()
+                }
+            })
+            .collect::<Vec<Command>>(),
+    )
+}
+
 /// Returns the center of a wall.
 ///
 /// The center of a wall is the point between its corners.
@@ -277,3 +780,244 @@ where
         (pos2, pos1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn stair_markers_levels(maze: TestMaze) {
+        let mut maze = MultiMaze::new(vec![maze.clone(), maze.clone(), maze]);
+        maze.add_stair(0, matrix_pos(0, 0), matrix_pos(1, 1))
+            .unwrap();
+        maze.add_stair(1, matrix_pos(2, 2), matrix_pos(2, 2))
+            .unwrap();
+
+        let count = |level, class| {
+            stair_markers(&maze, level)
+                .to_string()
+                .matches(&format!(r#"class="{}""#, class))
+                .count()
+        };
+        assert_eq!((1, 0), (count(0, "stair-up"), count(0, "stair-down")));
+        assert_eq!((1, 1), (count(1, "stair-up"), count(1, "stair-down")));
+        assert_eq!((0, 1), (count(2, "stair-up"), count(2, "stair-down")));
+        assert_eq!(0, stair_markers(&maze, 3).get_children().len());
+        assert_eq!(3, level_documents(&maze).len());
+    }
+
+    #[maze_test]
+    fn smooth_path_d_passes_rooms(mut maze: TestMaze) {
+        let log = Navigator::new(&mut maze).down(true).right(true).stop();
+        let path = maze.walk(log[0], *log.last().unwrap()).unwrap();
+        let data = path.to_smooth_path_d(1.0);
+
+        assert_eq!(data.len(), path.into_iter().count());
+        assert!(matches!(data[0], Command::Move(..)));
+        assert!(data
+            .iter()
+            .skip(1)
+            .all(|command| matches!(command, Command::CubicCurve(..))));
+    }
+
+    #[maze_test]
+    fn smooth_door_path_d_passes_doors(mut maze: TestMaze) {
+        let log = Navigator::new(&mut maze).down(true).right(true).stop();
+        let path = maze.walk(log[0], *log.last().unwrap()).unwrap();
+        let data = path.to_smooth_door_path_d(1.0);
+
+        assert_eq!(data.len(), path.into_iter().count() + 1);
+        for (command, wall_pos) in data.iter().skip(1).zip(
+            log.windows(2)
+                .map(|pair| maze.connecting_wall(pair[0], pair[1]).unwrap()),
+        ) {
+            if let Command::CubicCurve(_, parameters) = command {
+                let expected = center(&maze, wall_pos);
+                assert!(is_close(
+                    expected,
+                    physical::Pos {
+                        x: parameters[4],
+                        y: parameters[5],
+                    },
+                ));
+            } else {
+                panic!("unexpected command {:?}", command);
+            }
+        }
+    }
+
+    #[maze_test]
+    fn smooth_door_path_d_single_room(maze: TestMaze) {
+        let pos = matrix_pos(0, 0);
+        let path = maze.walk(pos, pos).unwrap();
+        let data = path.to_smooth_door_path_d(1.0);
+
+        assert_eq!(1, data.len());
+        if let Command::Move(_, parameters) = &data[0] {
+            let expected = maze.center(pos);
+            assert!(is_close(
+                expected,
+                physical::Pos {
+                    x: parameters[0],
+                    y: parameters[1],
+                },
+            ));
+        } else {
+            panic!("unexpected command {:?}", data[0]);
+        }
+    }
+
+    #[test]
+    fn simplify_joins_and_merges() {
+        let pos = |x, y| physical::Pos { x, y };
+        let simplified = simplify(vec![
+            Operation::Move(pos(0.0, 0.0)),
+            Operation::Line(pos(1.0, 0.0)),
+            Operation::Move(pos(2.0, 0.0)),
+            Operation::Line(pos(1.0, 0.0)),
+            Operation::Move(pos(2.0, 0.0)),
+            Operation::Line(pos(2.0, 1.0)),
+            Operation::Move(pos(5.0, 5.0)),
+            Operation::Line(pos(6.0, 5.0)),
+        ]);
+
+        assert_eq!(
+            vec![
+                (true, pos(0.0, 0.0)),
+                (false, pos(2.0, 0.0)),
+                (false, pos(2.0, 1.0)),
+                (true, pos(5.0, 5.0)),
+                (false, pos(6.0, 5.0)),
+            ],
+            simplified
+                .iter()
+                .map(|operation| {
+                    (matches!(operation, Operation::Move(..)), operation.pos())
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[maze_test]
+    fn to_diff_path_d(maze: TestMaze) {
+        assert!(maze.to_diff_path_d(&maze).is_empty());
+
+        let mut other = maze.clone();
+        let pos = matrix_pos(1, 1);
+        for wall in maze.walls(pos) {
+            other.open((pos, wall));
+        }
+        let d = maze.to_diff_path_d(&other);
+        assert!(!d.is_empty());
+        assert!(d.iter().all(|command| matches!(
+            command,
+            Command::Move(..) | Command::Line(..)
+        )));
+    }
+
+    #[maze_test]
+    fn to_path_d_covers_walls(maze: TestMaze) {
+        let maze = maze.initialize(
+            crate::initialize::Method::Winding(
+                crate::initialize::Bias::default(),
+            ),
+            &mut crate::initialize::LFSR::new(12345),
+        );
+        let data = maze.to_path_d();
+        let mut lines = Vec::new();
+        let mut current = physical::Pos::default();
+        for command in data.iter() {
+            match command {
+                Command::Move(_, parameters) => {
+                    current = (parameters[0], parameters[1]).into();
+                }
+                Command::Line(_, parameters) => {
+                    let next = (parameters[0], parameters[1]).into();
+                    lines.push((current, next));
+                    current = next;
+                }
+                _ => panic!("unexpected command {:?}", command),
+            }
+        }
+
+        // The midpoint of every wall is on a line
+        for (from, to) in maze.wall_segments() {
+            let midpoint = (from + to) / 2.0;
+            assert!(lines.iter().any(|&(a, b)| {
+                let (d, p) = (b - a, midpoint - a);
+                let t = (d.x * p.x + d.y * p.y) / d.value();
+                (0.0..=1.0).contains(&t) && is_close(a + d * t, midpoint)
+            }));
+        }
+    }
+
+    #[maze_test]
+    fn fill_rooms_visited(maze: TestMaze) {
+        let mut maze = maze;
+        let visited = [
+            matrix::Pos { col: 0, row: 0 },
+            matrix::Pos { col: 2, row: 1 },
+        ];
+        for pos in visited {
+            maze.rooms[pos].visited = true;
+        }
+        let color = |pos: matrix::Pos| Color {
+            red: pos.col as u8,
+            green: pos.row as u8,
+            blue: 0,
+            alpha: 255,
+        };
+
+        let data = fill_rooms(&maze, color).to_string();
+        let filled = svg::read(&data)
+            .unwrap()
+            .filter_map(|event| match event {
+                svg::parser::Event::Tag("path", _, attributes) => {
+                    assert!(!attributes.contains_key("data-col"));
+                    Some(attributes["fill"].to_string())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            filled,
+            visited
+                .iter()
+                .map(|&pos| color(pos).to_string())
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[maze_test]
+    fn fill_rooms_identified(maze: TestMaze) {
+        let data = super::fill_rooms_identified(&maze, |_| Color::default())
+            .to_string();
+        let identified = svg::read(&data)
+            .unwrap()
+            .filter_map(|event| match event {
+                svg::parser::Event::Tag("path", _, attributes) => Some((
+                    attributes["id"].to_string(),
+                    attributes["data-col"].parse::<isize>().unwrap(),
+                    attributes["data-row"].parse::<isize>().unwrap(),
+                )),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            identified,
+            maze.positions()
+                .filter(|&pos| maze.visited(pos))
+                .map(|pos| (
+                    format!("room-{}-{}", pos.col, pos.row),
+                    pos.col,
+                    pos.row,
+                ))
+                .collect::<Vec<_>>(),
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! # TikZ rendering
+//!
+//! A maze can be included in a _LaTeX_ document by rendering it as a
+//! `tikzpicture` environment, which requires the `tikz` package.
+//!
+//! One unit in the picture corresponds to the distance from the centre of a
+//! room to its corners. Scale the picture using the `scale` option of the
+//! environment or a surrounding `scope`.
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::physical;
+use crate::walk::Path;
+use crate::Maze;
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// Generates a `tikzpicture` environment drawing the walls of this maze,
+    /// and optionally a path through it.
+    ///
+    /// The Y axis of _TikZ_ points upwards, so the picture is flipped
+    /// vertically to match other renderers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::initialize;
+    /// # use maze::Shape;
+    /// let maze = Shape::Quad.create::<()>(5, 5).initialize(
+    ///     initialize::Method::Winding(initialize::Bias::default()),
+    ///     &mut initialize::LFSR::new(12345),
+    /// );
+    /// let solution = maze
+    ///     .walk((0isize, 0isize).into(), (4isize, 4isize).into())
+    ///     .unwrap();
+    /// let tikz = maze.to_tikz(Some(&solution));
+    ///
+    /// assert!(tikz.starts_with(r"\begin{tikzpicture}"));
+    /// assert!(tikz.ends_with("\\end{tikzpicture}\n"));
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `solution` - A path to draw in red on top of the walls.
+    pub fn to_tikz(&self, solution: Option<&Path<'_, T>>) -> String {
+        let point =
+            |pos: physical::Pos| alloc::format!("({}, {})", pos.x, pos.y);
+
+        let mut tikz = String::from(
+            "\\begin{tikzpicture}[yscale=-1, line cap=round, \
+             line join=round]\n",
+        );
+        for (from, to) in self.merged_wall_segments() {
+            let _ =
+                writeln!(tikz, "  \\draw {} -- {};", point(from), point(to));
+        }
+
+        if let Some(solution) = solution {
+            let mut rooms = solution.into_iter().map(|pos| self.center(pos));
+            if let Some(first) = rooms.next() {
+                let _ = write!(tikz, "  \\draw[red] {}", point(first));
+                for pos in rooms {
+                    let _ = write!(tikz, " -- {}", point(pos));
+                }
+                tikz.push_str(";\n");
+            }
+        }
+
+        tikz.push_str("\\end{tikzpicture}\n");
+        tikz
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use crate::initialize;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn to_tikz(maze: TestMaze) {
+        let tikz = maze.to_tikz(None);
+        assert_eq!(
+            tikz.matches("\\draw").count(),
+            maze.merged_wall_segments().len(),
+        );
+        assert!(!tikz.contains("\\draw[red]"));
+    }
+
+    #[maze_test]
+    fn to_tikz_solution(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Winding(initialize::Bias::default()),
+            &mut initialize::LFSR::new(12345),
+        );
+        let from = matrix_pos(0, 0);
+        let to = matrix_pos(maze.width() as isize - 1, 0);
+        let solution = maze.walk(from, to).unwrap();
+        let tikz = maze.to_tikz(Some(&solution));
+
+        let line = tikz.lines().find(|line| line.contains("\\draw[red]"));
+        assert_eq!(
+            line.unwrap().matches(" -- ").count() + 1,
+            solution.into_iter().count(),
+        );
+    }
+}
@@ -91,6 +91,11 @@ where
         self.walls &= !wall.mask();
     }
 
+    /// The bit mask of open walls.
+    pub(crate) fn mask(&self) -> wall::Mask {
+        self.walls
+    }
+
     /// Returns the number of open walls.
     pub fn open_walls(&self) -> usize {
         self.walls.count_ones() as usize
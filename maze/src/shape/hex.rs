@@ -1,5 +1,10 @@
-use std::f32::consts::PI;
+use core::f32::consts::PI;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
 use crate::matrix;
 use crate::physical;
 use crate::wall;
@@ -320,6 +325,7 @@ pub fn minimal_dimensions(width: f32, height: f32) -> (usize, usize) {
     (width, height)
 }
 
+#[inline]
 pub fn back_index(wall: usize) -> usize {
     wall ^ 0b0001
 }
@@ -337,6 +343,7 @@ pub fn opposite(wall_pos: WallPos) -> Option<&'static wall::Wall> {
     )
 }
 
+#[inline]
 pub fn walls(pos: matrix::Pos) -> &'static [&'static wall::Wall] {
     if pos.row & 1 == 1 {
         WALLS_ODD
@@ -345,6 +352,119 @@ pub fn walls(pos: matrix::Pos) -> &'static [&'static wall::Wall] {
     }
 }
 
+/// A room position in axial coordinates.
+///
+/// Rooms are stored in _offset coordinates_, where every even row is shifted
+/// half a room to the right. Axial coordinates instead use a column axis, `q`,
+/// that is skewed to follow the diagonal down to the right, which makes
+/// distances and rotations simple to calculate.
+///
+/// The row axis, `r`, is the same as the matrix row, and `(0, 0)` maps to the
+/// matrix position `(0, 0)`. This corresponds to the _even-r_ layout commonly
+/// used for pointy-top hex grids.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Axial {
+    /// The skewed column coordinate.
+    pub q: isize,
+
+    /// The row coordinate.
+    pub r: isize,
+}
+
+impl Axial {
+    /// The implicit third cube coordinate.
+    ///
+    /// The sum of `q`, `r` and `s` is always zero.
+    pub fn s(self) -> isize {
+        -self.q - self.r
+    }
+
+    /// The number of steps between two rooms, ignoring walls.
+    ///
+    /// # Arguments
+    /// *  `other` - The other position.
+    pub fn distance(self, other: Self) -> usize {
+        let (dq, dr) = (self.q - other.q, self.r - other.r);
+        (dq.unsigned_abs() + dr.unsigned_abs() + (dq + dr).unsigned_abs()) / 2
+    }
+
+    /// Rotates this position around a centre.
+    ///
+    /// A positive number of steps rotates clockwise, as seen when the maze is
+    /// rendered, and a negative number rotates counter-clockwise. Every step
+    /// is 60°.
+    ///
+    /// # Arguments
+    /// *  `center` - The centre of rotation.
+    /// *  `steps` - The number of steps to rotate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// # use maze::shape::hex::{from_axial, to_axial};
+    /// let center = to_axial(matrix::Pos { col: 2, row: 2 });
+    /// let pos = to_axial(matrix::Pos { col: 3, row: 2 });
+    ///
+    /// assert_eq!(
+    ///     from_axial(pos.rotate(center, 1)),
+    ///     matrix::Pos { col: 3, row: 3 },
+    /// );
+    /// assert_eq!(pos.rotate(center, 6), pos);
+    /// assert_eq!(pos.rotate(center, -1), pos.rotate(center, 5));
+    /// ```
+    pub fn rotate(self, center: Self, steps: isize) -> Self {
+        let (mut q, mut r) = (self.q - center.q, self.r - center.r);
+        for _ in 0..steps.rem_euclid(6) {
+            (q, r) = (-r, q + r);
+        }
+        Self {
+            q: q + center.q,
+            r: r + center.r,
+        }
+    }
+}
+
+/// Converts a matrix position to axial coordinates.
+///
+/// # Arguments
+/// *  `pos` - The matrix position.
+///
+/// # Example
+///
+/// ```
+/// # use maze::matrix;
+/// # use maze::shape::hex::{from_axial, to_axial, Axial};
+/// let pos = matrix::Pos { col: 3, row: 5 };
+///
+/// assert_eq!(to_axial(pos), Axial { q: 0, r: 5 });
+/// assert_eq!(from_axial(to_axial(pos)), pos);
+/// ```
+pub fn to_axial(pos: matrix::Pos) -> Axial {
+    Axial {
+        q: pos.col - (pos.row + 1).div_euclid(2),
+        r: pos.row,
+    }
+}
+
+/// Converts axial coordinates to a matrix position.
+///
+/// # Arguments
+/// *  `axial` - The axial coordinates.
+pub fn from_axial(axial: Axial) -> matrix::Pos {
+    matrix::Pos {
+        col: axial.q + (axial.r + 1).div_euclid(2),
+        row: axial.r,
+    }
+}
+
+#[inline]
+pub fn graph_distance_lower_bound(a: matrix::Pos, b: matrix::Pos) -> usize {
+    to_axial(a).distance(to_axial(b))
+}
+
+#[inline]
 pub fn cell_to_physical(pos: matrix::Pos) -> physical::Pos {
     physical::Pos {
         x: (pos.col as f32 + if pos.row & 1 == 1 { 0.5 } else { 1.0 })
@@ -353,6 +473,7 @@ pub fn cell_to_physical(pos: matrix::Pos) -> physical::Pos {
     }
 }
 
+#[inline]
 pub fn physical_to_cell(pos: physical::Pos) -> matrix::Pos {
     // Calculate approximations of the room position
     let (i, f) = matrix::partition(pos.y / VERTICAL_MULTIPLICATOR);
@@ -395,6 +516,7 @@ pub fn physical_to_cell(pos: physical::Pos) -> matrix::Pos {
 }
 
 #[allow(clippy::collapsible_else_if)]
+#[inline]
 pub fn physical_to_wall_pos(pos: physical::Pos) -> WallPos {
     let matrix_pos = physical_to_cell(pos);
     let odd_row = matrix_pos.row & 1 == 1;
@@ -428,7 +550,7 @@ pub fn physical_to_wall_pos(pos: physical::Pos) -> WallPos {
 mod tests {
     use maze_test::maze_test;
 
-    use super::walls;
+    use super::*;
     use crate::test_utils::*;
     use crate::WallPos;
 
@@ -714,4 +836,37 @@ mod tests {
                 .collect::<Vec<WallPos>>()
         );
     }
+
+    #[maze_test(hex)]
+    fn axial_round_trip(maze: TestMaze) {
+        for pos in maze.positions() {
+            assert_eq!(from_axial(to_axial(pos)), pos);
+        }
+    }
+
+    #[maze_test(hex)]
+    fn axial_neighbors(maze: TestMaze) {
+        for pos in maze.positions() {
+            let axial = to_axial(pos);
+            assert_eq!(axial.q + axial.r + axial.s(), 0);
+            for neighbor in maze.adjacent(pos) {
+                assert_eq!(axial.distance(to_axial(neighbor)), 1);
+            }
+        }
+    }
+
+    #[maze_test(hex)]
+    fn axial_rotate(maze: TestMaze) {
+        let center = to_axial(matrix_pos(2, 2));
+        let neighbors = maze
+            .adjacent(matrix_pos(2, 2))
+            .map(to_axial)
+            .collect::<Vec<_>>();
+        for &neighbor in &neighbors {
+            let rotated = neighbor.rotate(center, 1);
+            assert_ne!(rotated, neighbor);
+            assert!(neighbors.contains(&rotated));
+            assert_eq!(rotated.rotate(center, -1), neighbor);
+        }
+    }
 }
@@ -1,4 +1,7 @@
-use std::f32::consts::SQRT_2;
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::f32::consts::SQRT_2;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -9,8 +12,15 @@ use crate::wall;
 
 use crate::{Maze, WallPos};
 
+/// The distance beyond the ends of a wall, relative to its length, at which a
+/// path is still considered crossing it by [`Maze::paint_path`].
+///
+/// This prevents paths passing exactly through corners from escaping rooms
+/// because of rounding errors.
+const INTERSECTION_TOLERANCE: f32 = 1e-4;
+
 /// cos(30°)
-const COS_30: f32 = 0.866_025_4f32;
+pub(crate) const COS_30: f32 = 0.866_025_4f32;
 
 /// sin(30°)
 const SIN_30: f32 = 1.0 / 2.0;
@@ -21,6 +31,13 @@ const COS_45: f32 = 0.5 * SQRT_2;
 /// sin(45°)
 const SIN_45: f32 = 0.5 * SQRT_2;
 
+/// The distance outside of a room, relative to the distance between its
+/// centre and its walls, still considered inside by [`Shape::contains`].
+///
+/// This prevents positions exactly on a shared edge from being considered
+/// outside of both rooms because of rounding errors.
+const CONTAINS_TOLERANCE: f32 = 1e-5;
+
 /// The different types of mazes implemented, identified by number of walls.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, PartialOrd)]
 #[cfg_attr(
@@ -121,6 +138,58 @@ impl Shape {
         dispatch!(self => walls(pos))
     }
 
+    /// The number of steps between two rooms if no walls were closed.
+    ///
+    /// This is the length of the shortest path between the rooms in a maze
+    /// without walls, and thus a lower bound for the length of a path in any
+    /// maze of this shape.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// # use maze::Shape;
+    /// let (a, b) = (
+    ///     matrix::Pos { col: 0, row: 0 },
+    ///     matrix::Pos { col: 2, row: 2 },
+    /// );
+    /// assert_eq!(Shape::Quad.graph_distance_lower_bound(a, b), 4);
+    /// assert_eq!(Shape::Hex.graph_distance_lower_bound(a, b), 3);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `a` - One room position.
+    /// *  `b` - The other room position.
+    pub fn graph_distance_lower_bound(
+        self,
+        a: matrix::Pos,
+        b: matrix::Pos,
+    ) -> usize {
+        dispatch!(self => graph_distance_lower_bound(a, b))
+    }
+
+    /// The direction of a step between two adjacent rooms.
+    ///
+    /// Unlike the difference between the matrix positions, this is the same
+    /// for all steps in the same physical direction, regardless of the row.
+    ///
+    /// # Arguments
+    /// *  `from` - The room being left.
+    /// *  `to` - The room being entered.
+    pub(crate) fn step_direction(
+        self,
+        from: matrix::Pos,
+        to: matrix::Pos,
+    ) -> (isize, isize) {
+        if self == Shape::Hex {
+            // Offset coordinates depend on the row, so compare axial ones
+            let (from, to) = (hex::to_axial(from), hex::to_axial(to));
+            (to.q - from.q, to.r - from.r)
+        } else {
+            (to.col - from.col, to.row - from.row)
+        }
+    }
+
     /// Converts a physical position to a matrix cell.
     ///
     /// # Arguments
@@ -145,6 +214,211 @@ impl Shape {
         dispatch!(self => cell_to_physical(pos))
     }
 
+    /// Returns the corners of a matrix cell.
+    ///
+    /// The corners are returned in the same order as the walls of the cell,
+    /// and every corner is the one where the corresponding wall begins. The
+    /// outline of the cell is the closed polygon through these points.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// # use maze::Shape;
+    /// let pos = matrix::Pos { col: 1, row: 1 };
+    ///
+    /// assert_eq!(Shape::Hex.cell_polygon(pos).count(), 6);
+    /// assert_eq!(Shape::Quad.cell_polygon(pos).count(), 4);
+    /// assert_eq!(Shape::Tri.cell_polygon(pos).count(), 3);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `pos` - The matrix position.
+    pub fn cell_polygon(
+        self,
+        pos: matrix::Pos,
+    ) -> impl Iterator<Item = physical::Pos> {
+        let center = self.cell_to_physical(pos);
+        self.walls(pos).iter().map(move |wall| center + wall.span.0)
+    }
+
+    /// The area of a single room.
+    ///
+    /// All rooms of a shape have the same area. The unit of length is the
+    /// distance from the centre of a room to its corners.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::Shape;
+    /// assert!((Shape::Quad.cell_area() - 2.0).abs() < 1e-5);
+    /// assert!((Shape::Hex.cell_area() - 2.598076).abs() < 1e-5);
+    /// assert!((Shape::Tri.cell_area() - 1.299038).abs() < 1e-5);
+    /// ```
+    pub fn cell_area(self) -> f32 {
+        let corners = self
+            .cell_polygon(matrix::Pos { col: 0, row: 0 })
+            .collect::<Vec<_>>();
+
+        // Use the shoelace formula
+        corners
+            .iter()
+            .zip(corners.iter().cycle().skip(1))
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum::<f32>()
+            .abs()
+            / 2.0
+    }
+
+    /// The distance a corner of a room moves towards or away from the centre
+    /// when the walls of the room are moved by half of a thickness.
+    ///
+    /// Moving a corner this far along the line through the centre of the
+    /// room keeps it on both of the offset walls meeting there, so the faces
+    /// of thick walls meet without gaps or overlaps.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::Shape;
+    /// assert!((Shape::Tri.corner_offset(0.2) - 0.2).abs() < 1e-5);
+    /// assert!((Shape::Quad.corner_offset(0.2) - 0.141421).abs() < 1e-5);
+    /// assert!((Shape::Hex.corner_offset(0.2) - 0.115470).abs() < 1e-5);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `thickness` - The thickness of the walls.
+    pub fn corner_offset(self, thickness: f32) -> f32 {
+        // The distance from the centre to a wall, relative to the distance
+        // to a corner
+        let apothem = match self {
+            Shape::Tri => SIN_30,
+            Shape::Quad => COS_45,
+            Shape::Hex => COS_30,
+        };
+        0.5 * thickness / apothem
+    }
+
+    /// Whether a physical position is inside of a matrix cell.
+    ///
+    /// Unlike [`physical_to_cell`](Self::physical_to_cell), which finds the
+    /// cell with the closest centre, this checks the actual outline of the
+    /// cell. Positions on the edge are considered to be inside.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// # use maze::physical;
+    /// # use maze::Shape;
+    /// let pos = matrix::Pos { col: 1, row: 0 };
+    /// let center = Shape::Tri.cell_to_physical(pos);
+    ///
+    /// assert!(Shape::Tri.contains(pos, center));
+    /// assert!(!Shape::Tri.contains(
+    ///     pos,
+    ///     center + physical::Pos { x: 0.0, y: -1.5 },
+    /// ));
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `pos` - The matrix cell.
+    /// *  `physical_pos` - The physical position.
+    pub fn contains(
+        self,
+        pos: matrix::Pos,
+        physical_pos: physical::Pos,
+    ) -> bool {
+        let d = physical_pos - self.cell_to_physical(pos);
+        self.walls(pos).iter().all(|wall| {
+            let (a, b) = (
+                physical::Pos {
+                    x: wall.span.0.dx,
+                    y: wall.span.0.dy,
+                },
+                physical::Pos {
+                    x: wall.span.1.dx,
+                    y: wall.span.1.dy,
+                },
+            );
+
+            // The position relative to the wall, scaled so that the centre is
+            // at 1 and the wall at 0
+            let edge = b - a;
+            let cross = |p: physical::Pos| edge.x * p.y - edge.y * p.x;
+            cross(d - a) / cross(a * -1.0) >= -CONTAINS_TOLERANCE
+        })
+    }
+
+    /// Whether a matrix cell and a rectangle overlap.
+    ///
+    /// Cells merely touching the edge of the rectangle are considered
+    /// overlapping.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// # use maze::physical;
+    /// # use maze::Shape;
+    /// let pos = matrix::Pos { col: 1, row: 0 };
+    /// let center = Shape::Hex.cell_to_physical(pos);
+    ///
+    /// assert!(Shape::Hex.touches(
+    ///     pos,
+    ///     physical::ViewBox::centered_at(center, 0.1, 0.1),
+    /// ));
+    /// assert!(!Shape::Hex.touches(
+    ///     pos,
+    ///     physical::ViewBox::centered_at(
+    ///         center + physical::Pos { x: 0.0, y: -2.0 },
+    ///         0.1,
+    ///         0.1,
+    ///     ),
+    /// ));
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `pos` - The matrix cell.
+    /// *  `viewbox` - The rectangle.
+    pub fn touches(self, pos: matrix::Pos, viewbox: physical::ViewBox) -> bool {
+        let polygon = self.cell_polygon(pos).collect::<Vec<_>>();
+        let corners = [
+            (0.0, 0.0),
+            (viewbox.width, 0.0),
+            (viewbox.width, viewbox.height),
+            (0.0, viewbox.height),
+        ]
+        .map(|(dx, dy)| viewbox.corner + physical::Pos { x: dx, y: dy });
+
+        // Since cells are convex, they overlap the rectangle unless an axis
+        // of the rectangle or an edge normal of the cell separates them
+        let axes = [
+            physical::Pos { x: 1.0, y: 0.0 },
+            physical::Pos { x: 0.0, y: 1.0 },
+        ]
+        .into_iter()
+        .chain(polygon.iter().zip(polygon.iter().cycle().skip(1)).map(
+            |(&a, &b)| physical::Pos {
+                x: a.y - b.y,
+                y: b.x - a.x,
+            },
+        ));
+        let project = |points: &[physical::Pos], axis: physical::Pos| {
+            points
+                .iter()
+                .map(|p| p.x * axis.x + p.y * axis.y)
+                .fold((f32::MAX, f32::MIN), |(min, max), v| {
+                    (min.min(v), max.max(v))
+                })
+        };
+
+        axes.into_iter().all(|axis| {
+            let (a, b) = (project(&polygon, axis), project(&corners, axis));
+            a.0 <= b.1 && b.0 <= a.1
+        })
+    }
+
     /// Calculates the _view box_ for a maze with this shape when rendered.
     ///
     /// The returned value is the minimal rectangle that will contain a maze
@@ -160,31 +434,20 @@ impl Shape {
                 col: 0,
                 row: y as isize,
             };
-            let lcenter = self.cell_to_physical(lpos);
-            let left = dispatch!(self => walls(lpos))
-                .iter()
-                .map(|wall| (lcenter, wall));
-
             let rpos = matrix::Pos {
                 col: cols as isize - 1,
                 row: y as isize,
             };
-            let rcenter = self.cell_to_physical(rpos);
-            let right = dispatch!(self => walls(rpos))
-                .iter()
-                .map(|wall| (rcenter, wall));
 
-            window = left
-                .chain(right)
-                .map(|(center, wall)| {
-                    (center.x + wall.span.0.dx, center.y + wall.span.0.dy)
-                })
+            window = self
+                .cell_polygon(lpos)
+                .chain(self.cell_polygon(rpos))
                 .fold(window, |acc, v| {
                     (
-                        acc.0.min(v.0),
-                        acc.1.min(v.1),
-                        acc.2.max(v.0),
-                        acc.3.max(v.1),
+                        acc.0.min(v.x),
+                        acc.1.min(v.y),
+                        acc.2.max(v.x),
+                        acc.3.max(v.y),
                     )
                 });
         }
@@ -219,7 +482,7 @@ impl TryFrom<u32> for Shape {
     }
 }
 
-impl std::fmt::Display for Shape {
+impl core::fmt::Display for Shape {
     /// The opposite of [std::str::FromStr].
     ///
     /// # Examples
@@ -240,7 +503,7 @@ impl std::fmt::Display for Shape {
     ///     Ok(Shape::Quad),
     /// );
     /// ```
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         use Shape::*;
         match self {
             Tri => write!(f, "tri"),
@@ -250,7 +513,7 @@ impl std::fmt::Display for Shape {
     }
 }
 
-impl std::str::FromStr for Shape {
+impl core::str::FromStr for Shape {
     type Err = String;
 
     /// Converts a string to a maze type.
@@ -350,6 +613,37 @@ where
         self.shape.physical_to_cell(pos)
     }
 
+    /// The room containing a physical position.
+    ///
+    /// Unlike [`room_at`](Self::room_at), this uses the actual outline of
+    /// rooms, so it is suitable for hit testing. If the position is outside
+    /// of the maze, nothing is returned. Positions on a wall between two rooms
+    /// may belong to either.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// # use maze::physical;
+    /// let maze = maze::Shape::Hex.create::<()>(5, 5);
+    /// let pos = matrix::Pos { col: 2, row: 3 };
+    ///
+    /// assert_eq!(maze.room_at_exact(maze.center(pos)), Some(pos));
+    /// assert_eq!(
+    ///     maze.room_at_exact(physical::Pos { x: -10.0, y: -10.0 }),
+    ///     None,
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `pos` - The physical position.
+    pub fn room_at_exact(&self, pos: physical::Pos) -> Option<matrix::Pos> {
+        let start = self.room_at(pos);
+        surround(start, 0).chain(surround(start, 1)).find(|&cell| {
+            self.is_inside(cell) && self.shape.contains(cell, pos)
+        })
+    }
+
     /// The matrix position whose centre is closest to a physical position
     /// along with the closest wall.
     ///
@@ -362,6 +656,157 @@ where
         self.shape.physical_to_wall_pos(pos)
     }
 
+    /// The wall closest to a physical position, if the position is within
+    /// `tolerance` of the wall.
+    ///
+    /// Unlike [`Self::wall_pos_at`], this considers the distance to the wall
+    /// itself, so positions close to the centre of a room do not yield any
+    /// wall. Positions just outside of the maze yield the closest outer wall.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// # use maze::physical;
+    /// let maze = maze::Shape::Quad.create::<()>(5, 5);
+    /// let pos = matrix::Pos { col: 2, row: 2 };
+    /// let wall = maze.walls(pos)[0];
+    /// let (a, b) = maze.corners((pos, wall));
+    ///
+    /// assert_eq!(maze.wall_at((a + b) * 0.5, 0.1), Some((pos, wall)));
+    /// assert_eq!(maze.wall_at(maze.center(pos), 0.1), None);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `pos` - The physical position.
+    /// *  `tolerance` - The maximum distance from the wall.
+    pub fn wall_at(
+        &self,
+        pos: physical::Pos,
+        tolerance: f32,
+    ) -> Option<WallPos> {
+        // The closest wall of a room containing the position is always one of
+        // its own walls, since rooms are convex
+        let rooms = match self.room_at_exact(pos) {
+            Some(room) => Vec::from([room]),
+            None => {
+                let start = self.room_at(pos);
+                surround(start, 0)
+                    .chain(surround(start, 1))
+                    .filter(|&room| self.is_inside(room))
+                    .collect()
+            }
+        };
+
+        rooms
+            .into_iter()
+            .flat_map(|room| {
+                self.walls(room).iter().map(move |&wall| (room, wall))
+            })
+            .map(|wall_pos| {
+                let (a, b) = self.corners(wall_pos);
+                let (edge, d) = (b - a, pos - a);
+                let t = ((d.x * edge.x + d.y * edge.y) / edge.value())
+                    .clamp(0.0, 1.0);
+                (wall_pos, (d - edge * t).value())
+            })
+            .filter(|&(_, distance)| distance <= tolerance * tolerance)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(wall_pos, _)| wall_pos)
+    }
+
+    /// Opens every wall crossed by a path, carving a corridor along it.
+    ///
+    /// Only walls between rooms in the maze are opened; parts of the path
+    /// outside of the maze are ignored.
+    ///
+    /// The walls opened are returned in the order in which they are crossed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// let mut maze = maze::Shape::Hex.create::<()>(5, 5);
+    /// let (from, to) = (
+    ///     matrix::Pos { col: 0, row: 2 },
+    ///     matrix::Pos { col: 4, row: 2 },
+    /// );
+    ///
+    /// let opened = maze.paint_path(&[maze.center(from), maze.center(to)]);
+    /// assert_eq!(opened.len(), 4);
+    /// assert!(maze.walk(from, to).is_some());
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `path` - The points of the path.
+    pub fn paint_path(&mut self, path: &[physical::Pos]) -> Vec<WallPos> {
+        let mut result = Vec::new();
+        for (&from, &to) in path.iter().zip(path.iter().skip(1)) {
+            let d = to - from;
+            let cross =
+                |a: physical::Pos, b: physical::Pos| a.x * b.y - a.y * b.x;
+
+            // Start in the room containing the start of the segment, even if
+            // it is outside of the maze
+            let start = self.room_at(from);
+            let mut room = surround(start, 0)
+                .chain(surround(start, 1))
+                .find(|&cell| self.shape.contains(cell, from))
+                .unwrap_or(start);
+            let mut t = 0.0;
+            loop {
+                // Find the first wall through which the segment leaves the
+                // room
+                let center = self.center(room);
+                let exit = self
+                    .walls(room)
+                    .iter()
+                    .filter_map(|&wall| {
+                        // Only walls crossed from the inside are exits
+                        let (a, b) = self.corners((room, wall));
+                        let e = b - a;
+                        let normal = physical::Pos { x: e.y, y: -e.x };
+                        let outward = (a - center).x * normal.x
+                            + (a - center).y * normal.y;
+                        if (d.x * normal.x + d.y * normal.y) * outward <= 0.0 {
+                            return None;
+                        }
+
+                        // The positions of the intersection along the wall
+                        // and along the segment
+                        let denominator = cross(d, e);
+                        let on_wall = cross(a - from, d) / denominator;
+                        let on_path = cross(a - from, e) / denominator;
+                        if (-INTERSECTION_TOLERANCE
+                            ..=1.0 + INTERSECTION_TOLERANCE)
+                            .contains(&on_wall)
+                            && on_path >= t - INTERSECTION_TOLERANCE
+                        {
+                            Some((on_path, wall))
+                        } else {
+                            None
+                        }
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+
+                match exit {
+                    Some((on_path, wall)) if on_path <= 1.0 => {
+                        let back = self.back((room, wall));
+                        if self.is_inside(room) && self.is_inside(back.0) {
+                            self.open((room, wall));
+                            result.push((room, wall));
+                        }
+                        room = back.0;
+                        t = on_path;
+                    }
+                    _ => break,
+                }
+            }
+        }
+
+        result
+    }
+
     /// Yields all rooms that are touched by the rectangle described.
     ///
     /// This method does not perform an exhaustive check; rather, only the
@@ -369,7 +814,8 @@ where
     /// any of these points are inside of the rectangle are yielded.
     ///
     /// Thus, a small rectangle inside a room not touching the centre nor any
-    /// corner will not match.
+    /// corner will not match; use [`Self::rooms_touched_by_exact`] to find
+    /// those as well.
     ///
     /// # Arguments
     /// *  `viewbox` - The rectangle.
@@ -377,41 +823,99 @@ where
         &self,
         viewbox: physical::ViewBox,
     ) -> Vec<matrix::Pos> {
-        let center = viewbox.center();
         let left = viewbox.corner.x;
         let top = viewbox.corner.y;
         let right = left + viewbox.width;
         let bottom = top + viewbox.height;
-        let start = self.room_at(center);
+
+        self.rooms_around(viewbox.center(), 0, |pos| {
+            let center = self.center(pos);
+            (center.x >= left
+                && center.y >= top
+                && center.x <= right
+                && center.y <= bottom)
+                || self.shape().cell_polygon(pos).any(|pos| {
+                    pos.x >= left
+                        && pos.y >= top
+                        && pos.x <= right
+                        && pos.y <= bottom
+                })
+        })
+    }
+
+    /// Yields all rooms that overlap the rectangle described.
+    ///
+    /// Unlike [`Self::rooms_touched_by`], this method considers the actual
+    /// polygons of rooms, so no room overlapping the rectangle is missed,
+    /// however small the rectangle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// # use maze::physical;
+    /// let maze = maze::Shape::Tri.create::<()>(5, 5);
+    /// let pos = matrix::Pos { col: 2, row: 2 };
+    /// let corner = maze.walls(pos)[0].span.0;
+    /// let viewbox = physical::ViewBox::centered_at(
+    ///     maze.center(pos)
+    ///         + physical::Pos {
+    ///             x: 0.5 * corner.dx,
+    ///             y: 0.5 * corner.dy,
+    ///         },
+    ///     0.1,
+    ///     0.1,
+    /// );
+    ///
+    /// assert_eq!(maze.rooms_touched_by(viewbox), vec![]);
+    /// assert_eq!(maze.rooms_touched_by_exact(viewbox), vec![pos]);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `viewbox` - The rectangle.
+    pub fn rooms_touched_by_exact(
+        &self,
+        viewbox: physical::ViewBox,
+    ) -> Vec<matrix::Pos> {
+        // The room containing the centre of the rectangle is at most one step
+        // away from the closest room
+        self.rooms_around(viewbox.center(), 1, |pos| {
+            self.shape().touches(pos, viewbox)
+        })
+    }
+
+    /// Collects rooms matching a predicate in rings around a position.
+    ///
+    /// Rings are visited with increasing distance from the room closest to
+    /// the position, until a ring beyond `min_distance` contains no matching
+    /// rooms.
+    ///
+    /// # Arguments
+    /// *  `pos` - The physical position.
+    /// *  `min_distance` - The distance of the last ring always visited.
+    /// *  `predicate` - The predicate.
+    fn rooms_around<F>(
+        &self,
+        pos: physical::Pos,
+        min_distance: usize,
+        mut predicate: F,
+    ) -> Vec<matrix::Pos>
+    where
+        F: FnMut(matrix::Pos) -> bool,
+    {
+        let start = self.room_at(pos);
 
         let mut result = Vec::new();
         let mut distance = 0;
         loop {
             let before = result.len();
 
-            // Add all rooms inside of the rectangle
-            result.extend(surround(start, distance).filter(|&pos| {
-                let center = self.center(pos);
-                (center.x >= left
-                    && center.y >= top
-                    && center.x <= right
-                    && center.y <= bottom)
-                    || self
-                        .walls(pos)
-                        .iter()
-                        .map(|wall| physical::Pos {
-                            x: center.x + wall.span.0.dx,
-                            y: center.y + wall.span.0.dy,
-                        })
-                        .any(|pos| {
-                            pos.x >= left
-                                && pos.y >= top
-                                && pos.x <= right
-                                && pos.y <= bottom
-                        })
-            }));
-
-            if result.len() == before {
+            // Add all matching rooms in the ring
+            result.extend(
+                surround(start, distance).filter(|&pos| predicate(pos)),
+            );
+
+            if result.len() == before && distance >= min_distance {
                 break;
             } else {
                 distance += 1;
@@ -454,6 +958,103 @@ pub fn surround(
     top.chain(right).chain(bottom).chain(left)
 }
 
+/// A shape known at compile time.
+///
+/// The functions of this trait correspond to the methods of [`Shape`], but
+/// since they are resolved statically, they can be inlined into hot loops
+/// instead of dispatching on the shape for every call. Use [`Shape`] unless
+/// profiling suggests otherwise.
+///
+/// # Example
+///
+/// ```
+/// # use maze::matrix;
+/// # use maze::shape::{self, ShapeKind};
+/// # use maze::Shape;
+/// fn centers<S: ShapeKind>(width: isize) -> Vec<f32> {
+///     (0..width)
+///         .map(|col| S::cell_to_physical(matrix::Pos { col, row: 0 }).x)
+///         .collect()
+/// }
+///
+/// let pos = matrix::Pos { col: 2, row: 0 };
+/// assert_eq!(centers::<shape::Hex>(3)[2], Shape::Hex.cell_to_physical(pos).x);
+/// assert_eq!(shape::Hex::SHAPE, Shape::Hex);
+/// ```
+pub trait ShapeKind: Copy + Default + 'static {
+    /// The shape corresponding to this kind.
+    const SHAPE: Shape;
+
+    /// Returns all walls for this shape.
+    ///
+    /// See [`Shape::all_walls`].
+    fn all_walls() -> &'static [&'static wall::Wall];
+
+    /// Returns the back of a wall.
+    ///
+    /// See [`Shape::back`].
+    ///
+    /// # Arguments
+    /// *  `wall_pos` - The wall position.
+    fn back(wall_pos: WallPos) -> WallPos;
+
+    /// Returns all walls of a specific room.
+    ///
+    /// See [`Shape::walls`].
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    fn walls(pos: matrix::Pos) -> &'static [&'static wall::Wall];
+
+    /// The number of steps between two rooms if no walls were closed.
+    ///
+    /// See [`Shape::graph_distance_lower_bound`].
+    ///
+    /// # Arguments
+    /// *  `a` - One room position.
+    /// *  `b` - The other room position.
+    fn graph_distance_lower_bound(a: matrix::Pos, b: matrix::Pos) -> usize;
+
+    /// Converts a physical position to a matrix cell.
+    ///
+    /// See [`Shape::physical_to_cell`].
+    ///
+    /// # Arguments
+    /// *  `pos` - The physical position.
+    fn physical_to_cell(pos: physical::Pos) -> matrix::Pos;
+
+    /// Converts a physical position to a wall position.
+    ///
+    /// See [`Shape::physical_to_wall_pos`].
+    ///
+    /// # Arguments
+    /// *  `pos` - The physical position.
+    fn physical_to_wall_pos(pos: physical::Pos) -> WallPos;
+
+    /// Returns the physical centre of a matrix cell.
+    ///
+    /// See [`Shape::cell_to_physical`].
+    ///
+    /// # Arguments
+    /// *  `pos` - The matrix position.
+    fn cell_to_physical(pos: matrix::Pos) -> physical::Pos;
+}
+
+define_shape_kind! {
+    /// The kind of mazes with hexagonal rooms.
+    Hex => hex
+}
+
+define_shape_kind! {
+    /// The kind of mazes with quadratic rooms.
+    Quad => quad
+}
+
+define_shape_kind! {
+    /// The kind of mazes with triangular rooms.
+    Tri => tri
+}
+
 pub mod hex;
 pub mod quad;
 pub mod tri;
@@ -468,6 +1069,31 @@ mod tests {
     use crate::*;
     use test_utils::*;
 
+    #[maze_test(sizes(10x5, 7x8))]
+    fn graph_distance_lower_bound(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Clear,
+            &mut initialize::LFSR::new(12345),
+        );
+
+        // Without walls, the bound is the actual distance
+        for from in maze.positions() {
+            for (pos, distance) in maze.flood(from) {
+                assert_eq!(
+                    distance as usize,
+                    maze.shape().graph_distance_lower_bound(from, pos),
+                    "from {:?} to {:?}",
+                    from,
+                    pos,
+                );
+                assert_eq!(
+                    distance as usize,
+                    maze.shape().graph_distance_lower_bound(pos, from),
+                );
+            }
+        }
+    }
+
     #[test]
     fn surround_single() {
         assert_eq!(
@@ -624,6 +1250,87 @@ mod tests {
         }
     }
 
+    #[maze_test]
+    fn contains(maze: TestMaze) {
+        for pos in maze.positions() {
+            let center = maze.center(pos);
+            assert!(maze.shape().contains(pos, center));
+            for wall in maze.walls(pos) {
+                let (corner, midpoint) = (
+                    physical::Pos {
+                        x: wall.span.0.dx,
+                        y: wall.span.0.dy,
+                    },
+                    physical::Pos {
+                        x: 0.5 * (wall.span.0.dx + wall.span.1.dx),
+                        y: 0.5 * (wall.span.0.dy + wall.span.1.dy),
+                    },
+                );
+                for (d, expected) in [(0.95, true), (1.0, true), (1.05, false)]
+                {
+                    for offset in [corner, midpoint] {
+                        assert_eq!(
+                            expected,
+                            maze.shape().contains(pos, center + offset * d),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[maze_test]
+    fn cell_polygon(maze: TestMaze) {
+        for pos in maze.positions() {
+            let polygon = maze.shape().cell_polygon(pos).collect::<Vec<_>>();
+            assert_eq!(polygon.len(), maze.walls(pos).len());
+            for (&corner, wall) in polygon.iter().zip(maze.walls(pos)) {
+                let (start, _) = maze.corners((pos, wall));
+                assert!(is_close(start, corner));
+            }
+        }
+    }
+
+    #[maze_test]
+    fn room_at_exact(maze: TestMaze) {
+        let d = 0.95;
+        for pos in maze.positions() {
+            let center = maze.center(pos);
+            for wall in maze.walls(pos) {
+                for offset in [
+                    physical::Pos {
+                        x: wall.span.0.dx,
+                        y: wall.span.0.dy,
+                    },
+                    physical::Pos {
+                        x: 0.5 * (wall.span.0.dx + wall.span.1.dx),
+                        y: 0.5 * (wall.span.0.dy + wall.span.1.dy),
+                    },
+                ] {
+                    assert_eq!(
+                        Some(pos),
+                        maze.room_at_exact(center + offset * d),
+                    );
+                }
+            }
+        }
+
+        let viewbox = maze.viewbox();
+        assert_eq!(
+            None,
+            maze.room_at_exact(
+                viewbox.corner - physical::Pos { x: 0.1, y: 0.1 },
+            ),
+        );
+        assert_eq!(
+            None,
+            maze.room_at_exact(physical::Pos {
+                x: viewbox.corner.x - 1.0,
+                y: viewbox.center().y,
+            }),
+        );
+    }
+
     #[maze_test]
     fn wall_pos_at(maze: TestMaze) {
         let steps = 10;
@@ -631,7 +1338,7 @@ mod tests {
         for pos in maze.positions() {
             let center = maze.center(pos);
             for i in 0..steps {
-                let a = 2.0 * std::f32::consts::PI * (i as f32 / steps as f32);
+                let a = 2.0 * core::f32::consts::PI * (i as f32 / steps as f32);
                 let expected = (
                     pos,
                     maze.walls(pos)
@@ -648,7 +1355,7 @@ mod tests {
                             y: center.y + r * a.sin(),
                         }),
                         "Invalid wall for {}°",
-                        360.0 * a / (2.0 * std::f32::consts::PI),
+                        360.0 * a / (2.0 * core::f32::consts::PI),
                     );
                 }
             }
@@ -662,7 +1369,7 @@ mod tests {
             .filter(|pos| pos.row == 0)
             .map(|pos| maze.center(pos))
             .fold(
-                (std::f32::MAX, std::f32::MAX, std::f32::MIN, std::f32::MIN),
+                (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
                 |(l, t, r, b), p| {
                     (l.min(p.x), t.min(p.y), r.max(p.x), b.max(p.y))
                 },
@@ -697,7 +1404,7 @@ mod tests {
                 })
             })
             .fold(
-                (std::f32::MAX, std::f32::MAX, std::f32::MIN, std::f32::MIN),
+                (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
                 |(l, t, r, b), p| {
                     (l.min(p.x), t.min(p.y), r.max(p.x), b.max(p.y))
                 },
@@ -719,6 +1426,123 @@ mod tests {
         );
     }
 
+    #[maze_test]
+    fn rooms_touched_by_exact(maze: TestMaze) {
+        for pos in maze.positions() {
+            let center = maze.center(pos);
+            for wall in maze.walls(pos) {
+                // A tiny rectangle between the centre and each corner
+                let viewbox = physical::ViewBox::centered_at(
+                    center
+                        + physical::Pos {
+                            x: 0.5 * wall.span.0.dx,
+                            y: 0.5 * wall.span.0.dy,
+                        },
+                    0.01,
+                    0.01,
+                );
+                assert_eq!(vec![pos], maze.rooms_touched_by_exact(viewbox));
+            }
+        }
+
+        let viewbox = maze.viewbox();
+        assert_eq!(
+            maze.positions().collect::<HashSet<_>>(),
+            maze.rooms_touched_by_exact(viewbox)
+                .into_iter()
+                .filter(|&pos| maze.is_inside(pos))
+                .collect::<HashSet<_>>(),
+        );
+        assert!(maze
+            .rooms_touched_by_exact(viewbox)
+            .into_iter()
+            .all(|pos| maze.shape().touches(pos, viewbox)));
+    }
+
+    #[maze_test]
+    fn wall_at(maze: TestMaze) {
+        for pos in maze.positions() {
+            let center = maze.center(pos);
+            assert_eq!(None, maze.wall_at(center, 0.1));
+            for &wall in maze.walls(pos) {
+                let (a, b) = maze.corners((pos, wall));
+                let midpoint = (a + b) * 0.5;
+                let inside = midpoint + (center - midpoint) * 0.05;
+                assert_eq!(Some((pos, wall)), maze.wall_at(inside, 0.1));
+
+                let outside = midpoint + (midpoint - center) * 0.05;
+                let back = maze.back((pos, wall));
+                let expected = if maze.is_inside(back.0) {
+                    Some(back)
+                } else {
+                    Some((pos, wall))
+                };
+                assert_eq!(expected, maze.wall_at(outside, 0.1));
+                assert_eq!(None, maze.wall_at(outside, 0.01));
+            }
+        }
+    }
+
+    #[maze_test]
+    fn paint_path(maze: TestMaze) {
+        let mut maze = maze;
+        let (from, to) = (
+            matrix::Pos { col: 0, row: 0 },
+            matrix::Pos {
+                col: maze.width() as isize - 1,
+                row: maze.height() as isize - 1,
+            },
+        );
+        let path = [
+            maze.center(from) + physical::Pos { x: -10.0, y: 0.0 },
+            maze.center(from),
+            maze.center(to),
+        ];
+
+        let opened = maze.paint_path(&path);
+        assert!(!opened.is_empty());
+        assert!(opened.iter().all(|&wall_pos| maze.is_open(wall_pos)
+            && maze.is_inside(maze.back(wall_pos).0)));
+        assert!(maze.walk(from, to).is_some());
+        assert_eq!(
+            opened.len(),
+            maze.positions()
+                .flat_map(|pos| maze.walls(pos).iter().map(move |&w| (pos, w)))
+                .filter(|&wall_pos| maze.is_open(wall_pos))
+                .count()
+                / 2,
+        );
+    }
+
+    #[maze_test]
+    fn shape_kind(maze: TestMaze) {
+        let shape = maze.shape();
+        monomorphise!(shape, S => {
+            assert_eq!(shape, S::SHAPE);
+            assert_eq!(shape.all_walls(), S::all_walls());
+            for pos in maze.positions() {
+                let center = maze.center(pos);
+                assert_eq!(center, S::cell_to_physical(pos));
+                assert_eq!(pos, S::physical_to_cell(center));
+                assert_eq!(shape.walls(pos), S::walls(pos));
+                for &wall in S::walls(pos) {
+                    assert_eq!(shape.back((pos, wall)), S::back((pos, wall)));
+                    let (a, b) = maze.corners((pos, wall));
+                    let p = center + ((a + b) * 0.5 - center) * 0.9;
+                    assert_eq!(
+                        shape.physical_to_wall_pos(p),
+                        S::physical_to_wall_pos(p),
+                    );
+                }
+                let origin = matrix::Pos { col: 0, row: 0 };
+                assert_eq!(
+                    shape.graph_distance_lower_bound(pos, origin),
+                    S::graph_distance_lower_bound(pos, origin),
+                );
+            }
+        });
+    }
+
     #[maze_test]
     fn previous_and_next_wall(maze: TestMaze) {
         for pos in maze.positions() {
@@ -726,7 +1550,7 @@ mod tests {
                 let a1 = wall::Wall::normalized_angle(wall.span.0.a);
                 let a2 = wall::Wall::normalized_angle(wall.previous.span.1.a);
                 assert!(
-                    (a1 - a2).abs() < std::f32::EPSILON * 16.0,
+                    (a1 - a2).abs() < f32::EPSILON * 16.0,
                     "first wall {:?} for {:?} ({} != {})",
                     wall,
                     maze.shape(),
@@ -736,7 +1560,7 @@ mod tests {
                 let a1 = wall::Wall::normalized_angle(wall.span.1.a);
                 let a2 = wall::Wall::normalized_angle(wall.next.span.0.a);
                 assert!(
-                    (a1 - a2).abs() < std::f32::EPSILON * 16.0,
+                    (a1 - a2).abs() < f32::EPSILON * 16.0,
                     "second wall {:?} for {:?} ({} != {})",
                     wall,
                     maze.shape(),
@@ -1,3 +1,5 @@
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
 use crate::matrix;
 use crate::physical;
 use crate::wall;
@@ -10,10 +12,10 @@ use super::{COS_45, SIN_45};
 /// A span step angle
 ///
 /// This is half the angle span used by a single wall.
-const D: f32 = 2.0 * std::f32::consts::PI / 8.0;
+const D: f32 = 2.0 * core::f32::consts::PI / 8.0;
 
 /// The scale factor when converting maze coordinates to physical coordinates
-const MULTIPLICATOR: f32 = 2.0 / std::f32::consts::SQRT_2;
+const MULTIPLICATOR: f32 = 2.0 / core::f32::consts::SQRT_2;
 
 define_shape! {
     << Quad >>
@@ -120,6 +122,7 @@ pub fn minimal_dimensions(width: f32, height: f32) -> (usize, usize) {
     (width, height)
 }
 
+#[inline]
 pub fn back_index(wall: usize) -> usize {
     wall ^ 0b0010
 }
@@ -129,10 +132,17 @@ pub fn opposite(wall_pos: WallPos) -> Option<&'static wall::Wall> {
     Some(walls::ALL[(wall.index + walls::ALL.len() / 2) % walls::ALL.len()])
 }
 
+#[inline]
 pub fn walls(_pos: matrix::Pos) -> &'static [&'static wall::Wall] {
     WALLS
 }
 
+#[inline]
+pub fn graph_distance_lower_bound(a: matrix::Pos, b: matrix::Pos) -> usize {
+    (a.col.abs_diff(b.col)) + (a.row.abs_diff(b.row))
+}
+
+#[inline]
 pub fn cell_to_physical(pos: matrix::Pos) -> physical::Pos {
     physical::Pos {
         x: (pos.col as f32 + 0.5) * MULTIPLICATOR,
@@ -140,6 +150,7 @@ pub fn cell_to_physical(pos: matrix::Pos) -> physical::Pos {
     }
 }
 
+#[inline]
 pub fn physical_to_cell(pos: physical::Pos) -> matrix::Pos {
     matrix::Pos {
         col: (pos.x / MULTIPLICATOR).floor() as isize,
@@ -148,6 +159,7 @@ pub fn physical_to_cell(pos: physical::Pos) -> matrix::Pos {
 }
 
 #[allow(clippy::collapsible_else_if)]
+#[inline]
 pub fn physical_to_wall_pos(pos: physical::Pos) -> WallPos {
     let matrix_pos = physical_to_cell(pos);
     let center = cell_to_physical(matrix_pos);
@@ -1,5 +1,7 @@
-use std::f32::consts::PI;
+use core::f32::consts::PI;
 
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
 use crate::WallPos;
 
 use crate::matrix;
@@ -191,6 +193,50 @@ fn is_reversed(pos: matrix::Pos) -> bool {
     (pos.col + pos.row) & 1 != 0
 }
 
+/// The direction in which a triangular room points.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Orientation {
+    /// The room has a corner at the top and a horizontal wall at the bottom.
+    Up,
+
+    /// The room has a horizontal wall at the top and a corner at the bottom.
+    Down,
+}
+
+/// Returns the direction in which a room points.
+///
+/// Rooms alternate between pointing down and up along both rows and columns,
+/// and the room at `(0, 0)` points down.
+///
+/// # Arguments
+/// *  `pos` - the room position.
+///
+/// # Example
+///
+/// ```
+/// # use maze::matrix;
+/// # use maze::shape::tri::{orientation, Orientation};
+/// assert_eq!(
+///     orientation(matrix::Pos { col: 0, row: 0 }),
+///     Orientation::Down,
+/// );
+/// assert_eq!(
+///     orientation(matrix::Pos { col: 1, row: 0 }),
+///     Orientation::Up,
+/// );
+/// assert_eq!(
+///     orientation(matrix::Pos { col: 1, row: 1 }),
+///     Orientation::Down,
+/// );
+/// ```
+pub fn orientation(pos: matrix::Pos) -> Orientation {
+    if is_reversed(pos) {
+        Orientation::Up
+    } else {
+        Orientation::Down
+    }
+}
+
 pub fn minimal_dimensions(width: f32, height: f32) -> (usize, usize) {
     let height = (height.max(VERTICAL_MULTIPLICATOR) / VERTICAL_MULTIPLICATOR)
         .ceil() as usize;
@@ -201,6 +247,7 @@ pub fn minimal_dimensions(width: f32, height: f32) -> (usize, usize) {
     (width, height)
 }
 
+#[inline]
 pub fn back_index(wall: usize) -> usize {
     wall ^ 0b0001
 }
@@ -210,6 +257,7 @@ pub fn opposite(_pos: WallPos) -> Option<&'static wall::Wall> {
     None
 }
 
+#[inline]
 pub fn walls(pos: matrix::Pos) -> &'static [&'static wall::Wall] {
     if is_reversed(pos) {
         WALLS_ODD
@@ -218,6 +266,23 @@ pub fn walls(pos: matrix::Pos) -> &'static [&'static wall::Wall] {
     }
 }
 
+#[inline]
+pub fn graph_distance_lower_bound(a: matrix::Pos, b: matrix::Pos) -> usize {
+    // Every step crosses exactly one line of one of the three families of
+    // parallel lines making up the grid, so the distance is the sum of the
+    // differences of the strip indices
+    let strips = |pos: matrix::Pos| {
+        (
+            pos.row,
+            (pos.col - pos.row).div_euclid(2),
+            (pos.col + pos.row + 1).div_euclid(2),
+        )
+    };
+    let ((a1, b1, c1), (a2, b2, c2)) = (strips(a), strips(b));
+    a1.abs_diff(a2) + b1.abs_diff(b2) + c1.abs_diff(c2)
+}
+
+#[inline]
 pub fn cell_to_physical(pos: matrix::Pos) -> physical::Pos {
     physical::Pos {
         x: (pos.col as f32 + 1.0) * HORIZONTAL_MULTIPLICATOR,
@@ -226,6 +291,7 @@ pub fn cell_to_physical(pos: matrix::Pos) -> physical::Pos {
     }
 }
 
+#[inline]
 pub fn physical_to_cell(pos: physical::Pos) -> matrix::Pos {
     // Calculate approximations of the room position
     let (i, f) = matrix::partition(pos.y / VERTICAL_MULTIPLICATOR);
@@ -252,6 +318,7 @@ pub fn physical_to_cell(pos: physical::Pos) -> matrix::Pos {
 }
 
 #[allow(clippy::collapsible_if)]
+#[inline]
 pub fn physical_to_wall_pos(pos: physical::Pos) -> WallPos {
     let matrix_pos = physical_to_cell(pos);
     let flipped = (matrix_pos.col + matrix_pos.row) & 1 == 1;
@@ -447,4 +514,40 @@ mod tests {
                 .collect::<Vec<WallPos>>()
         );
     }
+
+    #[maze_test(tri)]
+    fn cell_polygon_shared(maze: TestMaze) {
+        for pos in maze.positions() {
+            let corners = maze.shape().cell_polygon(pos).collect::<Vec<_>>();
+            for wall in maze.walls(pos) {
+                let (start, end) = maze.corners((pos, wall));
+                assert!(corners.iter().any(|&c| is_close(c, start)));
+                assert!(corners.iter().any(|&c| is_close(c, end)));
+            }
+            for neighbor in maze.adjacent(pos) {
+                let shared = maze
+                    .shape()
+                    .cell_polygon(neighbor)
+                    .filter(|&a| corners.iter().any(|&b| is_close(a, b)))
+                    .count();
+                assert_eq!(shared, 2);
+            }
+        }
+    }
+
+    #[maze_test(tri)]
+    fn cell_polygon_orientation(maze: TestMaze) {
+        for pos in maze.positions() {
+            let center = maze.center(pos);
+            let below = maze
+                .shape()
+                .cell_polygon(pos)
+                .filter(|c| c.y > center.y)
+                .count();
+            match orientation(pos) {
+                Orientation::Up => assert_eq!(below, 2),
+                Orientation::Down => assert_eq!(below, 1),
+            }
+        }
+    }
 }
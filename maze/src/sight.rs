@@ -0,0 +1,245 @@
+//! # Line of sight
+//!
+//! Games built on a maze often need to know what is visible from a position.
+//! This module casts rays through the rooms of a maze, stopping at the first
+//! closed wall.
+
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+use crate::physical;
+use crate::{Maze, WallPos};
+
+/// The tolerance used when determining whether a ray passes through a wall.
+///
+/// This prevents rays passing exactly through a corner from missing both
+/// walls meeting there because of rounding errors.
+const TOLERANCE: f32 = 1e-5;
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// Casts a ray and finds the first closed wall it hits.
+    ///
+    /// The ray starts in the room containing `origin`, and passes through all
+    /// open walls between rooms. Openings leading out of the maze are treated
+    /// as closed walls.
+    ///
+    /// The returned value is the position where the ray hits the wall, and
+    /// the wall. If `origin` is outside of the maze, nothing is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// let mut maze = maze::Shape::Quad.create::<()>(3, 1);
+    /// let (pos1, pos2) = (
+    ///     matrix::Pos { col: 0, row: 0 },
+    ///     matrix::Pos { col: 1, row: 0 },
+    /// );
+    /// maze.open(maze.connecting_wall(pos1, pos2).unwrap());
+    ///
+    /// let (_, (pos, _)) = maze.cast_ray(maze.center(pos1), 0.0).unwrap();
+    /// assert_eq!(pos, pos2);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `origin` - The start of the ray.
+    /// *  `angle` - The direction of the ray, in radians.
+    pub fn cast_ray(
+        &self,
+        origin: physical::Pos,
+        angle: f32,
+    ) -> Option<(physical::Pos, WallPos)> {
+        let (dy, dx) = angle.sin_cos();
+        let direction = physical::Pos { x: dx, y: dy };
+        self.trace(origin, direction)
+            .map(|(t, wall_pos)| (origin + direction * t, wall_pos))
+    }
+
+    /// Whether the straight line between two positions passes only through
+    /// open walls.
+    ///
+    /// Both positions must be inside of the maze for there to be a line of
+    /// sight.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// let mut maze = maze::Shape::Quad.create::<()>(2, 2);
+    /// let (pos1, pos2, pos3) = (
+    ///     matrix::Pos { col: 0, row: 0 },
+    ///     matrix::Pos { col: 1, row: 0 },
+    ///     matrix::Pos { col: 0, row: 1 },
+    /// );
+    /// maze.open(maze.connecting_wall(pos1, pos2).unwrap());
+    ///
+    /// assert!(maze.line_of_sight(maze.center(pos1), maze.center(pos2)));
+    /// assert!(!maze.line_of_sight(maze.center(pos1), maze.center(pos3)));
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `a` - One position.
+    /// *  `b` - The other position.
+    pub fn line_of_sight(&self, a: physical::Pos, b: physical::Pos) -> bool {
+        if a == b {
+            self.room_at_exact(a).is_some()
+        } else {
+            self.room_at_exact(b).is_some()
+                && self.trace(a, b - a).map(|(t, _)| t >= 1.0).unwrap_or(false)
+        }
+    }
+
+    /// Follows a ray until it hits a closed wall.
+    ///
+    /// The returned value is the ray parameter at the hit, that is, the
+    /// number of `direction` steps from `origin`, and the wall hit.
+    ///
+    /// # Arguments
+    /// *  `origin` - The start of the ray.
+    /// *  `direction` - The direction of the ray. This does not have to be a
+    ///    unit vector.
+    pub(crate) fn trace(
+        &self,
+        origin: physical::Pos,
+        direction: physical::Pos,
+    ) -> Option<(f32, WallPos)> {
+        let mut pos = self.room_at_exact(origin)?;
+        let mut entry = None;
+        loop {
+            // Since rooms are convex, the ray leaves through the wall it
+            // intersects furthest along the ray
+            let (t, wall_pos) = self
+                .walls(pos)
+                .iter()
+                .map(|&wall| (pos, wall))
+                .filter(|&wall_pos| Some(wall_pos) != entry)
+                .filter_map(|wall_pos| {
+                    let (a, b) = self.corners(wall_pos);
+                    intersection(origin, direction, a, b).map(|t| (t, wall_pos))
+                })
+                .max_by(|a, b| a.0.total_cmp(&b.0))?;
+
+            let back = self.back(wall_pos);
+            if !self.is_open(wall_pos) || !self.is_inside(back.0) {
+                break Some((t, wall_pos));
+            } else {
+                pos = back.0;
+                entry = Some(back);
+            }
+        }
+    }
+}
+
+/// Calculates where a ray intersects a line segment.
+///
+/// The returned value is the number of `direction` steps from `origin`, which
+/// may be negative. If the ray is parallel to the segment or misses it,
+/// nothing is returned.
+///
+/// # Arguments
+/// *  `origin` - The start of the ray.
+/// *  `direction` - The direction of the ray.
+/// *  `a` - The start of the segment.
+/// *  `b` - The end of the segment.
+fn intersection(
+    origin: physical::Pos,
+    direction: physical::Pos,
+    a: physical::Pos,
+    b: physical::Pos,
+) -> Option<f32> {
+    let cross = |u: physical::Pos, v: physical::Pos| u.x * v.y - u.y * v.x;
+    let (edge, d) = (b - a, a - origin);
+    let denominator = cross(direction, edge);
+    if denominator.abs() <= f32::EPSILON {
+        None
+    } else {
+        let s = cross(d, direction) / denominator;
+        (-TOLERANCE..=1.0 + TOLERANCE)
+            .contains(&s)
+            .then(|| cross(d, edge) / denominator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn cast_ray_closed(maze: TestMaze) {
+        let steps = 12;
+        for pos in maze.positions() {
+            let center = maze.center(pos);
+            for i in 0..steps {
+                // Avoid passing exactly through corners
+                let a =
+                    2.0 * core::f32::consts::PI * i as f32 / steps as f32 + 0.1;
+                let (hit, wall_pos) = maze.cast_ray(center, a).unwrap();
+                assert_eq!(pos, wall_pos.0);
+                assert!(wall_pos.1.in_span(a));
+
+                let (from, to) = maze.corners(wall_pos);
+                let (d, p) = (to - from, hit - from);
+                assert!((d.x * p.y - d.y * p.x).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[maze_test]
+    fn cast_ray_open(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Clear,
+            &mut initialize::LFSR::new(12345),
+        );
+        for pos in maze.positions() {
+            for a in [0.1, 1.7, 3.3, 4.9] {
+                let (hit, wall_pos) =
+                    maze.cast_ray(maze.center(pos), a).unwrap();
+
+                // The ray only stops at the edge of the maze
+                assert!(!maze.is_inside(maze.back(wall_pos).0));
+                let (from, to) = maze.corners(wall_pos);
+                let (d, p) = (to - from, hit - from);
+                assert!((d.x * p.y - d.y * p.x).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[maze_test]
+    fn line_of_sight_neighbors(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Braid,
+            &mut initialize::LFSR::new(12345),
+        );
+        for pos in maze.positions() {
+            assert!(maze.line_of_sight(maze.center(pos), maze.center(pos)));
+            for wall in maze.walls(pos) {
+                let wall_pos = (pos, *wall);
+                let back = maze.back(wall_pos);
+                if maze.is_inside(back.0) {
+                    assert_eq!(
+                        maze.is_open(wall_pos),
+                        maze.line_of_sight(
+                            maze.center(pos),
+                            maze.center(back.0),
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn line_of_sight_outside() {
+        let maze = crate::Shape::Quad.create::<()>(2, 2);
+        let inside = maze.center(matrix_pos(0, 0));
+        let outside = physical::Pos { x: -1.0, y: -1.0 };
+        assert!(!maze.line_of_sight(inside, outside));
+        assert!(!maze.line_of_sight(outside, inside));
+    }
+}
@@ -16,13 +16,13 @@ pub fn is_close(expected: physical::Pos, actual: physical::Pos) -> bool {
 /// Determines whether two floating point values are close enough to be
 /// considered equal.
 ///
-/// This function lowers the resolution to `std::f32::EPSILON * 4.0`.
+/// This function lowers the resolution to `f32::EPSILON * 4.0`.
 ///
 /// # Arguments
 /// *  `a` - One value.
 /// *  `b` - Another value.
 pub fn nearly_equal(a: f32, b: f32) -> bool {
-    a == b || (a - b).abs() < std::f32::EPSILON * 4.0
+    a == b || (a - b).abs() < f32::EPSILON * 4.0
 }
 
 /// A simple helper to create a matrix position.
@@ -149,10 +149,9 @@ impl<'a> Navigator<'a> {
             self.pos = self
                 .maze
                 .positions()
-                .filter(|&pos| {
+                .find(|&pos| {
                     self.maze.walls(pos).iter().any(|wall| predicate(&wall))
-                })
-                .next();
+                });
         }
         let pos = self.pos.unwrap();
         self.log.push(pos);
@@ -162,13 +161,12 @@ impl<'a> Navigator<'a> {
             .walls(pos)
             .iter()
             .filter(predicate)
-            .filter(|wall| {
+            .find(|wall| {
                 self.maze.is_inside(matrix_pos(
                     pos.col + wall.dir.0,
                     pos.row + wall.dir.1,
                 ))
             })
-            .next()
             .unwrap();
         self.maze.set_open((pos, wall), open);
         self.pos = Some(matrix_pos(pos.col + wall.dir.0, pos.row + wall.dir.1));
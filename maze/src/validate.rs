@@ -0,0 +1,189 @@
+//! # Invariant checking
+//!
+//! A maze built only through the methods of [`Maze`] upholds a number of
+//! invariants, but a maze deserialised from untrusted data, or modified
+//! through the individual rooms, may not. This module checks them, which is
+//! useful as an oracle when fuzzing.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::matrix;
+use crate::wall;
+use crate::{Maze, WallPos};
+
+/// A broken invariant of a maze.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Violation {
+    /// A room has open walls that do not belong to rooms at its position.
+    ///
+    /// The mask contains the bits of the unknown walls.
+    UnknownWalls(matrix::Pos, wall::Mask),
+
+    /// A wall is open, but its back, in a room inside of the maze, is closed.
+    Asymmetric(WallPos),
+
+    /// A wall leading out of the maze is open.
+    OpenToOutside(WallPos),
+
+    /// A room with open walls is not marked as visited.
+    Unvisited(matrix::Pos),
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::UnknownWalls(pos, mask) => write!(
+                f,
+                "({}, {}) has unknown open walls {:#x}",
+                pos.col, pos.row, mask,
+            ),
+            Violation::Asymmetric((pos, wall)) => write!(
+                f,
+                "wall {} of ({}, {}) is open only on one side",
+                wall.name, pos.col, pos.row,
+            ),
+            Violation::OpenToOutside((pos, wall)) => write!(
+                f,
+                "wall {} of ({}, {}) leads out of the maze",
+                wall.name, pos.col, pos.row,
+            ),
+            Violation::Unvisited(pos) => {
+                write!(f, "({}, {}) is not visited", pos.col, pos.row)
+            }
+        }
+    }
+}
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// Checks the invariants of this maze.
+    ///
+    /// The following invariants are checked:
+    /// *  Only walls belonging to the shape of a room at its position are
+    ///    open.
+    /// *  Every open wall between two rooms is open on both sides.
+    /// *  No wall leading out of the maze is open. Such openings can be made
+    ///    deliberately through [`Maze::open`], so callers that do so should
+    ///    ignore [`Violation::OpenToOutside`].
+    /// *  Every room with open walls is marked as visited.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// # use maze::validate::Violation;
+    /// let mut maze = maze::Shape::Quad.create::<()>(2, 1);
+    /// let pos = matrix::Pos { col: 0, row: 0 };
+    /// maze.open(maze.connecting_wall(pos, (1isize, 0isize).into()).unwrap());
+    /// assert_eq!(Ok(()), maze.validate());
+    ///
+    /// let wall_pos = maze.connecting_wall(pos, (-1isize, 0isize).into());
+    /// maze.open(wall_pos.unwrap());
+    /// assert_eq!(
+    ///     Err(vec![Violation::OpenToOutside(wall_pos.unwrap())]),
+    ///     maze.validate(),
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// If any invariant is broken, all violations found are returned, ordered
+    /// by room.
+    pub fn validate(&self) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        for pos in self.positions() {
+            let room = &self.rooms[pos];
+            let walls = self.walls(pos);
+
+            let known = walls.iter().fold(0, |acc, wall| acc | wall.mask());
+            let unknown = room.mask() & !known;
+            if unknown != 0 {
+                violations.push(Violation::UnknownWalls(pos, unknown));
+            }
+
+            for &wall in walls {
+                let wall_pos = (pos, wall);
+                if !room.is_open(wall) {
+                    continue;
+                }
+                let back = self.back(wall_pos);
+                if !self.is_inside(back.0) {
+                    violations.push(Violation::OpenToOutside(wall_pos));
+                } else if !self.is_open(back) {
+                    violations.push(Violation::Asymmetric(wall_pos));
+                }
+            }
+
+            if room.mask() != 0 && !room.visited {
+                violations.push(Violation::Unvisited(pos));
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize;
+    use crate::test_utils::*;
+
+    #[maze_test(sizes(1x1, 3x7, 10x5), seeds(3))]
+    fn validate_initialized(maze: TestMaze, seed: u64) {
+        assert_eq!(Ok(()), maze.validate());
+        let maze = maze.initialize(
+            initialize::Method::Braid,
+            &mut initialize::SplitMix64::new(seed),
+        );
+        assert_eq!(Ok(()), maze.validate());
+    }
+
+    #[maze_test]
+    fn validate_asymmetric(mut maze: TestMaze) {
+        let pos = matrix_pos(1, 1);
+        let wall = maze.walls(pos)[0];
+        maze.rooms[pos].open(wall);
+        assert_eq!(
+            Err(vec![Violation::Asymmetric((pos, wall))]),
+            maze.validate()
+        );
+    }
+
+    #[maze_test]
+    fn validate_unknown_walls(mut maze: TestMaze) {
+        let pos = matrix_pos(1, 1);
+        let known = maze
+            .walls(pos)
+            .iter()
+            .fold(0, |acc, wall| acc | wall.mask());
+        let wall = maze
+            .all_walls()
+            .iter()
+            .chain(crate::Shape::Hex.all_walls())
+            .find(|wall| wall.mask() & known == 0)
+            .unwrap();
+        maze.rooms[pos].open(wall);
+        assert_eq!(
+            Err(vec![Violation::UnknownWalls(pos, wall.mask())]),
+            maze.validate(),
+        );
+    }
+
+    #[maze_test]
+    fn validate_unvisited(mut maze: TestMaze) {
+        let pos = matrix_pos(1, 1);
+        let wall_pos = (pos, maze.walls(pos)[0]);
+        maze.open(wall_pos);
+        maze.rooms[pos].visited = false;
+        assert_eq!(Err(vec![Violation::Unvisited(pos)]), maze.validate());
+    }
+}
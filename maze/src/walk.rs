@@ -1,10 +1,23 @@
-use std::collections::BinaryHeap;
+use alloc::collections::{BinaryHeap, VecDeque};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::Hash;
 
 use bit_set::BitSet;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+use crate::initialize::Randomizer;
 use crate::matrix;
+use crate::shape::ShapeKind;
+use crate::wall;
+use crate::{HashMap, HashSet};
 
 use crate::matrix::Matrix;
+use crate::Error;
 use crate::Maze;
 use crate::WallPos;
 
@@ -31,7 +44,7 @@ where
     /// # use maze::walk::*;
     /// # let maze = maze::Shape::Hex.create::<u32>(5, 5)
     /// #     .initialize(
-    /// #         maze::initialize::Method::Winding,
+    /// #         maze::initialize::Method::Winding(Default::default()),
     /// #         &mut maze::initialize::LFSR::new(12345),
     /// #     );
     ///
@@ -55,66 +68,217 @@ where
     /// # Arguments
     /// *  `from` - The starting position.
     /// *  `to` - The desired goal.
-    pub fn walk(&self, from: matrix::Pos, to: matrix::Pos) -> Option<Path<T>> {
-        // Reverse the positions to return the rooms in correct order
-        let (start, end) = (to, from);
+    pub fn walk(
+        &self,
+        from: matrix::Pos,
+        to: matrix::Pos,
+    ) -> Option<Path<'_, T>> {
+        self.walk_to_nearest(from, &[to])
+    }
 
-        // Assume that the distance between the centres of adjacent rooms is
-        // consistent
-        let distance = (self.center((0isize, 0isize).into())
-            - self.center((0isize, 1isize).into()))
-        .value();
+    /// Walks from `from` to `to` along the shortest path.
+    ///
+    /// This is the fallible variant of [`walk`](Self::walk), which describes
+    /// why no path was found.
+    ///
+    /// # Arguments
+    /// *  `from` - The starting position.
+    /// *  `to` - The desired goal.
+    pub fn try_walk(
+        &self,
+        from: matrix::Pos,
+        to: matrix::Pos,
+    ) -> Result<Path<'_, T>, Error> {
+        if !self.is_inside(from) {
+            Err(Error::OutOfBounds(from))
+        } else if !self.is_inside(to) {
+            Err(Error::OutOfBounds(to))
+        } else {
+            self.walk(from, to).ok_or(Error::Unreachable { from, to })
+        }
+    }
 
-        // The heuristic for a room position
-        let target = self.center(end);
-        let h =
-            |pos: matrix::Pos| Priority((target - self.center(pos)).value());
+    /// Walks from `from` to the closest of several goals along the shortest
+    /// path.
+    ///
+    /// All goals are considered in a single search. If no goal is reachable,
+    /// nothing is returned.
+    ///
+    /// # Arguments
+    /// *  `from` - The starting position.
+    /// *  `goals` - The possible goals.
+    pub fn walk_to_nearest(
+        &self,
+        from: matrix::Pos,
+        goals: &[matrix::Pos],
+    ) -> Option<Path<'_, T>> {
+        let goals = goals
+            .iter()
+            .copied()
+            .filter(|&pos| self.is_inside(pos))
+            .collect::<Vec<_>>();
+        if !self.is_inside(from) || goals.is_empty() {
+            return None;
+        }
+        if let [to] = goals[..] {
+            return self.walk_bidirectional(from, to);
+        }
 
-        // The room positions pending evaluation and their cost
-        let mut open_set = OpenSet::new(self.width(), self.height());
-        open_set.push(Priority(f32::MAX), start);
+        // The heuristic is the number of steps required to reach the closest
+        // goal if no walls existed
+        let rooms = monomorphise!(self.shape(), S => search(
+            MatrixStore::new(self.width(), self.height()),
+            OpenSet::with_store(MatrixStore::new(self.width(), self.height())),
+            from,
+            |pos| goals.contains(&pos),
+            |pos| {
+                S::walls(pos)
+                    .iter()
+                    .filter(move |&&wall| self.is_open((pos, wall)))
+                    .map(move |&wall| S::back((pos, wall)).0)
+                    .filter(|&next| self.is_inside(next))
+            },
+            |_, _| 1.0,
+            |pos| {
+                goals
+                    .iter()
+                    .map(|&goal| S::graph_distance_lower_bound(pos, goal))
+                    .min()
+                    .unwrap_or(0) as f32
+            },
+        ))?;
+
+        let mut path = Matrix::<Room>::new(self.width(), self.height());
+        for pair in rooms.windows(2) {
+            path[pair[0]].came_from = Some(pair[1]);
+        }
 
-        let mut rooms = Matrix::<Room>::new(self.width(), self.height());
-        rooms[start].g = Priority(0.0);
-        rooms[start].f = h(start);
+        Some(Path::new(self, *rooms.last().unwrap(), from, path))
+    }
 
-        while let Some(current) = open_set.pop() {
-            // Have we reached the target?
-            if current == end {
-                return Some(Path::new(self, start, end, rooms));
+    /// Walks from `from` through a sequence of waypoints, in order.
+    ///
+    /// The return value is one path for each waypoint; the first path leads
+    /// from `from` to the first waypoint, and every following path leads from
+    /// the previous waypoint to the next one. If any waypoint is unreachable,
+    /// nothing is returned.
+    ///
+    /// # Arguments
+    /// *  `from` - The starting position.
+    /// *  `waypoints` - The positions to visit.
+    pub fn walk_visiting(
+        &self,
+        from: matrix::Pos,
+        waypoints: &[matrix::Pos],
+    ) -> Option<Vec<Path<'_, T>>> {
+        let mut current = from;
+        waypoints
+            .iter()
+            .map(|&waypoint| {
+                let path = self.walk(current, waypoint);
+                current = waypoint;
+                path
+            })
+            .collect()
+    }
+
+    /// Walks from `from` to `to` along the shortest path using a
+    /// bidirectional search.
+    ///
+    /// The searches from both ends continue after they first meet, until no
+    /// room remaining in either open set can lead to a shorter path than the
+    /// best one found, so the returned path is always a shortest one. In
+    /// large mazes this visits considerably fewer rooms than a search from
+    /// one end.
+    ///
+    /// # Arguments
+    /// *  `from` - The starting position.
+    /// *  `to` - The desired goal.
+    fn walk_bidirectional(
+        &self,
+        from: matrix::Pos,
+        to: matrix::Pos,
+    ) -> Option<Path<'_, T>> {
+        if !self.is_inside(from) || !self.is_inside(to) {
+            return None;
+        }
+
+        // The heuristic is the number of steps required if no walls existed
+        let shape = self.shape();
+        let h = |pos: matrix::Pos, target: matrix::Pos| {
+            shape.graph_distance_lower_bound(pos, target) as f32
+        };
+
+        let mut forward = Frontier::new(self, from, h(from, to));
+        let mut backward = Frontier::new(self, to, h(to, from));
+
+        // The room through which the shortest known path passes, and its
+        // length
+        let mut best = if from == to { Some((from, 0)) } else { None };
+
+        // Stop once neither search can find a shorter path
+        while let (Some(forward_min), Some(backward_min)) =
+            (forward.min_priority(), backward.min_priority())
+        {
+            if let Some((_, length)) = best {
+                if forward_min >= length as f32 && backward_min >= length as f32
+                {
+                    break;
+                }
             }
 
-            rooms[current].visited = true;
-            for wall in self.doors(current) {
-                // Find the next room, and continue if we have already evaluated
-                // it to a better distance, or it is outside of the maze
-                let (next, _) = self.back((current, wall));
+            // Expand the smallest frontier
+            let (frontier, other, target) =
+                if forward.open.len() <= backward.open.len() {
+                    (&mut forward, &backward, to)
+                } else {
+                    (&mut backward, &forward, from)
+                };
+            let current = match frontier.pop() {
+                Some(current) => current,
+                None => continue,
+            };
+
+            let distance = frontier.distance(current).unwrap_or(0) + 1;
+            for next in self.neighbors(current) {
                 if !self.is_inside(next)
-                    || (rooms[next].visited
-                        && rooms[next].g > rooms[current].g + distance)
+                    || frontier
+                        .distance(next)
+                        .map(|d| d <= distance)
+                        .unwrap_or(false)
                 {
                     continue;
                 }
 
-                // The cost to get to this room is one more that the room from
-                // which we came
-                let g = rooms[current].g + distance;
-                let f = g + h(next);
-
-                let current_in_open_set = open_set.contains(current);
-                if !current_in_open_set || g >= rooms[current].g {
-                    rooms[next].g = g;
-                    rooms[next].f = f;
-                    rooms[next].came_from = Some(current);
-
-                    if !current_in_open_set {
-                        open_set.push(f, next);
+                frontier.push(current, next, distance, h(next, target));
+                if let Some(remaining) = other.distance(next) {
+                    let length = distance + remaining;
+                    if best.map(|(_, best)| length < best).unwrap_or(true) {
+                        best = Some((next, length));
                     }
                 }
             }
         }
 
-        None
+        let (meeting, _) = best?;
+
+        // Link the rooms from the meeting point towards the goal, and then
+        // from the start towards the meeting point
+        let mut rooms = Matrix::<Room>::new(self.width(), self.height());
+        let mut current = meeting;
+        while current != to {
+            let next = backward.came_from(current)?;
+            rooms[current].came_from = Some(next);
+            current = next;
+        }
+        let mut current = meeting;
+        while current != from {
+            let previous = forward.came_from(current)?;
+            rooms[previous].came_from = Some(current);
+            current = previous;
+        }
+
+        Some(Path::new(self, to, from, rooms))
     }
 
     /// Follows a wall.
@@ -136,6 +300,268 @@ where
     ) -> impl Iterator<Item = FollowWallItem> + '_ {
         Follower::new(self, wall_pos)
     }
+
+    /// Visits all rooms reachable from a room in breadth-first order.
+    ///
+    /// The items are the tuple `(pos, distance)`, where `distance` is the
+    /// number of doors passed through to reach `pos` from `from`. Rooms are
+    /// yielded in order of increasing distance, starting with `from` itself.
+    ///
+    /// If `from` is outside of the maze, the iterator will contain no
+    /// elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// # let maze = maze::Shape::Hex.create::<u32>(5, 5)
+    /// #     .initialize(
+    /// #         maze::initialize::Method::Winding(Default::default()),
+    /// #         &mut maze::initialize::LFSR::new(12345),
+    /// #     );
+    ///
+    /// let (furthest, distance) = maze
+    ///     .flood(matrix::Pos { col: 0, row: 0 })
+    ///     .last()
+    ///     .unwrap();
+    /// println!("{:?} is {} rooms away", furthest, distance);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `from` - The starting position.
+    pub fn flood(
+        &self,
+        from: matrix::Pos,
+    ) -> impl Iterator<Item = (matrix::Pos, u32)> + '_ {
+        Flood::new(self, from)
+    }
+}
+
+/// Finds the cheapest path from a starting node to a goal using the _A*_
+/// algorithm.
+///
+/// This function is generic over the node type, so it can be used for custom
+/// searches, such as searches where moving between rooms has different costs.
+///
+/// The return value is the list of nodes from `start` to the goal, inclusive.
+/// If no goal is reachable, nothing is returned.
+///
+/// # Example
+///
+/// ```
+/// # use maze::walk::astar;
+/// // Find the way from 0 to 7 when only steps of 2 and 3 are allowed
+/// let path = astar(
+///     0i32,
+///     |n| n == 7,
+///     |n| [n + 2, n + 3].into_iter().filter(|&n| n <= 7),
+///     |_, _| 1.0,
+///     |n| (7 - n) as f32 / 3.0,
+/// );
+/// assert_eq!(path.map(|path| path.len()), Some(4));
+/// ```
+///
+/// # Arguments
+/// *  `start` - The starting node.
+/// *  `goal` - A predicate determining whether a node is a goal.
+/// *  `neighbors` - A function returning the nodes reachable from a node.
+/// *  `cost` - The cost of moving from one node to a neighbouring node. This
+///    must never be negative.
+/// *  `heuristic` - An estimate of the remaining cost from a node to a goal.
+///    If this never overestimates the cost, the returned path will be the
+///    cheapest one.
+///
+/// If `cost` or `heuristic` returns `NaN`, the search does not panic, but the
+/// returned path is not guaranteed to be the cheapest one.
+pub fn astar<P, G, N, I, C, H>(
+    start: P,
+    goal: G,
+    neighbors: N,
+    cost: C,
+    heuristic: H,
+) -> Option<Vec<P>>
+where
+    P: Copy + Eq + Hash,
+    G: FnMut(P) -> bool,
+    N: FnMut(P) -> I,
+    I: IntoIterator<Item = P>,
+    C: FnMut(P, P) -> f32,
+    H: FnMut(P) -> f32,
+{
+    search(
+        HashMap::new(),
+        OpenSet::new(),
+        start,
+        goal,
+        neighbors,
+        cost,
+        heuristic,
+    )
+}
+
+/// Performs an A* search using specific storage for the bookkeeping.
+///
+/// See [`astar`] for a description of the arguments.
+///
+/// # Arguments
+/// *  `scores` - The storage for the cost of the cheapest known path to every
+///    node, and the node from which it was reached.
+/// *  `open_set` - The open set.
+fn search<P, S, O, G, N, I, C, H>(
+    mut scores: S,
+    mut open_set: OpenSet<P, O>,
+    start: P,
+    mut goal: G,
+    mut neighbors: N,
+    mut cost: C,
+    mut heuristic: H,
+) -> Option<Vec<P>>
+where
+    P: Copy + Eq + Hash,
+    S: Store<P, (f32, Option<P>)>,
+    O: Store<P, Priority>,
+    G: FnMut(P) -> bool,
+    N: FnMut(P) -> I,
+    I: IntoIterator<Item = P>,
+    C: FnMut(P, P) -> f32,
+    H: FnMut(P) -> f32,
+{
+    scores.insert(start, (0.0, None));
+    open_set.push(Priority(heuristic(start)), start);
+
+    while let Some(current) = open_set.pop() {
+        // Have we reached the target?
+        if goal(current) {
+            let mut result = vec![current];
+            while let Some((_, Some(previous))) =
+                scores.get(*result.last().unwrap())
+            {
+                result.push(previous);
+            }
+            result.reverse();
+            return Some(result);
+        }
+
+        let (current_g, _) = scores.get(current).unwrap();
+        for next in neighbors(current) {
+            // Update the neighbour only if this path to it is cheaper than
+            // any previously known path
+            let next_g = current_g + cost(current, next);
+            if scores
+                .get(next)
+                .map(|(old, _)| next_g < old)
+                .unwrap_or(true)
+            {
+                scores.insert(next, (next_g, Some(current)));
+                open_set.push(Priority(next_g + heuristic(next)), next);
+            }
+        }
+    }
+
+    None
+}
+
+/// Storage for values associated with nodes during a search.
+trait Store<P, V> {
+    /// The value of a node, if any.
+    ///
+    /// # Arguments
+    /// *  `pos` - The node.
+    fn get(&self, pos: P) -> Option<V>;
+
+    /// Sets the value of a node.
+    ///
+    /// # Arguments
+    /// *  `pos` - The node.
+    /// *  `value` - The new value.
+    fn insert(&mut self, pos: P, value: V);
+
+    /// Removes the value of a node.
+    ///
+    /// # Arguments
+    /// *  `pos` - The node.
+    fn remove(&mut self, pos: P);
+}
+
+impl<P, V> Store<P, V> for HashMap<P, V>
+where
+    P: Copy + Eq + Hash,
+    V: Copy,
+{
+    fn get(&self, pos: P) -> Option<V> {
+        HashMap::get(self, &pos).copied()
+    }
+
+    fn insert(&mut self, pos: P, value: V) {
+        HashMap::insert(self, pos, value);
+    }
+
+    fn remove(&mut self, pos: P) {
+        HashMap::remove(self, &pos);
+    }
+}
+
+/// Storage for values associated with the rooms of a maze.
+///
+/// Values for rooms inside of the maze are kept in a matrix, which is
+/// considerably faster than hashing positions; values for positions outside
+/// of the maze are kept in a hash map.
+struct MatrixStore<V>
+where
+    V: Copy,
+{
+    /// The values of rooms inside of the maze.
+    inside: Matrix<Option<V>>,
+
+    /// The values of positions outside of the maze.
+    outside: HashMap<matrix::Pos, V>,
+}
+
+impl<V> MatrixStore<V>
+where
+    V: Copy,
+{
+    /// Creates an empty store for a maze.
+    ///
+    /// # Arguments
+    /// *  `width` - The width of the maze.
+    /// *  `height` - The height of the maze.
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            inside: Matrix::new(width, height),
+            outside: HashMap::new(),
+        }
+    }
+}
+
+impl<V> Store<matrix::Pos, V> for MatrixStore<V>
+where
+    V: Copy,
+{
+    fn get(&self, pos: matrix::Pos) -> Option<V> {
+        match self.inside.get(pos) {
+            Some(value) => *value,
+            None => self.outside.get(&pos).copied(),
+        }
+    }
+
+    fn insert(&mut self, pos: matrix::Pos, value: V) {
+        match self.inside.get_mut(pos) {
+            Some(current) => *current = Some(value),
+            None => {
+                self.outside.insert(pos, value);
+            }
+        }
+    }
+
+    fn remove(&mut self, pos: matrix::Pos) {
+        match self.inside.get_mut(pos) {
+            Some(current) => *current = None,
+            None => {
+                self.outside.remove(&pos);
+            }
+        }
+    }
 }
 
 /// A path through a maze.
@@ -182,6 +608,93 @@ where
             b: start,
         }
     }
+
+    /// The number of rooms in this path, including the start and end rooms.
+    ///
+    /// # Panics
+    /// If the backing room matrix is incomplete.
+    pub fn len(&self) -> usize {
+        self.into_iter().count()
+    }
+
+    /// Whether this path contains no rooms.
+    ///
+    /// A path always contains at least the start room, so this is always
+    /// `false`.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// The physical length of this path.
+    ///
+    /// This is the sum of the distances between the centres of consecutive
+    /// rooms.
+    ///
+    /// # Panics
+    /// If the backing room matrix is incomplete.
+    pub fn physical_length(&self) -> f32 {
+        self.into_iter()
+            .map(|pos| self.maze.center(pos))
+            .collect::<Vec<_>>()
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).value().sqrt())
+            .sum()
+    }
+
+    /// The number of turns along this path.
+    ///
+    /// A turn is a room where the direction in which the path leaves differs
+    /// from the direction in which it entered. Triangular rooms have only one
+    /// vertical neighbour, so in such mazes every vertical step is a turn.
+    ///
+    /// # Panics
+    /// If the backing room matrix is incomplete.
+    pub fn turn_count(&self) -> usize {
+        let shape = self.maze.shape();
+        self.into_iter()
+            .collect::<Vec<_>>()
+            .windows(3)
+            .filter(|rooms| {
+                shape.step_direction(rooms[0], rooms[1])
+                    != shape.step_direction(rooms[1], rooms[2])
+            })
+            .count()
+    }
+
+    /// Backtraces this path by following the `came_from` fields.
+    ///
+    /// This is the fallible variant of iterating over this path.
+    pub fn try_positions(&self) -> Result<Vec<matrix::Pos>, Error> {
+        let (a, b) = (self.a, self.b);
+        let mut result = vec![a];
+
+        let mut current = a;
+        while current != b {
+            let next = self
+                .rooms
+                .get(current)
+                .ok_or(Error::OutOfBounds(current))?
+                .came_from
+                .ok_or(Error::IncompletePath(current))?;
+            result.push(next);
+            current = next;
+        }
+
+        Ok(result)
+    }
+
+    /// Iterates over all doors passed through when following this path.
+    ///
+    /// The wall positions are in the room being left.
+    ///
+    /// # Panics
+    /// If the backing room matrix is incomplete.
+    pub fn doors(&self) -> impl Iterator<Item = WallPos> + '_ {
+        let rooms = self.into_iter().collect::<Vec<_>>();
+        (1..rooms.len()).filter_map(move |i| {
+            self.maze.connecting_wall(rooms[i - 1], rooms[i])
+        })
+    }
 }
 
 impl<'a, T> IntoIterator for &'a Path<'a, T>
@@ -193,68 +706,308 @@ where
 
     /// Backtraces a path by following the `came_from` fields.
     ///
-    /// To generate
+    /// # Panics
+    /// If the backing room matrix is incomplete. Use
+    /// [`try_positions`](Path::try_positions) to avoid this.
+    fn into_iter(self) -> Self::IntoIter {
+        self.try_positions()
+            .unwrap_or_else(|e| panic!("failed to backtrace path: {}", e))
+            .into_iter()
+    }
+}
+
+/// A strategy used by an [`Agent`] to choose the next room.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Strategy {
+    /// Moves through a random door.
+    RandomWalk,
+
+    /// Keeps its left hand on the wall.
+    ///
+    /// In a maze without loops, this visits every room and returns to the
+    /// start after passing every door twice.
+    WallFollower,
+
+    /// Marks every door passed, and never passes a door more than twice.
+    ///
+    /// This is _Trémaux's algorithm_. When all rooms reachable from the start
+    /// have been explored, the agent has returned to the start, and it will
+    /// not move any more.
+    Tremaux,
+}
+
+/// An agent moving through a maze.
+///
+/// The agent moves one room at a time, and keeps track of the state required
+/// by its strategy.
+#[derive(Clone, Debug)]
+pub struct Agent {
+    /// The current room.
+    pos: matrix::Pos,
+
+    /// The strategy used to choose the next room.
+    strategy: Strategy,
+
+    /// The wall of the current room through which it was entered.
+    entry: Option<&'static wall::Wall>,
+
+    /// Whether the current room had been visited before it was last entered.
+    revisited: bool,
+
+    /// The number of times every door has been passed.
+    ///
+    /// Both sides of a door are marked.
+    marks: HashMap<WallPos, usize>,
+
+    /// All rooms visited.
+    visited: HashSet<matrix::Pos>,
+
+    /// The number of steps taken.
+    steps: usize,
+}
+
+impl Agent {
+    /// Creates an agent in a room.
+    ///
+    /// # Arguments
+    /// *  `pos` - The starting room.
+    /// *  `strategy` - The strategy used to choose the next room.
+    pub fn new(pos: matrix::Pos, strategy: Strategy) -> Self {
+        Self {
+            pos,
+            strategy,
+            entry: None,
+            revisited: false,
+            marks: HashMap::new(),
+            visited: [pos].into_iter().collect(),
+            steps: 0,
+        }
+    }
+
+    /// The current room.
+    pub fn pos(&self) -> matrix::Pos {
+        self.pos
+    }
+
+    /// The strategy used to choose the next room.
+    pub fn strategy(&self) -> Strategy {
+        self.strategy
+    }
+
+    /// The number of steps taken.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Whether a room has been visited.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn has_visited(&self, pos: matrix::Pos) -> bool {
+        self.visited.contains(&pos)
+    }
+
+    /// The number of times a door has been passed, in either direction.
     ///
     /// # Arguments
-    /// *  `start` - The starting position.
-    /// *  `end` - The end position.
+    /// *  `wall_pos` - The wall position.
+    pub fn marks(&self, wall_pos: WallPos) -> usize {
+        self.marks.get(&wall_pos).copied().unwrap_or(0)
+    }
+
+    /// Moves the agent to the next room.
     ///
-    /// # Panics
-    /// If the backing room matrix is incomplete.
-    fn into_iter(self) -> Self::IntoIter {
-        let (a, b) = (self.a, self.b);
-        let mut result = Vec::new();
-        result.push(a);
+    /// Only doors leading to rooms inside of the maze are passed. If the
+    /// agent cannot move, nothing is returned, otherwise the new room.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::initialize;
+    /// # use maze::matrix;
+    /// # use maze::walk::*;
+    /// let maze = maze::Shape::Quad.create::<()>(5, 5).initialize(
+    ///     initialize::Method::Winding(initialize::Bias::default()),
+    ///     &mut initialize::LFSR::new(12345),
+    /// );
+    /// let start = matrix::Pos { col: 0, row: 0 };
+    /// let mut agent = Agent::new(start, Strategy::Tremaux);
+    ///
+    /// let mut rng = initialize::LFSR::new(12345);
+    /// while agent.step(&maze, &mut rng).is_some() {}
+    /// assert_eq!(agent.pos(), start);
+    /// assert!(maze.positions().all(|pos| agent.has_visited(pos)));
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze through which to move.
+    /// *  `rng` - A random number generator.
+    pub fn step<T, R>(
+        &mut self,
+        maze: &Maze<T>,
+        rng: &mut R,
+    ) -> Option<matrix::Pos>
+    where
+        T: Clone,
+        R: Randomizer + ?Sized,
+    {
+        let pos = self.pos;
+        let doors = maze
+            .doors(pos)
+            .filter(|&wall| maze.is_inside(maze.back((pos, wall)).0))
+            .collect::<Vec<_>>();
+        let wall = match self.strategy {
+            Strategy::RandomWalk => {
+                (!doors.is_empty()).then(|| doors[rng.range(0, doors.len())])
+            }
+            Strategy::WallFollower => self.follow_wall(maze, &doors),
+            Strategy::Tremaux => self.tremaux(&doors, rng),
+        }?;
+
+        let back = maze.back((pos, wall));
+        *self.marks.entry((pos, wall)).or_default() += 1;
+        *self.marks.entry(back).or_default() += 1;
+        self.revisited = !self.visited.insert(back.0);
+        self.pos = back.0;
+        self.entry = Some(back.1);
+        self.steps += 1;
+
+        Some(self.pos)
+    }
 
-        let mut current = a;
-        while current != b {
-            if let Some(next) = self.rooms[current].came_from {
-                result.push(next);
-                if current == b {
-                    break;
-                } else {
-                    current = next;
-                }
-            } else {
-                panic!("attempted to backtrace an incomplete path!");
+    /// Chooses the next door for the wall follower strategy.
+    ///
+    /// Doors are considered clock-wise, starting with the one following the
+    /// entry.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze through which to move.
+    /// *  `doors` - The doors of the current room.
+    fn follow_wall<T>(
+        &self,
+        maze: &Maze<T>,
+        doors: &[&'static wall::Wall],
+    ) -> Option<&'static wall::Wall>
+    where
+        T: Clone,
+    {
+        match self.entry {
+            Some(entry) => {
+                core::iter::successors(Some(entry.next), |wall| Some(wall.next))
+                    .take(maze.walls(self.pos).len())
+                    .find(|wall| doors.contains(wall))
             }
+            None => doors.first().copied(),
         }
+    }
 
-        result.into_iter()
+    /// Chooses the next door for the Trémaux strategy.
+    ///
+    /// # Arguments
+    /// *  `doors` - The doors of the current room.
+    /// *  `rng` - A random number generator.
+    fn tremaux<R>(
+        &self,
+        doors: &[&'static wall::Wall],
+        rng: &mut R,
+    ) -> Option<&'static wall::Wall>
+    where
+        R: Randomizer + ?Sized,
+    {
+        let marks = |wall| self.marks((self.pos, wall));
+
+        // When entering an explored room through a new door, turn back
+        if let Some(entry) = self.entry.filter(|&entry| marks(entry) == 1) {
+            if self.revisited {
+                return Some(entry);
+            }
+        }
+
+        // Prefer the least used door, and only go back if no other exists
+        let candidates = doors
+            .iter()
+            .copied()
+            .filter(|&wall| Some(wall) != self.entry && marks(wall) < 2)
+            .collect::<Vec<_>>();
+        match candidates.iter().map(|&wall| marks(wall)).min() {
+            Some(min) => {
+                let best = candidates
+                    .into_iter()
+                    .filter(|&wall| marks(wall) == min)
+                    .collect::<Vec<_>>();
+                Some(best[rng.range(0, best.len())])
+            }
+            None => self.entry.filter(|&entry| marks(entry) < 2),
+        }
     }
 }
 
-/// A rooms description for the walk algorithm.
-#[derive(Clone)]
+/// A rooms description for a path.
+#[derive(Clone, Default)]
 struct Room {
-    /// The F score.
-    ///
-    ///This is the cost from start to a room along the best known path
-    f: Priority,
+    /// The next room on the path.
+    came_from: Option<matrix::Pos>,
+}
 
-    /// The G score.
-    ///
-    /// This is the estimated cost from start to end through a room.
-    g: Priority,
+/// Visits rooms in breadth-first order.
+struct Flood<'a, T>
+where
+    T: Clone,
+{
+    /// The maze.
+    maze: &'a Maze<T>,
+
+    /// The rooms pending a visit, and their distances.
+    queue: VecDeque<(matrix::Pos, u32)>,
 
-    /// Whether the rooms has been visited.
-    visited: bool,
+    /// The rooms already added to the queue.
+    seen: Matrix<bool>,
+}
 
-    /// The room from which we came.
+impl<'a, T> Flood<'a, T>
+where
+    T: Clone,
+{
+    /// Prepares a flood fill.
     ///
-    /// When the algorithm has competed, this will be the room on the shortest
-    /// path.
-    came_from: Option<matrix::Pos>,
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `from` - The starting position.
+    fn new(maze: &'a Maze<T>, from: matrix::Pos) -> Self {
+        let mut seen = Matrix::new(maze.width(), maze.height());
+        let mut queue = VecDeque::new();
+        if let Some(s) = seen.get_mut(from) {
+            *s = true;
+            queue.push_back((from, 0));
+        }
+
+        Self { maze, queue, seen }
+    }
 }
 
-impl Default for Room {
-    fn default() -> Self {
-        Room {
-            f: Priority(f32::MAX),
-            g: Priority(f32::MAX),
-            visited: false,
-            came_from: None,
+impl<'a, T> Iterator for Flood<'a, T>
+where
+    T: Clone,
+{
+    type Item = (matrix::Pos, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (pos, distance) = self.queue.pop_front()?;
+        for next in self.maze.neighbors(pos) {
+            if let Some(seen) = self.seen.get_mut(next) {
+                if !*seen {
+                    *seen = true;
+                    self.queue.push_back((next, distance + 1));
+                }
+            }
         }
+
+        Some((pos, distance))
     }
 }
 
@@ -334,19 +1087,118 @@ where
     }
 }
 
+/// One direction of a bidirectional search.
+struct Frontier {
+    /// The rooms pending evaluation.
+    open: BinaryHeap<PriorityPos<matrix::Pos>>,
+
+    /// The room from which each room was reached, and the distance from the
+    /// origin.
+    seen: Matrix<Option<(matrix::Pos, u32)>>,
+
+    /// The rooms already evaluated.
+    closed: BitSet,
+}
+
+impl Frontier {
+    /// Creates a new frontier.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze being searched.
+    /// *  `origin` - The origin of the search.
+    /// *  `estimate` - The estimated distance from the origin to the target.
+    pub fn new<T>(maze: &Maze<T>, origin: matrix::Pos, estimate: f32) -> Self
+    where
+        T: Clone,
+    {
+        let mut seen = Matrix::new(maze.width(), maze.height());
+        seen[origin] = Some((origin, 0));
+        let mut open = BinaryHeap::new();
+        open.push(PriorityPos(Priority(estimate), origin));
+
+        Self {
+            open,
+            seen,
+            closed: BitSet::with_capacity(maze.width() * maze.height()),
+        }
+    }
+
+    /// The lowest priority in the open set, if it is not empty.
+    ///
+    /// The priority is the distance from the origin plus the estimated
+    /// remaining distance to the target. Rooms already evaluated may remain
+    /// in the open set, so this is never higher than the priority of the next
+    /// room to evaluate.
+    pub fn min_priority(&self) -> Option<f32> {
+        self.open
+            .peek()
+            .map(|PriorityPos(Priority(priority), _)| *priority)
+    }
+
+    /// Pops the room with the lowest estimate.
+    ///
+    /// If the room has already been evaluated, nothing is returned.
+    pub fn pop(&mut self) -> Option<matrix::Pos> {
+        let PriorityPos(_, pos) = self.open.pop()?;
+        if self
+            .closed
+            .insert(pos.col as usize + pos.row as usize * self.seen.width)
+        {
+            Some(pos)
+        } else {
+            None
+        }
+    }
+
+    /// Records that a room has been reached.
+    ///
+    /// # Arguments
+    /// *  `from` - The room from which `pos` was reached.
+    /// *  `pos` - The room reached.
+    /// *  `distance` - The distance from the origin to `pos`.
+    /// *  `estimate` - The estimated remaining distance to the target.
+    pub fn push(
+        &mut self,
+        from: matrix::Pos,
+        pos: matrix::Pos,
+        distance: u32,
+        estimate: f32,
+    ) {
+        self.seen[pos] = Some((from, distance));
+        self.open
+            .push(PriorityPos(Priority(distance as f32 + estimate), pos));
+    }
+
+    /// The distance from the origin to a room, if it has been reached.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn distance(&self, pos: matrix::Pos) -> Option<u32> {
+        self.seen[pos].map(|(_, distance)| distance)
+    }
+
+    /// The room from which a room was reached, if it has been reached.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn came_from(&self, pos: matrix::Pos) -> Option<matrix::Pos> {
+        self.seen[pos].map(|(from, _)| from)
+    }
+}
+
 /// A priority in an open set.
 #[derive(Clone, Copy)]
 struct Priority(f32);
 
-impl ::std::cmp::PartialEq for Priority {
+impl core::cmp::PartialEq for Priority {
     fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+        self.cmp(other) == core::cmp::Ordering::Equal
     }
 }
 
-impl ::std::cmp::Eq for Priority {}
+impl core::cmp::Eq for Priority {}
 
-impl ::std::cmp::PartialOrd for Priority {
+impl core::cmp::PartialOrd for Priority {
     /// Compares priorities.
     ///
     /// Note that this operation is the inverse of comparing the wrapped `f32`
@@ -354,116 +1206,127 @@ impl ::std::cmp::PartialOrd for Priority {
     ///
     /// # Arguments
     /// *  `other` - The other value.
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl ::std::cmp::Ord for Priority {
-    fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
-        other.0.partial_cmp(&self.0).expect("comparable priorities")
+impl core::cmp::Ord for Priority {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        // Use a total order, since costs and heuristics may be NaN
+        other.0.total_cmp(&self.0)
     }
 }
 
-impl ::std::ops::Add<f32> for Priority {
-    type Output = Self;
+/// A position with a priority.
+///
+/// Positions with priorities are ordered by priority only.
+struct PriorityPos<P>(Priority, P);
 
-    fn add(self, rhs: f32) -> Self::Output {
-        Self(self.0 + rhs)
+impl<P> core::cmp::PartialEq for PriorityPos<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
     }
 }
 
-impl ::std::ops::Add<Priority> for Priority {
-    type Output = Self;
+impl<P> core::cmp::Eq for PriorityPos<P> {}
 
-    fn add(self, rhs: Priority) -> Self::Output {
-        Self(self.0 + rhs.0)
+impl<P> core::cmp::PartialOrd for PriorityPos<P> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-/// A room position with a priority.
-type PriorityPos = (Priority, matrix::Pos);
+impl<P> core::cmp::Ord for PriorityPos<P> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
 
-/// A set of rooms and priorities.
+/// A set of positions and priorities.
 ///
 /// This struct supports adding a position with a priority, retrieving the
 /// position with the highest priority and querying whether a position is in the
 /// set.
-struct OpenSet {
-    /// The width of the set.
-    width: usize,
-
-    /// The height of the set.
-    height: usize,
-
+///
+/// Adding a position already in the set with a higher priority updates its
+/// priority.
+struct OpenSet<P, S = HashMap<P, Priority>> {
     /// The heap containing prioritised positions.
-    heap: BinaryHeap<PriorityPos>,
+    ///
+    /// This may contain stale entries for positions whose priorities have
+    /// been updated; these are ignored when popped.
+    heap: BinaryHeap<PriorityPos<P>>,
 
-    /// The positions present in the heap.
-    present: BitSet,
+    /// The current priorities of the positions present in the set.
+    present: S,
 }
 
-impl OpenSet {
+impl<P> OpenSet<P>
+where
+    P: Copy + Eq + Hash,
+{
     /// Creates a new open set.
-    pub fn new(width: usize, height: usize) -> OpenSet {
-        OpenSet {
-            width,
-            height,
+    pub fn new() -> Self {
+        Self::with_store(HashMap::new())
+    }
+}
+
+impl<P, S> OpenSet<P, S>
+where
+    P: Copy + Eq + Hash,
+    S: Store<P, Priority>,
+{
+    /// Creates a new open set keeping the priorities in a specific store.
+    ///
+    /// # Arguments
+    /// *  `present` - The store for the priorities of present positions.
+    fn with_store(present: S) -> Self {
+        Self {
             heap: BinaryHeap::new(),
-            present: BitSet::with_capacity(width * height),
+            present,
         }
     }
 
     /// Adds a position with a priority.
     ///
+    /// If the position is already present with a higher priority, this
+    /// method does nothing.
+    ///
     /// # Arguments
-    /// *  priority` - The priority of the position.
-    /// *  pos` - The position.
-    pub fn push(&mut self, priority: Priority, pos: matrix::Pos) {
-        if let Some(index) = self.index(pos) {
-            self.heap.push((priority, pos));
-            self.present.insert(index);
+    /// *  `priority` - The priority of the position.
+    /// *  `pos` - The position.
+    pub fn push(&mut self, priority: Priority, pos: P) {
+        if self
+            .present
+            .get(pos)
+            .map(|current| priority > current)
+            .unwrap_or(true)
+        {
+            self.present.insert(pos, priority);
+            self.heap.push(PriorityPos(priority, pos));
         }
     }
 
-    /// Pops the room with the highest priority.
-    pub fn pop(&mut self) -> Option<matrix::Pos> {
-        if let Some(pos) = self.heap.pop().map(|(_, pos)| pos) {
-            if let Some(index) = self.index(pos) {
-                self.present.remove(index);
+    /// Pops the position with the highest priority.
+    pub fn pop(&mut self) -> Option<P> {
+        while let Some(PriorityPos(priority, pos)) = self.heap.pop() {
+            if self.present.get(pos) == Some(priority) {
+                self.present.remove(pos);
+                return Some(pos);
             }
-            Some(pos)
-        } else {
-            None
         }
-    }
 
-    /// Checks whether a position is in the set.
-    ///
-    /// # Arguments
-    /// *  `pos` - The position.
-    pub fn contains(&mut self, pos: matrix::Pos) -> bool {
-        self.index(pos)
-            .map(|i| self.present.contains(i))
-            .unwrap_or(false)
+        None
     }
 
-    /// Calculates the index of a position.
-    ///
-    /// If the position is outside of this set, nothing is returned.
+    /// Checks whether a position is in the set.
     ///
     /// # Arguments
     /// *  `pos` - The position.
-    fn index(&self, pos: matrix::Pos) -> Option<usize> {
-        if pos.col >= 0
-            && pos.row >= 0
-            && pos.col < self.width as isize
-            && pos.row < self.height as isize
-        {
-            Some(pos.col as usize + pos.row as usize * self.width)
-        } else {
-            None
-        }
+    #[cfg(test)]
+    pub fn contains(&self, pos: P) -> bool {
+        self.present.get(pos).is_some()
     }
 }
 
@@ -472,14 +1335,12 @@ mod tests {
     use maze_test::maze_test;
 
     use super::*;
+    use crate::initialize;
     use crate::test_utils::*;
 
     #[maze_test]
     fn walk_single(maze: TestMaze) {
-        let map = Matrix::<Room>::new_with_data(10, 10, |_| Room {
-            f: Priority(0.0),
-            ..Default::default()
-        });
+        let map = Matrix::<Room>::new(10, 10);
 
         assert_eq!(
             Path::new(&maze, matrix_pos(0, 0), matrix_pos(0, 0), map)
@@ -491,10 +1352,7 @@ mod tests {
 
     #[maze_test]
     fn walk_path(maze: TestMaze) {
-        let mut map = Matrix::<Room>::new_with_data(10, 10, |_| Room {
-            f: Priority(0.0),
-            ..Default::default()
-        });
+        let mut map = Matrix::<Room>::new(10, 10);
         map[matrix_pos(1, 1)].came_from = Some(matrix_pos(2, 2));
         map[matrix_pos(2, 2)].came_from = Some(matrix_pos(2, 3));
         map[matrix_pos(2, 3)].came_from = Some(matrix_pos(2, 4));
@@ -517,6 +1375,40 @@ mod tests {
         assert!(maze.walk(matrix_pos(0, 0), matrix_pos(0, 1)).is_none());
     }
 
+    #[maze_test]
+    fn try_walk_errors(maze: TestMaze) {
+        let inside = matrix_pos(0, 0);
+        let outside = matrix_pos(-1, 0);
+        assert_eq!(
+            Some(Error::OutOfBounds(outside)),
+            maze.try_walk(outside, inside).err(),
+        );
+        assert_eq!(
+            Some(Error::OutOfBounds(outside)),
+            maze.try_walk(inside, outside).err(),
+        );
+        assert_eq!(
+            Some(Error::Unreachable {
+                from: inside,
+                to: matrix_pos(0, 1),
+            }),
+            maze.try_walk(inside, matrix_pos(0, 1)).err(),
+        );
+    }
+
+    #[maze_test]
+    fn try_positions_incomplete(maze: TestMaze) {
+        let from = matrix_pos(0, 0);
+        let to = matrix_pos(0, 1);
+        let path = Path::new(
+            &maze,
+            to,
+            from,
+            Matrix::new(maze.width(), maze.height()),
+        );
+        assert_eq!(Err(Error::IncompletePath(from)), path.try_positions());
+    }
+
     #[maze_test]
     fn walk_same(maze: TestMaze) {
         let from = matrix_pos(0, 0);
@@ -547,6 +1439,234 @@ mod tests {
         );
     }
 
+    #[maze_test]
+    fn path_len(mut maze: TestMaze) {
+        let log = Navigator::new(&mut maze).down(true).right(true).stop();
+
+        let path = maze.walk(log[0], *log.last().unwrap()).unwrap();
+        assert_eq!(path.len(), log.len());
+        assert!(!path.is_empty());
+    }
+
+    #[maze_test]
+    fn path_physical_length(mut maze: TestMaze) {
+        let log = Navigator::new(&mut maze).down(true).right(true).stop();
+
+        let path = maze.walk(log[0], *log.last().unwrap()).unwrap();
+        let expected = log
+            .windows(2)
+            .map(|pair| {
+                (maze.center(pair[1]) - maze.center(pair[0])).value().sqrt()
+            })
+            .sum::<f32>();
+        assert!(nearly_equal(path.physical_length(), expected));
+        assert!(nearly_equal(
+            maze.walk(log[0], log[0]).unwrap().physical_length(),
+            0.0,
+        ));
+    }
+
+    #[maze_test]
+    fn path_turn_count_straight(mut maze: TestMaze) {
+        let log = Navigator::new(&mut maze)
+            .right(true)
+            .right(true)
+            .right(true)
+            .stop();
+
+        let path = maze.walk(log[0], *log.last().unwrap()).unwrap();
+        assert_eq!(path.turn_count(), 0);
+        assert_eq!(maze.walk(log[0], log[0]).unwrap().turn_count(), 0);
+    }
+
+    #[maze_test(quad, hex)]
+    fn path_turn_count(mut maze: TestMaze) {
+        let log = Navigator::new(&mut maze)
+            .right(true)
+            .right(true)
+            .down(true)
+            .right(true)
+            .stop();
+
+        let path = maze.walk(log[0], *log.last().unwrap()).unwrap();
+        assert_eq!(path.turn_count(), 2);
+    }
+
+    #[maze_test]
+    fn path_doors(mut maze: TestMaze) {
+        let log = Navigator::new(&mut maze).down(true).right(true).stop();
+
+        let path = maze.walk(log[0], *log.last().unwrap()).unwrap();
+        assert_eq!(
+            path.doors().collect::<Vec<_>>(),
+            log.windows(2)
+                .map(|pair| maze.connecting_wall(pair[0], pair[1]).unwrap())
+                .collect::<Vec<_>>(),
+        );
+        assert!(path.doors().all(|wall_pos| maze.is_open(wall_pos)));
+    }
+
+    #[maze_test]
+    fn walk_to_nearest_closest(mut maze: TestMaze) {
+        let log = Navigator::new(&mut maze)
+            .down(true)
+            .right(true)
+            .right(true)
+            .stop();
+
+        let from = log[0];
+        let path = maze
+            .walk_to_nearest(from, &[log[3], log[1], matrix_pos(9, 4)])
+            .unwrap();
+        assert_eq!(path.into_iter().collect::<Vec<_>>(), log[..2]);
+    }
+
+    #[maze_test]
+    fn walk_to_nearest_unreachable(mut maze: TestMaze) {
+        let log = Navigator::new(&mut maze).down(true).stop();
+
+        assert!(maze.walk_to_nearest(log[0], &[]).is_none());
+        assert!(maze
+            .walk_to_nearest(log[0], &[matrix_pos(9, 4), matrix_pos(-1, -1)])
+            .is_none());
+    }
+
+    #[maze_test]
+    fn walk_visiting_segments(mut maze: TestMaze) {
+        let log = Navigator::new(&mut maze)
+            .down(true)
+            .right(true)
+            .right(true)
+            .stop();
+
+        let paths = maze.walk_visiting(log[0], &[log[3], log[1]]).unwrap();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].into_iter().collect::<Vec<_>>(), log);
+        assert_eq!(
+            paths[1].into_iter().collect::<Vec<_>>(),
+            log[1..].iter().rev().copied().collect::<Vec<_>>(),
+        );
+        assert!(maze
+            .walk_visiting(log[0], &[log[3], matrix_pos(9, 4)])
+            .is_none());
+    }
+
+    /// Finds the shortest path between two rooms using a plain A* search.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `from` - The starting position.
+    /// *  `to` - The goal.
+    fn astar_path(
+        maze: &TestMaze,
+        from: matrix::Pos,
+        to: matrix::Pos,
+    ) -> Option<Vec<matrix::Pos>> {
+        astar(
+            from,
+            |pos| pos == to,
+            |pos| {
+                maze.neighbors(pos)
+                    .filter(|&next| maze.is_inside(next))
+                    .collect::<Vec<_>>()
+            },
+            |_, _| 1.0,
+            |_| 0.0,
+        )
+    }
+
+    #[maze_test]
+    fn walk_bidirectional_simple(mut maze: TestMaze) {
+        let log = Navigator::new(&mut maze)
+            .down(true)
+            .right(true)
+            .right(true)
+            .stop();
+
+        for (from, to) in [(log[0], log[3]), (log[3], log[0]), (log[1], log[1])]
+        {
+            assert_eq!(
+                maze.walk_bidirectional(from, to)
+                    .unwrap()
+                    .into_iter()
+                    .collect::<Vec<_>>(),
+                astar_path(&maze, from, to).unwrap(),
+            );
+        }
+        assert!(maze.walk_bidirectional(log[0], matrix_pos(9, 4)).is_none());
+        assert!(maze.walk_bidirectional(log[0], matrix_pos(-1, 0)).is_none());
+    }
+
+    #[maze_test]
+    fn walk_bidirectional_valid(maze: TestMaze) {
+        let maze = maze.initialize(
+            crate::initialize::Method::Braid,
+            &mut rand::thread_rng(),
+        );
+
+        for from in maze.positions() {
+            let to = matrix_pos(
+                maze.width() as isize - 1 - from.col,
+                maze.height() as isize - 1 - from.row,
+            );
+            let path = maze.walk_bidirectional(from, to).unwrap();
+            let rooms = path.into_iter().collect::<Vec<_>>();
+            assert_eq!(rooms.first(), Some(&from));
+            assert_eq!(rooms.last(), Some(&to));
+            assert!(rooms
+                .windows(2)
+                .all(|pair| pair[0] != pair[1]
+                    && maze.connected(pair[0], pair[1])));
+        }
+    }
+
+    #[maze_test]
+    fn walk_bidirectional_braid_shortest(maze: TestMaze) {
+        let maze = maze.initialize(
+            crate::initialize::Method::Braid,
+            &mut rand::thread_rng(),
+        );
+
+        for from in maze.positions() {
+            let to = matrix_pos(
+                maze.width() as isize - 1 - from.col,
+                maze.height() as isize - 1 - from.row,
+            );
+            assert_eq!(
+                maze.walk(from, to).unwrap().into_iter().count(),
+                astar_path(&maze, from, to).unwrap().len(),
+                "{:?} -> {:?}",
+                from,
+                to,
+            );
+        }
+    }
+
+    #[maze_test]
+    fn walk_bidirectional_shortest(maze: TestMaze) {
+        let maze = maze.initialize(
+            crate::initialize::Method::Branching(
+                crate::initialize::Bias::default(),
+            ),
+            &mut rand::thread_rng(),
+        );
+
+        for from in maze.positions() {
+            let to = matrix_pos(
+                maze.width() as isize - 1 - from.col,
+                maze.height() as isize - 1 - from.row,
+            );
+            // There is only one path in a perfect maze
+            assert_eq!(
+                maze.walk_bidirectional(from, to)
+                    .unwrap()
+                    .into_iter()
+                    .collect::<Vec<_>>(),
+                astar_path(&maze, from, to).unwrap(),
+            );
+        }
+    }
+
     #[maze_test]
     fn walk_shortest(mut maze: TestMaze) {
         let log = Navigator::new(&mut maze)
@@ -585,14 +1705,14 @@ mod tests {
 
     #[test]
     fn pop_empty() {
-        let mut os = OpenSet::new(10, 10);
+        let mut os = OpenSet::<matrix::Pos>::new();
 
         assert!(os.pop().is_none());
     }
 
     #[test]
     fn pop_nonempty() {
-        let mut os = OpenSet::new(10, 10);
+        let mut os = OpenSet::new();
 
         os.push(Priority(0.0), matrix_pos(0, 0));
         assert!(os.pop().is_some());
@@ -600,7 +1720,7 @@ mod tests {
 
     #[test]
     fn pop_correct() {
-        let mut os = OpenSet::new(10, 10);
+        let mut os = OpenSet::new();
         let expected = (Priority(0.0), matrix_pos(1, 2));
 
         os.push(expected.0, expected.1);
@@ -611,7 +1731,7 @@ mod tests {
 
     #[test]
     fn contains_same() {
-        let mut os = OpenSet::new(10, 10);
+        let mut os = OpenSet::new();
         let expected = (Priority(10.0), matrix_pos(1, 2));
 
         assert!(!os.contains(expected.1));
@@ -628,4 +1748,207 @@ mod tests {
         os.pop();
         assert!(!os.contains(expected.1));
     }
+
+    #[test]
+    fn decrease_key() {
+        let mut os = OpenSet::new();
+
+        os.push(Priority(10.0), matrix_pos(1, 2));
+        os.push(Priority(5.0), matrix_pos(3, 4));
+        os.push(Priority(1.0), matrix_pos(1, 2));
+        os.push(Priority(20.0), matrix_pos(1, 2));
+        assert_eq!(os.pop(), Some(matrix_pos(1, 2)));
+        assert!(!os.contains(matrix_pos(1, 2)));
+        assert_eq!(os.pop(), Some(matrix_pos(3, 4)));
+        assert_eq!(os.pop(), None);
+    }
+
+    #[test]
+    fn astar_cheapest() {
+        // Moving to the next number costs 1.0 and skipping one number costs
+        // 3.0, so the cheapest path visits every number
+        let path = astar(
+            0u32,
+            |n| n == 4,
+            |n| [n + 1, n + 2],
+            |a, b| if b - a == 1 { 1.0 } else { 3.0 },
+            |n| 4u32.saturating_sub(n) as f32 / 2.0,
+        );
+        assert_eq!(path, Some(vec![0, 1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn astar_nan() {
+        // A heuristic returning NaN must not cause a panic...
+        let path = astar(
+            0u32,
+            |n| n == 4,
+            |n| (n + 1)..=(n + 2).min(4),
+            |_, _| 1.0,
+            |n| if n % 2 == 0 { f32::NAN } else { 0.0 },
+        );
+        assert_eq!(path.map(|path| path.last().copied()), Some(Some(4)));
+
+        // Neither must a cost returning NaN
+        let path = astar(
+            0u32,
+            |n| n == 4,
+            |n| (n + 1)..=(n + 2).min(4),
+            |_, next| if next == 2 { f32::NAN } else { 1.0 },
+            |_| 0.0,
+        );
+        assert_eq!(path.map(|path| path.last().copied()), Some(Some(4)));
+    }
+
+    #[test]
+    fn astar_unreachable() {
+        assert_eq!(
+            astar(
+                0u32,
+                |n| n == 5,
+                |n| (n < 4).then_some(n + 2),
+                |_, _| 1.0,
+                |_| 0.0
+            ),
+            None,
+        );
+    }
+
+    #[maze_test]
+    fn walk_shortest_loops(maze: TestMaze) {
+        let maze = maze.initialize(
+            crate::initialize::Method::Braid,
+            &mut rand::thread_rng(),
+        );
+
+        // Calculate the actual distances using a breadth-first search
+        let from = matrix_pos(0, 0);
+        let mut distances =
+            Matrix::<Option<usize>>::new(maze.width(), maze.height());
+        distances[from] = Some(0);
+        let mut queue = alloc::collections::VecDeque::from([from]);
+        while let Some(current) = queue.pop_front() {
+            for next in maze.neighbors(current) {
+                if maze.is_inside(next) && distances[next].is_none() {
+                    distances[next] = distances[current].map(|d| d + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        for to in maze.positions() {
+            assert_eq!(
+                maze.walk(from, to).map(|path| path.len() - 1),
+                distances[to],
+            );
+        }
+    }
+
+    #[maze_test]
+    fn flood_order(mut maze: TestMaze) {
+        let log = Navigator::new(&mut maze)
+            .down(true)
+            .right(true)
+            .right(true)
+            .stop();
+
+        assert_eq!(
+            maze.flood(log[0]).collect::<Vec<_>>(),
+            log.iter()
+                .enumerate()
+                .map(|(i, &pos)| (pos, i as u32))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            maze.flood(log[1]).map(|(_, d)| d).collect::<Vec<_>>(),
+            vec![0, 1, 1, 2],
+        );
+    }
+
+    #[maze_test]
+    fn flood_all(maze: TestMaze) {
+        let maze = maze.initialize(
+            crate::initialize::Method::Branching(
+                crate::initialize::Bias::default(),
+            ),
+            &mut rand::thread_rng(),
+        );
+
+        let rooms = maze.flood(matrix_pos(0, 0)).collect::<Vec<_>>();
+        assert_eq!(rooms.len(), maze.width() * maze.height());
+        assert!(rooms.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert_eq!(maze.flood(matrix_pos(-1, 0)).count(), 0);
+    }
+
+    #[maze_test]
+    fn agent_closed(maze: TestMaze) {
+        let mut rng = initialize::LFSR::new(12345);
+        for strategy in [
+            Strategy::RandomWalk,
+            Strategy::WallFollower,
+            Strategy::Tremaux,
+        ] {
+            let mut agent = Agent::new(matrix_pos(0, 0), strategy);
+            assert_eq!(None, agent.step(&maze, &mut rng));
+            assert_eq!(0, agent.steps());
+        }
+    }
+
+    #[maze_test]
+    fn agent_random_walk(maze: TestMaze) {
+        let mut rng = initialize::LFSR::new(12345);
+        let maze = maze.initialize(
+            initialize::Method::Winding(initialize::Bias::default()),
+            &mut rng,
+        );
+        let mut agent = Agent::new(matrix_pos(0, 0), Strategy::RandomWalk);
+        while !maze.positions().all(|pos| agent.has_visited(pos)) {
+            let pos = agent.pos();
+            let next = agent.step(&maze, &mut rng).unwrap();
+            assert!(maze.neighbors(pos).any(|p| p == next));
+        }
+    }
+
+    #[maze_test]
+    fn agent_wall_follower(maze: TestMaze) {
+        let mut rng = initialize::LFSR::new(12345);
+        let maze = maze.initialize(
+            initialize::Method::Winding(initialize::Bias::default()),
+            &mut rng,
+        );
+        let start = matrix_pos(0, 0);
+        let mut agent = Agent::new(start, Strategy::WallFollower);
+
+        // In a maze without loops, every door is passed twice
+        let rooms = maze.positions().count();
+        for _ in 0..2 * (rooms - 1) {
+            agent.step(&maze, &mut rng).unwrap();
+        }
+        assert_eq!(start, agent.pos());
+        assert!(maze.positions().all(|pos| agent.has_visited(pos)));
+    }
+
+    #[maze_test]
+    fn agent_tremaux(maze: TestMaze) {
+        let mut rng = initialize::LFSR::new(12345);
+        let maze = maze.initialize(initialize::Method::Braid, &mut rng);
+        let start = matrix_pos(0, 0);
+        let mut agent = Agent::new(start, Strategy::Tremaux);
+        while agent.step(&maze, &mut rng).is_some() {}
+
+        // Every door reachable from the start is passed once in each
+        // direction
+        assert_eq!(start, agent.pos());
+        let mut doors = 0;
+        for (pos, _) in maze.flood(start) {
+            assert!(agent.has_visited(pos));
+            for wall in maze.doors(pos) {
+                if maze.is_inside(maze.back((pos, wall)).0) {
+                    assert_eq!(2, agent.marks((pos, wall)));
+                    doors += 1;
+                }
+            }
+        }
+        assert_eq!(doors, agent.steps());
+    }
 }
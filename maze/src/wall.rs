@@ -1,4 +1,4 @@
-use std::f32::consts::TAU;
+use core::f32::consts::TAU;
 
 #[cfg(feature = "serde")]
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
@@ -133,10 +133,10 @@ impl PartialEq for Wall {
 
 impl Eq for Wall {}
 
-impl std::hash::Hash for Wall {
+impl core::hash::Hash for Wall {
     fn hash<H>(&self, state: &mut H)
     where
-        H: std::hash::Hasher,
+        H: core::hash::Hasher,
     {
         self.shape.hash(state);
         self.index.hash(state);
@@ -144,20 +144,20 @@ impl std::hash::Hash for Wall {
     }
 }
 
-impl std::fmt::Debug for Wall {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+impl core::fmt::Debug for Wall {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
         f.write_str(self.name)
     }
 }
 
 impl Ord for Wall {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         self.index.cmp(&other.index)
     }
 }
 
 impl PartialOrd for Wall {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
@@ -192,7 +192,7 @@ impl Serialize for Wall {
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
-    use std::f32::consts::PI;
+    use core::f32::consts::PI;
 
     use maze_test::maze_test;
 
@@ -248,7 +248,7 @@ mod tests {
 
         for pos in maze.positions() {
             for wall in maze.walls(pos) {
-                let d = 16.0 * std::f32::EPSILON;
+                let d = 16.0 * f32::EPSILON;
                 assert_span(wall, wall.span.0.a + d);
                 assert_not_span(wall, wall.span.0.a - d);
                 assert_span(wall.previous, wall.span.0.a - d);
@@ -293,7 +293,7 @@ mod tests {
         for pos in maze.positions() {
             let walls = maze.walls(pos);
             for wall in walls {
-                let d = 16.0 * std::f32::EPSILON;
+                let d = 16.0 * f32::EPSILON;
                 assert!(
                     wall.in_span(wall.previous.span.1.a + d),
                     "invalid wall order {:?}: {:?} <=> {:?}",
@@ -9,114 +9,526 @@ use proc_macro::{
 /// The different shapes of mazes for which to generate tests.
 const SHAPES: &[&str] = &["hex", "quad", "tri"];
 
+/// The size of the mazes used when no sizes are specified.
+const DEFAULT_SIZE: (usize, usize) = (10, 5);
+
+/// An executor running a future to completion on the current thread, used by
+/// asynchronous tests.
+///
+/// The thread is parked while the future is pending, and unparked when it is
+/// woken.
+const BLOCK_ON: &str = "
+    fn block_on<F: ::core::future::Future>(future: F) -> F::Output {
+        struct Unpark(::std::thread::Thread);
+
+        impl ::std::task::Wake for Unpark {
+            fn wake(self: ::std::sync::Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let waker = ::std::task::Waker::from(::std::sync::Arc::new(
+            Unpark(::std::thread::current()),
+        ));
+        let mut context = ::std::task::Context::from_waker(&waker);
+        let mut future = ::core::pin::pin!(future);
+        loop {
+            match ::core::future::Future::poll(
+                future.as_mut(),
+                &mut context,
+            ) {
+                ::core::task::Poll::Ready(output) => return output,
+                ::core::task::Poll::Pending => ::std::thread::park(),
+            }
+        }
+    }
+";
+
 /// Marks a function as a test for a maze.
 ///
 /// Adding this attribute macro will ensure that the function is run as a test
 /// for all kinds of mazes.
 ///
 /// The annotated function should take one argument, which is the maze
-/// instance.
+/// instance. Other attributes on the function, such as `#[should_panic]`, are
+/// kept, and the function may return a `Result<(), E>`, in which case the
+/// test fails on the first error.
+///
+/// The function may also be `async`. The generated test then runs the future
+/// to completion on the test thread, without requiring an external runtime,
+/// so only futures woken through their context are supported.
+///
+/// The attribute accepts a comma separated list of shapes, and the options
+/// `sizes` and `seeds`. The test is run once for every combination of shape,
+/// size and seed:
+///
+/// *  `sizes(5x5, 10..20)` runs the test for every listed size, where a
+///    single number or range of numbers is a list of square mazes. The
+///    default size is 10×5.
+/// *  `seeds(3)` runs the test for the seeds `0`, `1` and `2`. When this
+///    option is present, the annotated function takes the seed as a second
+///    argument of type `u64`.
+///
+/// Invalid input is reported as a compiler error pointing at the offending
+/// tokens.
 #[proc_macro_attribute]
 pub fn maze_test(attr: TokenStream, item: TokenStream) -> TokenStream {
-    // Extract the interesting parts of the original function
-    let (span, name, args, inner_body) = split(item);
-
-    // Extract the shapes for which to generate tests
-    let shapes = shapes(attr);
-
-    // Generate the body of the new function
-    let body = {
-        let mut body =
-            function(span, Ident::new("inner", span), args, inner_body);
-
-        // Iterate through known shapes for consistent ordering
-        for shape in SHAPES {
-            if shapes.iter().any(|s| s == shape) {
-                body.extend(
-                    format!(
-                        "inner(\"{}\".parse::<crate::Shape>()
-                        .unwrap().create(10, 5));",
-                        shape,
-                    )
-                    .parse::<TokenStream>()
-                    .unwrap(),
-                );
+    Options::parse(attr)
+        .and_then(|options| Ok((options, Function::parse(item)?)))
+        .map(|(options, function)| function.expand(&options))
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+/// An error in the input to the macro.
+struct Error {
+    /// The span of the offending tokens.
+    span: Span,
+
+    /// A description of the error.
+    message: String,
+}
+
+impl Error {
+    /// Creates a new error.
+    ///
+    /// # Arguments
+    /// *  `span` - The span of the offending tokens.
+    /// *  `message` - A description of the error.
+    fn new<T>(span: Span, message: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            span,
+            message: message.into(),
+        }
+    }
+
+    /// Converts this error to an invocation of `compile_error!` with the
+    /// span of the offending tokens.
+    fn into_compile_error(self) -> TokenStream {
+        respan(
+            format!("compile_error!({:?});", self.message)
+                .parse()
+                .unwrap(),
+            self.span,
+        )
+    }
+}
+
+/// The options passed to the attribute.
+struct Options {
+    /// The shapes for which to generate tests.
+    shapes: HashSet<String>,
+
+    /// The sizes, as `(width, height)`, for which to generate tests.
+    sizes: Vec<(usize, usize)>,
+
+    /// The number of seeds for which to generate tests, if seeds are passed.
+    seeds: Option<u64>,
+}
+
+impl Options {
+    /// Parses the options of an attribute.
+    ///
+    /// # Arguments
+    /// *  `attr` - The attribute token stream.
+    ///
+    /// # Errors
+    /// An error is returned if the token stream is not a comma separated list
+    /// of shape identifiers and options, or if any value is invalid.
+    fn parse(attr: TokenStream) -> Result<Self, Error> {
+        let mut shapes = HashSet::new();
+        let mut sizes = Vec::new();
+        let mut seeds = None;
+
+        let mut tokens = attr.into_iter().peekable();
+        while let Some(tree) = tokens.next() {
+            match tree {
+                TokenTree::Ident(ref ident) => {
+                    let name = ident.to_string();
+                    let group = match tokens.peek() {
+                        Some(TokenTree::Group(group))
+                            if group.delimiter() == Delimiter::Parenthesis =>
+                        {
+                            Some(group.clone())
+                        }
+                        _ => None,
+                    };
+                    match (name.as_str(), group) {
+                        ("sizes", Some(group)) => {
+                            tokens.next();
+                            sizes.extend(parse_sizes(group.stream())?);
+                        }
+                        ("seeds", Some(group)) => {
+                            tokens.next();
+                            seeds = Some(parse_count(&group)?);
+                        }
+                        (_, None) if SHAPES.contains(&name.as_str()) => {
+                            shapes.insert(name);
+                        }
+                        (_, None) => {
+                            return Err(Error::new(
+                                ident.span(),
+                                format!("Unknown shape: {}", name),
+                            ))
+                        }
+                        (_, Some(_)) => {
+                            return Err(Error::new(
+                                ident.span(),
+                                format!("Unknown option: {}", name),
+                            ))
+                        }
+                    }
+                }
+                TokenTree::Punct(ref punct) if punct.as_char() == ',' => {}
+                _ => {
+                    return Err(Error::new(
+                        tree.span(),
+                        format!("Unexpected token: {}", tree),
+                    ))
+                }
             }
         }
-        body
-    };
-
-    let mut result = TokenStream::new();
-    result.extend(test_attr(span));
-    result.extend(function(
-        span,
-        name,
-        Group::new(Delimiter::Parenthesis, TokenStream::new()),
-        Group::new(Delimiter::Brace, body),
-    ));
-    result
+
+        Ok(Self {
+            shapes: if shapes.is_empty() {
+                SHAPES.iter().cloned().map(String::from).collect()
+            } else {
+                shapes
+            },
+            sizes: if sizes.is_empty() {
+                vec![DEFAULT_SIZE]
+            } else {
+                sizes
+            },
+            seeds,
+        })
+    }
+}
+
+/// The parts of the annotated function.
+struct Function {
+    /// The attributes of the function.
+    attrs: TokenStream,
+
+    /// The span of the `fn` keyword.
+    span: Span,
+
+    /// Whether the function is `async`.
+    asyncness: bool,
+
+    /// The function name.
+    name: Ident,
+
+    /// The function arguments.
+    args: Group,
+
+    /// The return type, including the arrow, or nothing if the function does
+    /// not return a value.
+    output: TokenStream,
+
+    /// The function body.
+    body: Group,
+}
+
+impl Function {
+    /// Splits a function definition into the components we use.
+    ///
+    /// This function does not validate the function arguments or the return
+    /// type; any errors in those are reported by the compiler.
+    ///
+    /// # Arguments
+    /// *  `item` - The token stream to split.
+    ///
+    /// # Errors
+    /// An error is returned if the token stream is not a function definition.
+    fn parse(item: TokenStream) -> Result<Self, Error> {
+        let mut tokens = item.into_iter().peekable();
+
+        // Keep all attributes, including documentation comments
+        let mut attrs = TokenStream::new();
+        while let Some(TokenTree::Punct(punct)) = tokens.peek() {
+            if punct.as_char() != '#' {
+                break;
+            }
+            attrs.extend(tokens.next());
+            match tokens.next() {
+                Some(TokenTree::Group(group))
+                    if group.delimiter() == Delimiter::Bracket =>
+                {
+                    attrs.extend(Some(TokenTree::Group(group)));
+                }
+                tree => return Err(expected(tree, "attribute")),
+            }
+        }
+
+        let asyncness = matches!(
+            tokens.peek(),
+            Some(TokenTree::Ident(head)) if head.to_string() == "async",
+        );
+        if asyncness {
+            tokens.next();
+        }
+        let span = match tokens.next() {
+            Some(TokenTree::Ident(head)) if head.to_string() == "fn" => {
+                head.span()
+            }
+            tree => return Err(expected(tree, "function")),
+        };
+        let name = match tokens.next() {
+            Some(TokenTree::Ident(name)) => name,
+            tree => return Err(expected(tree, "function name")),
+        };
+        let args = match tokens.next() {
+            Some(TokenTree::Group(args))
+                if args.delimiter() == Delimiter::Parenthesis =>
+            {
+                args
+            }
+            tree => return Err(expected(tree, "function arguments")),
+        };
+
+        // The body is the last token, and everything before it is the return
+        // type
+        let mut rest = tokens.collect::<Vec<_>>();
+        let body = match rest.pop() {
+            Some(TokenTree::Group(body))
+                if body.delimiter() == Delimiter::Brace =>
+            {
+                body
+            }
+            tree => return Err(expected(tree, "function body")),
+        };
+        let output = rest.into_iter().collect();
+
+        Ok(Self {
+            attrs,
+            span,
+            asyncness,
+            name,
+            args,
+            output,
+            body,
+        })
+    }
+
+    /// Generates the test function.
+    ///
+    /// The original function is moved into the body of the test function,
+    /// which calls it once for every combination of options.
+    ///
+    /// # Arguments
+    /// *  `options` - The options passed to the attribute.
+    fn expand(self, options: &Options) -> TokenStream {
+        let fallible = !self.output.is_empty();
+
+        // Generate the body of the new function
+        let body = {
+            let mut body = TokenStream::new();
+            if self.asyncness {
+                body.extend(Some(TokenTree::Ident(Ident::new(
+                    "async", self.span,
+                ))));
+            }
+            body.extend(function(
+                self.span,
+                Ident::new("inner", self.span),
+                self.args,
+                self.output.clone(),
+                self.body,
+            ));
+            let mut calls = TokenStream::new();
+
+            // Iterate through known shapes for consistent ordering
+            for shape in SHAPES {
+                if !options.shapes.iter().any(|s| s == shape) {
+                    continue;
+                }
+                for (width, height) in options.sizes.iter() {
+                    let maze = format!(
+                        "\"{}\".parse::<crate::Shape>()
+                            .unwrap().create({}, {})",
+                        shape, width, height,
+                    );
+                    let call = |args: String| {
+                        format!(
+                            "inner({}){}{};",
+                            args,
+                            if self.asyncness { ".await" } else { "" },
+                            if fallible { "?" } else { "" }
+                        )
+                    };
+                    let text = match options.seeds {
+                        Some(seeds) => (0..seeds)
+                            .map(|seed| call(format!("{}, {}", maze, seed)))
+                            .collect::<String>(),
+                        None => call(maze),
+                    };
+                    calls.extend(respan(
+                        text.parse().unwrap(),
+                        self.name.span(),
+                    ));
+                }
+            }
+            if fallible {
+                calls.extend(respan("Ok(())".parse().unwrap(), self.span));
+            }
+            if self.asyncness {
+                body.extend(respan(BLOCK_ON.parse().unwrap(), self.span));
+                body.extend(respan("block_on".parse().unwrap(), self.span));
+                body.extend(Some(TokenTree::Group(Group::new(
+                    Delimiter::Parenthesis,
+                    vec![
+                        TokenTree::Ident(Ident::new("async", self.span)),
+                        TokenTree::Group(Group::new(Delimiter::Brace, calls)),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ))));
+            } else {
+                body.extend(calls);
+            }
+            body
+        };
+
+        let mut result = TokenStream::new();
+        result.extend(test_attr(self.span));
+        result.extend(self.attrs);
+        result.extend(function(
+            self.span,
+            self.name,
+            Group::new(Delimiter::Parenthesis, TokenStream::new()),
+            self.output,
+            Group::new(Delimiter::Brace, body),
+        ));
+        result
+    }
 }
 
-/// Splits a token stream into the components we use.
+/// Creates an error for an unexpected token.
 ///
-/// This function expects a function definition. It does not validate the
-/// function arguments.
+/// # Arguments
+/// *  `tree` - The unexpected token, or `None` if the input ended.
+/// *  `what` - A description of what was expected.
+fn expected(tree: Option<TokenTree>, what: &str) -> Error {
+    match tree {
+        Some(tree) => Error::new(
+            tree.span(),
+            format!("Expected {}, found {}", what, tree),
+        ),
+        None => Error::new(Span::call_site(), format!("Expected {}", what)),
+    }
+}
+
+/// Parses a comma separated list of sizes.
+///
+/// Every size is either `WxH`, a single number `N` meaning `NxN`, or a range
+/// `A..B` meaning all square sizes from `A` up to, but not including, `B`.
 ///
 /// # Arguments
-/// *  `item` - The token stream to split.
+/// *  `stream` - The contents of the `sizes` option.
 ///
-/// # Panics
-/// This function will panic if the token stream does not contain the expected
-/// tokens.
-fn split(item: TokenStream) -> (Span, Ident, Group, Group) {
-    let mut items = item.into_iter();
-
-    match (items.next(), items.next(), items.next(), items.next()) {
-        (
-            Some(TokenTree::Ident(head)),
-            Some(TokenTree::Ident(name)),
-            Some(TokenTree::Group(args)),
-            Some(TokenTree::Group(body)),
-        ) => {
-            if head.to_string() != "fn" {
-                panic!("Expected function")
-            } else {
-                (head.span(), name, args, body)
+/// # Errors
+/// An error is returned if a size is invalid.
+fn parse_sizes(stream: TokenStream) -> Result<Vec<(usize, usize)>, Error> {
+    let mut result = Vec::new();
+    let mut tokens = stream.into_iter().peekable();
+    while let Some(tree) = tokens.next() {
+        match tree {
+            TokenTree::Literal(ref literal) => {
+                let (text, span) = (literal.to_string(), literal.span());
+                if let Some((width, height)) = text.split_once('x') {
+                    result.push((
+                        parse_number(width, span)?,
+                        parse_number(height, span)?,
+                    ));
+                } else if matches!(
+                    tokens.peek(),
+                    Some(TokenTree::Punct(punct)) if punct.as_char() == '.',
+                ) {
+                    tokens.next();
+                    tokens.next();
+                    let end = match tokens.next() {
+                        Some(TokenTree::Literal(end)) => {
+                            parse_number(&end.to_string(), end.span())?
+                        }
+                        tree => return Err(expected(tree, "end of range")),
+                    };
+                    result.extend(
+                        (parse_number(&text, span)?..end).map(|n| (n, n)),
+                    );
+                } else {
+                    let n = parse_number(&text, span)?;
+                    result.push((n, n));
+                }
             }
+            TokenTree::Punct(ref punct) if punct.as_char() == ',' => {}
+            _ => return Err(expected(Some(tree), "size")),
         }
-        _ => panic!("Expected function"),
     }
+    Ok(result)
 }
 
-/// Generates a set of shapes.
+/// Parses an option containing a single number.
 ///
-/// If the attribute is empty, a set containing all shapes will be returned
-/// instead.
+/// # Arguments
+/// *  `group` - The option group.
 ///
-/// # Panics
-/// This function panics if the token stream is not a comma separated list of
-/// identifiers, or if any identifier is not in `SHAPES`.
-fn shapes(attr: TokenStream) -> HashSet<String> {
-    let shapes = attr
-        .into_iter()
-        .flat_map(|tree| match tree {
-            TokenTree::Ident(ref shape) => Some(shape.to_string()),
-            TokenTree::Punct(ref punct) if punct.as_char() == ',' => None,
-            _ => panic!("Unexpected token: {}", tree),
-        })
-        .collect::<HashSet<_>>();
-    if shapes.is_empty() {
-        SHAPES.iter().cloned().map(String::from).collect()
-    } else {
-        for shape in shapes.iter() {
-            if !SHAPES.iter().any(|&s| s == shape) {
-                panic!("Unknown shape: {}", shape);
-            }
+/// # Errors
+/// An error is returned if the group does not contain a single number.
+fn parse_count(group: &Group) -> Result<u64, Error> {
+    let mut tokens = group.stream().into_iter();
+    match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(literal)), None) => {
+            parse_number(&literal.to_string(), literal.span())
+                .map(|value| value as u64)
         }
-        shapes
+        _ => Err(Error::new(group.span(), "Expected a number")),
+    }
+}
+
+/// Parses a positive integer.
+///
+/// # Arguments
+/// *  `text` - The text to parse.
+/// *  `span` - The span of the text.
+///
+/// # Errors
+/// An error is returned if the text is not a positive integer.
+fn parse_number(text: &str, span: Span) -> Result<usize, Error> {
+    match text.parse() {
+        Ok(value) if value > 0 => Ok(value),
+        _ => Err(Error::new(span, format!("Invalid number: {}", text))),
     }
 }
 
+/// Replaces the span of all tokens in a stream.
+///
+/// This makes compiler errors in generated code point at the original
+/// source.
+///
+/// # Arguments
+/// *  `stream` - The token stream.
+/// *  `span` - The new span.
+fn respan(stream: TokenStream, span: Span) -> TokenStream {
+    stream
+        .into_iter()
+        .map(|tree| match tree {
+            TokenTree::Group(group) => {
+                let mut result =
+                    Group::new(group.delimiter(), respan(group.stream(), span));
+                result.set_span(span);
+                TokenTree::Group(result)
+            }
+            mut tree => {
+                tree.set_span(span);
+                tree
+            }
+        })
+        .collect()
+}
+
 /// Generates a test attribute.
 ///
 /// # Arguments
@@ -141,14 +553,23 @@ fn test_attr(span: Span) -> TokenStream {
 /// *  `span` - The span of the original function.
 /// *  `name` - The function name.
 /// *  `args` - The function arguments.
+/// *  `output` - The return type, including the arrow.
 /// *  `body` - The function body.
-fn function(span: Span, name: Ident, args: Group, body: Group) -> TokenStream {
-    vec![
+fn function(
+    span: Span,
+    name: Ident,
+    args: Group,
+    output: TokenStream,
+    body: Group,
+) -> TokenStream {
+    let mut result = vec![
         TokenTree::Ident(Ident::new("fn", span)),
         TokenTree::Ident(name),
         TokenTree::Group(args),
-        TokenTree::Group(body),
     ]
     .into_iter()
-    .collect::<TokenStream>()
+    .collect::<TokenStream>();
+    result.extend(output);
+    result.extend(Some(TokenTree::Group(body)));
+    result
 }
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use maze::{matrix, physical};
+use maze::{matrix, physical, shape};
+
+use crate::cell::{Accumulator, Splitter};
 
 #[macro_use]
 mod macros;
@@ -100,8 +102,8 @@ impl Alphabet {
         columns: usize,
         horizontal_resolution: usize,
     ) -> AlphabetRenderer<'_> {
+        let text = text.chars().collect::<Vec<_>>();
         let rows = (text.len() as f32 / columns as f32).ceil() as usize;
-        let text = text.chars().collect();
         let resolution = horizontal_resolution / columns;
         let current = 0;
         let limit = columns * rows * resolution * resolution;
@@ -116,6 +118,70 @@ impl Alphabet {
     }
 }
 
+impl Alphabet {
+    /// Calculates how much of every room of a maze is covered by a text.
+    ///
+    /// The characters are laid out in a grid with as many columns as rows,
+    /// stretched to cover the entire maze. The values of the returned matrix
+    /// are in the range `[0, 1]`.
+    ///
+    /// # Arguments
+    /// *  `text` - The text to render.
+    /// *  `maze` - The maze on which to render the text.
+    pub fn intensities<T>(
+        &self,
+        text: &str,
+        maze: &maze::Maze<T>,
+    ) -> matrix::Matrix<f32>
+    where
+        T: Clone,
+    {
+        let physical::ViewBox { width, height, .. } = maze.viewbox();
+        let length = text.chars().count();
+        let columns = (length as f32).sqrt().ceil().max(1.0) as usize;
+        let rows = (length as f32 / columns as f32).ceil().max(1.0);
+        let mut values =
+            self.render(text, columns, 16 * maze.width())
+                .map(|(pos, v)| {
+                    (
+                        physical::Pos {
+                            x: width * pos.x / columns as f32,
+                            y: height * pos.y / rows,
+                        },
+                        Accumulator(v),
+                    )
+                });
+        let (cols, rows) = (maze.width(), maze.height());
+        match maze.shape() {
+            maze::Shape::Hex => values.split_by(&shape::Hex, cols, rows),
+            maze::Shape::Quad => values.split_by(&shape::Quad, cols, rows),
+            maze::Shape::Tri => values.split_by(&shape::Tri, cols, rows),
+        }
+    }
+
+    /// Determines which rooms of a maze are covered by a text.
+    ///
+    /// See [`intensities`](Self::intensities) for a description of the
+    /// layout.
+    ///
+    /// # Arguments
+    /// *  `text` - The text to render.
+    /// *  `maze` - The maze on which to render the text.
+    /// *  `threshold` - The intensity above which a room is covered.
+    pub fn rooms<T>(
+        &self,
+        text: &str,
+        maze: &maze::Maze<T>,
+        threshold: f32,
+    ) -> matrix::Matrix<bool>
+    where
+        T: Clone,
+    {
+        self.intensities(text, maze)
+            .map(|&intensity| intensity > threshold)
+    }
+}
+
 /// An iterator over bit samples for a rendered text.
 pub struct AlphabetRenderer<'a> {
     /// The alphabet to use.
@@ -201,6 +267,29 @@ impl Alphabet {
 mod tests {
     use super::*;
 
+    #[test]
+    fn alphabet_rooms() {
+        let maze = maze::Shape::Quad.create::<()>(16, 16);
+        let rooms = default::ALPHABET.rooms("I", &maze, 0.5);
+        let covered = rooms.values().filter(|&&covered| covered).count();
+        assert!(covered > 0 && covered < 16 * 16);
+        assert!(default::ALPHABET
+            .rooms(" ", &maze, 0.5)
+            .values()
+            .all(|&covered| !covered));
+    }
+
+    #[test]
+    fn alphabet_intensities_multibyte() {
+        // A single character covers the entire maze, even if it is encoded
+        // using several bytes
+        let maze = maze::Shape::Quad.create::<()>(16, 16);
+        let intensities = default::ALPHABET.intensities("Å", &maze);
+        assert!(maze
+            .positions()
+            .any(|pos| pos.col >= 8 && intensities[pos] > 0.5));
+    }
+
     #[test]
     fn character_interpolated() {
         let character = Character(character! {
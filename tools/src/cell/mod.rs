@@ -2,6 +2,7 @@ use std::ops;
 
 use maze::matrix;
 use maze::physical;
+use maze::shape::{self, ShapeKind};
 
 /// Translates physical positions to cells.
 pub trait Cells {
@@ -18,6 +19,24 @@ impl Cells for maze::Shape {
     }
 }
 
+impl Cells for shape::Hex {
+    fn cell(&self, pos: physical::Pos) -> matrix::Pos {
+        Self::physical_to_cell(pos)
+    }
+}
+
+impl Cells for shape::Quad {
+    fn cell(&self, pos: physical::Pos) -> matrix::Pos {
+        Self::physical_to_cell(pos)
+    }
+}
+
+impl Cells for shape::Tri {
+    fn cell(&self, pos: physical::Pos) -> matrix::Pos {
+        Self::physical_to_cell(pos)
+    }
+}
+
 /// Splits values into matrix cells.
 pub trait Splitter<C, T, U>
 where
@@ -27,6 +46,10 @@ where
 {
     /// Passes values through cells and collects their average in a matrix.
     ///
+    /// When splitting many values, pass one of the shape kinds, such as
+    /// [`shape::Hex`], rather than a [`maze::Shape`] to avoid dispatching on
+    /// the shape for every value.
+    ///
     /// # Arguments
     /// *  `cells` - The cells used to translate physical coordinates to matrix
     ///    coordinates.
@@ -40,7 +63,7 @@ where
     ) -> matrix::Matrix<T>;
 }
 
-impl<'a, C, I, T, U> Splitter<C, T, U> for &'a mut I
+impl<C, I, T, U> Splitter<C, T, U> for &mut I
 where
     C: Cells,
     I: Iterator<Item = (physical::Pos, U)>,
@@ -66,3 +89,221 @@ where
         .map(|(count, value)| *value / *count)
     }
 }
+
+/// A sum of values, used to calculate their average.
+///
+/// This implements the operations required by both [`Splitter::split_by`]
+/// and [`sample`].
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Accumulator(pub(crate) f32);
+
+impl ops::Add for Accumulator {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Accumulator(self.0 + other.0)
+    }
+}
+
+impl ops::Mul<f32> for Accumulator {
+    type Output = Self;
+
+    fn mul(self, factor: f32) -> Self {
+        Accumulator(self.0 * factor)
+    }
+}
+
+impl ops::Div<f32> for Accumulator {
+    type Output = f32;
+
+    fn div(self, divisor: f32) -> Self::Output {
+        self.0 / divisor
+    }
+}
+
+impl ops::Div<usize> for Accumulator {
+    type Output = f32;
+
+    fn div(self, divisor: usize) -> Self::Output {
+        self.0 / divisor as f32
+    }
+}
+
+/// Samples a raster stretched to cover the view box of a maze.
+///
+/// Every room receives the average value of the pixels it overlaps, weighted
+/// by the area of the overlap with the polygon of the room. Unlike
+/// [`Splitter::split_by`], this follows the actual shape of rooms, so
+/// sampling is accurate for hexagonal and triangular mazes as well.
+///
+/// # Arguments
+/// *  `maze` - The maze whose rooms to sample.
+/// *  `cols` - The width of the raster, in pixels.
+/// *  `rows` - The height of the raster, in pixels.
+/// *  `pixel` - A function returning the value of a pixel given its column
+///    and row.
+pub fn sample<V, F, T, U>(
+    maze: &maze::Maze<V>,
+    cols: u32,
+    rows: u32,
+    pixel: F,
+) -> matrix::Matrix<T>
+where
+    V: Clone,
+    F: Fn(u32, u32) -> U,
+    T: Copy + Default,
+    U: Copy
+        + Default
+        + ops::Add<U, Output = U>
+        + ops::Mul<f32, Output = U>
+        + ops::Div<f32, Output = T>,
+{
+    let physical::ViewBox {
+        corner,
+        width,
+        height,
+    } = maze.viewbox();
+    let (sx, sy) = (cols as f32 / width, rows as f32 / height);
+
+    matrix::Matrix::new_with_data(maze.width(), maze.height(), |pos| {
+        // The polygon of the room, in pixel coordinates
+        let polygon = maze
+            .shape()
+            .cell_polygon(pos)
+            .map(|p| ((p.x - corner.x) * sx, (p.y - corner.y) * sy))
+            .collect::<Vec<_>>();
+        let (left, top, right, bottom) = polygon.iter().fold(
+            (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+            |acc, &(x, y)| {
+                (acc.0.min(x), acc.1.min(y), acc.2.max(x), acc.3.max(y))
+            },
+        );
+
+        let (mut total, mut area) = (U::default(), 0.0);
+        for row in range(top, bottom, rows) {
+            for col in range(left, right, cols) {
+                let overlap = overlap(&polygon, col as f32, row as f32);
+                if overlap > 0.0 {
+                    total = total + pixel(col, row) * overlap;
+                    area += overlap;
+                }
+            }
+        }
+
+        if area > 0.0 {
+            total / area
+        } else {
+            T::default()
+        }
+    })
+}
+
+/// The pixels covering an interval, clamped to the raster.
+///
+/// # Arguments
+/// *  `start` - The start of the interval, in pixels.
+/// *  `end` - The end of the interval, in pixels.
+/// *  `size` - The size of the raster, in pixels.
+fn range(start: f32, end: f32, size: u32) -> ops::Range<u32> {
+    let clamp = |v: f32| (v.max(0.0) as u32).min(size);
+    clamp(start.floor())..clamp(end.ceil())
+}
+
+/// An edge of a pixel, as a function selecting a coordinate of a point, the
+/// value of the coordinate along the edge and whether points with a lower
+/// value are inside.
+type Edge = (fn((f32, f32)) -> f32, f32, bool);
+
+/// The area of the part of a convex polygon covering a single pixel.
+///
+/// The polygon is clipped to the pixel one edge at a time, and the area of the
+/// remainder is calculated using the shoelace formula.
+///
+/// # Arguments
+/// *  `polygon` - The polygon.
+/// *  `x` - The left edge of the pixel.
+/// *  `y` - The top edge of the pixel.
+fn overlap(polygon: &[(f32, f32)], x: f32, y: f32) -> f32 {
+    let edges: [Edge; 4] = [
+        (|p| p.0, x, false),
+        (|p| p.0, x + 1.0, true),
+        (|p| p.1, y, false),
+        (|p| p.1, y + 1.0, true),
+    ];
+
+    let clipped = edges.iter().fold(
+        polygon.to_vec(),
+        |points, &(coordinate, limit, below)| {
+            let inside = |p| {
+                if below {
+                    coordinate(p) <= limit
+                } else {
+                    coordinate(p) >= limit
+                }
+            };
+            let mut result = Vec::with_capacity(points.len() + 1);
+            for (i, &current) in points.iter().enumerate() {
+                let previous = points[(i + points.len() - 1) % points.len()];
+                if inside(current) != inside(previous) {
+                    let t = (limit - coordinate(previous))
+                        / (coordinate(current) - coordinate(previous));
+                    result.push((
+                        previous.0 + t * (current.0 - previous.0),
+                        previous.1 + t * (current.1 - previous.1),
+                    ));
+                }
+                if inside(current) {
+                    result.push(current);
+                }
+            }
+            result
+        },
+    );
+
+    0.5 * clipped
+        .iter()
+        .zip(clipped.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum::<f32>()
+        .abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlap() {
+        let square = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+        assert_eq!(1.0, super::overlap(&square, 1.0, 1.0));
+        assert_eq!(0.0, super::overlap(&square, 2.0, 1.0));
+
+        let triangle = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)];
+        assert_eq!(0.5, super::overlap(&triangle, 0.0, 0.0));
+    }
+
+    #[test]
+    fn sample() {
+        for shape in [maze::Shape::Hex, maze::Shape::Quad, maze::Shape::Tri] {
+            let maze = shape.create::<()>(6, 4);
+            let viewbox = maze.viewbox();
+            let (cols, rows) = (64, 48);
+
+            // Pixels are the x coordinate of their centre
+            let sampled = super::sample(&maze, cols, rows, |col, _| {
+                Accumulator(
+                    viewbox.corner.x
+                        + viewbox.width * (col as f32 + 0.5) / cols as f32,
+                )
+            });
+
+            // The centroid of the polygon of a room is its centre
+            for pos in maze.positions() {
+                let expected =
+                    maze.shape().cell_polygon(pos).map(|p| p.x).sum::<f32>()
+                        / maze.walls(pos).len() as f32;
+                assert!((sampled[pos] - expected).abs() < 0.02);
+            }
+        }
+    }
+}
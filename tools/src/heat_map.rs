@@ -0,0 +1,126 @@
+//! # Heat maps
+//!
+//! A heat map describes how often every room of a maze is part of a path
+//! through it. The heat map types here select which paths to walk.
+use std::str::FromStr;
+
+use maze::matrix;
+
+/// A type of heat map.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HeatMapType {
+    /// The heat map is generated by traversing vertically.
+    Vertical,
+
+    /// The heat map is generated by traversing horisontally.
+    Horizontal,
+
+    /// The heat map is generated by travesing from every edge room to the one
+    /// on the opposite side.
+    Full,
+
+    /// The heat map is the betweenness centrality of every room.
+    Betweenness,
+}
+
+impl FromStr for HeatMapType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<HeatMapType, Self::Err> {
+        match s {
+            "vertical" => Ok(HeatMapType::Vertical),
+            "horizontal" => Ok(HeatMapType::Horizontal),
+            "full" => Ok(HeatMapType::Full),
+            "betweenness" => Ok(HeatMapType::Betweenness),
+            _ => Err(format!("unknown heat map type: {}", s)),
+        }
+    }
+}
+
+impl HeatMapType {
+    /// Generates a heat map based on this heat map type.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze for which to generate a heat map.
+    pub fn generate<T>(self, maze: &maze::Maze<T>) -> maze::HeatMap
+    where
+        T: Clone + Sync,
+    {
+        match self {
+            HeatMapType::Betweenness => maze::betweenness(maze),
+            _ => maze::heatmap(maze, self.paths(maze).into_iter()),
+        }
+    }
+
+    /// The start and end rooms of the paths walked to generate a heat map of
+    /// this type.
+    ///
+    /// Heat maps of betweenness centrality consider all paths, so for them
+    /// nothing is returned.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze for which to generate a heat map.
+    pub fn paths<T>(
+        self,
+        maze: &maze::Maze<T>,
+    ) -> Vec<(matrix::Pos, matrix::Pos)>
+    where
+        T: Clone,
+    {
+        let (width, height) = (maze.width() as isize, maze.height() as isize);
+        let pos = |col, row| matrix::Pos { col, row };
+        match self {
+            HeatMapType::Vertical => (0..width)
+                .map(|col| (pos(col, 0), pos(col, height - 1)))
+                .collect(),
+            HeatMapType::Horizontal => (0..height)
+                .map(|row| (pos(0, row), pos(width - 1, row)))
+                .collect(),
+            HeatMapType::Full => maze
+                .positions()
+                .filter(|&p| p.col == 0 || p.row == 0)
+                .map(|p| (p, pos(width - 1 - p.col, height - 1 - p.row)))
+                .collect(),
+            HeatMapType::Betweenness => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        for (s, expected) in [
+            ("vertical", Ok(HeatMapType::Vertical)),
+            ("horizontal", Ok(HeatMapType::Horizontal)),
+            ("full", Ok(HeatMapType::Full)),
+            ("betweenness", Ok(HeatMapType::Betweenness)),
+            ("Full", Err(String::from("unknown heat map type: Full"))),
+            ("", Err(String::from("unknown heat map type: "))),
+        ] {
+            assert_eq!(expected, s.parse::<HeatMapType>(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn paths() {
+        let maze = maze::Shape::Quad.create::<()>(3, 2);
+        let pos = |col, row| matrix::Pos { col, row };
+        assert_eq!(
+            vec![
+                (pos(0, 0), pos(0, 1)),
+                (pos(1, 0), pos(1, 1)),
+                (pos(2, 0), pos(2, 1))
+            ],
+            HeatMapType::Vertical.paths(&maze),
+        );
+        assert_eq!(
+            vec![(pos(0, 0), pos(2, 0)), (pos(0, 1), pos(2, 1))],
+            HeatMapType::Horizontal.paths(&maze),
+        );
+        assert_eq!(4, HeatMapType::Full.paths(&maze).len());
+        assert!(HeatMapType::Betweenness.paths(&maze).is_empty());
+    }
+}
@@ -3,5 +3,6 @@ extern crate lazy_static;
 
 pub mod alphabet;
 pub mod cell;
-pub mod image;
+pub mod heat_map;
+pub mod mask;
 pub mod voronoi;
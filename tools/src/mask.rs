@@ -0,0 +1,96 @@
+//! # Masks
+//!
+//! An image stretched to cover a maze can be used as a mask determining which
+//! rooms are part of the maze: rooms covering bright parts of the image are
+//! kept, and rooms covering dark parts are excluded.
+use std::ops;
+
+use maze::matrix;
+
+use crate::cell::{sample, Accumulator};
+
+/// Calculates the average intensity of the part of an image covering each
+/// room of a maze.
+///
+/// The image is stretched to cover the entire maze, and every pixel is
+/// weighted by the area of it covered by a room. The intensity of a pixel is
+/// the average of its channels, and the intensities are values between 0 and
+/// 1.
+///
+/// # Arguments
+/// *  `image` - The image.
+/// *  `maze` - The maze.
+pub fn intensities<P, C, T>(
+    image: &image::ImageBuffer<P, C>,
+    maze: &maze::Maze<T>,
+) -> matrix::Matrix<f32>
+where
+    P: image::Pixel<Subpixel = u8>,
+    C: ops::Deref<Target = [u8]>,
+    T: Clone,
+{
+    let (cols, rows) = image.dimensions();
+    let scale = 1.0 / 255.0 / f32::from(P::CHANNEL_COUNT);
+    sample(maze, cols, rows, |x, y| {
+        Accumulator(
+            scale
+                * image
+                    .get_pixel(x, y)
+                    .channels()
+                    .iter()
+                    .map(|&b| f32::from(b))
+                    .sum::<f32>(),
+        )
+    })
+}
+
+/// Generates a filter for the rooms of a maze from an image.
+///
+/// Rooms for which the average intensity of the covered part of the image is
+/// above a threshold are part of the maze.
+///
+/// # Arguments
+/// *  `image` - The mask image.
+/// *  `maze` - The maze.
+/// *  `threshold` - The intensity, between 0 and 1, above which rooms are
+///    part of the maze.
+pub fn filter<P, C, T>(
+    image: &image::ImageBuffer<P, C>,
+    maze: &maze::Maze<T>,
+    threshold: f32,
+) -> matrix::Matrix<bool>
+where
+    P: image::Pixel<Subpixel = u8>,
+    C: ops::Deref<Target = [u8]>,
+    T: Clone,
+{
+    intensities(image, maze).map(|&v| v > threshold)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter() {
+        let maze = maze::Shape::Quad.create::<()>(2, 2);
+        let image = image::RgbImage::from_fn(4, 4, |x, y| {
+            if x >= 2 && y < 2 {
+                image::Rgb([0, 0, 0])
+            } else {
+                image::Rgb([255, 255, 255])
+            }
+        });
+        let filter = super::filter(&image, &maze, 0.5);
+        assert!(filter[matrix::Pos { col: 0, row: 0 }]);
+        assert!(!filter[matrix::Pos { col: 1, row: 0 }]);
+        assert!(filter[matrix::Pos { col: 0, row: 1 }]);
+        assert!(filter[matrix::Pos { col: 1, row: 1 }]);
+
+        let gray = image::GrayImage::from_pixel(1, 1, image::Luma([51]));
+        let intensities = intensities(&gray, &maze);
+        assert!(maze
+            .positions()
+            .all(|pos| (intensities[pos] - 0.2).abs() < 1e-5));
+    }
+}
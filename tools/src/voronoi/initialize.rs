@@ -103,23 +103,43 @@ where
         F: Fn(matrix::Pos) -> bool,
         T: Clone,
         P: Iterator<Item = super::Point<usize>>,
+    {
+        self.initialize_with_observer(maze, rng, filter, points, |_| {})
+    }
+
+    /// Initialises a maze by applying all methods defined for this
+    /// collection, and reports every modification to an observer.
+    ///
+    /// This is the equivalent of [`Self::initialize`], except that
+    /// `observer` is called every time a wall is opened or closed.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to initialise.
+    /// *  `rng` - A random number generator.
+    /// *  `filter` - An additional filter applied to all methods.
+    /// *  `points` - The points and weights to generate a Voronoi diagram.
+    /// *  `observer` - A function notified of every modified wall.
+    pub fn initialize_with_observer<F, T, P, O>(
+        self,
+        maze: maze::Maze<T>,
+        rng: &mut R,
+        filter: F,
+        points: P,
+        observer: O,
+    ) -> InitializedMaze<T>
+    where
+        F: Fn(matrix::Pos) -> bool,
+        T: Clone,
+        P: Iterator<Item = super::Point<usize>>,
+        O: FnMut(initialize::Event),
     {
         // Generate the areas
         let areas =
             super::matrix(&maze, points.take(self.methods.len()).collect());
 
         // Use a different initialisation method for each segment
-        let mut maze = self.methods.into_iter().enumerate().fold(
-            maze,
-            |maze, (i, method)| {
-                maze.initialize_filter(method, rng, |pos| {
-                    filter(pos) && areas[pos] == i
-                })
-            },
-        );
-
-        // Make sure all segments are connected
-        initialize::connect_all(&mut maze, rng, filter);
+        let maze = initialize::Mixed::new(self.methods)
+            .initialize_with_areas(maze, rng, &areas, filter, observer);
 
         InitializedMaze { maze, areas }
     }
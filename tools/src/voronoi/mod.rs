@@ -19,25 +19,10 @@ where
     V: Clone + Default,
     T: Clone,
 {
-    let mut result = matrix::Matrix::new(maze.width(), maze.height());
-
-    // For each cell in the resulting matrix, retrieve the value of the point
-    // closest to the centre of the room corresponding to the cell by iterating
-    // over all points and selecting the one where the distance / weight ratio
-    // is the smallest
-    for pos in result.positions() {
-        let center = maze.center(pos);
-        if let Some(val) = points
-            .iter()
-            .map(|(val, (p, w))| ((*p - center).value() / w, val))
-            // We assume that that the weights are not exotic enough to cause
-            // this to fail
-            .min_by(|v1, v2| v1.0.partial_cmp(&v2.0).unwrap())
-            .map(|(_, val)| val)
-        {
-            result[pos] = val.clone();
-        }
+    let (values, points): (Vec<_>, Vec<_>) = points.into_iter().unzip();
+    if values.is_empty() {
+        matrix::Matrix::new(maze.width(), maze.height())
+    } else {
+        maze::initialize::voronoi(maze, &points).map(|&i| values[i].clone())
     }
-
-    result
 }
@@ -0,0 +1,161 @@
+//! # Maze bindings for the browser
+//!
+//! This crate exposes the maze crate to _JavaScript_ through `wasm-bindgen`,
+//! so that mazes can be generated locally in a browser.
+//!
+//! Mazes are initialised using the _LFSR_ of the maze crate, so a seed
+//! generates the same maze in the browser as on the server.
+//!
+//! ```javascript
+//! const maze = new Maze("hex", 10, 10);
+//! maze.initialize("braid", 42n);
+//! path.setAttribute("d", maze.to_path_d());
+//! ```
+use wasm_bindgen::prelude::*;
+
+use maze::initialize;
+use maze::matrix;
+use maze::render::svg::ToPath;
+
+/// A maze.
+#[wasm_bindgen]
+pub struct Maze(maze::Maze<()>);
+
+#[wasm_bindgen]
+impl Maze {
+    /// Creates a new maze with all walls closed.
+    ///
+    /// # Arguments
+    /// *  `shape` - The shape of the rooms; one of `"tri"`, `"quad"` and
+    ///    `"hex"`.
+    /// *  `width` - The width of the maze, in rooms.
+    /// *  `height` - The height of the maze, in rooms.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        shape: &str,
+        width: usize,
+        height: usize,
+    ) -> Result<Maze, String> {
+        let shape = shape
+            .parse::<maze::Shape>()
+            .map_err(|e| format!("unknown shape: {}", e))?;
+        Ok(Self(shape.create(width, height)))
+    }
+
+    /// The width of the maze, in rooms.
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    /// The height of the maze, in rooms.
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> usize {
+        self.0.height()
+    }
+
+    /// Initialises the maze.
+    ///
+    /// The same method and seed always generate the same maze.
+    ///
+    /// # Arguments
+    /// *  `method` - The initialisation method; one of `"braid"`,
+    ///    `"branching"`, `"clear"` and `"winding"`.
+    /// *  `seed` - The seed for the random number generator.
+    pub fn initialize(
+        &mut self,
+        method: &str,
+        seed: u64,
+    ) -> Result<(), String> {
+        let method = method
+            .parse::<initialize::Method>()
+            .map_err(|e| format!("unknown method: {}", e))?;
+        self.0 = self
+            .0
+            .clone()
+            .initialize(method, &mut initialize::LFSR::new(seed));
+        Ok(())
+    }
+
+    /// Finds the shortest path between two rooms.
+    ///
+    /// The path is returned as a flat list of column and row pairs, or
+    /// `undefined` if no path exists.
+    ///
+    /// # Arguments
+    /// *  `from_col` - The column of the start room.
+    /// *  `from_row` - The row of the start room.
+    /// *  `to_col` - The column of the end room.
+    /// *  `to_row` - The row of the end room.
+    pub fn walk(
+        &self,
+        from_col: isize,
+        from_row: isize,
+        to_col: isize,
+        to_row: isize,
+    ) -> Option<Vec<isize>> {
+        let from = matrix::Pos {
+            col: from_col,
+            row: from_row,
+        };
+        let to = matrix::Pos {
+            col: to_col,
+            row: to_row,
+        };
+        if !self.0.is_inside(from) || !self.0.is_inside(to) {
+            return None;
+        }
+
+        self.0.walk(from, to).map(|path| {
+            path.into_iter()
+                .flat_map(|pos| [pos.col, pos.row])
+                .collect()
+        })
+    }
+
+    /// The view box of the maze, as `[x, y, width, height]`.
+    pub fn viewbox(&self) -> Vec<f32> {
+        let (x, y, width, height) = self.0.viewbox().tuple();
+        vec![x, y, width, height]
+    }
+
+    /// Generates _SVG_ path data for the walls of the maze.
+    pub fn to_path_d(&self) -> String {
+        svg::node::Value::from(self.0.to_path_d()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn initialize() {
+        let mut maze = Maze::new("quad", 5, 4).unwrap();
+        maze.initialize("branching", 42).unwrap();
+        assert_eq!(5, maze.width());
+        assert_eq!(4, maze.height());
+
+        let expected = maze::Shape::Quad
+            .create::<()>(5, 4)
+            .initialize(
+                initialize::Method::Branching(initialize::Bias::default()),
+                &mut initialize::LFSR::new(42),
+            )
+            .to_path_d();
+        assert_eq!(
+            svg::node::Value::from(expected).to_string(),
+            maze.to_path_d(),
+        );
+    }
+
+    #[test]
+    fn walk() {
+        let mut maze = Maze::new("hex", 3, 3).unwrap();
+        maze.initialize("winding", 1).unwrap();
+        let path = maze.walk(0, 0, 2, 2).unwrap();
+        assert_eq!(&[0, 0], &path[..2]);
+        assert_eq!(&[2, 2], &path[path.len() - 2..]);
+        assert_eq!(None, maze.walk(0, 0, 3, 3));
+    }
+}
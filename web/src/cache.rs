@@ -0,0 +1,103 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// A cache retaining the most recently used values.
+///
+/// When the cache is full, inserting a new value evicts the least recently
+/// used one.
+pub struct Cache<K, V> {
+    /// The maximum number of values.
+    capacity: usize,
+
+    /// The values, and the time at which they were last used.
+    values: HashMap<K, (u64, V)>,
+
+    /// The keys, ordered by the time at which they were last used.
+    usage: BTreeMap<u64, K>,
+
+    /// The current time.
+    time: u64,
+}
+
+impl<K, V> Cache<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+{
+    /// Creates an empty cache.
+    ///
+    /// # Arguments
+    /// *  `capacity` - The maximum number of values.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            values: HashMap::new(),
+            usage: BTreeMap::new(),
+            time: 0,
+        }
+    }
+
+    /// Retrieves a value and marks it as recently used.
+    ///
+    /// # Arguments
+    /// *  `key` - The key of the value.
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        self.time += 1;
+        let (time, value) = self.values.get_mut(key)?;
+        self.usage.remove(time);
+        self.usage.insert(self.time, key.clone());
+        *time = self.time;
+        Some(value.clone())
+    }
+
+    /// Inserts a value, evicting the least recently used value if the cache
+    /// is full.
+    ///
+    /// # Arguments
+    /// *  `key` - The key of the value.
+    /// *  `value` - The value.
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.time += 1;
+        if let Some((time, _)) = self.values.remove(&key) {
+            self.usage.remove(&time);
+        } else if self.values.len() >= self.capacity {
+            if let Some((_, oldest)) = self.usage.pop_first() {
+                self.values.remove(&oldest);
+            }
+        }
+        self.usage.insert(self.time, key.clone());
+        self.values.insert(key, (self.time, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = Cache::new(2);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        assert_eq!(Some(1), cache.get(&"a"));
+        cache.insert("c", 3);
+        assert_eq!(Some(1), cache.get(&"a"));
+        assert_eq!(None, cache.get(&"b"));
+        assert_eq!(Some(3), cache.get(&"c"));
+    }
+
+    #[test]
+    fn replaces() {
+        let mut cache = Cache::new(2);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.insert("a", 3);
+        cache.insert("c", 4);
+        assert_eq!(Some(3), cache.get(&"a"));
+        assert_eq!(None, cache.get(&"b"));
+    }
+}
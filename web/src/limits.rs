@@ -0,0 +1,302 @@
+//! # Request limits
+//!
+//! The limits are read from the environment when the server starts:
+//!
+//! *  `MAZE_MAX_ROOMS` - The maximum number of rooms of a maze. This defaults
+//!    to `1000`.
+//! *  `MAZE_MAX_WIDTH` and `MAZE_MAX_HEIGHT` - The maximum width and height of
+//!    a maze, in rooms. By default, only the number of rooms is limited.
+//! *  `MAZE_RATE_LIMIT` - The maximum number of requests per client and
+//!    minute. By default, requests are not rate limited.
+//! *  `MAZE_API_KEYS` - A comma separated list of API keys. Clients passing a
+//!    valid key in the `X-Api-Key` header are not rate limited.
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::types;
+
+/// The default maximum number of rooms.
+const DEFAULT_MAX_ROOMS: usize = 1000;
+
+/// The duration of a rate limiting window.
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// The header containing the API key of a request.
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// The limits applied to requests.
+pub struct Limits {
+    /// The maximum number of rooms of a maze.
+    pub max_rooms: usize,
+
+    /// The maximum width of a maze, in rooms.
+    pub max_width: Option<usize>,
+
+    /// The maximum height of a maze, in rooms.
+    pub max_height: Option<usize>,
+
+    /// The maximum number of requests per client and minute.
+    pub rate_limit: Option<u32>,
+
+    /// The API keys exempting clients from rate limiting.
+    pub api_keys: HashSet<String>,
+
+    /// The usage of all clients.
+    usage: Mutex<Usage>,
+}
+
+/// The usage of all clients.
+struct Usage {
+    /// The time at which stale clients were last removed.
+    pruned: Instant,
+
+    /// The start of the current window, and the number of requests made
+    /// during it, for every client.
+    clients: HashMap<String, (Instant, u32)>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_rooms: DEFAULT_MAX_ROOMS,
+            max_width: None,
+            max_height: None,
+            rate_limit: None,
+            api_keys: HashSet::new(),
+            usage: Mutex::new(Usage {
+                pruned: Instant::now(),
+                clients: HashMap::new(),
+            }),
+        }
+    }
+}
+
+impl Limits {
+    /// Reads the limits from the environment.
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Reads the limits from variables.
+    ///
+    /// Variables that are not set keep their default values.
+    ///
+    /// # Arguments
+    /// *  `var` - A function returning the value of a variable.
+    fn from_vars<F>(var: F) -> Result<Self, String>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut result = Self::default();
+        if let Some(max_rooms) = parse(&var, "MAZE_MAX_ROOMS")? {
+            result.max_rooms = max_rooms;
+        }
+        result.max_width = parse(&var, "MAZE_MAX_WIDTH")?;
+        result.max_height = parse(&var, "MAZE_MAX_HEIGHT")?;
+        result.rate_limit = parse(&var, "MAZE_RATE_LIMIT")?;
+        result.api_keys = var("MAZE_API_KEYS")
+            .map(|keys| {
+                keys.split(',')
+                    .map(str::trim)
+                    .filter(|key| !key.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(result)
+    }
+
+    /// Ensures that a maze with specific dimensions may be generated.
+    ///
+    /// # Arguments
+    /// *  `dimensions` - The dimensions of the maze.
+    pub fn check(
+        &self,
+        dimensions: types::Dimensions,
+    ) -> Result<(), types::Error> {
+        let types::Dimensions { width, height } = dimensions;
+        if width.saturating_mul(height) > self.max_rooms
+            || self.max_width.map(|max| width > max).unwrap_or(false)
+            || self.max_height.map(|max| height > max).unwrap_or(false)
+        {
+            Err(types::Error::TooLarge)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Registers a request and ensures that it may be served.
+    ///
+    /// Requests with an unknown API key are rejected. Requests without a key
+    /// count towards the rate limit of their client.
+    ///
+    /// Clients whose window has ended are forgotten once per window, so that
+    /// the usage does not grow with the number of clients ever seen.
+    ///
+    /// # Arguments
+    /// *  `key` - The API key passed with the request.
+    /// *  `client` - An identifier for the client.
+    /// *  `now` - The time of the request.
+    pub fn admit(
+        &self,
+        key: Option<&str>,
+        client: &str,
+        now: Instant,
+    ) -> Result<(), types::Error> {
+        if let Some(key) = key {
+            return if self.api_keys.contains(key) {
+                Ok(())
+            } else {
+                Err(types::Error::Unauthorized)
+            };
+        }
+        let Some(rate_limit) = self.rate_limit else {
+            return Ok(());
+        };
+
+        let mut usage = self.usage.lock().unwrap();
+        if now.duration_since(usage.pruned) >= RATE_WINDOW {
+            usage.clients.retain(|_, (start, _)| {
+                now.duration_since(*start) < RATE_WINDOW
+            });
+            usage.pruned = now;
+        }
+        let (start, count) =
+            usage.clients.entry(client.into()).or_insert((now, 0));
+        if now.duration_since(*start) >= RATE_WINDOW {
+            *start = now;
+            *count = 0;
+        }
+        if *count < rate_limit {
+            *count += 1;
+            Ok(())
+        } else {
+            Err(types::Error::RateLimited)
+        }
+    }
+}
+
+/// Parses the value of a variable, if it is set.
+///
+/// # Arguments
+/// *  `var` - A function returning the value of a variable.
+/// *  `name` - The name of the variable.
+fn parse<F, T>(var: F, name: &str) -> Result<Option<T>, String>
+where
+    F: Fn(&str) -> Option<String>,
+    T: FromStr,
+{
+    var(name)
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid value for {}", name))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_vars() {
+        let limits = Limits::from_vars(|name| match name {
+            "MAZE_MAX_ROOMS" => Some("50".into()),
+            "MAZE_MAX_WIDTH" => Some("10".into()),
+            "MAZE_API_KEYS" => Some("a, b,".into()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(50, limits.max_rooms);
+        assert_eq!(Some(10), limits.max_width);
+        assert_eq!(None, limits.max_height);
+        assert_eq!(None, limits.rate_limit);
+        assert_eq!(
+            HashSet::from([String::from("a"), String::from("b")]),
+            limits.api_keys,
+        );
+
+        assert_eq!(
+            Some(String::from("invalid value for MAZE_RATE_LIMIT")),
+            Limits::from_vars(
+                |name| (name == "MAZE_RATE_LIMIT").then(|| "many".into())
+            )
+            .err(),
+        );
+    }
+
+    #[test]
+    fn check() {
+        let limits = Limits {
+            max_rooms: 100,
+            max_width: Some(20),
+            ..Default::default()
+        };
+        let dimensions = |width, height| types::Dimensions { width, height };
+        assert_eq!(Ok(()), limits.check(dimensions(10, 10)));
+        assert_eq!(Ok(()), limits.check(dimensions(20, 5)));
+        assert_eq!(
+            Err(types::Error::TooLarge),
+            limits.check(dimensions(10, 11)),
+        );
+        assert_eq!(
+            Err(types::Error::TooLarge),
+            limits.check(dimensions(25, 1)),
+        );
+    }
+
+    #[test]
+    fn admit() {
+        let limits = Limits {
+            rate_limit: Some(2),
+            api_keys: HashSet::from([String::from("key")]),
+            ..Default::default()
+        };
+        let now = Instant::now();
+        assert_eq!(Ok(()), limits.admit(None, "a", now));
+        assert_eq!(Ok(()), limits.admit(None, "a", now));
+        assert_eq!(
+            Err(types::Error::RateLimited),
+            limits.admit(None, "a", now),
+        );
+        assert_eq!(Ok(()), limits.admit(None, "b", now));
+        assert_eq!(Ok(()), limits.admit(Some("key"), "a", now));
+        assert_eq!(
+            Err(types::Error::Unauthorized),
+            limits.admit(Some("other"), "a", now),
+        );
+        assert_eq!(Ok(()), limits.admit(None, "a", now + RATE_WINDOW));
+    }
+
+    #[test]
+    fn admit_prune() {
+        let limits = Limits {
+            rate_limit: Some(1),
+            ..Default::default()
+        };
+        let now = Instant::now();
+        for client in ["a", "b", "c"] {
+            assert_eq!(Ok(()), limits.admit(None, client, now));
+        }
+        assert_eq!(3, limits.usage.lock().unwrap().clients.len());
+
+        let later = now + 2 * RATE_WINDOW;
+        assert_eq!(Ok(()), limits.admit(None, "d", later));
+        assert_eq!(
+            vec!["d"],
+            limits
+                .usage
+                .lock()
+                .unwrap()
+                .clients
+                .keys()
+                .collect::<Vec<_>>(),
+        );
+    }
+}
@@ -1,35 +1,489 @@
-use actix_web::{get, web, App, HttpResponse, HttpServer, Responder};
+use std::time::Instant;
+
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header;
+use actix_web::middleware::{from_fn, Next};
+use actix_web::{
+    error::InternalError, get, post, web, App, HttpRequest, HttpResponse,
+    HttpServer, ResponseError,
+};
+use futures_util::StreamExt;
 use serde::Deserialize;
+use tokio::sync::mpsc;
 
+mod cache;
+mod limits;
+mod openapi;
 mod types;
 
-#[derive(Deserialize)]
-struct Query {
+/// The maximum number of generation events buffered for a client.
+const EVENT_BUFFER: usize = 64;
+
+/// The maximum number of rendered images kept in memory.
+const CACHE_CAPACITY: usize = 256;
+
+/// The cache of rendered images, keyed by their parameters.
+type ImageCache = std::sync::Mutex<cache::Cache<String, types::Image>>;
+
+/// The path parameters identifying a maze.
+type MazePath = web::Path<(types::MazeType, types::Dimensions)>;
+
+/// Defines the query parameters of maze requests.
+///
+/// The documentation of each field is used as the description of the
+/// parameter in the _OpenAPI_ specification, and the type of each field
+/// provides its schema.
+macro_rules! query {
+    ($($(#[doc = $doc:literal])+ $name:ident: $type:ty,)*) => {
+        #[derive(Deserialize)]
+        struct Query {
+            $(
+                $(#[doc = $doc])+
+                $name: $type,
+            )*
+        }
+
+        impl openapi::Parameters for Query {
+            fn parameters() -> Vec<serde_json::Value> {
+                vec![$(
+                    openapi::parameter(
+                        "query",
+                        stringify!($name),
+                        concat!($($doc),+).trim(),
+                        <$type as openapi::Schema>::schema(),
+                    ),
+                )*]
+            }
+        }
+    };
+}
+
+query! {
+    /// The initialisation method.
+    method: Option<types::Method>,
+    /// The random seed. Mazes with a seed are cached.
     seed: Option<types::Seed>,
+    /// The type of heat map to draw.
+    heat_map: Option<types::HeatMapType>,
+    /// Whether to add identifying attributes to rooms.
+    room_ids: Option<bool>,
+    /// Whether to draw the solution.
     solve: Option<bool>,
+    /// The colour of the walls.
+    wall_color: Option<types::Color>,
+    /// The colour of the background.
+    background_color: Option<types::Color>,
+    /// The width of the walls.
+    stroke_width: Option<f32>,
+    /// The colour of the solution.
+    solution_color: Option<types::Color>,
+    /// The physical width of the maze.
+    width_mm: Option<f32>,
+    /// The physical height of the maze.
+    height_mm: Option<f32>,
+    /// The physical width of a room.
+    room_size: Option<f32>,
 }
+
+impl Query {
+    /// Creates a maze description from these query parameters.
+    ///
+    /// If a physical size is passed, it replaces the requested dimensions.
+    ///
+    /// # Arguments
+    /// *  `maze_type` - The requested maze type.
+    /// *  `dimensions` - The requested dimensions.
+    /// *  `mask` - A mask shaping the maze.
+    /// *  `limits` - The limits for the maze.
+    fn maze(
+        self,
+        maze_type: types::MazeType,
+        dimensions: types::Dimensions,
+        mask: Option<types::Mask>,
+        limits: &limits::Limits,
+    ) -> Result<types::Maze, types::Error> {
+        let dimensions = dimensions
+            .with_physical_size(
+                maze_type.shape(),
+                self.width_mm,
+                self.height_mm,
+                self.room_size,
+            )
+            .map_err(types::Error::Invalid)?;
+        limits.check(dimensions)?;
+
+        Ok(types::Maze {
+            maze_type,
+            dimensions,
+            method: self.method.unwrap_or_default(),
+            seed: self.seed.unwrap_or_else(types::Seed::random),
+            heat_map: self.heat_map,
+            room_ids: self.room_ids.unwrap_or(false),
+            mask,
+            solve: self.solve.unwrap_or(false),
+            render_options: types::RenderOptions::new(
+                self.wall_color,
+                self.background_color,
+                self.stroke_width,
+                self.solution_color,
+            )
+            .map_err(types::Error::Invalid)?,
+        })
+    }
+}
+
 #[get("/{maze_type}/{dimensions}/image.svg")]
 async fn maze_svg(
-    (path, query): (
-        web::Path<(types::MazeType, types::Dimensions)>,
+    (path, query, cache, limits): (
+        MazePath,
         web::Query<Query>,
+        web::Data<ImageCache>,
+        web::Data<limits::Limits>,
     ),
-) -> impl Responder {
+) -> Result<HttpResponse, types::Error> {
+    let (maze_type, dimensions) = path.into_inner();
+    respond(
+        maze_type,
+        dimensions,
+        query.into_inner(),
+        None,
+        &cache,
+        &limits,
+    )
+}
+
+/// Describes the layout of a maze as _JSON_.
+#[get("/{maze_type}/{dimensions}/maze.json")]
+async fn maze_json(
+    (path, query, limits): (
+        MazePath,
+        web::Query<Query>,
+        web::Data<limits::Limits>,
+    ),
+) -> Result<HttpResponse, types::Error> {
+    let (maze_type, dimensions) = path.into_inner();
+    let query = query.into_inner();
+    let cacheable = query.seed.is_some();
+    let layout = query.maze(maze_type, dimensions, None, &limits)?.layout()?;
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, cache_control(cacheable)))
+        .json(layout))
+}
+
+/// Describes a maze and its solution as a puzzle in _JSON_.
+///
+/// See [`maze::io::puzzle_json`] for the format.
+#[get("/{maze_type}/{dimensions}/puzzle.json")]
+async fn puzzle_json(
+    (path, query, limits): (
+        MazePath,
+        web::Query<Query>,
+        web::Data<limits::Limits>,
+    ),
+) -> Result<HttpResponse, types::Error> {
+    let (maze_type, dimensions) = path.into_inner();
+    let query = query.into_inner();
+    let cacheable = query.seed.is_some();
+    let puzzle = query.maze(maze_type, dimensions, None, &limits)?.puzzle()?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .insert_header((header::CACHE_CONTROL, cache_control(cacheable)))
+        .body(puzzle))
+}
+
+/// Serves a page on which a maze can be navigated using the arrow keys.
+///
+/// The page contains the maze image, and loads the layout from the _JSON_
+/// endpoint for the same maze.
+#[get("/play/{maze_type}/{dimensions}")]
+async fn play(
+    request: HttpRequest,
+    path: MazePath,
+    query: web::Query<Query>,
+    limits: web::Data<limits::Limits>,
+) -> Result<HttpResponse, types::Error> {
+    let (maze_type, dimensions) = path.into_inner();
+    let maze = query
+        .into_inner()
+        .maze(maze_type, dimensions, None, &limits)?;
+    let dimensions = maze.dimensions;
+    let image = maze.render()?;
+
+    let layout = format!(
+        "/{}/{}x{}/maze.json?seed={}&amp;method={}",
+        &request.match_info()["maze_type"],
+        dimensions.width,
+        dimensions.height,
+        image.seed,
+        image.method,
+    );
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .insert_header((header::CACHE_CONTROL, cache_control(false)))
+        .body(
+            include_str!("play.html")
+                .replace("{layout}", &layout)
+                .replace("{image}", &image.data),
+        ))
+}
+
+/// Generates a maze shaped by a mask.
+///
+/// The request body is either a _PNG_ image, with the content type
+/// `image/png`, or a text, with the content type `text/plain`.
+#[post("/{maze_type}/{dimensions}/image.svg")]
+async fn masked_maze_svg(
+    request: HttpRequest,
+    path: MazePath,
+    query: web::Query<Query>,
+    body: web::Bytes,
+    cache: web::Data<ImageCache>,
+    limits: web::Data<limits::Limits>,
+) -> Result<HttpResponse, types::Error> {
     let (maze_type, dimensions) = path.into_inner();
-    let Query { seed, solve } = query.into_inner();
-    HttpResponse::from(types::Maze {
+    let content_type = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(str::trim);
+    let mask = match content_type {
+        Some("image/png") => types::Mask::from_png(&body),
+        Some("text/plain") => std::str::from_utf8(&body)
+            .map_err(|_| String::from("invalid mask: not UTF-8"))
+            .and_then(types::Mask::from_text),
+        _ => return Err(types::Error::UnsupportedMask),
+    }
+    .map_err(types::Error::Invalid)?;
+    respond(
         maze_type,
         dimensions,
-        seed: seed.unwrap_or_else(types::Seed::random),
-        solve: solve.unwrap_or(false),
+        query.into_inner(),
+        Some(mask),
+        &cache,
+        &limits,
+    )
+}
+
+/// Streams the events of generating a maze as server-sent events.
+///
+/// This lets clients animate the maze being generated.
+#[get("/{maze_type}/{dimensions}/events")]
+async fn maze_events(
+    (path, query, limits): (
+        MazePath,
+        web::Query<Query>,
+        web::Data<limits::Limits>,
+    ),
+) -> Result<HttpResponse, types::Error> {
+    let (maze_type, dimensions) = path.into_inner();
+    let query = query.into_inner();
+    let cacheable = query.seed.is_some();
+    let maze = query.maze(maze_type, dimensions, None, &limits)?;
+
+    // Generate the maze on a blocking task, which waits for events to be
+    // sent to the client while the channel is full
+    let (sender, mut receiver) = mpsc::channel(EVENT_BUFFER);
+    let generation =
+        actix_web::rt::task::spawn_blocking(move || maze.events(sender));
+
+    // No event is sent if the maze cannot be generated
+    let Some(start) = receiver.recv().await else {
+        return Err(generation
+            .await
+            .expect("failed to generate maze")
+            .expect_err("no events sent"));
+    };
+    let events = futures_util::stream::once(async { start })
+        .chain(futures_util::stream::unfold(
+            receiver,
+            |mut receiver| async {
+                receiver.recv().await.map(|event| (event, receiver))
+            },
+        ))
+        .map(|event| {
+            Ok::<_, actix_web::Error>(web::Bytes::from(event.to_sse()))
+        });
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, cache_control(cacheable)))
+        .streaming(events))
+}
+
+/// Describes the API as an _OpenAPI_ specification.
+#[get("/openapi.json")]
+async fn openapi_json() -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, cache_control(false)))
+        .json(openapi::document())
+}
+
+/// Generates the response for a maze request.
+///
+/// Images of mazes with an explicit seed are deterministic, so they are
+/// cached both in memory and by clients. Images of mazes with a random seed
+/// are never cached, and neither are images of masked mazes, since the mask
+/// is sent in the body of a request, which clients do not use as a cache key.
+///
+/// # Arguments
+/// *  `maze_type` - The requested maze type.
+/// *  `dimensions` - The requested dimensions.
+/// *  `query` - The query parameters.
+/// *  `mask` - A mask shaping the maze.
+/// *  `cache` - The cache of rendered images.
+/// *  `limits` - The limits for the maze.
+fn respond(
+    maze_type: types::MazeType,
+    dimensions: types::Dimensions,
+    query: Query,
+    mask: Option<types::Mask>,
+    cache: &ImageCache,
+    limits: &limits::Limits,
+) -> Result<HttpResponse, types::Error> {
+    let cacheable = query.seed.is_some() && mask.is_none();
+    let maze = query.maze(maze_type, dimensions, mask, limits)?;
+
+    let key = maze.cache_key().filter(|_| cacheable);
+    let cached = key.as_ref().and_then(|key| cache.lock().unwrap().get(key));
+    let image = match cached {
+        Some(image) => image,
+        None => {
+            let image = maze.render()?;
+            if let Some(key) = key {
+                cache.lock().unwrap().insert(key, image.clone());
+            }
+            image
+        }
+    };
+
+    let mut response = HttpResponse::from(image);
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, cache_control(cacheable));
+    Ok(response)
+}
+
+/// The cache control header value for a response.
+///
+/// # Arguments
+/// *  `cacheable` - Whether the response is deterministic.
+fn cache_control(cacheable: bool) -> header::HeaderValue {
+    header::HeaderValue::from_static(if cacheable {
+        "public, max-age=31536000, immutable"
+    } else {
+        "no-store"
     })
 }
 
+/// Rejects requests with unknown API keys and requests exceeding the rate
+/// limit of their client.
+///
+/// Clients are identified by their address.
+async fn admit(
+    request: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    let admitted = request
+        .app_data::<web::Data<limits::Limits>>()
+        .map(|limits| {
+            let key = request
+                .headers()
+                .get(limits::API_KEY_HEADER)
+                .and_then(|value| value.to_str().ok());
+            let client = request
+                .peer_addr()
+                .map(|address| address.ip().to_string())
+                .unwrap_or_default();
+            limits.admit(key, &client, Instant::now())
+        })
+        .unwrap_or(Ok(()));
+
+    match admitted {
+        Ok(()) => next
+            .call(request)
+            .await
+            .map(ServiceResponse::map_into_boxed_body),
+        Err(e) => Ok(request.into_response(e.error_response())),
+    }
+}
+
+/// Converts errors from extracting request parameters to _JSON_ responses.
+///
+/// # Arguments
+/// *  `error` - The extraction error.
+fn invalid<E>(error: E) -> actix_web::Error
+where
+    E: std::fmt::Debug + std::fmt::Display + 'static,
+{
+    let response = types::Error::Invalid(error.to_string()).error_response();
+    InternalError::from_response(error, response).into()
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    HttpServer::new(|| App::new().service(maze_svg))
-        .bind("0.0.0.0:8000")
-        .unwrap()
-        .run()
-        .await
+    let cache =
+        web::Data::new(ImageCache::new(cache::Cache::new(CACHE_CAPACITY)));
+    let limits = web::Data::new(
+        limits::Limits::from_env().map_err(std::io::Error::other)?,
+    );
+    HttpServer::new(move || {
+        App::new()
+            .wrap(from_fn(admit))
+            .app_data(cache.clone())
+            .app_data(limits.clone())
+            .app_data(web::PayloadConfig::new(types::MAX_MASK_BYTES))
+            .app_data(
+                web::PathConfig::default()
+                    .error_handler(|error, _| invalid(error)),
+            )
+            .app_data(
+                web::QueryConfig::default()
+                    .error_handler(|error, _| invalid(error)),
+            )
+            .configure(openapi::configure)
+    })
+    .bind("0.0.0.0:8000")
+    .unwrap()
+    .run()
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn cache_control_masked() {
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(web::Data::new(limits::Limits::default()))
+                .app_data(web::Data::new(ImageCache::new(cache::Cache::new(1))))
+                .configure(openapi::configure),
+        )
+        .await;
+
+        let request = TestRequest::get()
+            .uri("/quad/5x5/image.svg?seed=1")
+            .to_request();
+        let response = actix_web::test::call_service(&app, request).await;
+        assert_eq!(
+            Some(&cache_control(true)),
+            response.headers().get(header::CACHE_CONTROL),
+        );
+
+        let request = TestRequest::post()
+            .uri("/quad/5x5/image.svg?seed=1")
+            .insert_header((header::CONTENT_TYPE, "text/plain"))
+            .set_payload("xxxxx\nxxxxx\nxxxxx\nxxxxx\nxxxxx\n")
+            .to_request();
+        let response = actix_web::test::call_service(&app, request).await;
+        assert!(response.status().is_success());
+        assert_eq!(
+            Some(&cache_control(false)),
+            response.headers().get(header::CACHE_CONTROL),
+        );
+    }
 }
@@ -0,0 +1,416 @@
+//! # OpenAPI specification
+//!
+//! The specification served at `/openapi.json` is assembled from the types
+//! accepted and returned by the handlers: every such type describes itself by
+//! implementing [`Schema`], and the query parameters by implementing
+//! [`Parameters`].
+//!
+//! The handlers are registered from the same table of operations as is used
+//! to generate the specification, so every route is described.
+
+use actix_web::http::StatusCode;
+use actix_web::{web, ResponseError};
+use serde_json::{json, Map, Value};
+
+use crate::types;
+
+/// The version of the _OpenAPI_ specification format.
+const OPENAPI_VERSION: &str = "3.0.3";
+
+/// A type with a _JSON_ schema.
+pub trait Schema {
+    /// The schema of this type.
+    fn schema() -> Value;
+}
+
+impl Schema for bool {
+    fn schema() -> Value {
+        json!({ "type": "boolean", "example": true })
+    }
+}
+
+impl Schema for f32 {
+    fn schema() -> Value {
+        json!({ "type": "number", "example": 1 })
+    }
+}
+
+impl<T> Schema for Option<T>
+where
+    T: Schema,
+{
+    fn schema() -> Value {
+        T::schema()
+    }
+}
+
+/// A type describing the parameters of a request.
+pub trait Parameters {
+    /// The parameters described by this type.
+    fn parameters() -> Vec<Value>;
+}
+
+/// Describes a parameter.
+///
+/// # Arguments
+/// *  `location` - Where the parameter is passed; `path` or `query`.
+/// *  `name` - The name of the parameter.
+/// *  `description` - A description of the parameter.
+/// *  `schema` - The schema of the parameter value.
+pub fn parameter(
+    location: &str,
+    name: &str,
+    description: &str,
+    schema: Value,
+) -> Value {
+    json!({
+        "in": location,
+        "name": name,
+        "description": description,
+        "required": location == "path",
+        "schema": schema,
+    })
+}
+
+/// A reference to a schema among the components of the specification.
+///
+/// # Arguments
+/// *  `name` - The name of the schema.
+fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+/// A description of a single operation of the API.
+struct Operation {
+    /// The HTTP method.
+    method: &'static str,
+
+    /// The path, with parameters on the form `{name}`.
+    path: &'static str,
+
+    /// A short description of the operation.
+    summary: &'static str,
+
+    /// The request body, as a map from content type to schema.
+    body: Option<Value>,
+
+    /// The content type of successful responses.
+    content_type: &'static str,
+
+    /// The schema of successful responses.
+    response: Value,
+
+    /// Registers the handler of this operation.
+    service: fn(&mut web::ServiceConfig),
+}
+
+impl Operation {
+    /// Describes this operation.
+    ///
+    /// Operations on mazes accept the maze parameters, and may fail with any
+    /// error.
+    fn describe(&self) -> Value {
+        let mut responses = Map::new();
+        responses.insert(
+            "200".into(),
+            json!({
+                "description": "Success",
+                "content": {
+                    self.content_type: { "schema": self.response },
+                },
+            }),
+        );
+
+        let mut result = json!({
+            "summary": self.summary,
+            "responses": responses,
+        });
+        if self.path.contains("{maze_type}") {
+            let mut parameters = vec![
+                parameter(
+                    "path",
+                    "maze_type",
+                    "The shape of the rooms.",
+                    types::MazeType::schema(),
+                ),
+                parameter(
+                    "path",
+                    "dimensions",
+                    "The size of the maze, in rooms.",
+                    types::Dimensions::schema(),
+                ),
+            ];
+            parameters.extend(crate::Query::parameters());
+            result["parameters"] = parameters.into();
+            for (status, description) in errors() {
+                result["responses"][status.as_str()] = json!({
+                    "description": description,
+                    "content": {
+                        "application/json": {
+                            "schema": reference("Error"),
+                        },
+                    },
+                });
+            }
+        }
+        if let Some(body) = &self.body {
+            let content = body
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(content_type, schema)| {
+                    (content_type.clone(), json!({ "schema": schema }))
+                })
+                .collect::<Map<_, _>>();
+            result["requestBody"] = json!({
+                "required": true,
+                "content": content,
+            });
+        }
+
+        result
+    }
+}
+
+/// The status codes of errors, with the descriptions of all errors sharing
+/// the status code.
+fn errors() -> Vec<(StatusCode, String)> {
+    let mut result = Vec::<(StatusCode, String)>::new();
+    for error in types::Error::kinds() {
+        let description = match error {
+            types::Error::Invalid(_) => "the request is invalid",
+            ref error => error.message(),
+        };
+        let status = error.status_code();
+        match result.iter_mut().find(|(s, _)| *s == status) {
+            Some((_, descriptions)) => {
+                descriptions.push_str("; ");
+                descriptions.push_str(description);
+            }
+            None => result.push((status, description.into())),
+        }
+    }
+    result
+}
+
+/// The operations of the API.
+fn operations() -> Vec<Operation> {
+    let svg = json!({ "type": "string" });
+    vec![
+        Operation {
+            method: "get",
+            path: "/{maze_type}/{dimensions}/image.svg",
+            summary: "Renders a maze as an SVG image.",
+            body: None,
+            content_type: "image/svg+xml",
+            response: svg.clone(),
+            service: |config| {
+                config.service(crate::maze_svg);
+            },
+        },
+        Operation {
+            method: "post",
+            path: "/{maze_type}/{dimensions}/image.svg",
+            summary: "Renders a maze shaped by a mask as an SVG image.",
+            body: Some(types::Mask::schema()),
+            content_type: "image/svg+xml",
+            response: svg,
+            service: |config| {
+                config.service(crate::masked_maze_svg);
+            },
+        },
+        Operation {
+            method: "get",
+            path: "/{maze_type}/{dimensions}/maze.json",
+            summary: "Describes the layout of a maze.",
+            body: None,
+            content_type: "application/json",
+            response: reference("Layout"),
+            service: |config| {
+                config.service(crate::maze_json);
+            },
+        },
+        Operation {
+            method: "get",
+            path: "/{maze_type}/{dimensions}/puzzle.json",
+            summary: "Describes a maze and its solution as a puzzle.",
+            body: None,
+            content_type: "application/json",
+            response: json!({ "type": "object" }),
+            service: |config| {
+                config.service(crate::puzzle_json);
+            },
+        },
+        Operation {
+            method: "get",
+            path: "/{maze_type}/{dimensions}/events",
+            summary: "Streams the events of generating a maze.",
+            body: None,
+            content_type: "text/event-stream",
+            response: json!({ "type": "string" }),
+            service: |config| {
+                config.service(crate::maze_events);
+            },
+        },
+        Operation {
+            method: "get",
+            path: "/play/{maze_type}/{dimensions}",
+            summary: "Serves a page on which a maze can be navigated.",
+            body: None,
+            content_type: "text/html",
+            response: json!({ "type": "string" }),
+            service: |config| {
+                config.service(crate::play);
+            },
+        },
+        Operation {
+            method: "get",
+            path: "/openapi.json",
+            summary: "Describes the API.",
+            body: None,
+            content_type: "application/json",
+            response: json!({ "type": "object" }),
+            service: |config| {
+                config.service(crate::openapi_json);
+            },
+        },
+    ]
+}
+
+/// Registers the handlers of all operations of the API.
+///
+/// # Arguments
+/// *  `config` - The configuration of the application.
+pub fn configure(config: &mut web::ServiceConfig) {
+    for operation in operations() {
+        (operation.service)(config);
+    }
+}
+
+/// Generates the specification of the API.
+pub fn document() -> Value {
+    let mut paths = Map::new();
+    for operation in operations() {
+        let path = paths
+            .entry(operation.path)
+            .or_insert_with(|| Value::Object(Map::new()));
+        path[operation.method] = operation.describe();
+    }
+
+    json!({
+        "openapi": OPENAPI_VERSION,
+        "info": {
+            "title": "Maze",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": {
+            "schemas": {
+                "Error": types::Error::schema(),
+                "Layout": types::Layout::schema(),
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{test::TestRequest, App};
+
+    use super::*;
+
+    /// Collects all schema references in a value.
+    fn references(value: &Value, result: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(r)) = map.get("$ref") {
+                    result.push(r.clone());
+                }
+                map.values().for_each(|v| references(v, result));
+            }
+            Value::Array(values) => {
+                values.iter().for_each(|v| references(v, result))
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn document() {
+        let document = super::document();
+        assert_eq!(7, operations().len());
+        assert_eq!(6, document["paths"].as_object().unwrap().len());
+        let image = &document["paths"]["/{maze_type}/{dimensions}/image.svg"];
+        assert!(image["get"].is_object());
+        assert!(
+            image["post"]["requestBody"]["content"]["image/png"].is_object()
+        );
+        assert_eq!(
+            "#/components/schemas/Error",
+            image["get"]["responses"]["429"]["content"]["application/json"]
+                ["schema"]["$ref"],
+        );
+
+        let mut refs = Vec::new();
+        references(&document, &mut refs);
+        assert!(!refs.is_empty());
+        for r in refs {
+            let name = r.strip_prefix("#/components/schemas/").unwrap();
+            assert!(document["components"]["schemas"][name].is_object());
+        }
+    }
+
+    #[actix_web::test]
+    async fn routes() {
+        // Every operation must be served by a handler on its path
+        for operation in operations() {
+            let app = actix_web::test::init_service(
+                App::new()
+                    .app_data(web::Data::new(crate::limits::Limits::default()))
+                    .app_data(web::Data::new(crate::ImageCache::new(
+                        crate::cache::Cache::new(1),
+                    )))
+                    .configure(operation.service),
+            )
+            .await;
+            let uri = operation
+                .path
+                .replace("{maze_type}", "quad")
+                .replace("{dimensions}", "5x5");
+            let request = TestRequest::default()
+                .method(operation.method.to_uppercase().parse().unwrap())
+                .uri(&format!("{}?seed=1", uri))
+                .to_request();
+            let response = actix_web::test::call_service(&app, request).await;
+            assert_ne!(
+                StatusCode::NOT_FOUND,
+                response.status(),
+                "{} {}",
+                operation.method,
+                operation.path,
+            );
+        }
+    }
+
+    #[test]
+    fn parameters() {
+        let query = crate::Query::parameters()
+            .iter()
+            .map(|parameter| {
+                let example = &parameter["schema"]["example"];
+                format!(
+                    "{}={}",
+                    parameter["name"].as_str().unwrap(),
+                    example
+                        .as_str()
+                        .map(String::from)
+                        .unwrap_or_else(|| { example.to_string() }),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+        assert!(serde_urlencoded::from_str::<crate::Query>(&query)
+            .map(|query| query.seed.is_some() && query.room_size.is_some())
+            .unwrap_or(false));
+    }
+}
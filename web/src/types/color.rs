@@ -0,0 +1,86 @@
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::openapi::Schema;
+
+/// A colour, convertible from a query string.
+///
+/// Colours are either hex encoded on the form `#RGB`, `#RRGGBB` or
+/// `#RRGGBBAA`, or colour names consisting of only ASCII letters.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(try_from = "String")]
+pub struct Color(String);
+
+impl Color {
+    /// Creates a colour from a value known to be valid.
+    ///
+    /// # Arguments
+    /// *  `value` - The colour value.
+    pub(crate) fn named(value: &str) -> Self {
+        Self(value.into())
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let valid = if let Some(hex) = value.strip_prefix('#') {
+            [3, 6, 8].contains(&hex.len())
+                && hex.chars().all(|c| c.is_ascii_hexdigit())
+        } else {
+            !value.is_empty() && value.chars().all(|c| c.is_ascii_alphabetic())
+        };
+        if valid {
+            Ok(Self(value))
+        } else {
+            Err(format!("invalid colour: {}", value))
+        }
+    }
+}
+
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Schema for Color {
+    fn schema() -> serde_json::Value {
+        json!({
+            "type": "string",
+            "pattern": "^(#([0-9a-fA-F]{3}|[0-9a-fA-F]{6}|[0-9a-fA-F]{8})\
+                |[a-zA-Z]+)$",
+            "example": "#000000",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        assert_eq!(
+            Color::named("#00ff00"),
+            serde_urlencoded::from_str::<Vec<(String, Color)>>(
+                "color=%2300ff00"
+            )
+            .unwrap()[0]
+                .1,
+        );
+        assert_eq!(
+            Ok(Color::named("rebeccapurple")),
+            Color::try_from(String::from("rebeccapurple")),
+        );
+        assert_eq!(
+            Err(String::from("invalid colour: #12345")),
+            Color::try_from(String::from("#12345")),
+        );
+        assert_eq!(
+            Err(String::from("invalid colour: red\"")),
+            Color::try_from(String::from("red\"")),
+        );
+    }
+}
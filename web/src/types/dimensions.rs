@@ -1,7 +1,10 @@
 use serde::Deserialize;
+use serde_json::json;
+
+use crate::openapi::Schema;
 
 /// Dimensions of a maze.
-#[derive(Debug, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
 #[serde(try_from = "String")]
 pub struct Dimensions {
     /// The width.
@@ -30,6 +33,74 @@ impl TryFrom<String> for Dimensions {
     }
 }
 
+impl Dimensions {
+    /// Calculates the dimensions of a maze from its physical size.
+    ///
+    /// The number of rooms along an axis is the smallest number that makes the
+    /// maze at least as large as requested, as for the `--ratio` option of the
+    /// maker. If only one of `width` and `height` is passed, the other axis
+    /// keeps its number of rooms.
+    ///
+    /// The room size is the width of a single room; the maze is scaled
+    /// uniformly.
+    ///
+    /// # Arguments
+    /// *  `shape` - The shape of the rooms.
+    /// *  `width` - The requested physical width.
+    /// *  `height` - The requested physical height.
+    /// *  `room_size` - The physical width of a room.
+    pub fn with_physical_size(
+        self,
+        shape: maze::Shape,
+        width: Option<f32>,
+        height: Option<f32>,
+        room_size: Option<f32>,
+    ) -> Result<Self, String> {
+        let room_size = match (width.or(height), room_size) {
+            (None, None) => return Ok(self),
+            (None, Some(_)) => {
+                return Err(String::from(
+                    "room_size requires width_mm or height_mm",
+                ))
+            }
+            (Some(_), None) => {
+                return Err(String::from("a physical size requires room_size"))
+            }
+            (Some(_), Some(room_size)) => room_size,
+        };
+        if !room_size.is_finite() || room_size <= 0.0 {
+            return Err(String::from("invalid room size"));
+        }
+        if [width, height]
+            .iter()
+            .flatten()
+            .any(|&size| !size.is_finite() || size <= 0.0)
+        {
+            return Err(String::from("invalid physical size"));
+        }
+
+        let scale = shape.viewbox(1, 1).width / room_size;
+        let current = shape.viewbox(self.width, self.height);
+        let (width, height) = shape.minimal_dimensions(
+            width.map(|width| width * scale).unwrap_or(current.width),
+            height
+                .map(|height| height * scale)
+                .unwrap_or(current.height),
+        );
+        Ok(Self { width, height })
+    }
+}
+
+impl Schema for Dimensions {
+    fn schema() -> serde_json::Value {
+        json!({
+            "type": "string",
+            "pattern": "^[0-9]+x[0-9]+$",
+            "example": "10x10",
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,4 +127,48 @@ mod tests {
             Dimensions::try_from(String::from("1xb")),
         );
     }
+
+    #[test]
+    fn with_physical_size() {
+        let dimensions = Dimensions {
+            width: 3,
+            height: 4,
+        };
+        let shape = maze::Shape::Quad;
+        assert_eq!(
+            Ok(dimensions),
+            dimensions.with_physical_size(shape, None, None, None),
+        );
+        assert_eq!(
+            Ok(Dimensions {
+                width: 40,
+                height: 4,
+            }),
+            dimensions.with_physical_size(shape, Some(198.0), None, Some(5.0)),
+        );
+        assert_eq!(
+            Ok(Dimensions {
+                width: 40,
+                height: 21,
+            }),
+            dimensions.with_physical_size(
+                shape,
+                Some(198.0),
+                Some(101.0),
+                Some(5.0),
+            ),
+        );
+        assert_eq!(
+            Err(String::from("a physical size requires room_size")),
+            dimensions.with_physical_size(shape, Some(200.0), None, None),
+        );
+        assert_eq!(
+            Err(String::from("room_size requires width_mm or height_mm")),
+            dimensions.with_physical_size(shape, None, None, Some(5.0)),
+        );
+        assert_eq!(
+            Err(String::from("invalid room size")),
+            dimensions.with_physical_size(shape, Some(200.0), None, Some(0.0)),
+        );
+    }
 }
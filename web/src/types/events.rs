@@ -0,0 +1,160 @@
+use serde::Serialize;
+
+use maze::initialize;
+use maze::matrix;
+use maze::physical;
+
+use super::Method;
+
+/// An event reported while generating a maze, sent to clients as a
+/// server-sent event.
+///
+/// The name of the event is the name of the variant in lower case, and the
+/// data is the fields of the variant encoded as _JSON_.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Event {
+    /// Generation has started.
+    Start {
+        /// The seed used to generate the maze.
+        seed: u64,
+
+        /// The initialisation method used to generate the maze.
+        method: String,
+
+        /// The view box of the maze, as `(x, y, width, height)`.
+        viewbox: (f32, f32, f32, f32),
+    },
+
+    /// A wall was opened.
+    Opened {
+        /// The room of the wall.
+        pos: matrix::Pos,
+
+        /// The name of the wall.
+        wall: &'static str,
+
+        /// The corners of the wall.
+        span: (physical::Pos, physical::Pos),
+    },
+
+    /// A wall was closed.
+    Closed {
+        /// The room of the wall.
+        pos: matrix::Pos,
+
+        /// The name of the wall.
+        wall: &'static str,
+
+        /// The corners of the wall.
+        span: (physical::Pos, physical::Pos),
+    },
+
+    /// A room was visited for the first time.
+    Visited {
+        /// The room.
+        pos: matrix::Pos,
+    },
+
+    /// Generation has finished.
+    Done {},
+}
+
+impl Event {
+    /// The event starting the generation of a maze.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `seed` - The seed used to generate the maze.
+    /// *  `method` - The initialisation method used to generate the maze.
+    pub fn start<T>(maze: &maze::Maze<T>, seed: u64, method: Method) -> Self
+    where
+        T: Clone,
+    {
+        Event::Start {
+            seed,
+            method: method.to_string(),
+            viewbox: maze.viewbox().tuple(),
+        }
+    }
+
+    /// Converts an event reported by the maze library.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze being generated.
+    /// *  `event` - The event.
+    pub fn new<T>(maze: &maze::Maze<T>, event: initialize::Event) -> Self
+    where
+        T: Clone,
+    {
+        match event {
+            initialize::Event::Opened(wall_pos) => Event::Opened {
+                pos: wall_pos.0,
+                wall: wall_pos.1.name,
+                span: maze.corners(wall_pos),
+            },
+            initialize::Event::Closed(wall_pos) => Event::Closed {
+                pos: wall_pos.0,
+                wall: wall_pos.1.name,
+                span: maze.corners(wall_pos),
+            },
+            initialize::Event::Visited(pos) => Event::Visited { pos },
+        }
+    }
+
+    /// The name of this event.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::Start { .. } => "start",
+            Event::Opened { .. } => "opened",
+            Event::Closed { .. } => "closed",
+            Event::Visited { .. } => "visited",
+            Event::Done {} => "done",
+        }
+    }
+
+    /// Encodes this event as a server-sent event.
+    pub fn to_sse(&self) -> String {
+        format!(
+            "event: {}\ndata: {}\n\n",
+            self.name(),
+            serde_json::to_string(self).unwrap(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let maze = maze::Shape::Quad.create::<()>(2, 1);
+        let pos = matrix::Pos { col: 0, row: 0 };
+        let wall_pos = (pos, maze.walls(pos)[0]);
+        assert_eq!(
+            Event::Opened {
+                pos,
+                wall: wall_pos.1.name,
+                span: maze.corners(wall_pos),
+            },
+            Event::new(&maze, initialize::Event::Opened(wall_pos)),
+        );
+        assert_eq!(
+            Event::Visited { pos },
+            Event::new(&maze, initialize::Event::Visited(pos)),
+        );
+    }
+
+    #[test]
+    fn to_sse() {
+        assert_eq!(
+            "event: visited\ndata: {\"pos\":{\"col\":1,\"row\":2}}\n\n",
+            Event::Visited {
+                pos: matrix::Pos { col: 1, row: 2 },
+            }
+            .to_sse(),
+        );
+        assert_eq!("event: done\ndata: {}\n\n", Event::Done {}.to_sse());
+    }
+}
@@ -0,0 +1,71 @@
+use serde::Deserialize;
+use serde_json::json;
+
+use maze_tools::heat_map;
+
+use crate::openapi::Schema;
+
+/// A type of heat map, convertible from a query string.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HeatMapType {
+    /// The heat map is generated by traversing vertically.
+    Vertical,
+
+    /// The heat map is generated by traversing horisontally.
+    Horizontal,
+
+    /// The heat map is generated by travesing from every edge room to the one
+    /// on the opposite side.
+    Full,
+}
+
+impl HeatMapType {
+    /// Generates a heat map based on this heat map type.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze for which to generate a heat map.
+    pub fn generate<T>(self, maze: &maze::Maze<T>) -> maze::HeatMap
+    where
+        T: Clone + Sync,
+    {
+        heat_map::HeatMapType::from(self).generate(maze)
+    }
+}
+
+impl From<HeatMapType> for heat_map::HeatMapType {
+    fn from(source: HeatMapType) -> Self {
+        match source {
+            HeatMapType::Vertical => heat_map::HeatMapType::Vertical,
+            HeatMapType::Horizontal => heat_map::HeatMapType::Horizontal,
+            HeatMapType::Full => heat_map::HeatMapType::Full,
+        }
+    }
+}
+
+impl Schema for HeatMapType {
+    fn schema() -> serde_json::Value {
+        json!({
+            "type": "string",
+            "enum": ["vertical", "horizontal", "full"],
+            "example": "full",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        assert_eq!(
+            HeatMapType::Full,
+            serde_urlencoded::from_str::<Vec<(String, HeatMapType)>>(
+                "heat_map=full"
+            )
+            .unwrap()[0]
+                .1,
+        );
+    }
+}
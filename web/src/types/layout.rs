@@ -0,0 +1,163 @@
+use serde::Serialize;
+use serde_json::json;
+
+use maze::matrix;
+use maze::physical;
+
+use super::Method;
+use crate::openapi::Schema;
+
+/// A description of the layout of a generated maze.
+///
+/// This contains enough information for a client to let a user navigate the
+/// maze.
+#[derive(Debug, Serialize)]
+pub struct Layout {
+    /// The seed used to generate the maze.
+    pub seed: u64,
+
+    /// The initialisation method used to generate the maze.
+    pub method: String,
+
+    /// The view box of the maze, as `(x, y, width, height)`.
+    pub viewbox: (f32, f32, f32, f32),
+
+    /// The room in which the solution starts.
+    pub start: matrix::Pos,
+
+    /// The room in which the solution ends.
+    pub finish: matrix::Pos,
+
+    /// All rooms of the maze.
+    pub rooms: Vec<Room>,
+}
+
+/// A room in a maze layout.
+#[derive(Debug, Serialize)]
+pub struct Room {
+    /// The position of the room.
+    pub pos: matrix::Pos,
+
+    /// The centre of the room.
+    pub center: physical::Pos,
+
+    /// The rooms reachable through an open wall of this room.
+    ///
+    /// Only rooms inside of the maze are included.
+    pub neighbors: Vec<matrix::Pos>,
+}
+
+impl Layout {
+    /// Describes the layout of a maze.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `seed` - The seed used to generate the maze.
+    /// *  `method` - The initialisation method used to generate the maze.
+    /// *  `start` - The room in which the solution starts.
+    /// *  `finish` - The room in which the solution ends.
+    pub fn new<T>(
+        maze: &maze::Maze<T>,
+        seed: u64,
+        method: Method,
+        start: matrix::Pos,
+        finish: matrix::Pos,
+    ) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            seed,
+            method: method.to_string(),
+            viewbox: maze.viewbox().tuple(),
+            start,
+            finish,
+            rooms: maze
+                .positions()
+                .map(|pos| Room {
+                    pos,
+                    center: maze.center(pos),
+                    neighbors: maze
+                        .neighbors(pos)
+                        .filter(|&neighbor| maze.is_inside(neighbor))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Schema for Layout {
+    fn schema() -> serde_json::Value {
+        let pos = |x: &str, y: &str| {
+            json!({
+                "type": "object",
+                "properties": {
+                    x: { "type": "integer" },
+                    y: { "type": "integer" },
+                },
+                "required": [x, y],
+            })
+        };
+        let matrix_pos = pos("col", "row");
+        let mut physical_pos = pos("x", "y");
+        physical_pos["properties"]["x"]["type"] = "number".into();
+        physical_pos["properties"]["y"]["type"] = "number".into();
+        json!({
+            "type": "object",
+            "properties": {
+                "seed": { "type": "integer", "format": "int64" },
+                "method": super::Method::schema(),
+                "viewbox": {
+                    "type": "array",
+                    "items": { "type": "number" },
+                    "minItems": 4,
+                    "maxItems": 4,
+                },
+                "start": matrix_pos,
+                "finish": matrix_pos,
+                "rooms": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "pos": matrix_pos,
+                            "center": physical_pos,
+                            "neighbors": {
+                                "type": "array",
+                                "items": matrix_pos,
+                            },
+                        },
+                        "required": ["pos", "center", "neighbors"],
+                    },
+                },
+            },
+            "required": [
+                "seed", "method", "viewbox", "start", "finish", "rooms",
+            ],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::types::Seed;
+
+    #[test]
+    fn new() {
+        let maze = maze::Shape::Quad.create::<()>(2, 1).initialize(
+            maze::initialize::Method::Branching(
+                maze::initialize::Bias::default(),
+            ),
+            &mut Seed::from(0),
+        );
+        let start = matrix::Pos { col: 0, row: 0 };
+        let finish = matrix::Pos { col: 1, row: 0 };
+        let layout = Layout::new(&maze, 0, Method::default(), start, finish);
+        assert_eq!(2, layout.rooms.len());
+        assert_eq!(vec![finish], layout.rooms[0].neighbors);
+        assert_eq!(vec![start], layout.rooms[1].neighbors);
+    }
+}
@@ -0,0 +1,134 @@
+use serde_json::json;
+
+use maze::matrix;
+use maze_tools::mask;
+
+use crate::openapi::Schema;
+
+/// The maximum size of an uploaded mask, in bytes.
+pub const MAX_MASK_BYTES: usize = 64 * 1024;
+
+/// The maximum width and height of a mask, in pixels or characters.
+const MAX_MASK_SIZE: u32 = 256;
+
+/// The intensity above which a part of a mask is considered inside the maze.
+const THRESHOLD: f32 = 0.5;
+
+/// A mask determining which rooms are part of a maze.
+///
+/// The mask is stretched to cover the entire maze, and rooms for which the
+/// average intensity of the covered part of the mask is above one half are
+/// kept. Since uploaded masks may be smaller than the maze, the mask is
+/// resampled to a fixed number of samples per room before being applied.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mask(image::GrayImage);
+
+impl Mask {
+    /// Loads a mask from _PNG_ data.
+    ///
+    /// Bright pixels are considered inside the maze.
+    ///
+    /// # Arguments
+    /// *  `data` - The encoded image.
+    pub fn from_png(data: &[u8]) -> Result<Self, String> {
+        let image =
+            image::load_from_memory_with_format(data, image::ImageFormat::Png)
+                .map_err(|e| format!("invalid mask: {}", e))?;
+        Self::validated(image.to_luma8())
+    }
+
+    /// Loads a mask from text.
+    ///
+    /// Every character corresponds to a pixel, and every line to a row of
+    /// pixels. White space is considered outside of the maze, and all other
+    /// characters inside. Short lines are padded with white space.
+    ///
+    /// # Arguments
+    /// *  `text` - The mask text.
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let lines = text
+            .lines()
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let width = lines.iter().map(Vec::len).max().unwrap_or(0);
+        let height = lines.len();
+        if width > MAX_MASK_SIZE as usize || height > MAX_MASK_SIZE as usize {
+            return Err(String::from("the mask is too large"));
+        }
+
+        Self::validated(image::GrayImage::from_fn(
+            width as u32,
+            height as u32,
+            |x, y| {
+                let inside = lines[y as usize]
+                    .get(x as usize)
+                    .map(|c| !c.is_whitespace())
+                    .unwrap_or(false);
+                image::Luma([if inside { 255 } else { 0 }])
+            },
+        ))
+    }
+
+    /// Generates a filter for a maze from this mask.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to which to apply the mask.
+    pub fn filter<T>(&self, maze: &maze::Maze<T>) -> matrix::Matrix<bool>
+    where
+        T: Clone,
+    {
+        mask::filter(&self.0, maze, THRESHOLD)
+    }
+
+    /// Ensures that the dimensions of an image are acceptable.
+    ///
+    /// # Arguments
+    /// *  `image` - The mask image.
+    fn validated(image: image::GrayImage) -> Result<Self, String> {
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0 {
+            Err(String::from("the mask is empty"))
+        } else if width > MAX_MASK_SIZE || height > MAX_MASK_SIZE {
+            Err(String::from("the mask is too large"))
+        } else {
+            Ok(Self(image))
+        }
+    }
+}
+
+impl Schema for Mask {
+    /// The schema of a mask, keyed by the content type of its encoding.
+    fn schema() -> serde_json::Value {
+        json!({
+            "image/png": { "type": "string", "format": "binary" },
+            "text/plain": { "type": "string" },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_text() {
+        let mask = Mask::from_text("##\n #").unwrap();
+        let maze = maze::Shape::Quad.create::<()>(2, 2);
+        let filter = mask.filter(&maze);
+        assert!(filter[matrix::Pos { col: 0, row: 0 }]);
+        assert!(filter[matrix::Pos { col: 1, row: 0 }]);
+        assert!(!filter[matrix::Pos { col: 0, row: 1 }]);
+        assert!(filter[matrix::Pos { col: 1, row: 1 }]);
+
+        assert_eq!(Err(String::from("the mask is empty")), Mask::from_text(""),);
+        assert_eq!(
+            Err(String::from("the mask is too large")),
+            Mask::from_text(&"#".repeat(MAX_MASK_SIZE as usize + 1)),
+        );
+    }
+
+    #[test]
+    fn from_png() {
+        assert!(Mask::from_png(b"not a png").is_err());
+    }
+}
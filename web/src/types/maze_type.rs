@@ -1,11 +1,19 @@
 use serde::Deserialize;
+use serde_json::json;
+
+use crate::openapi::Schema;
 
 /// A maze type, convertible from a query string.
-#[derive(Deserialize)]
+#[derive(Clone, Copy, Debug, Deserialize)]
 #[serde(transparent)]
 pub struct MazeType(maze::Shape);
 
 impl MazeType {
+    /// The shape of rooms of this maze type.
+    pub fn shape(self) -> maze::Shape {
+        self.0
+    }
+
     pub fn create<T>(self, dimensions: super::Dimensions) -> maze::Maze<T>
     where
         T: Clone + Copy + Default,
@@ -13,3 +21,16 @@ impl MazeType {
         self.0.create(dimensions.width, dimensions.height)
     }
 }
+
+impl Schema for MazeType {
+    fn schema() -> serde_json::Value {
+        let shapes = [maze::Shape::Tri, maze::Shape::Quad, maze::Shape::Hex];
+        json!({
+            "type": "string",
+            "enum": shapes
+                .iter()
+                .map(|shape| serde_json::to_value(shape).unwrap())
+                .collect::<Vec<_>>(),
+        })
+    }
+}
@@ -0,0 +1,63 @@
+use serde::Deserialize;
+use serde_json::json;
+
+use maze::initialize;
+
+use crate::openapi::Schema;
+
+/// An initialisation method, convertible from a query string.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(try_from = "String")]
+pub struct Method(pub initialize::Method);
+
+impl TryFrom<String> for Method {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value
+            .parse()
+            .map(Self)
+            .map_err(|_| format!("unknown method: {}", value))
+    }
+}
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Schema for Method {
+    fn schema() -> serde_json::Value {
+        json!({
+            "type": "string",
+            "description": "The name of an initialisation method, \
+                optionally followed by bias parameters on the form \
+                `:name=value`.",
+            "pattern": "^(braid|clear|(branching|winding)\
+                (:(horizontal|branch)=[0-9]+)*)$",
+            "example": Method::default().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        assert_eq!(
+            Method(initialize::Method::Winding(initialize::Bias::default())),
+            serde_urlencoded::from_str::<Vec<(String, Method)>>(
+                "method=winding"
+            )
+            .unwrap()[0]
+                .1,
+        );
+        assert_eq!(
+            Err(String::from("unknown method: other")),
+            Method::try_from(String::from("other")),
+        );
+    }
+}
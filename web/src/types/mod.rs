@@ -1,66 +1,408 @@
-use actix_web::HttpResponse;
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+use serde::Serialize;
+use serde_json::json;
 use svg::Node;
+use tokio::sync::mpsc;
 
-use maze::initialize;
-use maze::render::svg::ToPath;
+use maze::render::svg::{fill_rooms, fill_rooms_identified, ToPath};
 
+use crate::openapi::Schema;
+
+mod color;
+pub use self::color::*;
+mod layout;
+pub use self::layout::*;
+mod mask;
+pub use self::mask::*;
 mod maze_type;
 pub use self::maze_type::*;
 mod dimensions;
 pub use self::dimensions::*;
+mod events;
+pub use self::events::*;
+mod heat_map;
+pub use self::heat_map::*;
+mod method;
+pub use self::method::*;
+mod render_options;
+pub use self::render_options::*;
 mod seed;
 pub use self::seed::*;
 
-/// The maximum nmber of rooms.
-const MAX_ROOMS: usize = 1000;
-
 /// A responder providing an image of a maze.
 pub struct Maze {
     pub maze_type: MazeType,
     pub dimensions: Dimensions,
+    pub method: Method,
     pub seed: Seed,
+    pub heat_map: Option<HeatMapType>,
+    pub room_ids: bool,
+    pub mask: Option<Mask>,
     pub solve: bool,
+    pub render_options: RenderOptions,
+}
+
+/// An error preventing a request from being served.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The request is invalid.
+    Invalid(String),
+
+    /// The mask has an unsupported content type.
+    UnsupportedMask,
+
+    /// The requested maze exceeds the configured limits.
+    TooLarge,
+
+    /// The mask excludes all rooms.
+    EmptyMask,
+
+    /// The start and end of the solution are not connected.
+    Unsolvable,
+
+    /// The API key of the request is unknown.
+    Unauthorized,
+
+    /// The client has made too many requests.
+    RateLimited,
+}
+
+impl Error {
+    /// One error of every kind.
+    ///
+    /// This is used to describe the possible errors of the API.
+    pub fn kinds() -> [Self; 7] {
+        [
+            Error::Invalid(String::new()),
+            Error::UnsupportedMask,
+            Error::TooLarge,
+            Error::EmptyMask,
+            Error::Unsolvable,
+            Error::Unauthorized,
+            Error::RateLimited,
+        ]
+    }
+
+    /// A stable identifier of the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Invalid(_) => "invalid",
+            Error::UnsupportedMask => "unsupported_mask",
+            Error::TooLarge => "too_large",
+            Error::EmptyMask => "empty_mask",
+            Error::Unsolvable => "unsolvable",
+            Error::Unauthorized => "unauthorized",
+            Error::RateLimited => "rate_limited",
+        }
+    }
+
+    /// A description of this error.
+    pub fn message(&self) -> &str {
+        match self {
+            Error::Invalid(message) => message,
+            Error::UnsupportedMask => {
+                "the mask must be image/png or text/plain"
+            }
+            Error::TooLarge => "the requested maze is too large",
+            Error::EmptyMask => "the mask excludes all rooms",
+            Error::Unsolvable => "the maze has no solution",
+            Error::Unauthorized => "the API key is invalid",
+            Error::RateLimited => "too many requests",
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// The body of error responses.
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    /// The kind of the error.
+    code: &'static str,
+
+    /// A description of the error.
+    error: &'a str,
 }
 
-impl From<Maze> for HttpResponse {
-    fn from(mut source: Maze) -> Self {
-        let room_count = source.dimensions.width * source.dimensions.height;
-        if room_count > MAX_ROOMS {
-            HttpResponse::InsufficientStorage()
-                .body("the requested maze is too large")
+impl Schema for Error {
+    fn schema() -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "code": {
+                    "type": "string",
+                    "enum": Self::kinds()
+                        .iter()
+                        .map(Self::code)
+                        .collect::<Vec<_>>(),
+                },
+                "error": { "type": "string" },
+            },
+            "required": ["code", "error"],
+        })
+    }
+}
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Error::Invalid(_)
+            | Error::TooLarge
+            | Error::EmptyMask
+            | Error::Unsolvable => StatusCode::BAD_REQUEST,
+            Error::UnsupportedMask => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Error::Unauthorized => StatusCode::UNAUTHORIZED,
+            Error::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(ErrorBody {
+            code: self.code(),
+            error: self.message(),
+        })
+    }
+}
+
+/// A rendered image of a maze.
+#[derive(Clone)]
+pub struct Image {
+    /// The SVG data.
+    pub data: String,
+
+    /// The seed used to generate the maze.
+    pub seed: u64,
+
+    /// The initialisation method used to generate the maze.
+    pub method: Method,
+}
+
+impl Maze {
+    /// A key uniquely identifying the image rendered for this maze.
+    ///
+    /// Masked mazes have no key, since their masks may be large.
+    pub fn cache_key(&self) -> Option<String> {
+        if self.mask.is_some() {
+            None
         } else {
-            let maze = source
-                .maze_type
-                .create::<()>(source.dimensions)
-                .initialize(initialize::Method::Branching, &mut source.seed);
+            Some(format!(
+                "{:?}/{}x{}/{}/{}/{:?}/{}/{}/{:?}",
+                self.maze_type,
+                self.dimensions.width,
+                self.dimensions.height,
+                self.method,
+                self.seed.value(),
+                self.heat_map,
+                self.room_ids,
+                self.solve,
+                self.render_options,
+            ))
+        }
+    }
 
-            let mut container = svg::node::element::Group::new();
+    /// Generates and renders this maze.
+    pub fn render(mut self) -> Result<Image, Error> {
+        let seed = self.seed.value();
+        let (maze, from, to) = self.generate()?;
+
+        let options = &self.render_options;
+        let mut container = svg::node::element::Group::new();
+        if let Some(background) = options.background(maze.viewbox()) {
+            container.append(background);
+        }
+        if self.heat_map.is_some() || self.room_ids {
             container.append(
-                svg::node::element::Path::new()
-                    .set("class", "walls")
-                    .set("d", maze.to_path_d()),
+                draw_rooms(&maze, self.heat_map, self.room_ids).set(
+                    "class",
+                    if self.heat_map.is_some() {
+                        "heat-map"
+                    } else {
+                        "rooms"
+                    },
+                ),
             );
-            if source.solve {
-                container.append(
-                    svg::node::element::Path::new().set("class", "path").set(
-                        "d",
-                        maze.walk(
-                            maze::matrix::Pos { col: 0, row: 0 },
-                            maze::matrix::Pos {
-                                col: maze.width() as isize - 1,
-                                row: maze.height() as isize - 1,
-                            },
-                        )
-                        .unwrap()
-                        .to_path_d(),
-                    ),
-                );
+        }
+        container.append(options.walls(maze.to_path_d()));
+        if self.solve {
+            if let Some(path) = maze.walk(from, to) {
+                container.append(options.solution(path.to_path_d()));
             }
-            let data = svg::Document::new()
-                .set("viewBox", maze.viewbox().tuple())
-                .add(container)
-                .to_string();
-            HttpResponse::Ok().content_type("image/svg+xml").body(data)
         }
+        let data = svg::Document::new()
+            .set("viewBox", maze.viewbox().tuple())
+            .add(container)
+            .to_string();
+
+        Ok(Image {
+            data,
+            seed,
+            method: self.method,
+        })
+    }
+
+    /// Generates this maze and describes its layout.
+    pub fn layout(mut self) -> Result<Layout, Error> {
+        let seed = self.seed.value();
+        let (maze, from, to) = self.generate()?;
+        Ok(Layout::new(&maze, seed, self.method, from, to))
+    }
+
+    /// Generates this maze and describes it as a puzzle.
+    ///
+    /// See [`maze::io::puzzle_json`] for the format.
+    pub fn puzzle(mut self) -> Result<String, Error> {
+        let (maze, from, to) = self.generate()?;
+        let path = maze.walk(from, to).ok_or(Error::Unsolvable)?;
+        Ok(maze::io::puzzle_json(&maze, &path))
+    }
+
+    /// Generates this maze and sends the events of its generation.
+    ///
+    /// The events are preceded by a start event, and followed by a done
+    /// event. Sending blocks while the channel is full, so this should be
+    /// called on a blocking task. If the receiver is dropped, the remaining
+    /// events are discarded.
+    ///
+    /// If the maze cannot be generated, no event is sent.
+    ///
+    /// # Arguments
+    /// *  `sender` - The channel to which to send the events.
+    pub fn events(mut self, sender: mpsc::Sender<Event>) -> Result<(), Error> {
+        let seed = self.seed.value();
+        let (maze, filter, _, _) = self.prepare()?;
+        let geometry = maze.clone();
+        let send = |event| {
+            let _ = sender.blocking_send(event);
+        };
+
+        send(Event::start(&geometry, seed, self.method));
+        self.initialize(maze, filter, |event| {
+            send(Event::new(&geometry, event))
+        });
+        send(Event::Done {});
+        Ok(())
+    }
+
+    /// Generates this maze.
+    ///
+    /// The maze is returned with the endpoints of its solution.
+    fn generate(
+        &mut self,
+    ) -> Result<(maze::Maze<()>, maze::matrix::Pos, maze::matrix::Pos), Error>
+    {
+        let (maze, filter, from, to) = self.prepare()?;
+        Ok((self.initialize(maze, filter, |_| {}), from, to))
+    }
+
+    /// Creates the uninitialised maze and the filter of its rooms, and finds
+    /// the endpoints of its solution.
+    #[allow(clippy::type_complexity)]
+    fn prepare(
+        &self,
+    ) -> Result<
+        (
+            maze::Maze<()>,
+            Option<maze::matrix::Matrix<bool>>,
+            maze::matrix::Pos,
+            maze::matrix::Pos,
+        ),
+        Error,
+    > {
+        let maze = self.maze_type.create::<()>(self.dimensions);
+        let filter = self.mask.as_ref().map(|mask| mask.filter(&maze));
+        let (from, to) =
+            endpoints(&maze, filter.as_ref()).ok_or(Error::EmptyMask)?;
+        Ok((maze, filter, from, to))
+    }
+
+    /// Initialises a prepared maze while notifying an observer of every
+    /// event.
+    ///
+    /// # Arguments
+    /// *  `maze` - The uninitialised maze.
+    /// *  `filter` - The filter of the rooms to initialise.
+    /// *  `observer` - A function notified of every generation event.
+    fn initialize<O>(
+        &mut self,
+        maze: maze::Maze<()>,
+        filter: Option<maze::matrix::Matrix<bool>>,
+        observer: O,
+    ) -> maze::Maze<()>
+    where
+        O: FnMut(maze::initialize::Event),
+    {
+        maze.initialize_filter_with_observer(
+            self.method.0,
+            &mut self.seed,
+            |pos| filter.as_ref().map(|filter| filter[pos]).unwrap_or(true),
+            observer,
+        )
+    }
+}
+
+impl From<Image> for HttpResponse {
+    fn from(source: Image) -> Self {
+        HttpResponse::Ok()
+            .content_type("image/svg+xml")
+            .insert_header(("X-Maze-Seed", source.seed.to_string()))
+            .insert_header(("X-Maze-Method", source.method.to_string()))
+            .body(source.data)
+    }
+}
+
+/// Finds the endpoints of the solution of a maze.
+///
+/// These are the first and last rooms that are part of the maze.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+/// *  `filter` - A filter describing which rooms are part of the maze.
+fn endpoints(
+    maze: &maze::Maze<()>,
+    filter: Option<&maze::matrix::Matrix<bool>>,
+) -> Option<(maze::matrix::Pos, maze::matrix::Pos)> {
+    let mut positions = maze
+        .positions()
+        .filter(|&pos| filter.map(|filter| filter[pos]).unwrap_or(true));
+    let from = positions.next()?;
+    Some((from, positions.last().unwrap_or(from)))
+}
+
+/// Draws the rooms of a maze, optionally coloured by their heat.
+///
+/// Rooms are transparent unless a heat map is drawn.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+/// *  `heat_map_type` - The type of heat map to draw.
+/// *  `identify` - Whether to add identifying attributes to rooms.
+fn draw_rooms(
+    maze: &maze::Maze<()>,
+    heat_map_type: Option<HeatMapType>,
+    identify: bool,
+) -> svg::node::element::Group {
+    let heat_map = heat_map_type.map(|t| t.generate(maze).normalize());
+    let red = maze::render::Color {
+        red: 255,
+        green: 0,
+        blue: 0,
+        alpha: 255,
+    };
+
+    let colors = |pos| {
+        red.fade(
+            red.transparent(),
+            heat_map.as_ref().map(|h| h[pos]).unwrap_or(0.0),
+        )
+    };
+    if identify {
+        fill_rooms_identified(maze, colors)
+    } else {
+        fill_rooms(maze, colors)
     }
 }
@@ -0,0 +1,148 @@
+use super::Color;
+
+/// The smallest accepted stroke width.
+const MIN_STROKE_WIDTH: f32 = 0.01;
+
+/// The largest accepted stroke width.
+const MAX_STROKE_WIDTH: f32 = 1.0;
+
+/// Options controlling the appearance of a rendered maze.
+///
+/// All styling is applied as attributes, so the rendered image does not rely
+/// on external style sheets.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderOptions {
+    /// The colour of walls.
+    pub wall_color: Color,
+
+    /// The background colour, if any.
+    pub background_color: Option<Color>,
+
+    /// The width of walls and the solution, relative to the size of rooms.
+    pub stroke_width: f32,
+
+    /// The colour of the solution.
+    pub solution_color: Color,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            wall_color: Color::named("black"),
+            background_color: None,
+            stroke_width: 0.1,
+            solution_color: Color::named("red"),
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Creates render options, using default values for all values not
+    /// specified.
+    ///
+    /// # Arguments
+    /// *  `wall_color` - The colour of walls.
+    /// *  `background_color` - The background colour.
+    /// *  `stroke_width` - The width of walls and the solution.
+    /// *  `solution_color` - The colour of the solution.
+    pub fn new(
+        wall_color: Option<Color>,
+        background_color: Option<Color>,
+        stroke_width: Option<f32>,
+        solution_color: Option<Color>,
+    ) -> Result<Self, String> {
+        let default = Self::default();
+        let stroke_width = match stroke_width {
+            Some(w) if (MIN_STROKE_WIDTH..=MAX_STROKE_WIDTH).contains(&w) => w,
+            Some(w) => return Err(format!("invalid stroke width: {}", w)),
+            None => default.stroke_width,
+        };
+        Ok(Self {
+            wall_color: wall_color.unwrap_or(default.wall_color),
+            background_color,
+            stroke_width,
+            solution_color: solution_color.unwrap_or(default.solution_color),
+        })
+    }
+
+    /// Creates a path element styled as walls.
+    ///
+    /// # Arguments
+    /// *  `data` - The path data.
+    pub fn walls(
+        &self,
+        data: svg::node::element::path::Data,
+    ) -> svg::node::element::Path {
+        self.line(data, &self.wall_color).set("class", "walls")
+    }
+
+    /// Creates a path element styled as the solution.
+    ///
+    /// # Arguments
+    /// *  `data` - The path data.
+    pub fn solution(
+        &self,
+        data: svg::node::element::path::Data,
+    ) -> svg::node::element::Path {
+        self.line(data, &self.solution_color).set("class", "path")
+    }
+
+    /// Creates a rectangle filling a view box with the background colour.
+    ///
+    /// # Arguments
+    /// *  `viewbox` - The view box to fill.
+    pub fn background(
+        &self,
+        viewbox: maze::physical::ViewBox,
+    ) -> Option<svg::node::element::Rectangle> {
+        self.background_color.as_ref().map(|color| {
+            svg::node::element::Rectangle::new()
+                .set("x", viewbox.corner.x)
+                .set("y", viewbox.corner.y)
+                .set("width", viewbox.width)
+                .set("height", viewbox.height)
+                .set("fill", color.to_string())
+        })
+    }
+
+    /// Creates a stroked path element.
+    ///
+    /// # Arguments
+    /// *  `data` - The path data.
+    /// *  `color` - The stroke colour.
+    fn line(
+        &self,
+        data: svg::node::element::path::Data,
+        color: &Color,
+    ) -> svg::node::element::Path {
+        svg::node::element::Path::new()
+            .set("fill", "none")
+            .set("stroke", color.to_string())
+            .set("stroke-width", self.stroke_width)
+            .set("stroke-linecap", "round")
+            .set("stroke-linejoin", "round")
+            .set("d", data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            Ok(RenderOptions::default()),
+            RenderOptions::new(None, None, None, None),
+        );
+        assert_eq!(
+            Ok(0.5),
+            RenderOptions::new(None, None, Some(0.5), None)
+                .map(|options| options.stroke_width),
+        );
+        assert_eq!(
+            Err(String::from("invalid stroke width: 2")),
+            RenderOptions::new(None, None, Some(2.0), None),
+        );
+    }
+}
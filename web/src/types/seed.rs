@@ -1,23 +1,63 @@
 use serde::Deserialize;
+use serde_json::json;
 
 use maze::initialize;
 
+use crate::openapi::Schema;
+
 /// A random seed.
+///
+/// When deserialised, the seed is either a number, or a phrase from which the
+/// seed is derived.
 #[derive(Debug, Deserialize, Eq, PartialEq)]
-#[serde(transparent)]
+#[serde(from = "String")]
 pub struct Seed {
+    /// The seed value.
+    value: u64,
+
     /// The LFSR initialised with the seed.
     lfsr: initialize::LFSR,
 }
 
 impl Seed {
     pub fn random() -> Self {
+        rand::random::<u64>().into()
+    }
+
+    /// Derives a seed from a phrase.
+    ///
+    /// # Arguments
+    /// *  `phrase` - The phrase.
+    pub fn from_phrase(phrase: &str) -> Self {
+        initialize::seed_from_phrase(phrase).into()
+    }
+
+    /// The value used to create this seed.
+    ///
+    /// Passing this value as seed will generate the same maze again.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+impl From<u64> for Seed {
+    fn from(source: u64) -> Self {
         Self {
-            lfsr: initialize::LFSR::new(rand::random()),
+            value: source,
+            lfsr: initialize::LFSR::new(source),
         }
     }
 }
 
+impl From<String> for Seed {
+    fn from(source: String) -> Self {
+        source
+            .parse::<u64>()
+            .map(Self::from)
+            .unwrap_or_else(|_| Self::from_phrase(&source))
+    }
+}
+
 impl initialize::Randomizer for Seed {
     fn range(&mut self, a: usize, b: usize) -> usize {
         self.lfsr.range(a, b)
@@ -28,6 +68,17 @@ impl initialize::Randomizer for Seed {
     }
 }
 
+impl Schema for Seed {
+    fn schema() -> serde_json::Value {
+        json!({
+            "type": "string",
+            "description": "A number, or a phrase from which the seed is \
+                derived.",
+            "example": "1234",
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -36,6 +87,7 @@ mod tests {
     fn deserialize() {
         assert_eq!(
             Seed {
+                value: 1234,
                 lfsr: initialize::LFSR::new(1234)
             },
             serde_urlencoded::from_str::<Vec<(String, Seed)>>("seed=1234")
@@ -43,4 +95,16 @@ mod tests {
                 .1,
         );
     }
+
+    #[test]
+    fn deserialize_phrase() {
+        assert_eq!(
+            Seed::from(initialize::seed_from_phrase("hello world")),
+            serde_urlencoded::from_str::<Vec<(String, Seed)>>(
+                "seed=hello+world"
+            )
+            .unwrap()[0]
+                .1,
+        );
+    }
 }